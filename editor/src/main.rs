@@ -2,6 +2,7 @@
 use fyrox::event_loop::EventLoop;
 use fyroxed_base::{Editor, StartupData};
 use station_iapetus::{
+    bot::patrol::PatrolMode,
     door::{DoorDirection, DoorState},
     GameConstructor,
 };
@@ -19,6 +20,7 @@ fn main() {
     let editors = &editor.inspector.property_editors;
     editors.register_inheritable_enum::<DoorState, _>();
     editors.register_inheritable_enum::<DoorDirection, _>();
+    editors.register_inheritable_enum::<PatrolMode, _>();
 
     editor.add_game_plugin(GameConstructor);
     editor.run(event_loop)
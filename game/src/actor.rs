@@ -1,5 +1,11 @@
 use crate::bot::BotKind;
-use crate::{bot::Bot, character::Character, level::UpdateContext, player::Player};
+use crate::{
+    bot::Bot,
+    character::Character,
+    faction::Faction,
+    level::UpdateContext,
+    player::{Player, PLAYER_DEFINITION},
+};
 use fyrox::{
     core::{
         algebra::Vector3,
@@ -7,7 +13,7 @@ use fyrox::{
         visitor::{Visit, VisitResult, Visitor},
     },
     resource::texture::Texture,
-    scene::Scene,
+    scene::{node::Node, Scene},
 };
 use std::ops::{Deref, DerefMut};
 
@@ -41,6 +47,22 @@ impl Actor {
     pub fn clean_up(&mut self, scene: &mut Scene) {
         static_dispatch!(self, clean_up, scene)
     }
+
+    /// Returns sounds of which one is played when the actor takes damage.
+    pub fn pain_sounds(&self) -> &[String] {
+        match self {
+            Actor::Bot(bot) => &bot.definition.pain_sounds,
+            Actor::Player(_) => &PLAYER_DEFINITION.pain_sounds,
+        }
+    }
+
+    /// Returns sounds of which one is played when the actor dies.
+    pub fn death_sounds(&self) -> &[String] {
+        match self {
+            Actor::Bot(bot) => &bot.definition.death_sounds,
+            Actor::Player(_) => &PLAYER_DEFINITION.death_sounds,
+        }
+    }
 }
 
 impl Deref for Actor {
@@ -76,7 +98,17 @@ pub struct TargetDescriptor {
     pub handle: Handle<Actor>,
     pub health: f32,
     pub position: Vector3<f32>,
+    pub velocity: Vector3<f32>,
     pub kind: TargetKind,
+    pub capsule_collider: Handle<Node>,
+    pub hit_boxes: Vec<Handle<Node>>,
+    /// Whether the laser sight of actor's current weapon is on.
+    pub laser_sight: bool,
+    /// Direction in which the actor looks.
+    pub facing: Vector3<f32>,
+    /// Whether the actor knows about some target. The player is always aware.
+    pub is_aware: bool,
+    pub faction: Faction,
 }
 
 #[derive(Default, Visit)]
@@ -106,6 +138,10 @@ impl ActorContainer {
         self.pool.try_borrow(actor)
     }
 
+    pub fn try_get_mut(&mut self, actor: Handle<Actor>) -> Option<&mut Actor> {
+        self.pool.try_borrow_mut(actor)
+    }
+
     pub fn contains(&self, actor: Handle<Actor>) -> bool {
         self.pool.is_valid_handle(actor)
     }
@@ -124,6 +160,15 @@ impl ActorContainer {
         self.pool.free(actor_handle);
     }
 
+    /// Checks whether the attacker can damage the victim according to their factions. Damage
+    /// from the environment and self-inflicted damage is always allowed.
+    pub fn can_damage(&self, attacker: Handle<Actor>, victim: Handle<Actor>) -> bool {
+        match (self.try_get(attacker), self.try_get(victim)) {
+            (Some(a), Some(v)) if attacker != victim => a.faction.can_damage(v.faction),
+            _ => true,
+        }
+    }
+
     pub fn count(&self) -> u32 {
         self.pool.alive_count()
     }
@@ -136,19 +181,42 @@ impl ActorContainer {
                     handle,
                     health: actor.health,
                     position: actor.position(&context.scene.graph),
+                    velocity: actor.velocity(&context.scene.graph),
                     kind: match actor {
                         Actor::Bot(bot) => TargetKind::Bot(bot.kind),
                         Actor::Player(_) => TargetKind::Player,
                     },
+                    capsule_collider: actor.capsule_collider,
+                    hit_boxes: actor.hit_boxes.iter().map(|h| h.collider).collect(),
+                    laser_sight: context
+                        .weapons
+                        .try_get(actor.current_weapon())
+                        .map_or(false, |weapon| {
+                            weapon.laser_sight().is_visible(&context.scene.graph)
+                        }),
+                    facing: match actor {
+                        Actor::Bot(bot) => bot.facing(&context.scene.graph),
+                        Actor::Player(player) => context.scene.graph[player.body].look_vector(),
+                    },
+                    is_aware: match actor {
+                        Actor::Bot(bot) => bot.is_aware(),
+                        Actor::Player(_) => true,
+                    },
+                    faction: actor.faction,
                 });
             }
         }
 
         for (handle, actor) in self.pool.pair_iter_mut() {
+            actor.update_status_effects(handle, context.sender, context.time.delta);
+            actor.update_pain_sound_timeout(context.time.delta);
+
             match actor {
                 Actor::Bot(bot) => bot.update(handle, context, &self.target_descriptors),
-                Actor::Player(player) => player.update(handle, context),
+                Actor::Player(player) => player.update(handle, context, &self.target_descriptors),
             }
+
+            actor.update_knockback(&mut context.scene.graph, context.time.delta);
         }
     }
 
@@ -0,0 +1,88 @@
+use crate::bot::behavior::{movement::move_to, BehaviorContext};
+use fyrox::{
+    core::{algebra::Vector3, visitor::prelude::*},
+    utils::behavior::{Behavior, Status},
+};
+
+/// Time (in seconds) between attempts to find a cover when there is no suitable one nearby.
+const COVER_SEARCH_INTERVAL: f32 = 1.0;
+/// Cover point is considered occupied if some other actor is closer than this to it.
+const OCCUPIED_RADIUS: f32 = 0.75;
+
+/// Succeeds if the bot is armed and being suppressed by fire.
+#[derive(Default, Debug, PartialEq, Visit)]
+pub struct NeedsCover;
+
+impl<'a> Behavior<'a> for NeedsCover {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, context: &mut Self::Context) -> Status {
+        if context.suppression > 0.0
+            && context.target.is_some()
+            && context.definition.ranged_attack.is_some()
+            && !context.character.weapons.is_empty()
+        {
+            Status::Success
+        } else {
+            Status::Failure
+        }
+    }
+}
+
+/// Moves the bot to the closest cover point that hides it from the last known position of its
+/// target and keeps it there while it is suppressed. Fails if there is no suitable cover nearby.
+#[derive(Default, Debug, PartialEq, Visit)]
+pub struct TakeCover {
+    cover: Option<Vector3<f32>>,
+    search_timeout: f32,
+}
+
+impl<'a> Behavior<'a> for TakeCover {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, context: &mut Self::Context) -> Status {
+        let threat = match context.target.as_ref() {
+            Some(target) => target.position,
+            None => return Status::Failure,
+        };
+
+        let graph = &context.scene.graph;
+
+        // Target could move, so the cover may not hide from it anymore.
+        if let Some(cover) = self.cover {
+            if !context.cover_points.hides_from(cover, threat, graph) {
+                self.cover = None;
+            }
+        }
+
+        if self.cover.is_none() {
+            self.search_timeout -= context.time.delta;
+            if self.search_timeout > 0.0 {
+                return Status::Failure;
+            }
+            self.search_timeout = COVER_SEARCH_INTERVAL;
+
+            let bot_handle = context.bot_handle;
+            self.cover = context.cover_points.find_cover(
+                context.character.position(graph),
+                threat,
+                context
+                    .targets
+                    .iter()
+                    .filter(|desc| desc.handle != bot_handle)
+                    .map(|desc| desc.position),
+                OCCUPIED_RADIUS,
+                graph,
+            );
+        }
+
+        match self.cover {
+            Some(cover) => {
+                // Stay in cover while suppressed, the bot will peek out to shoot after that.
+                move_to(context, Some(cover), 0.3);
+                Status::Running
+            }
+            None => Status::Failure,
+        }
+    }
+}
@@ -42,15 +42,19 @@ impl<'a> Behavior<'a> for StayDead {
     type Context = BehaviorContext<'a>;
 
     fn tick(&mut self, context: &mut Self::Context) -> Status {
-        for &animation in &[
-            context.upper_body_machine.dying_animation,
-            context.lower_body_machine.dying_animation,
-        ] {
-            context
-                .scene
-                .animations
-                .get_mut(animation)
-                .set_enabled(true);
+        if context.definition.ragdoll {
+            context.character.activate_ragdoll(&mut context.scene.graph);
+        } else {
+            for &animation in &[
+                context.upper_body_machine.dying_animation,
+                context.lower_body_machine.dying_animation,
+            ] {
+                context
+                    .scene
+                    .animations
+                    .get_mut(animation)
+                    .set_enabled(true);
+            }
         }
 
         for &animation in context.upper_body_machine.attack_animations.iter() {
@@ -70,6 +74,19 @@ impl<'a> Behavior<'a> for StayDead {
                 })
             }
 
+            if context.character.ragdoll.is_active() {
+                // Keep the model, it is driven by the ragdoll from now on.
+                let graph = &mut context.scene.graph;
+                let pivot = context.character.pivot;
+                let position = graph[pivot].global_position();
+                let rotation = graph.global_rotation(pivot);
+                graph.unlink_nodes(pivot);
+                graph[pivot]
+                    .local_transform_mut()
+                    .set_position(position)
+                    .set_rotation(rotation);
+            }
+
             // TODO
             context.scene.remove_node(context.character.body);
             context.character.body = Default::default();
@@ -1,35 +1,157 @@
 use crate::{
-    actor::TargetKind,
-    bot::{behavior::BehaviorContext, BotHostility, Target},
+    actor::TargetDescriptor,
+    bot::{behavior::BehaviorContext, AwarenessState, Target},
 };
 use fyrox::scene::collider::{ColliderShape, InteractionGroups};
-use fyrox::scene::graph::physics::RayCastOptions;
+use fyrox::scene::graph::physics::{Intersection, RayCastOptions};
 use fyrox::{
     core::{
-        algebra::{Matrix4, Point3, Vector3},
-        math::{frustum::Frustum, ray::Ray},
-        pool::Handle,
+        algebra::{Point3, Vector3},
+        math::ray::Ray,
+        rand::Rng,
         visitor::prelude::*,
     },
-    scene::{graph::Graph, node::Node},
+    rand,
     utils::behavior::{Behavior, Status},
 };
 
+/// Distance at which bots "feel" targets even if they cannot see them.
+const CLOSE_RANGE: f32 = 1.6;
+/// Time (in seconds) after which bot stops chasing a target it cannot see and starts searching.
+const LOSE_SIGHT_TIMEOUT: f32 = 5.0;
+/// Time (in seconds) that bot spends searching for a lost target before giving up.
+const SEARCH_TIMEOUT: f32 = 10.0;
+/// Chance (per second) to notice a laser beam of a target that bot cannot see directly.
+const LASER_NOTICE_CHANCE: f32 = 0.15;
+/// Time (in seconds) that an unaware bot needs to recognize a target at the edge of its vision
+/// range, closer targets are recognized faster.
+const RECOGNITION_TIME: f32 = 2.0;
+/// Minimal fraction of the recognition time, so even close targets are not recognized instantly
+/// (unless they are closer than `CLOSE_RANGE`).
+const MIN_RECOGNITION_FACTOR: f32 = 0.2;
+/// How fast (per second) suspicion decays without any stimulus.
+const SUSPICION_DECAY: f32 = 0.1;
+
 #[derive(Default, Debug, PartialEq, Visit)]
-pub struct FindTarget {
-    frustum: Frustum,
-}
+pub struct FindTarget;
 
 impl FindTarget {
-    fn update_frustum(&mut self, position: Vector3<f32>, graph: &Graph, model: Handle<Node>) {
-        let head_pos = position + Vector3::new(0.0, 0.4, 0.0);
-        let up = graph[model].up_vector();
-        let look_at = head_pos + graph[model].look_vector();
-        let view_matrix = Matrix4::look_at_rh(&Point3::from(head_pos), &Point3::from(look_at), &up);
-        let projection_matrix =
-            Matrix4::new_perspective(16.0 / 9.0, 90.0f32.to_radians(), 0.1, 20.0);
-        let view_projection_matrix = projection_matrix * view_matrix;
-        self.frustum = Frustum::from(view_projection_matrix).unwrap();
+    fn can_see(
+        &self,
+        context: &BehaviorContext,
+        position: Vector3<f32>,
+        desc: &TargetDescriptor,
+        query_buffer: &mut Vec<Intersection>,
+    ) -> bool {
+        let eye_position = position + Vector3::new(0.0, 0.4, 0.0);
+
+        let to_target = desc.position - eye_position;
+        let distance = to_target.norm();
+        if distance > context.definition.vision_range {
+            return false;
+        }
+
+        // Smoke blocks vision.
+        if context
+            .smoke_clouds
+            .is_line_of_sight_blocked(eye_position, desc.position)
+        {
+            return false;
+        }
+
+        if distance >= CLOSE_RANGE {
+            let look_vector = context.scene.graph[context.model].look_vector();
+            let angle = look_vector
+                .try_normalize(f32::EPSILON)
+                .zip(to_target.try_normalize(f32::EPSILON))
+                .map_or(0.0, |(a, b)| a.dot(&b).clamp(-1.0, 1.0).acos());
+            if angle > context.definition.vision_angle.to_radians() * 0.5 {
+                return false;
+            }
+        }
+
+        // Target is visible if at least one of its hit boxes is not occluded.
+        let mut points = desc
+            .hit_boxes
+            .iter()
+            .filter_map(|&hit_box| context.scene.graph.try_get(hit_box))
+            .map(|hit_box| hit_box.global_position())
+            .collect::<Vec<_>>();
+        if points.is_empty() {
+            points.push(desc.position);
+        }
+
+        'point_loop: for point in points {
+            let ray = Ray::from_two_points(eye_position, point);
+            context.scene.graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::from(ray.origin),
+                    ray_direction: ray.dir,
+                    groups: InteractionGroups::default(),
+                    max_len: ray.dir.norm(),
+                    sort_results: true,
+                },
+                query_buffer,
+            );
+
+            for hit in query_buffer.iter() {
+                if hit.collider == desc.capsule_collider || desc.hit_boxes.contains(&hit.collider) {
+                    return true;
+                }
+
+                if context
+                    .character
+                    .hit_boxes
+                    .iter()
+                    .any(|h| h.collider == hit.collider)
+                {
+                    continue;
+                }
+
+                let collider = context.scene.graph[hit.collider].as_collider();
+                if !matches!(collider.shape(), ColliderShape::Capsule(_)) {
+                    // Point is behind something.
+                    continue 'point_loop;
+                }
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Idle bots have a small chance to notice a beam of a laser sight of a hostile target
+    /// within vision range.
+    fn notice_laser_sight(
+        &self,
+        context: &BehaviorContext,
+        position: Vector3<f32>,
+    ) -> Option<Target> {
+        if context.awareness.state != AwarenessState::Idle {
+            return None;
+        }
+
+        let chance = (LASER_NOTICE_CHANCE * context.time.delta).min(1.0);
+
+        context
+            .targets
+            .iter()
+            .filter(|desc| {
+                desc.handle != context.bot_handle
+                    && desc.laser_sight
+                    && !context.character.faction.is_friend_of(desc.faction)
+                    && context
+                        .definition
+                        .hostility
+                        .is_hostile_to(context.kind, &desc.kind)
+                    && position.metric_distance(&desc.position) <= context.definition.vision_range
+            })
+            .find(|_| rand::thread_rng().gen_bool(chance as f64))
+            .map(|desc| Target {
+                position: desc.position,
+                handle: desc.handle,
+            })
     }
 }
 
@@ -38,90 +160,121 @@ impl<'a> Behavior<'a> for FindTarget {
 
     fn tick(&mut self, context: &mut Self::Context) -> Status {
         let position = context.character.position(&context.scene.graph);
+        let targets = context.targets;
+        let bot_handle = context.bot_handle;
+        let mut query_buffer = Vec::default();
 
-        self.update_frustum(position, &context.scene.graph, context.model);
+        // Check if existing target is still valid.
+        if let Some(target) = context.target.clone() {
+            let desc = targets.iter().find(|desc| {
+                desc.handle != bot_handle && desc.handle == target.handle && desc.health > 0.0
+            });
 
-        // Check if existing target is valid.
-        if let Some(target) = context.target {
-            for target_desc in context.targets {
-                if target_desc.handle != context.bot_handle
-                    && target_desc.handle == target.handle
-                    && target_desc.health > 0.0
-                {
-                    target.position = target_desc.position;
+            if let Some(desc) = desc {
+                if self.can_see(context, position, desc, &mut query_buffer) {
+                    context.target.as_mut().unwrap().position = desc.position;
+                    context.awareness.set_state(AwarenessState::Aggro);
+                    context.awareness.timer = 0.0;
                     return Status::Success;
                 }
+            } else {
+                *context.target = None;
+                context.awareness.set_state(AwarenessState::Idle);
             }
         }
 
-        let mut closest_distance = f32::MAX;
+        if context.awareness.state != AwarenessState::Idle {
+            context.awareness.timer += context.time.delta;
+        }
 
-        let bot_handle = context.bot_handle;
-        let mut query_buffer = Vec::default();
-        'target_loop: for desc in context
-            .targets
-            .iter()
-            .filter(|desc| desc.handle != bot_handle)
-        {
-            match context.definition.hostility {
-                BotHostility::OtherSpecies => {
-                    if let TargetKind::Bot(kind) = desc.kind {
-                        if kind == context.kind {
-                            continue 'target_loop;
-                        }
-                    }
+        match context.awareness.state {
+            AwarenessState::Aggro if context.target.is_some() => {
+                if context.awareness.timer < LOSE_SIGHT_TIMEOUT {
+                    // Keep chasing last known position of the target.
+                    return Status::Success;
                 }
-                BotHostility::Player => {
-                    if let TargetKind::Bot(_) = desc.kind {
-                        continue 'target_loop;
-                    }
+                context.awareness.set_state(AwarenessState::Search);
+            }
+            AwarenessState::Search => {
+                if context.awareness.timer >= SEARCH_TIMEOUT {
+                    *context.target = None;
+                    context.awareness.set_state(AwarenessState::Idle);
                 }
-                BotHostility::Everyone => {}
+            }
+            _ => (),
+        }
+
+        let mut closest_distance = f32::MAX;
+        let mut closest = None;
+
+        let faction = context.character.faction;
+        for desc in targets.iter().filter(|desc| desc.handle != bot_handle) {
+            if faction.is_friend_of(desc.faction)
+                || !context
+                    .definition
+                    .hostility
+                    .is_hostile_to(context.kind, &desc.kind)
+            {
+                continue;
             }
 
             let distance = position.metric_distance(&desc.position);
-            if distance != 0.0 && distance < 1.6 || self.frustum.is_contains_point(desc.position) {
-                let ray = Ray::from_two_points(desc.position, position);
-                context.scene.graph.physics.cast_ray(
-                    RayCastOptions {
-                        ray_origin: Point3::from(ray.origin),
-                        ray_direction: ray.dir,
-                        groups: InteractionGroups::default(),
-                        max_len: ray.dir.norm(),
-                        sort_results: true,
-                    },
-                    &mut query_buffer,
-                );
-
-                'hit_loop: for hit in query_buffer.iter() {
-                    let collider = context.scene.graph[hit.collider].as_collider();
-
-                    if let ColliderShape::Capsule(_) = collider.shape() {
-                        // Prevent setting self as target.
-                        if context.character.capsule_collider == hit.collider {
-                            continue 'hit_loop;
-                        }
-                    } else {
-                        // Target is behind something.
-                        continue 'target_loop;
-                    }
-                }
+            if distance < closest_distance
+                && self.can_see(context, position, desc, &mut query_buffer)
+            {
+                closest = Some(Target {
+                    position: desc.position,
+                    handle: desc.handle,
+                });
+                closest_distance = distance;
+            }
+        }
 
-                if distance < closest_distance {
-                    *context.target = Some(Target {
-                        position: desc.position,
-                        handle: desc.handle,
-                    });
-                    closest_distance = distance;
+        if let Some(closest) = closest {
+            if context.awareness.state == AwarenessState::Idle && closest_distance >= CLOSE_RANGE {
+                // Unaware bot becomes suspicious first, it needs some time to recognize the target.
+                let factor = (closest_distance / context.definition.vision_range)
+                    .max(MIN_RECOGNITION_FACTOR);
+                context.awareness.suspicion += context.time.delta / (RECOGNITION_TIME * factor);
+                if context.awareness.suspicion < 1.0 {
+                    return Status::Failure;
                 }
             }
+
+            *context.target = Some(closest);
+            context.awareness.set_state(AwarenessState::Aggro);
+            return Status::Success;
+        }
+
+        if context.awareness.state == AwarenessState::Idle {
+            context.awareness.suspicion =
+                (context.awareness.suspicion - SUSPICION_DECAY * context.time.delta).max(0.0);
+        }
+
+        if let Some(noticed) = self.notice_laser_sight(context, position) {
+            // Bot does not know where the target is exactly, so it goes to check the position.
+            *context.target = Some(noticed);
+            context.awareness.set_state(AwarenessState::Search);
+            Status::Failure
+        } else {
+            // Let search or patrol branches do their job.
+            Status::Failure
         }
+    }
+}
+
+/// Succeeds if the bot has lost its target and should check the last known position of it.
+#[derive(Default, Debug, PartialEq, Visit)]
+pub struct IsSearching;
+
+impl<'a> Behavior<'a> for IsSearching {
+    type Context = BehaviorContext<'a>;
 
-        if context.target.is_some() {
+    fn tick(&mut self, context: &mut Self::Context) -> Status {
+        if context.awareness.state == AwarenessState::Search && context.target.is_some() {
             Status::Success
         } else {
-            // Keep looking.
-            Status::Running
+            Status::Failure
         }
     }
 }
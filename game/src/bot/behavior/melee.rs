@@ -50,10 +50,26 @@ impl<'a> Behavior<'a> for DoMeleeAttack {
                 .rewind();
 
             context.is_attacking = true;
+
+            // Telegraph the attack, so the target has a chance to dodge it during the windup.
+            if let Some(attack_sound) = context
+                .definition
+                .attack_sounds
+                .iter()
+                .choose(&mut fyrox::rand::thread_rng())
+            {
+                context.sender.send(Message::PlaySound {
+                    path: attack_sound.clone().into(),
+                    position: context.character.position(&context.scene.graph),
+                    gain: 1.0,
+                    rolloff_factor: 1.0,
+                    radius: 1.0,
+                });
+            }
         }
 
         if self.attack_timeout < 0.0 && attack_animation_ended {
-            self.attack_timeout = 0.3;
+            self.attack_timeout = context.definition.attack_cooldown;
         }
         self.attack_timeout -= context.time.delta;
 
@@ -61,6 +77,15 @@ impl<'a> Behavior<'a> for DoMeleeAttack {
 
         // Apply damage to target from melee attack
         if let Some(target) = context.target.as_ref() {
+            let self_position = context.character.position(&context.scene.graph);
+            let target_position = context
+                .targets
+                .iter()
+                .find(|desc| desc.handle == target.handle)
+                .map_or(target.position, |desc| desc.position);
+            let in_range =
+                self_position.metric_distance(&target_position) <= context.definition.melee_range;
+
             while let Some(event) = context
                 .scene
                 .animations
@@ -68,32 +93,27 @@ impl<'a> Behavior<'a> for DoMeleeAttack {
                 .pop_event()
             {
                 if event.signal_id == UpperBodyMachine::HIT_SIGNAL
+                    && in_range
                     && !can_shoot(context.upper_body_machine, context.definition)
                 {
+                    let attack =
+                        &context.definition.attack_animations[self.attack_animation_index as usize];
+
                     context.sender.send(Message::DamageActor {
                         actor: target.handle,
-                        who: Default::default(),
+                        who: context.bot_handle,
+                        weapon: Default::default(),
                         hitbox: None,
                         /// TODO: Find hit box maybe?
-                        amount: context.definition.attack_animations
-                            [self.attack_animation_index as usize]
-                            .damage
-                            .amount(),
+                        amount: attack.damage.amount(),
                         critical_shot_probability: 0.0,
                     });
 
-                    if let Some(attack_sound) = context
-                        .definition
-                        .attack_sounds
-                        .iter()
-                        .choose(&mut fyrox::rand::thread_rng())
-                    {
-                        context.sender.send(Message::PlaySound {
-                            path: attack_sound.clone().into(),
-                            position: context.character.position(&context.scene.graph),
-                            gain: 1.0,
-                            rolloff_factor: 1.0,
-                            radius: 1.0,
+                    if let Some(effect) = attack.status_effect {
+                        context.sender.send(Message::ApplyStatusEffect {
+                            actor: target.handle,
+                            who: context.bot_handle,
+                            effect,
                         });
                     }
                 }
@@ -4,17 +4,22 @@ use crate::{
     bot::{
         behavior::{
             aim::AimOnTarget,
+            cover::{NeedsCover, TakeCover},
             death::{IsDead, StayDead},
-            find::FindTarget,
+            find::{FindTarget, IsSearching},
             melee::{CanMeleeAttack, DoMeleeAttack},
             movement::MoveToTarget,
+            patrol::FollowPatrolRoute,
             shoot::{CanShootTarget, ShootTarget},
         },
+        cover::CoverPointContainer,
         lower_body::LowerBodyMachine,
+        patrol::Patrol,
         upper_body::UpperBodyMachine,
-        BotDefinition, BotKind, Target,
+        Awareness, BotDefinition, BotKind, Target,
     },
     character::Character,
+    level::smoke::SmokeCloudContainer,
     utils::BodyImpactHandler,
     weapon::WeaponContainer,
     GameTime, MessageSender,
@@ -34,10 +39,12 @@ use fyrox::{
 };
 
 pub mod aim;
+pub mod cover;
 pub mod death;
 pub mod find;
 pub mod melee;
 pub mod movement;
+pub mod patrol;
 pub mod shoot;
 pub mod threat;
 
@@ -47,6 +54,7 @@ pub enum Action {
     IsDead(IsDead),
     StayDead(StayDead),
     FindTarget(FindTarget),
+    IsSearching(IsSearching),
     MoveToTarget(MoveToTarget),
     CanMeleeAttack(CanMeleeAttack),
     AimOnTarget(AimOnTarget),
@@ -55,6 +63,9 @@ pub enum Action {
     ShootTarget(ShootTarget),
     NeedsThreatenTarget(NeedsThreatenTarget),
     ThreatenTarget(ThreatenTarget),
+    FollowPatrolRoute(FollowPatrolRoute),
+    NeedsCover(NeedsCover),
+    TakeCover(TakeCover),
 }
 
 impl Default for Action {
@@ -70,6 +81,7 @@ impl<'a> Behavior<'a> for Action {
         match self {
             Action::Unknown => unreachable!(),
             Action::FindTarget(v) => v.tick(context),
+            Action::IsSearching(v) => v.tick(context),
             Action::MoveToTarget(v) => v.tick(context),
             Action::DoMeleeAttack(v) => v.tick(context),
             Action::ShootTarget(v) => v.tick(context),
@@ -80,6 +92,9 @@ impl<'a> Behavior<'a> for Action {
             Action::CanShootTarget(v) => v.tick(context),
             Action::NeedsThreatenTarget(v) => v.tick(context),
             Action::ThreatenTarget(v) => v.tick(context),
+            Action::FollowPatrolRoute(v) => v.tick(context),
+            Action::NeedsCover(v) => v.tick(context),
+            Action::TakeCover(v) => v.tick(context),
         }
     }
 }
@@ -107,6 +122,11 @@ pub struct BehaviorContext<'a> {
     pub move_speed: f32,
     pub target_move_speed: &'a mut f32,
     pub threaten_timeout: &'a mut f32,
+    pub awareness: &'a mut Awareness,
+    pub patrol: &'a mut Patrol,
+    pub smoke_clouds: &'a SmokeCloudContainer,
+    pub cover_points: &'a CoverPointContainer,
+    pub suppression: f32,
 
     // Output
     pub attack_animation_index: usize,
@@ -145,6 +165,12 @@ impl BotBehavior {
                                     .add_to(&mut tree),
                             ])
                             .add_to(&mut tree),
+                            CompositeNode::new_sequence(vec![
+                                LeafNode::new(Action::NeedsCover(NeedsCover)).add_to(&mut tree),
+                                LeafNode::new(Action::TakeCover(TakeCover::default()))
+                                    .add_to(&mut tree),
+                            ])
+                            .add_to(&mut tree),
                             CompositeNode::new_sequence(vec![
                                 LeafNode::new(Action::CanShootTarget(CanShootTarget))
                                     .add_to(&mut tree),
@@ -173,6 +199,17 @@ impl BotBehavior {
                 .add_to(&mut tree),
             ])
             .add_to(&mut tree),
+            // Target was lost, check its last known position.
+            CompositeNode::new_sequence(vec![
+                LeafNode::new(Action::IsSearching(IsSearching)).add_to(&mut tree),
+                LeafNode::new(AimOnTarget::new(spine)).add_to(&mut tree),
+                LeafNode::new(Action::MoveToTarget(MoveToTarget { min_distance: 1.0 }))
+                    .add_to(&mut tree),
+            ])
+            .add_to(&mut tree),
+            // Nothing to do, walk the patrol route (if any).
+            LeafNode::new(Action::FollowPatrolRoute(FollowPatrolRoute::default()))
+                .add_to(&mut tree),
         ])
         .add_to(&mut tree);
 
@@ -38,69 +38,89 @@ fn calculate_movement_speed_factor(
     k
 }
 
-impl<'a> Behavior<'a> for MoveToTarget {
-    type Context = BehaviorContext<'a>;
-
-    fn tick(&mut self, context: &mut Self::Context) -> Status {
-        context.movement_speed_factor = calculate_movement_speed_factor(
-            context.upper_body_machine,
-            &context.character.hit_boxes,
-            context.impact_handler,
-            context.scene,
-        );
+/// Moves the bot towards given destination using navmesh. Returns `Status::Success` when the bot
+/// is close enough to the destination.
+pub fn move_to(
+    context: &mut BehaviorContext,
+    destination: Option<Vector3<f32>>,
+    min_distance: f32,
+) -> Status {
+    context.movement_speed_factor = calculate_movement_speed_factor(
+        context.upper_body_machine,
+        &context.character.hit_boxes,
+        context.impact_handler,
+        context.scene,
+    );
 
-        let body = context.scene.graph[context.character.body].as_rigid_body_mut();
-        let position = body.global_position();
+    let body = context.scene.graph[context.character.body].as_rigid_body_mut();
+    let position = body.global_position();
 
-        *context.target_move_speed = context.definition.walk_speed * context.movement_speed_factor;
+    *context.target_move_speed = context.definition.walk_speed * context.movement_speed_factor;
 
-        context.agent.set_speed(context.move_speed);
-        let navmesh = &mut context.scene.navmeshes[context.navmesh];
-        context.agent.set_position(position);
+    context.agent.set_speed(context.move_speed);
+    context.agent.set_position(position);
 
-        if let Some(target) = context.target.as_ref() {
-            context.agent.set_target(target.position);
+    if let Some(destination) = destination {
+        context.agent.set_target(destination);
+        if context.navmesh.is_some() {
+            let navmesh = &mut context.scene.navmeshes[context.navmesh];
             let _ = context.agent.update(context.time.delta, navmesh);
-        }
-
-        let has_reached_destination =
-            context.agent.target().metric_distance(&position) <= self.min_distance;
-        if has_reached_destination {
-            body.set_lin_vel(Vector3::new(0.0, body.lin_vel().y, 0.0));
         } else {
-            let mut vel = (context.agent.position() - position).scale(1.0 / context.time.delta);
-            vel.y = body.lin_vel().y;
-            body.set_lin_vel(vel);
+            // There is no navmesh on the level, the best we can do is to walk straight to the
+            // destination.
+            let to_destination = destination - position;
+            let step = (context.move_speed * context.time.delta).min(to_destination.norm());
+            if let Some(direction) = to_destination.try_normalize(f32::EPSILON) {
+                context.agent.set_position(position + direction.scale(step));
+            }
         }
+    }
+
+    let has_reached_destination = context.agent.target().metric_distance(&position) <= min_distance;
+    if has_reached_destination {
+        body.set_lin_vel(Vector3::new(0.0, body.lin_vel().y, 0.0));
+    } else {
+        let mut vel = (context.agent.position() - position).scale(1.0 / context.time.delta);
+        vel.y = body.lin_vel().y;
+        body.set_lin_vel(vel);
+    }
 
-        // Emit step sounds from walking animation.
-        if context.lower_body_machine.is_walking() {
-            while let Some(event) = context
-                .scene
-                .animations
-                .get_mut(context.lower_body_machine.walk_animation)
-                .pop_event()
-            {
-                if event.signal_id == LowerBodyMachine::STEP_SIGNAL {
-                    let begin = context.scene.graph[context.model].global_position()
-                        + Vector3::new(0.0, 0.5, 0.0);
+    // Emit step sounds from walking animation.
+    if context.lower_body_machine.is_walking() {
+        while let Some(event) = context
+            .scene
+            .animations
+            .get_mut(context.lower_body_machine.walk_animation)
+            .pop_event()
+        {
+            if event.signal_id == LowerBodyMachine::STEP_SIGNAL {
+                let begin = context.scene.graph[context.model].global_position()
+                    + Vector3::new(0.0, 0.5, 0.0);
 
-                    footstep_ray_check(
-                        begin,
-                        context.scene,
-                        context.character.capsule_collider,
-                        context.sender.clone(),
-                    );
-                }
+                footstep_ray_check(
+                    begin,
+                    context.scene,
+                    context.character.capsule_collider,
+                    context.sender.clone(),
+                );
             }
         }
+    }
 
-        if has_reached_destination {
-            context.is_moving = false;
-            Status::Success
-        } else {
-            context.is_moving = true;
-            Status::Running
-        }
+    if has_reached_destination {
+        context.is_moving = false;
+        Status::Success
+    } else {
+        context.is_moving = true;
+        Status::Running
+    }
+}
+
+impl<'a> Behavior<'a> for MoveToTarget {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, context: &mut Self::Context) -> Status {
+        let destination = context.target.as_ref().map(|t| t.position);
+        move_to(context, destination, self.min_distance)
     }
 }
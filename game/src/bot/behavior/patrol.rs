@@ -0,0 +1,64 @@
+use crate::bot::behavior::{movement::move_to, BehaviorContext};
+use fyrox::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        math::SmoothAngle,
+        visitor::prelude::*,
+    },
+    utils::behavior::{Behavior, Status},
+};
+
+#[derive(Debug, PartialEq, Visit)]
+pub struct FollowPatrolRoute {
+    yaw: SmoothAngle,
+}
+
+impl Default for FollowPatrolRoute {
+    fn default() -> Self {
+        Self {
+            yaw: SmoothAngle {
+                angle: f32::NAN, // Nan means undefined.
+                target: 0.0,
+                speed: 180.0f32.to_radians(),
+            },
+        }
+    }
+}
+
+impl<'a> Behavior<'a> for FollowPatrolRoute {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, context: &mut Self::Context) -> Status {
+        let waypoint = match context.patrol.current_point() {
+            Some(waypoint) => waypoint,
+            None => return Status::Failure,
+        };
+
+        if matches!(move_to(context, Some(waypoint), 0.5), Status::Success) {
+            context.patrol.wait(context.time.delta);
+        } else {
+            // Turn towards movement direction.
+            let body = context.scene.graph[context.character.body].as_rigid_body_mut();
+            let velocity = body.lin_vel();
+            let look_dir = Vector3::new(velocity.x, 0.0, velocity.z);
+            if look_dir.norm_squared() > f32::EPSILON {
+                if self.yaw.angle.is_nan() {
+                    let local_look = body.look_vector();
+                    self.yaw.angle = local_look.x.atan2(local_look.z);
+                }
+
+                self.yaw
+                    .set_target(look_dir.x.atan2(look_dir.z))
+                    .update(context.time.delta);
+
+                body.local_transform_mut()
+                    .set_rotation(UnitQuaternion::from_axis_angle(
+                        &Vector3::y_axis(),
+                        self.yaw.angle(),
+                    ));
+            }
+        }
+
+        Status::Running
+    }
+}
@@ -1,7 +1,12 @@
 use crate::item::ItemKind;
-use crate::{bot::behavior::BehaviorContext, message::Message};
+use crate::{
+    bot::behavior::BehaviorContext,
+    message::Message,
+    utils::deviate,
+    weapon::{definition::WeaponProjectile, projectile::Projectile},
+};
 use fyrox::{
-    core::visitor::prelude::*,
+    core::{algebra::Vector3, visitor::prelude::*},
     utils::behavior::{Behavior, Status},
 };
 
@@ -31,17 +36,38 @@ impl<'a> Behavior<'a> for ShootTarget {
                     .try_extract_exact_items(ItemKind::Ammo, ammo_per_shot)
                     == ammo_per_shot
                 {
+                    // Aim at the target directly, so shots could be blocked only by obstacles
+                    // between the bot and the target. Accuracy degrades with the distance and
+                    // the speed of the target. Skilled bots lead moving targets when firing
+                    // projectiles.
+                    let direction = context.target.as_ref().and_then(|target| {
+                        let ranged_attack = context.definition.ranged_attack.as_ref()?;
+                        let velocity = context
+                            .targets
+                            .iter()
+                            .find(|desc| desc.handle == target.handle)
+                            .map_or_else(Vector3::default, |desc| desc.velocity);
+                        let shot_position = weapon.shot_position(&context.scene.graph);
+                        let mut to_target = target.position - shot_position;
+                        if let WeaponProjectile::Projectile(kind) = weapon.definition.projectile {
+                            if let Some(speed) = Projectile::get_definition(kind).flight_speed() {
+                                to_target += ranged_attack.lead(to_target.norm(), velocity, speed);
+                            }
+                        }
+                        Some(deviate(
+                            to_target,
+                            ranged_attack.spread(to_target.norm(), velocity.norm()),
+                        ))
+                    });
+
                     context.sender.send(Message::ShootWeapon {
                         weapon: weapon_handle,
-                        direction: None,
+                        direction,
                     });
 
-                    context
-                        .v_recoil
-                        .set_target(weapon.definition.gen_v_recoil_angle());
-                    context
-                        .h_recoil
-                        .set_target(weapon.definition.gen_h_recoil_angle());
+                    let (v_recoil, h_recoil) = weapon.gen_recoil_angles(context.time);
+                    context.v_recoil.set_target(v_recoil);
+                    context.h_recoil.set_target(h_recoil);
 
                     return Status::Success;
                 } else {
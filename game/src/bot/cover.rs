@@ -0,0 +1,168 @@
+//! Cover points for bots. A cover point is defined in the editor by assigning `CoverPoint` script
+//! to a node placed behind an obstacle (a crate, a wall corner, etc.) at the floor level. Armed
+//! bots hide at cover points when they are under fire.
+
+use crate::{current_level_mut, GameConstructor};
+use fyrox::{
+    core::{
+        algebra::{Point3, Vector3},
+        inspect::prelude::*,
+        math::ray::Ray,
+        pool::Handle,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        collider::{ColliderShape, InteractionGroups},
+        graph::{physics::RayCastOptions, Graph},
+        node::{Node, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+
+/// Height of eyes of a bot crouching behind a cover.
+const COVER_EYE_HEIGHT: f32 = 0.6;
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct CoverPoint {
+    #[inspect(
+        description = "Maximum distance from which bots will run to the cover point.",
+        min_value = "0.0"
+    )]
+    reach_distance: InheritableVariable<f32>,
+}
+
+impl Default for CoverPoint {
+    fn default() -> Self {
+        Self {
+            reach_distance: InheritableVariable::new(8.0),
+        }
+    }
+}
+
+impl_component_provider!(CoverPoint);
+
+impl TypeUuidProvider for CoverPoint {
+    fn type_uuid() -> Uuid {
+        uuid!("b5e0d7a4-2c9f-4f61-8e3a-6d7c1f9a0b52")
+    }
+}
+
+impl ScriptTrait for CoverPoint {
+    fn on_init(&mut self, context: ScriptContext) {
+        current_level_mut(context.plugin)
+            .cover_points
+            .add(context.handle);
+    }
+
+    fn on_deinit(&mut self, context: ScriptDeinitContext) {
+        current_level_mut(context.plugin)
+            .cover_points
+            .remove(context.node_handle);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+pub fn cover_point_ref(handle: Handle<Node>, graph: &Graph) -> &CoverPoint {
+    graph[handle]
+        .script()
+        .and_then(|s| s.cast::<CoverPoint>())
+        .unwrap()
+}
+
+#[derive(Default, Visit)]
+pub struct CoverPointContainer {
+    points: Vec<Handle<Node>>,
+}
+
+/// Checks whether something (except actors) blocks line of sight between two points.
+fn is_line_of_sight_blocked(graph: &Graph, from: Vector3<f32>, to: Vector3<f32>) -> bool {
+    let ray = Ray::from_two_points(from, to);
+    let mut query_buffer = Vec::new();
+    graph.physics.cast_ray(
+        RayCastOptions {
+            ray_origin: Point3::from(ray.origin),
+            ray_direction: ray.dir,
+            groups: InteractionGroups::default(),
+            max_len: ray.dir.norm(),
+            sort_results: false,
+        },
+        &mut query_buffer,
+    );
+
+    // Capsules belong to actors, they can't be used as cover.
+    query_buffer.iter().any(|hit| {
+        !matches!(
+            graph[hit.collider].as_collider().shape(),
+            ColliderShape::Capsule(_)
+        )
+    })
+}
+
+impl CoverPointContainer {
+    pub fn add(&mut self, point: Handle<Node>) {
+        if !self.points.contains(&point) {
+            self.points.push(point);
+        }
+    }
+
+    pub fn remove(&mut self, point: Handle<Node>) {
+        if let Some(position) = self.points.iter().position(|p| *p == point) {
+            self.points.remove(position);
+        }
+    }
+
+    /// Checks whether a bot at given cover position is hidden from a threat at given position.
+    pub fn hides_from(&self, cover: Vector3<f32>, threat: Vector3<f32>, graph: &Graph) -> bool {
+        is_line_of_sight_blocked(
+            graph,
+            threat,
+            cover + Vector3::new(0.0, COVER_EYE_HEIGHT, 0.0),
+        )
+    }
+
+    /// Returns position of the closest reachable cover point that hides from a threat at given
+    /// position. Cover points that are closer than `occupied_radius` to any of `occupants` are
+    /// skipped.
+    pub fn find_cover(
+        &self,
+        position: Vector3<f32>,
+        threat: Vector3<f32>,
+        occupants: impl Iterator<Item = Vector3<f32>> + Clone,
+        occupied_radius: f32,
+        graph: &Graph,
+    ) -> Option<Vector3<f32>> {
+        self.points
+            .iter()
+            .filter(|&&point| graph.is_valid_handle(point))
+            .filter_map(|&point| {
+                let point_position = graph[point].global_position();
+                let reach_distance = *cover_point_ref(point, graph).reach_distance;
+                if point_position.metric_distance(&position) <= reach_distance {
+                    Some(point_position)
+                } else {
+                    None
+                }
+            })
+            .filter(|point| {
+                !occupants
+                    .clone()
+                    .any(|occupant| occupant.metric_distance(point) < occupied_radius)
+            })
+            .filter(|&point| self.hides_from(point, threat, graph))
+            .min_by(|a, b| {
+                a.metric_distance(&position)
+                    .total_cmp(&b.metric_distance(&position))
+            })
+    }
+}
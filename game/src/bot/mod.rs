@@ -1,24 +1,27 @@
 use crate::{
-    actor::{Actor, TargetDescriptor},
+    actor::{Actor, TargetDescriptor, TargetKind},
     bot::{
         behavior::{BehaviorContext, BotBehavior},
         lower_body::{LowerBodyMachine, LowerBodyMachineInput},
+        patrol::Patrol,
         upper_body::{UpperBodyMachine, UpperBodyMachineInput},
     },
-    character::{find_hit_boxes, Character},
-    door::{door_ref, DoorContainer},
+    character::{find_hit_boxes, Character, HitBox},
+    door::DoorContainer,
+    faction::Faction,
     inventory::{Inventory, ItemEntry},
     item::ItemKind,
     level::UpdateContext,
+    sound::NoiseKind,
+    status::StatusEffectDefinition,
     utils::BodyImpactHandler,
-    weapon::projectile::Damage,
+    weapon::{definition::WeaponKind, projectile::Damage},
     CollisionGroups, Message, MessageSender,
 };
 use fyrox::{
     animation::machine::{Machine, PoseNode},
     core::{
-        algebra::{Point3, UnitQuaternion, Vector3},
-        arrayvec::ArrayVec,
+        algebra::{UnitQuaternion, Vector3},
         color::Color,
         math::SmoothAngle,
         pool::Handle,
@@ -33,14 +36,11 @@ use fyrox::{
         base::BaseBuilder,
         collider::{BitMask, ColliderBuilder, ColliderShape, InteractionGroups},
         debug::SceneDrawingContext,
-        graph::{
-            physics::CoefficientCombineRule,
-            physics::{Intersection, RayCastOptions},
-            Graph,
-        },
+        graph::{physics::CoefficientCombineRule, Graph},
         node::Node,
         pivot::PivotBuilder,
-        rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+        rigidbody::{RigidBodyBuilder, RigidBodyType},
+        sprite::SpriteBuilder,
         transform::TransformBuilder,
         Scene,
     },
@@ -57,9 +57,23 @@ use std::{
 };
 
 mod behavior;
+pub mod cover;
 mod lower_body;
+pub mod patrol;
 mod upper_body;
 
+/// Upper limit of time (in seconds) that bot could be pinned down in cover.
+const MAX_SUPPRESSION: f32 = 6.0;
+/// Height of the alert indicator above the head of a bot.
+const ALERT_INDICATOR_OFFSET: f32 = 0.35;
+const ALERT_INDICATOR_SIZE: f32 = 0.06;
+/// A path is rebuilt when the destination moves further than this distance from the end of
+/// the current path, so bots keep following a moving target.
+const PATH_RECALCULATION_THRESHOLD: f32 = 1.0;
+/// Suspicion that an unaware bot gains from a single footstep, it starts investigating when the
+/// suspicion is full.
+const FOOTSTEP_SUSPICION: f32 = 0.4;
+
 #[derive(Deserialize, Copy, Clone, PartialEq, Eq, Hash, Debug, Visit)]
 #[repr(i32)]
 pub enum BotKind {
@@ -76,6 +90,13 @@ impl BotKind {
             BotKind::Zombie => "Zombie",
         }
     }
+
+    /// Finds a kind by its description, case is ignored.
+    pub fn from_description(description: &str) -> Option<Self> {
+        [BotKind::Mutant, BotKind::Parasite, BotKind::Zombie]
+            .into_iter()
+            .find(|kind| kind.description().eq_ignore_ascii_case(description))
+    }
 }
 
 #[derive(Deserialize, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash)]
@@ -86,12 +107,97 @@ pub enum BotHostility {
     Player = 2,
 }
 
+impl BotHostility {
+    /// Checks whether a bot of given kind should attack a target of given kind.
+    pub fn is_hostile_to(self, self_kind: BotKind, target: &TargetKind) -> bool {
+        match self {
+            BotHostility::Everyone => true,
+            BotHostility::OtherSpecies => {
+                !matches!(target, TargetKind::Bot(kind) if *kind == self_kind)
+            }
+            BotHostility::Player => matches!(target, TargetKind::Player),
+        }
+    }
+}
+
 #[derive(Debug, Visit, Default, Clone)]
 pub struct Target {
     position: Vector3<f32>,
     handle: Handle<Actor>,
 }
 
+#[derive(Debug, Visit, Copy, Clone, PartialEq, Eq)]
+pub enum AwarenessState {
+    /// Bot does not know about any target.
+    Idle,
+    /// Bot sees its target (or has seen it recently) and attacks it.
+    Aggro,
+    /// Bot has lost its target and checks the last known position of it.
+    Search,
+}
+
+impl Default for AwarenessState {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
+
+/// Alertness of a bot as it is shown to the player.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AlertLevel {
+    Unaware,
+    /// Bot has noticed something, but has not recognized a target yet or is calming down.
+    Suspicious,
+    /// Bot investigates a noise or searches for a lost target.
+    Alert,
+    Combat,
+}
+
+impl AlertLevel {
+    fn indicator_color(self) -> Color {
+        match self {
+            AlertLevel::Unaware => Color::TRANSPARENT,
+            AlertLevel::Suspicious => Color::from_rgba(255, 220, 0, 220),
+            AlertLevel::Alert => Color::from_rgba(255, 130, 0, 230),
+            AlertLevel::Combat => Color::from_rgba(255, 20, 20, 240),
+        }
+    }
+}
+
+#[derive(Debug, Visit, Default, Clone)]
+pub struct Awareness {
+    pub state: AwarenessState,
+    /// Time since the target was seen last time in aggro state, or time since the search
+    /// has started in search state.
+    pub timer: f32,
+    /// Grows while an idle bot sees a target, the target is recognized when it reaches 1.0. It
+    /// slowly decays without any stimulus.
+    #[visit(optional)]
+    pub suspicion: f32,
+}
+
+impl Awareness {
+    pub fn set_state(&mut self, state: AwarenessState) {
+        if self.state != state {
+            self.state = state;
+            self.timer = 0.0;
+            if state != AwarenessState::Idle {
+                // Bot stays suspicious for a while after it has calmed down.
+                self.suspicion = 1.0;
+            }
+        }
+    }
+
+    pub fn alert_level(&self) -> AlertLevel {
+        match self.state {
+            AwarenessState::Aggro => AlertLevel::Combat,
+            AwarenessState::Search => AlertLevel::Alert,
+            AwarenessState::Idle if self.suspicion > 0.0 => AlertLevel::Suspicious,
+            AwarenessState::Idle => AlertLevel::Unaware,
+        }
+    }
+}
+
 #[derive(Visit)]
 pub struct Bot {
     target: Option<Target>,
@@ -115,6 +221,19 @@ pub struct Bot {
     move_speed: f32,
     target_move_speed: f32,
     threaten_timeout: f32,
+    #[visit(optional)]
+    awareness: Awareness,
+    #[visit(optional)]
+    patrol: Patrol,
+    /// Bones of limbs that were torn off, they're kept in the hierarchy, but scaled down to zero.
+    #[visit(optional)]
+    destroyed_limbs: Vec<Handle<Node>>,
+    /// Time (in seconds) that armed bot will stay in cover, it grows when bullets land nearby.
+    #[visit(optional)]
+    suppression: f32,
+    /// A sprite over the head of the bot that shows its alert level.
+    #[visit(optional)]
+    alert_indicator: Handle<Node>,
 }
 
 impl Deref for Bot {
@@ -153,6 +272,11 @@ impl Default for Bot {
             move_speed: 0.0,
             target_move_speed: 0.0,
             threaten_timeout: 0.0,
+            awareness: Default::default(),
+            patrol: Default::default(),
+            destroyed_limbs: Default::default(),
+            suppression: 0.0,
+            alert_indicator: Default::default(),
         }
     }
 }
@@ -164,6 +288,61 @@ pub struct AttackAnimationDefinition {
     timestamp: f32,
     damage: Damage,
     speed: f32,
+    /// Status effect that is applied to the target on hit.
+    #[serde(default)]
+    status_effect: Option<StatusEffectDefinition>,
+}
+
+#[derive(Deserialize)]
+pub struct RangedAttackDefinition {
+    /// Weapon that armed bots are spawned with.
+    pub weapon: WeaponKind,
+    /// Maximum deviation (in degrees) of shots at a still target right in front of the bot.
+    pub spread: f32,
+    /// Additional spread (in degrees) per meter of distance to the target.
+    pub spread_per_meter: f32,
+    /// Additional spread (in degrees) per meter per second of speed of the target.
+    pub spread_per_target_speed: f32,
+    /// Upper limit of deviation (in degrees) of shots.
+    pub max_spread: f32,
+    /// How accurately the bot leads a moving target when firing projectiles: zero means that
+    /// the bot shoots at the current position of the target, one means that the bot shoots at
+    /// the predicted position of the target.
+    #[serde(default)]
+    pub lead_skill: f32,
+}
+
+impl RangedAttackDefinition {
+    /// Returns maximum deviation (in radians) of shots at a target at given distance moving
+    /// with given speed.
+    pub fn spread(&self, distance: f32, target_speed: f32) -> f32 {
+        (self.spread
+            + self.spread_per_meter * distance
+            + self.spread_per_target_speed * target_speed)
+            .min(self.max_spread)
+            .to_radians()
+    }
+
+    /// Returns offset of an aim point from a target moving with given velocity, so a projectile
+    /// with given speed would meet the target.
+    pub fn lead(
+        &self,
+        distance: f32,
+        target_velocity: Vector3<f32>,
+        projectile_speed: f32,
+    ) -> Vector3<f32> {
+        let flight_time = distance / projectile_speed.max(f32::EPSILON);
+        target_velocity.scale(flight_time * self.lead_skill.clamp(0.0, 1.0))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DismembermentDefinition {
+    /// Minimal damage of a single hit (with damage factor of a hit box applied) that tears off
+    /// a limb, as a fraction of the max health of the bot.
+    pub damage_fraction: f32,
+    /// Whether a killing blow to a limb tears it off regardless of the damage.
+    pub killing_blow: bool,
 }
 
 #[derive(Deserialize)]
@@ -182,11 +361,32 @@ pub struct BotDefinition {
     pub v_aim_angle_hack: f32,
     pub can_use_weapons: bool,
     pub close_combat_distance: f32,
+    /// Time (in seconds) between the end of a melee attack and the start of the next one.
+    pub attack_cooldown: f32,
+    /// Maximum distance to the target at the moment of the hit, the target dodges the attack if
+    /// it managed to get farther during the windup.
+    pub melee_range: f32,
     pub pain_sounds: Vec<String>,
+    #[serde(default)]
+    pub death_sounds: Vec<String>,
     pub scream_sounds: Vec<String>,
     pub idle_sounds: Vec<String>,
     pub attack_sounds: Vec<String>,
     pub hostility: BotHostility,
+    /// Bots never attack friends, see [`Faction`] for more info.
+    pub faction: Faction,
+    /// Whether the bot should turn into a ragdoll on death instead of playing dying animation.
+    pub ragdoll: bool,
+    /// Maximum distance at which the bot can see its targets.
+    pub vision_range: f32,
+    /// Full angle (in degrees) of the view cone of the bot.
+    pub vision_angle: f32,
+    /// Maximum distance at which the bot can hear noises (shots, footsteps, etc.).
+    pub hearing_radius: f32,
+    /// `None` means that limbs of the bot cannot be torn off.
+    pub dismemberment: Option<DismembermentDefinition>,
+    /// Weapon and accuracy of armed bots, `None` means that bots of this kind are never armed.
+    pub ranged_attack: Option<RangedAttackDefinition>,
 
     // Animations.
     pub idle_animation: String,
@@ -253,6 +453,11 @@ impl Bot {
             );
         }
 
+        let alert_indicator = SpriteBuilder::new(BaseBuilder::new().with_visibility(false))
+            .with_size(ALERT_INDICATOR_SIZE)
+            .with_texture(resource_manager.request_texture("data/particles/circle_05.png"))
+            .build(&mut scene.graph);
+
         let pivot;
         let capsule_collider;
         let body = RigidBodyBuilder::new(
@@ -277,8 +482,10 @@ impl Bot {
                         capsule_collider
                     },
                     {
-                        pivot = PivotBuilder::new(BaseBuilder::new().with_children(&[model]))
-                            .build(&mut scene.graph);
+                        pivot = PivotBuilder::new(
+                            BaseBuilder::new().with_children(&[model, alert_indicator]),
+                        )
+                        .build(&mut scene.graph);
                         pivot
                     },
                 ]),
@@ -349,6 +556,7 @@ impl Bot {
                 health: definition.health,
                 hit_boxes: find_hit_boxes(pivot, scene),
                 inventory: Inventory::from_inner(items),
+                faction: definition.faction,
                 ..Default::default()
             },
             hips,
@@ -361,63 +569,84 @@ impl Bot {
             agent: NavmeshAgentBuilder::new()
                 .with_position(position)
                 .with_speed(definition.walk_speed)
+                .with_recalculation_threshold(PATH_RECALCULATION_THRESHOLD)
                 .build(),
             behavior: BotBehavior::new(spine, definition),
+            alert_indicator,
             ..Default::default()
         }
     }
 
+    pub fn alert_level(&self) -> AlertLevel {
+        self.awareness.alert_level()
+    }
+
+    /// Shows the alert level over the head of the bot, the indicator of a suspicious bot fades
+    /// in and out together with its suspicion.
+    fn update_alert_indicator(&self, graph: &mut Graph) {
+        let level = if self.is_dead() {
+            AlertLevel::Unaware
+        } else {
+            self.alert_level()
+        };
+
+        let pivot_height = graph[self.pivot].global_position().y;
+        let head_height = self
+            .hit_boxes
+            .iter()
+            .find(|hit_box| hit_box.is_head)
+            .and_then(|hit_box| graph.try_get(hit_box.collider))
+            .map(|head| head.global_position().y - pivot_height);
+
+        if let Some(indicator) = graph.try_get_mut(self.alert_indicator) {
+            indicator.set_visibility(level != AlertLevel::Unaware);
+            if level == AlertLevel::Unaware {
+                return;
+            }
+
+            if let Some(head_height) = head_height {
+                indicator.local_transform_mut().set_position(Vector3::new(
+                    0.0,
+                    head_height + ALERT_INDICATOR_OFFSET,
+                    0.0,
+                ));
+            }
+
+            let mut color = level.indicator_color();
+            if level == AlertLevel::Suspicious {
+                let alpha = color.a as f32 * self.awareness.suspicion.clamp(0.2, 1.0);
+                color = color.with_new_alpha(alpha as u8);
+            }
+            indicator.as_sprite_mut().set_color(color);
+        }
+    }
+
+    /// Opens closed doors on the way of the bot, so it won't get stuck in front of them. The bot
+    /// follows its navmesh path or walks straight to its target when there is no path.
     fn check_doors(
-        &mut self,
+        &self,
         self_handle: Handle<Actor>,
         scene: &Scene,
         door_container: &DoorContainer,
         sender: &MessageSender,
     ) {
-        if let Some(target) = self.target.as_ref() {
-            let mut query_storage = ArrayVec::<Intersection, 64>::new();
-
-            let position = self.position(&scene.graph);
-            let ray_direction = target.position - position;
-
-            scene.graph.physics.cast_ray(
-                RayCastOptions {
-                    ray_origin: Point3::from(position),
-                    ray_direction,
-                    max_len: ray_direction.norm(),
-                    groups: Default::default(),
-                    sort_results: true,
-                },
-                &mut query_storage,
-            );
-
-            for intersection in query_storage {
-                for &door_handle in &door_container.doors {
-                    let door = door_ref(door_handle, &scene.graph);
-
-                    let close_enough = position.metric_distance(&door.initial_position()) < 1.25;
-                    if !close_enough {
-                        continue;
-                    }
-
-                    for &child in scene.graph[door_handle].children() {
-                        if let Some(rigid_body) = scene.graph[child].cast::<RigidBody>() {
-                            for &collider in rigid_body.children() {
-                                if collider == intersection.collider {
-                                    sender.send(Message::TryOpenDoor {
-                                        door: door_handle,
-                                        actor: self_handle,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        let mut path = vec![self.position(&scene.graph)];
+        if !self.agent.path().is_empty() {
+            path.extend_from_slice(self.agent.path());
+        } else if let Some(target) = self.target.as_ref() {
+            path.push(target.position);
+        } else {
+            return;
         }
+
+        door_container.check_path(&path, self_handle, &self.inventory, &scene.graph, sender);
     }
 
     pub fn can_be_removed(&self, scene: &Scene) -> bool {
+        if self.ragdoll.is_active() {
+            return self.ragdoll.is_settled();
+        }
+
         scene
             .animations
             .get(self.upper_body_machine.dying_animation)
@@ -440,6 +669,64 @@ impl Bot {
 
     pub fn set_target(&mut self, handle: Handle<Actor>, position: Vector3<f32>) {
         self.target = Some(Target { position, handle });
+        // Bot knows exactly where the attacker is.
+        self.awareness.set_state(AwarenessState::Aggro);
+    }
+
+    /// Returns `true` if the bot attacks or searches for given actor.
+    pub fn is_hunting(&self, actor: Handle<Actor>) -> bool {
+        self.awareness.state != AwarenessState::Idle
+            && self.target.as_ref().map_or(false, |t| t.handle == actor)
+    }
+
+    /// Returns `true` if the bot knows about some target, unaware bots can be taken down silently.
+    pub fn is_aware(&self) -> bool {
+        self.awareness.state != AwarenessState::Idle
+    }
+
+    /// Returns direction in which the bot looks.
+    pub fn facing(&self, graph: &Graph) -> Vector3<f32> {
+        graph[self.model].look_vector()
+    }
+
+    /// Makes the bot feel being under fire for given amount of time (in seconds).
+    pub fn suppress(&mut self, time: f32) {
+        self.suppression = (self.suppression + time).min(MAX_SUPPRESSION);
+    }
+
+    pub fn set_patrol(&mut self, patrol: Patrol) {
+        self.patrol = patrol;
+    }
+
+    /// Makes the bot investigate position of a noise made by given actor. A gunshot is investigated
+    /// right away, while footsteps only make an unaware bot suspicious at first.
+    pub fn hear_noise(&mut self, source: Handle<Actor>, position: Vector3<f32>, kind: NoiseKind) {
+        match self.awareness.state {
+            AwarenessState::Aggro => {
+                // Refine last known position of the current target.
+                if let Some(target) = self.target.as_mut() {
+                    if target.handle == source {
+                        target.position = position;
+                    }
+                }
+            }
+            AwarenessState::Idle | AwarenessState::Search => {
+                if self.awareness.state == AwarenessState::Idle && kind == NoiseKind::FootStep {
+                    self.awareness.suspicion =
+                        (self.awareness.suspicion + FOOTSTEP_SUSPICION).min(1.0);
+                    if self.awareness.suspicion < 1.0 {
+                        return;
+                    }
+                }
+
+                self.target = Some(Target {
+                    position,
+                    handle: source,
+                });
+                self.awareness.set_state(AwarenessState::Search);
+                self.awareness.timer = 0.0;
+            }
+        }
     }
 
     pub fn update(
@@ -471,6 +758,11 @@ impl Bot {
             target_move_speed: &mut self.target_move_speed,
             move_speed: self.move_speed,
             threaten_timeout: &mut self.threaten_timeout,
+            awareness: &mut self.awareness,
+            patrol: &mut self.patrol,
+            smoke_clouds: context.smoke_clouds,
+            cover_points: context.cover_points,
+            suppression: self.suppression,
 
             // Output
             attack_animation_index: 0,
@@ -493,48 +785,58 @@ impl Bot {
 
         drop(behavior_context);
 
+        self.update_alert_indicator(&mut context.scene.graph);
+
         self.restoration_time -= time.delta;
+        self.suppression = (self.suppression - time.delta).max(0.0);
         self.move_speed += (self.target_move_speed - self.move_speed) * 0.1;
         self.threaten_timeout -= time.delta;
 
         self.check_doors(self_handle, context.scene, context.doors, context.sender);
 
-        self.lower_body_machine.apply(
-            context.scene,
-            time.delta,
-            LowerBodyMachineInput {
-                walk: is_moving,
-                scream: is_screaming,
-                dead: self.is_dead(),
-                movement_speed_factor,
-            },
-        );
-
-        self.upper_body_machine.apply(
-            context.scene,
-            time,
-            UpperBodyMachineInput {
-                attack: is_attacking,
-                walk: is_moving,
-                scream: is_screaming,
-                dead: self.is_dead(),
-                aim: is_aiming,
-                attack_animation_index: attack_animation_index as u32,
-            },
-        );
-        self.impact_handler
-            .update_and_apply(time.delta, context.scene);
-
-        self.v_recoil.update(time.delta);
-        self.h_recoil.update(time.delta);
+        if self.ragdoll.is_active() {
+            // Animations must not fight with physics.
+            self.character
+                .ragdoll
+                .update(&mut context.scene.graph, time.delta);
+        } else {
+            self.lower_body_machine.apply(
+                context.scene,
+                time.delta,
+                LowerBodyMachineInput {
+                    walk: is_moving,
+                    scream: is_screaming,
+                    dead: self.is_dead(),
+                    movement_speed_factor,
+                },
+            );
 
-        let spine_transform = context.scene.graph[self.spine].local_transform_mut();
-        let rotation = **spine_transform.rotation();
-        spine_transform.set_rotation(
-            rotation
-                * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.v_recoil.angle())
-                * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.h_recoil.angle()),
-        );
+            self.upper_body_machine.apply(
+                context.scene,
+                time,
+                UpperBodyMachineInput {
+                    attack: is_attacking,
+                    walk: is_moving,
+                    scream: is_screaming,
+                    dead: self.is_dead(),
+                    aim: is_aiming,
+                    attack_animation_index: attack_animation_index as u32,
+                },
+            );
+            self.impact_handler
+                .update_and_apply(time.delta, context.scene);
+
+            self.v_recoil.update(time.delta);
+            self.h_recoil.update(time.delta);
+
+            let spine_transform = context.scene.graph[self.spine].local_transform_mut();
+            let rotation = **spine_transform.rotation();
+            spine_transform.set_rotation(
+                rotation
+                    * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.v_recoil.angle())
+                    * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.h_recoil.angle()),
+            );
+        }
 
         if self.head_exploded {
             let head = context
@@ -547,6 +849,64 @@ impl Bot {
                     .set_scale(Vector3::new(0.0, 0.0, 0.0));
             }
         }
+
+        // Animations are overwriting transforms of bones, so scale has to be set every frame.
+        for &limb in self.destroyed_limbs.iter() {
+            if let Some(limb) = context.scene.graph.try_get_mut(limb) {
+                limb.local_transform_mut()
+                    .set_scale(Vector3::new(0.0, 0.0, 0.0));
+            }
+        }
+    }
+
+    /// Tears off a limb of the bot if the damage dealt to its hit box was heavy enough. Hit boxes
+    /// of the limb and every limb below it are removed, so they won't become parts of ragdoll.
+    /// Returns position of the limb.
+    pub fn dismember(
+        &mut self,
+        hit_box: &HitBox,
+        amount: f32,
+        graph: &mut Graph,
+    ) -> Option<Vector3<f32>> {
+        let definition = self.definition.dismemberment.as_ref()?;
+
+        // Limbs of ragdoll are simulated independently, they cannot be torn off.
+        if !hit_box.is_limb || self.ragdoll.is_active() {
+            return None;
+        }
+
+        let threshold = definition.damage_fraction * self.definition.health;
+        if amount < threshold && !(definition.killing_blow && self.is_dead()) {
+            return None;
+        }
+
+        let bone = graph.try_get(hit_box.collider)?.parent();
+        let position = graph.try_get(bone)?.global_position();
+
+        let is_below_bone = |graph: &Graph, mut node: Handle<Node>| {
+            while node.is_some() {
+                if node == bone {
+                    return true;
+                }
+                node = graph[node].parent();
+            }
+            false
+        };
+
+        let (lost, kept): (Vec<HitBox>, Vec<HitBox>) = self
+            .character
+            .hit_boxes
+            .iter()
+            .copied()
+            .partition(|h| is_below_bone(graph, h.collider));
+        self.character.hit_boxes = kept;
+        for lost in lost {
+            graph.remove_node(lost.collider);
+        }
+
+        self.destroyed_limbs.push(bone);
+
+        Some(position)
     }
 
     pub fn blow_up_head(&mut self, _graph: &mut Graph) {
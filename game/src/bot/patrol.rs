@@ -0,0 +1,169 @@
+//! Patrol routes for bots. A route is defined in the editor by assigning `PatrolRoute` script to a
+//! bot spawn point and filling its list of waypoints.
+
+use crate::GameConstructor;
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        inspect::prelude::*,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        graph::{map::NodeHandleMap, Graph},
+        node::{NodeHandle, TypeUuidProvider},
+    },
+    script::ScriptTrait,
+};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+#[derive(
+    Copy,
+    Clone,
+    Reflect,
+    Inspect,
+    Visit,
+    Debug,
+    AsRefStr,
+    PartialEq,
+    Eq,
+    EnumString,
+    EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum PatrolMode {
+    /// Go back to the first waypoint after the last one.
+    Loop = 0,
+    /// Walk the waypoints in reverse order after the last one.
+    PingPong = 1,
+}
+
+impl Default for PatrolMode {
+    fn default() -> Self {
+        Self::Loop
+    }
+}
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct PatrolRoute {
+    #[inspect(description = "An ordered list of handles to nodes that bot should walk through.")]
+    waypoints: Vec<NodeHandle>,
+
+    #[inspect(
+        description = "Time (in seconds) that bot waits at each waypoint.",
+        min_value = "0.0"
+    )]
+    dwell_time: InheritableVariable<f32>,
+
+    #[inspect(description = "What bot should do when it has reached the last waypoint.")]
+    mode: InheritableVariable<PatrolMode>,
+}
+
+impl Default for PatrolRoute {
+    fn default() -> Self {
+        Self {
+            waypoints: Default::default(),
+            dwell_time: InheritableVariable::new(2.0),
+            mode: Default::default(),
+        }
+    }
+}
+
+impl_component_provider!(PatrolRoute);
+
+impl TypeUuidProvider for PatrolRoute {
+    fn type_uuid() -> Uuid {
+        uuid!("a3c4d1e2-6f0b-4b8e-9c57-2d1f8e0a7b64")
+    }
+}
+
+impl ScriptTrait for PatrolRoute {
+    fn remap_handles(&mut self, old_new_mapping: &NodeHandleMap) {
+        old_new_mapping.try_map_slice(&mut self.waypoints);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+impl PatrolRoute {
+    /// Creates runtime patrol state using actual positions of waypoints.
+    pub fn make_patrol(&self, graph: &Graph) -> Patrol {
+        Patrol {
+            points: self
+                .waypoints
+                .iter()
+                .filter_map(|&waypoint| graph.try_get(*waypoint))
+                .map(|waypoint| waypoint.global_position())
+                .collect(),
+            dwell_time: *self.dwell_time,
+            mode: *self.mode,
+            ..Default::default()
+        }
+    }
+}
+
+/// Runtime state of a bot patrolling.
+#[derive(Visit, Default, Debug, Clone)]
+pub struct Patrol {
+    points: Vec<Vector3<f32>>,
+    dwell_time: f32,
+    mode: PatrolMode,
+    current: u32,
+    backwards: bool,
+    dwell_timer: f32,
+}
+
+impl Patrol {
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn current_point(&self) -> Option<Vector3<f32>> {
+        self.points.get(self.current as usize).cloned()
+    }
+
+    /// Must be called every frame while bot stands at current waypoint. Switches to next waypoint
+    /// when dwell time is over.
+    pub fn wait(&mut self, dt: f32) {
+        self.dwell_timer += dt;
+        if self.dwell_timer >= self.dwell_time {
+            self.dwell_timer = 0.0;
+            self.advance();
+        }
+    }
+
+    fn advance(&mut self) {
+        let count = self.points.len() as u32;
+        if count < 2 {
+            return;
+        }
+
+        match self.mode {
+            PatrolMode::Loop => {
+                self.current = (self.current + 1) % count;
+            }
+            PatrolMode::PingPong => {
+                if self.backwards && self.current == 0
+                    || !self.backwards && self.current + 1 == count
+                {
+                    self.backwards = !self.backwards;
+                }
+
+                if self.backwards {
+                    self.current -= 1;
+                } else {
+                    self.current += 1;
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,85 @@
 use crate::{
+    actor::Actor,
+    faction::Faction,
     inventory::Inventory,
+    item::ItemKind,
     message::Message,
+    ragdoll::Ragdoll,
+    status::StatusEffects,
     weapon::{definition::WeaponKind, Weapon, WeaponContainer},
     MessageSender,
 };
 use fyrox::scene::collider::Collider;
 use fyrox::{
     core::{algebra::Vector3, pool::Handle, visitor::prelude::*},
-    scene::{graph::Graph, node::Node, Scene},
+    lazy_static::lazy_static,
+    scene::{graph::Graph, node::Node, rigidbody::RigidBody, Scene},
 };
+use serde::Deserialize;
+use std::fs::File;
+
+/// Minimal time (in seconds) between two pain sounds of a character.
+const PAIN_SOUND_INTERVAL: f32 = 0.6;
+/// Hits weaker than this do not make a character grunt.
+const PAIN_SOUND_MIN_DAMAGE: f32 = 5.0;
+/// Amount of ammo given instead of a weapon that a character already has.
+const DUPLICATE_WEAPON_AMMO: u32 = 24;
+
+#[derive(Deserialize)]
+pub struct ArmorDefinition {
+    /// Amount of armor given by a single armor pickup.
+    pub pickup_amount: f32,
+    pub max_armor: f32,
+    /// Fraction of incoming damage that is absorbed by armor while it lasts.
+    pub absorption: f32,
+}
+
+impl ArmorDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/armor.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct KnockbackDefinition {
+    /// Mass (in kilograms) that is used to turn impulses of hits into knockback of actors.
+    pub actor_mass: f32,
+    /// Upper limit of knockback speed of an actor.
+    pub max_speed: f32,
+    /// How fast knockback of a living actor fades away.
+    pub damping: f32,
+    /// Impulse of an explosion per unit of damage at one meter from its center, the impulse falls
+    /// off with distance.
+    pub explosion_impulse: f32,
+    /// Fraction of knockback that is applied to the player, zero disables knockback of the
+    /// player.
+    pub player_scale: f32,
+}
+
+impl KnockbackDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/knockback.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    pub static ref ARMOR_DEFINITION: ArmorDefinition = ArmorDefinition::load();
+    pub static ref KNOCKBACK: KnockbackDefinition = KnockbackDefinition::load();
+}
+
+/// Describes what happened when a weapon was given to a character.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GiveResult {
+    /// The character has no such weapon yet, the weapon must be created and added to the
+    /// character using [`Character::add_weapon`].
+    NewWeapon,
+    /// The character already has such weapon, it was converted to given amount of ammo.
+    Ammo(u32),
+    /// The character already has such weapon and its ammo reserve is full.
+    ReserveFull,
+}
 
 #[derive(Visit)]
 pub struct Character {
@@ -23,6 +94,19 @@ pub struct Character {
     #[visit(skip)]
     pub hit_boxes: Vec<HitBox>,
     pub inventory: Inventory,
+    #[visit(optional)]
+    pub ragdoll: Ragdoll,
+    #[visit(optional)]
+    pub armor: f32,
+    #[visit(optional)]
+    pub status_effects: StatusEffects,
+    /// Velocity that is added to the velocity of the body, fades away over time.
+    #[visit(skip)]
+    pub knockback: Vector3<f32>,
+    #[visit(optional)]
+    pub faction: Faction,
+    #[visit(skip)]
+    pain_sound_timeout: f32,
 }
 
 impl Default for Character {
@@ -38,6 +122,12 @@ impl Default for Character {
             weapon_pivot: Handle::NONE,
             hit_boxes: Default::default(),
             inventory: Default::default(),
+            ragdoll: Default::default(),
+            armor: 0.0,
+            status_effects: Default::default(),
+            knockback: Default::default(),
+            faction: Default::default(),
+            pain_sound_timeout: 0.0,
         }
     }
 }
@@ -53,24 +143,28 @@ pub fn find_hit_boxes(from: Handle<Node>, scene: &Scene) -> Vec<HitBox> {
                 damage_factor: 0.25,
                 movement_speed_factor: 1.0,
                 is_head: false,
+                is_limb: true,
             }),
             "HitBoxLeg" => hit_boxes.push(HitBox {
                 collider: descendant,
                 damage_factor: 0.35,
                 movement_speed_factor: 0.5,
                 is_head: false,
+                is_limb: true,
             }),
             "HitBoxBody" => hit_boxes.push(HitBox {
                 collider: descendant,
                 damage_factor: 0.60,
                 movement_speed_factor: 0.75,
                 is_head: false,
+                is_limb: false,
             }),
             "HitBoxHead" => hit_boxes.push(HitBox {
                 collider: descendant,
                 damage_factor: 1.0,
                 movement_speed_factor: 0.1,
                 is_head: true,
+                is_limb: false,
             }),
             _ => (),
         }
@@ -110,8 +204,72 @@ impl Character {
         graph[self.pivot].global_position()
     }
 
+    pub fn velocity(&self, graph: &Graph) -> Vector3<f32> {
+        graph
+            .try_get(self.body)
+            .and_then(|body| body.cast::<RigidBody>())
+            .map_or_else(Vector3::default, |body| body.lin_vel())
+    }
+
+    /// Armor absorbs a part of the damage while it lasts, the rest of the damage (including the
+    /// part that armor was unable to absorb) goes to health.
     pub fn damage(&mut self, amount: f32) {
-        self.health -= amount.abs();
+        let amount = amount.abs();
+        let absorbed = (amount * ARMOR_DEFINITION.absorption).min(self.armor);
+        self.armor -= absorbed;
+        self.health -= amount - absorbed;
+    }
+
+    /// Pushes the character with given velocity. Limbs of an active ragdoll are pushed directly,
+    /// otherwise the knockback is added to the velocity of the body in the next updates (or is
+    /// given to the ragdoll when it activates).
+    pub fn knock_back(&mut self, velocity: Vector3<f32>, graph: &mut Graph) {
+        if self.ragdoll.is_active() {
+            self.ragdoll.add_velocity(velocity, graph);
+        } else {
+            self.knockback += velocity;
+            if self.knockback.norm() > KNOCKBACK.max_speed {
+                self.knockback = self.knockback.normalize().scale(KNOCKBACK.max_speed);
+            }
+        }
+    }
+
+    pub fn update_knockback(&mut self, graph: &mut Graph, dt: f32) {
+        if self.ragdoll.is_active() || self.knockback == Vector3::default() {
+            return;
+        }
+
+        if let Some(body) = graph
+            .try_get_mut(self.body)
+            .and_then(|n| n.cast_mut::<RigidBody>())
+        {
+            body.set_lin_vel(body.lin_vel() + self.knockback);
+        }
+
+        self.knockback = self
+            .knockback
+            .scale((1.0 - KNOCKBACK.damping * dt).max(0.0));
+        if self.knockback.norm() < 0.01 {
+            self.knockback = Default::default();
+        }
+    }
+
+    /// Turns the character into a ragdoll, pending knockback is given to the ragdoll, so the
+    /// character will be thrown by the hit that killed it.
+    pub fn activate_ragdoll(&mut self, graph: &mut Graph) {
+        if !self.ragdoll.is_active() {
+            self.ragdoll.activate(&self.hit_boxes, graph);
+            self.ragdoll.add_velocity(self.knockback, graph);
+            self.knockback = Default::default();
+        }
+    }
+
+    pub fn get_armor(&self) -> f32 {
+        self.armor
+    }
+
+    pub fn add_armor(&mut self, amount: f32) {
+        self.armor = (self.armor + amount.abs()).min(ARMOR_DEFINITION.max_armor);
     }
 
     pub fn heal(&mut self, amount: f32) {
@@ -126,6 +284,35 @@ impl Character {
         self.health <= 0.0
     }
 
+    /// Deals damage of active status effects, dead characters lose all of their effects.
+    pub fn update_status_effects(
+        &mut self,
+        self_handle: Handle<Actor>,
+        sender: &MessageSender,
+        dt: f32,
+    ) {
+        if self.is_dead() {
+            self.status_effects.clear();
+        } else {
+            self.status_effects.update(self_handle, sender, dt);
+        }
+    }
+
+    pub fn update_pain_sound_timeout(&mut self, dt: f32) {
+        self.pain_sound_timeout = (self.pain_sound_timeout - dt).max(0.0);
+    }
+
+    /// Returns `true` if the character should make a pain sound after taking given amount of
+    /// damage, pain sounds are throttled so rapid hits do not overlap into noise.
+    pub fn try_start_pain_sound(&mut self, damage: f32) -> bool {
+        if damage >= PAIN_SOUND_MIN_DAMAGE && self.pain_sound_timeout <= 0.0 {
+            self.pain_sound_timeout = PAIN_SOUND_INTERVAL;
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn weapon_pivot(&self) -> Handle<Node> {
         self.weapon_pivot
     }
@@ -148,6 +335,27 @@ impl Character {
         self.request_current_weapon_visible(true, sender);
     }
 
+    pub fn has_weapon(&self, kind: WeaponKind, weapons: &WeaponContainer) -> bool {
+        self.weapons.iter().any(|&w| weapons[w].kind() == kind)
+    }
+
+    /// Gives a weapon of given kind to the character, a duplicate of a weapon that the character
+    /// already has is converted to ammo. New weapons are not created here, because creation of a
+    /// weapon requires loading of its resources.
+    pub fn give_weapon(&mut self, kind: WeaponKind, weapons: &WeaponContainer) -> GiveResult {
+        if !self.has_weapon(kind, weapons) {
+            return GiveResult::NewWeapon;
+        }
+
+        let ammo = DUPLICATE_WEAPON_AMMO.min(self.inventory.free_space(ItemKind::Ammo));
+        if ammo > 0 {
+            self.inventory.add_item(ItemKind::Ammo, ammo);
+            GiveResult::Ammo(ammo)
+        } else {
+            GiveResult::ReserveFull
+        }
+    }
+
     pub fn select_weapon(
         &mut self,
         weapon: WeaponKind,
@@ -230,6 +438,8 @@ impl Character {
     }
 
     pub fn clean_up(&mut self, scene: &mut Scene) {
+        self.ragdoll.clean_up(&mut scene.graph);
+
         if scene.graph.is_valid_handle(self.body) {
             scene.remove_node(self.body);
         } else {
@@ -256,4 +466,6 @@ pub struct HitBox {
     pub damage_factor: f32,
     pub movement_speed_factor: f32,
     pub is_head: bool,
+    /// Arms and legs can be torn off, see [`crate::bot::Bot::dismember`].
+    pub is_limb: bool,
 }
@@ -1,13 +1,17 @@
 use crate::control_scheme::ControlScheme;
-use fyrox::{plugin::PluginContext, renderer::QualitySettings};
+use fyrox::{core::color::Color, plugin::PluginContext, renderer::QualitySettings};
 use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 
+/// Missing fields (of settings saved by an older version) are taken from the default config.
 #[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
 pub struct SoundConfig {
     pub master_volume: f32,
     pub music_volume: f32,
+    /// Multiplier for gains of every sound effect played on a level.
+    pub sfx_volume: f32,
     pub use_hrtf: bool,
 }
 
@@ -16,17 +20,186 @@ impl Default for SoundConfig {
         Self {
             master_volume: 1.0,
             music_volume: 0.5,
+            sfx_volume: 1.0,
             use_hrtf: true,
         }
     }
 }
 
+#[derive(Deserialize, Serialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LaserSightColor {
+    Blue,
+    Red,
+    Green,
+    Yellow,
+}
+
+impl LaserSightColor {
+    pub const ALL: [LaserSightColor; 4] = [
+        LaserSightColor::Blue,
+        LaserSightColor::Red,
+        LaserSightColor::Green,
+        LaserSightColor::Yellow,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LaserSightColor::Blue => "Blue",
+            LaserSightColor::Red => "Red",
+            LaserSightColor::Green => "Green",
+            LaserSightColor::Yellow => "Yellow",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            LaserSightColor::Blue => Color::from_rgba(0, 162, 232, 200),
+            LaserSightColor::Red => Color::from_rgba(232, 20, 20, 200),
+            LaserSightColor::Green => Color::from_rgba(20, 232, 60, 200),
+            LaserSightColor::Yellow => Color::from_rgba(232, 212, 20, 200),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct LaserSightConfig {
+    pub color: LaserSightColor,
+    /// Whether the dot at the end of the beam should be shown.
+    pub show_dot: bool,
+}
+
+impl Default for LaserSightConfig {
+    fn default() -> Self {
+        Self {
+            color: LaserSightColor::Blue,
+            show_dot: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct HealthRegenerationConfig {
+    /// Health regeneration is an alternative to medkits, so it is disabled by default.
+    pub enabled: bool,
+    /// Time (in seconds) without taking any damage before health starts to regenerate.
+    pub delay: f32,
+    /// Amount of health restored per second.
+    pub rate: f32,
+    /// Health never regenerates above this value.
+    pub cap: f32,
+}
+
+impl Default for HealthRegenerationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay: 5.0,
+            rate: 5.0,
+            cap: 60.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct DownedStateConfig {
+    /// Downed state makes the game easier, so it is disabled by default.
+    pub enabled: bool,
+    /// Time (in seconds) that the player has to revive, the player dies when it is over.
+    pub duration: f32,
+    /// Fraction of normal movement speed while crawling.
+    pub crawl_speed: f32,
+}
+
+impl Default for DownedStateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration: 15.0,
+            crawl_speed: 0.2,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct WeaponJamConfig {
+    /// Jamming is meant for hardcore players, so it is disabled by default.
+    pub enabled: bool,
+    /// Condition that a weapon loses with every shot, a new weapon has condition of 1.0.
+    pub wear_per_shot: f32,
+    /// Weapons in a condition below this value could jam.
+    pub jam_threshold: f32,
+    /// Chance to jam on a shot of a completely worn out weapon, the chance grows linearly
+    /// from zero at the threshold.
+    pub max_jam_chance: f32,
+    /// Time (in seconds) needed to clear a jam.
+    pub clear_time: f32,
+}
+
+impl Default for WeaponJamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            wear_per_shot: 0.002,
+            jam_threshold: 0.6,
+            max_jam_chance: 0.15,
+            clear_time: 1.2,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, PartialEq, Debug)]
+pub struct DoubleJumpConfig {
+    /// Classic mode allows a single jump, so double jump is disabled by default.
+    pub enabled: bool,
+}
+
+/// Missing fields (of settings saved by an older version) are taken from the default config.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct CameraConfig {
+    /// Field of view (in degrees) of the camera without zoom.
+    pub field_of_view: f32,
+    /// Whether sprinting widens the view and shows speed lines.
+    pub sprint_effects: bool,
+    /// Increase of field of view (in degrees) at full sprint.
+    pub sprint_field_of_view: f32,
+    /// Speed lines could be distracting, so they can be turned off separately.
+    pub speed_lines: bool,
+    /// Whether the inventory, the journal and the ammo display are shown in the world (on the
+    /// wrist and on the weapon) or as screen-space panels on the HUD.
+    pub diegetic_displays: bool,
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Self {
+            field_of_view: 75.0,
+            sprint_effects: true,
+            sprint_field_of_view: 8.0,
+            speed_lines: true,
+            diegetic_displays: true,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Default, Clone)]
 pub struct Config {
     pub graphics_settings: QualitySettings,
     pub controls: ControlScheme,
     pub sound: SoundConfig,
     pub show_debug_info: bool,
+    #[serde(default)]
+    pub laser_sight: LaserSightConfig,
+    #[serde(default)]
+    pub health_regeneration: HealthRegenerationConfig,
+    #[serde(default)]
+    pub camera: CameraConfig,
+    #[serde(default)]
+    pub downed_state: DownedStateConfig,
+    #[serde(default)]
+    pub weapon_jam: WeaponJamConfig,
+    #[serde(default)]
+    pub double_jump: DoubleJumpConfig,
 }
 
 #[derive(Debug)]
@@ -60,12 +233,24 @@ impl Config {
         control_scheme: ControlScheme,
         sound_config: SoundConfig,
         show_debug_info: bool,
+        laser_sight: LaserSightConfig,
+        health_regeneration: HealthRegenerationConfig,
+        camera: CameraConfig,
+        downed_state: DownedStateConfig,
+        weapon_jam: WeaponJamConfig,
+        double_jump: DoubleJumpConfig,
     ) -> Result<(), ConfigError> {
         let config = Self {
             graphics_settings: context.renderer.get_quality_settings(),
             controls: control_scheme,
             sound: sound_config,
             show_debug_info,
+            laser_sight,
+            health_regeneration,
+            camera,
+            downed_state,
+            weapon_jam,
+            double_jump,
         };
         let file = File::create(Self::PATH)?;
         ron::ser::to_writer_pretty(file, &config, PrettyConfig::default())?;
@@ -1,3 +1,4 @@
+use crate::player::hotbar::HOTBAR_SIZE;
 use fyrox::event::VirtualKeyCode;
 use serde::{Deserialize, Serialize};
 
@@ -33,7 +34,9 @@ pub struct ControlButtonDefinition {
     pub button: ControlButton,
 }
 
+/// Missing fields (of settings saved by an older version) are taken from the default scheme.
 #[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
 pub struct ControlScheme {
     pub move_forward: ControlButtonDefinition,
     pub move_backward: ControlButtonDefinition,
@@ -43,24 +46,65 @@ pub struct ControlScheme {
     pub shoot: ControlButtonDefinition,
     pub next_weapon: ControlButtonDefinition,
     pub prev_weapon: ControlButtonDefinition,
+    /// Switches back to the weapon that was held before the current one.
+    pub last_weapon: ControlButtonDefinition,
     pub run: ControlButtonDefinition,
+    /// Crouched player moves slower, but shoots more accurately.
+    pub crouch: ControlButtonDefinition,
     pub aim: ControlButtonDefinition,
     pub toss_grenade: ControlButtonDefinition,
+    pub switch_grenade: ControlButtonDefinition,
+    pub throw_knife: ControlButtonDefinition,
     pub journal: ControlButtonDefinition,
     pub flash_light: ControlButtonDefinition,
+    pub toggle_silencer: ControlButtonDefinition,
+    pub fire_mode: ControlButtonDefinition,
+    pub toggle_laser_sight: ControlButtonDefinition,
+    /// Clears a jammed weapon, weapons jam only if jamming is enabled.
+    pub clear_jam: ControlButtonDefinition,
     pub grab_ak47: ControlButtonDefinition,
     pub grab_m4: ControlButtonDefinition,
     pub grab_pistol: ControlButtonDefinition,
     pub grab_plasma_gun: ControlButtonDefinition,
+    /// Slot buttons select a weapon by its position in the inventory of the player.
+    pub weapon_slot_1: ControlButtonDefinition,
+    pub weapon_slot_2: ControlButtonDefinition,
+    pub weapon_slot_3: ControlButtonDefinition,
+    pub weapon_slot_4: ControlButtonDefinition,
+    pub weapon_slot_5: ControlButtonDefinition,
+    /// Hotbar buttons use an item that is assigned to the slot, or assign the selected item while
+    /// the inventory is open.
+    pub hotbar_slot_1: ControlButtonDefinition,
+    pub hotbar_slot_2: ControlButtonDefinition,
+    pub hotbar_slot_3: ControlButtonDefinition,
     pub inventory: ControlButtonDefinition,
     pub action: ControlButtonDefinition,
     pub drop_item: ControlButtonDefinition,
+    /// Takes every attachment off the weapon that is selected in the inventory.
+    pub detach_attachments: ControlButtonDefinition,
     pub cursor_up: ControlButtonDefinition,
     pub cursor_down: ControlButtonDefinition,
     pub cursor_left: ControlButtonDefinition,
     pub cursor_right: ControlButtonDefinition,
+    /// Freezes the game and detaches the camera from the player.
+    pub photo_mode: ControlButtonDefinition,
     pub mouse_sens: f32,
     pub mouse_y_inverse: bool,
+    /// Turn speed (in radians per second) of the camera at full deflection of the right stick.
+    pub stick_sens: f32,
+    /// Aim assist makes aiming with a gamepad stick easier, it does nothing for the mouse.
+    pub aim_assist: bool,
+    /// How much the aim assist slows down and pulls the camera, in `[0; 1]` range.
+    pub aim_assist_strength: f32,
+    /// Whether the aim button toggles aiming instead of being held.
+    #[serde(default)]
+    pub aim_toggle: bool,
+    /// Whether the run button toggles running instead of being held.
+    #[serde(default)]
+    pub run_toggle: bool,
+    /// Whether the crouch button toggles crouching instead of being held.
+    #[serde(default)]
+    pub crouch_toggle: bool,
 }
 
 impl Default for ControlScheme {
@@ -98,10 +142,18 @@ impl Default for ControlScheme {
                 description: "Previous Weapon".to_string(),
                 button: ControlButton::WheelDown,
             },
+            last_weapon: ControlButtonDefinition {
+                description: "Last Weapon".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::X),
+            },
             run: ControlButtonDefinition {
                 description: "Run".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::LShift),
             },
+            crouch: ControlButtonDefinition {
+                description: "Crouch".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::LControl),
+            },
             aim: ControlButtonDefinition {
                 description: "Aim".to_string(),
                 button: ControlButton::Mouse(3),
@@ -110,6 +162,14 @@ impl Default for ControlScheme {
                 description: "Toss Grenade".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::G),
             },
+            switch_grenade: ControlButtonDefinition {
+                description: "Switch Grenade".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::T),
+            },
+            throw_knife: ControlButtonDefinition {
+                description: "Throw Knife".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Q),
+            },
             journal: ControlButtonDefinition {
                 description: "Journal".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::J),
@@ -118,22 +178,70 @@ impl Default for ControlScheme {
                 description: "Flash Light".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::F),
             },
+            toggle_silencer: ControlButtonDefinition {
+                description: "Toggle Silencer".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::V),
+            },
+            fire_mode: ControlButtonDefinition {
+                description: "Fire Mode".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::B),
+            },
+            toggle_laser_sight: ControlButtonDefinition {
+                description: "Toggle Laser Sight".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::L),
+            },
+            clear_jam: ControlButtonDefinition {
+                description: "Clear Jam".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::K),
+            },
             grab_pistol: ControlButtonDefinition {
                 description: "Grab Pistol".to_string(),
-                button: ControlButton::Key(VirtualKeyCode::Key1),
+                button: ControlButton::Key(VirtualKeyCode::Numpad1),
             },
             grab_ak47: ControlButtonDefinition {
                 description: "Grab AK47".to_string(),
-                button: ControlButton::Key(VirtualKeyCode::Key2),
+                button: ControlButton::Key(VirtualKeyCode::Numpad2),
             },
             grab_m4: ControlButtonDefinition {
                 description: "Grab M4".to_string(),
-                button: ControlButton::Key(VirtualKeyCode::Key3),
+                button: ControlButton::Key(VirtualKeyCode::Numpad3),
             },
             grab_plasma_gun: ControlButtonDefinition {
                 description: "Grab Plasma Gun".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Numpad4),
+            },
+            weapon_slot_1: ControlButtonDefinition {
+                description: "Weapon Slot 1".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Key1),
+            },
+            weapon_slot_2: ControlButtonDefinition {
+                description: "Weapon Slot 2".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Key2),
+            },
+            weapon_slot_3: ControlButtonDefinition {
+                description: "Weapon Slot 3".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Key3),
+            },
+            weapon_slot_4: ControlButtonDefinition {
+                description: "Weapon Slot 4".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::Key4),
             },
+            weapon_slot_5: ControlButtonDefinition {
+                description: "Weapon Slot 5".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Key5),
+            },
+            hotbar_slot_1: ControlButtonDefinition {
+                description: "Hotbar Slot 1".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Key6),
+            },
+            hotbar_slot_2: ControlButtonDefinition {
+                description: "Hotbar Slot 2".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Key7),
+            },
+            hotbar_slot_3: ControlButtonDefinition {
+                description: "Hotbar Slot 3".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Key8),
+            },
             inventory: ControlButtonDefinition {
                 description: "Inventory".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::I),
@@ -146,6 +254,10 @@ impl Default for ControlScheme {
                 description: "Drop Item".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::R),
             },
+            detach_attachments: ControlButtonDefinition {
+                description: "Detach Attachments".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::Z),
+            },
             cursor_up: ControlButtonDefinition {
                 description: "Cursor Up".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::Up),
@@ -162,14 +274,24 @@ impl Default for ControlScheme {
                 description: "Cursor Right".to_string(),
                 button: ControlButton::Key(VirtualKeyCode::Right),
             },
+            photo_mode: ControlButtonDefinition {
+                description: "Photo Mode".to_string(),
+                button: ControlButton::Key(VirtualKeyCode::P),
+            },
             mouse_sens: 0.3,
             mouse_y_inverse: false,
+            stick_sens: 2.5,
+            aim_assist: true,
+            aim_assist_strength: 0.5,
+            aim_toggle: false,
+            run_toggle: false,
+            crouch_toggle: false,
         }
     }
 }
 
 impl ControlScheme {
-    pub fn buttons_mut(&mut self) -> [&mut ControlButtonDefinition; 24] {
+    pub fn buttons_mut(&mut self) -> [&mut ControlButtonDefinition; 42] {
         [
             &mut self.move_forward,
             &mut self.move_backward,
@@ -177,28 +299,46 @@ impl ControlScheme {
             &mut self.move_right,
             &mut self.action,
             &mut self.drop_item,
+            &mut self.detach_attachments,
             &mut self.jump,
             &mut self.shoot,
             &mut self.next_weapon,
             &mut self.prev_weapon,
+            &mut self.last_weapon,
             &mut self.run,
+            &mut self.crouch,
             &mut self.aim,
             &mut self.inventory,
             &mut self.toss_grenade,
+            &mut self.switch_grenade,
+            &mut self.throw_knife,
             &mut self.journal,
             &mut self.flash_light,
+            &mut self.toggle_silencer,
+            &mut self.fire_mode,
+            &mut self.toggle_laser_sight,
+            &mut self.clear_jam,
             &mut self.grab_pistol,
             &mut self.grab_ak47,
             &mut self.grab_m4,
             &mut self.grab_plasma_gun,
+            &mut self.weapon_slot_1,
+            &mut self.weapon_slot_2,
+            &mut self.weapon_slot_3,
+            &mut self.weapon_slot_4,
+            &mut self.weapon_slot_5,
+            &mut self.hotbar_slot_1,
+            &mut self.hotbar_slot_2,
+            &mut self.hotbar_slot_3,
             &mut self.cursor_up,
             &mut self.cursor_down,
             &mut self.cursor_left,
             &mut self.cursor_right,
+            &mut self.photo_mode,
         ]
     }
 
-    pub fn buttons(&self) -> [&ControlButtonDefinition; 24] {
+    pub fn buttons(&self) -> [&ControlButtonDefinition; 42] {
         [
             &self.move_forward,
             &self.move_backward,
@@ -206,24 +346,60 @@ impl ControlScheme {
             &self.move_right,
             &self.action,
             &self.drop_item,
+            &self.detach_attachments,
             &self.jump,
             &self.shoot,
             &self.next_weapon,
             &self.prev_weapon,
+            &self.last_weapon,
             &self.run,
+            &self.crouch,
             &self.aim,
             &self.inventory,
             &self.toss_grenade,
+            &self.switch_grenade,
+            &self.throw_knife,
             &self.journal,
             &self.flash_light,
+            &self.toggle_silencer,
+            &self.fire_mode,
+            &self.toggle_laser_sight,
+            &self.clear_jam,
             &self.grab_pistol,
             &self.grab_ak47,
             &self.grab_m4,
             &self.grab_plasma_gun,
+            &self.weapon_slot_1,
+            &self.weapon_slot_2,
+            &self.weapon_slot_3,
+            &self.weapon_slot_4,
+            &self.weapon_slot_5,
+            &self.hotbar_slot_1,
+            &self.hotbar_slot_2,
+            &self.hotbar_slot_3,
             &self.cursor_up,
             &self.cursor_down,
             &self.cursor_left,
             &self.cursor_right,
+            &self.photo_mode,
+        ]
+    }
+
+    pub fn weapon_slots(&self) -> [&ControlButtonDefinition; 5] {
+        [
+            &self.weapon_slot_1,
+            &self.weapon_slot_2,
+            &self.weapon_slot_3,
+            &self.weapon_slot_4,
+            &self.weapon_slot_5,
+        ]
+    }
+
+    pub fn hotbar_slots(&self) -> [&ControlButtonDefinition; HOTBAR_SIZE] {
+        [
+            &self.hotbar_slot_1,
+            &self.hotbar_slot_2,
+            &self.hotbar_slot_3,
         ]
     }
 
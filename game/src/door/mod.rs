@@ -1,10 +1,10 @@
 use crate::{
-    current_level_mut, game_mut, inventory::Inventory, item::ItemKind, message::Message, Actor,
-    MessageSender,
+    current_level_mut, current_level_ref, effects::EffectKind, game_mut, inventory::Inventory,
+    item::ItemKind, message::Message, utils::spatial_grid::SpatialGrid, Actor, MessageSender,
 };
 use fyrox::{
     core::{
-        algebra::Vector3,
+        algebra::{UnitQuaternion, Vector3},
         color::Color,
         inspect::prelude::*,
         parking_lot::Mutex,
@@ -36,6 +36,10 @@ use crate::GameConstructor;
 
 pub mod ui;
 
+/// Maximum distance between a door and a path of an actor at which the path is considered to
+/// go through the door.
+const PATH_CROSSING_DISTANCE: f32 = 1.0;
+
 #[derive(
     Copy,
     Clone,
@@ -95,7 +99,7 @@ struct OpenRequest {
     has_key: bool,
 }
 
-#[derive(Visit, Reflect, Inspect, Default, Debug, Clone)]
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
 pub struct Door {
     #[inspect(
         description = "An array of handles to light sources that indicates state of the door."
@@ -118,6 +122,37 @@ pub struct Door {
     #[visit(optional)]
     open_offset_amount: InheritableVariable<f32>,
 
+    #[inspect(
+        description = "Whether the door can be destroyed by shooting at it or by explosions."
+    )]
+    #[visit(optional)]
+    breakable: InheritableVariable<bool>,
+
+    #[inspect(
+        description = "Amount of damage that breakable door can take before it breaks.",
+        min_value = "0.0"
+    )]
+    #[visit(optional)]
+    health: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Whether the door is locked initially. Locked door can be opened only with a master key or unlocked by a terminal."
+    )]
+    #[visit(optional)]
+    locked: InheritableVariable<bool>,
+
+    #[inspect(
+        description = "Maximum distance at which the player can open the door.",
+        min_value = "0.0"
+    )]
+    #[visit(optional)]
+    interaction_radius: InheritableVariable<f32>,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(optional)]
+    damage_taken: f32,
+
     #[inspect(skip)]
     #[reflect(hidden)]
     #[visit(skip)]
@@ -144,6 +179,27 @@ pub struct Door {
     self_handle: Handle<Node>,
 }
 
+impl Default for Door {
+    fn default() -> Self {
+        Self {
+            lights: Default::default(),
+            screens: Default::default(),
+            open_direction: Default::default(),
+            open_offset_amount: Default::default(),
+            breakable: InheritableVariable::new(false),
+            health: InheritableVariable::new(100.0),
+            locked: InheritableVariable::new(false),
+            interaction_radius: InheritableVariable::new(1.25),
+            damage_taken: 0.0,
+            offset: 0.0,
+            state: Default::default(),
+            initial_position: Default::default(),
+            open_request: None,
+            self_handle: Default::default(),
+        }
+    }
+}
+
 impl_component_provider!(Door);
 
 impl TypeUuidProvider for Door {
@@ -156,6 +212,9 @@ impl ScriptTrait for Door {
     fn on_init(&mut self, context: ScriptContext) {
         self.self_handle = context.handle;
         self.initial_position = context.scene.graph[context.handle].global_position();
+        if *self.locked {
+            self.state = DoorState::Locked;
+        }
 
         let game = game_mut(context.plugin);
         let texture = game.door_ui_container.create_ui(
@@ -171,15 +230,13 @@ impl ScriptTrait for Door {
 
         current_level_mut(context.plugin)
             .doors
-            .doors
-            .push(context.handle);
+            .add(context.handle, self.initial_position);
     }
 
     fn on_deinit(&mut self, context: ScriptDeinitContext) {
-        let doors = &mut current_level_mut(context.plugin).doors.doors;
-        if let Some(position) = doors.iter().position(|d| *d == context.node_handle) {
-            doors.remove(position);
-        }
+        current_level_mut(context.plugin)
+            .doors
+            .remove(context.node_handle);
     }
 
     fn on_update(&mut self, context: ScriptContext) {
@@ -191,6 +248,10 @@ impl ScriptTrait for Door {
             ..
         } = context;
 
+        if current_level_ref(plugin).is_photo_mode_active() {
+            return;
+        }
+
         let game = game_mut(plugin);
 
         let speed = 0.55;
@@ -226,6 +287,27 @@ impl ScriptTrait for Door {
             });
         }
 
+        if self.state != DoorState::Broken && *self.breakable && self.damage_taken >= *self.health {
+            self.state = DoorState::Broken;
+            self.shatter(&mut scene.graph);
+
+            let position = self.actual_position(&scene.graph);
+
+            game.message_sender.send(Message::CreateEffect {
+                kind: EffectKind::Smoke,
+                position,
+                orientation: UnitQuaternion::default(),
+            });
+
+            game.message_sender.send(Message::PlaySound {
+                path: PathBuf::from("data/sounds/bullet_impact_metal.ogg"),
+                position,
+                gain: 1.0,
+                rolloff_factor: 1.0,
+                radius: 3.0,
+            });
+        }
+
         if let Some(ui) = game.door_ui_container.get_ui_mut(handle) {
             let text = match self.state {
                 DoorState::Opened => "Opened",
@@ -406,17 +488,105 @@ impl Door {
         }
     }
 
+    /// Hides every part of the door and removes its colliders, so actors can walk through it.
+    fn shatter(&self, graph: &mut Graph) {
+        // Removing a collider removes its children too, so split the nodes before touching them.
+        let (colliders, others): (Vec<_>, Vec<_>) = graph
+            .traverse_handle_iter(self.self_handle)
+            .filter(|&h| h != self.self_handle)
+            .partition(|&descendant| graph[descendant].is_collider());
+
+        for node in others {
+            graph[node].set_visibility(false);
+        }
+
+        for collider in colliders {
+            if graph.is_valid_handle(collider) {
+                graph.remove_node(collider);
+            }
+        }
+    }
+
+    pub fn interaction_radius(&self) -> f32 {
+        *self.interaction_radius
+    }
+
+    /// Returns `true` if the door is closed and an actor can try to open it.
+    pub fn can_be_opened(&self) -> bool {
+        matches!(self.state, DoorState::Closed | DoorState::Locked)
+    }
+
+    /// Returns `true` if the door is fully closed, closed doors block sounds like walls do.
+    pub fn is_closed(&self) -> bool {
+        matches!(self.state, DoorState::Closed | DoorState::Locked)
+    }
+
+    pub fn is_breakable(&self) -> bool {
+        *self.breakable
+    }
+
+    /// Accumulates damage, the door will break on next update if it is breakable and the damage
+    /// has exceeded its health.
+    pub fn damage(&mut self, amount: f32) {
+        if *self.breakable && self.state != DoorState::Broken {
+            self.damage_taken += amount.max(0.0);
+        }
+    }
+
+    /// Unlocks the door, so it could be opened by anyone.
+    pub fn unlock(&mut self) {
+        self.locked.set(false);
+        if self.state == DoorState::Locked {
+            self.state = DoorState::Closed;
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.state == DoorState::Locked
+    }
+
+    /// Returns `true` if an actor with given inventory is able to open the door right now, locked
+    /// doors require a master key.
+    pub fn can_be_opened_by(&self, inventory: Option<&Inventory>) -> bool {
+        match self.state {
+            DoorState::Closed => true,
+            DoorState::Locked => has_key(inventory),
+            _ => false,
+        }
+    }
+
     pub fn try_open(&mut self, inventory: Option<&Inventory>) {
-        let has_key = inventory
-            .map(|i| i.item_count(ItemKind::MasterKey) > 0)
-            .unwrap_or(false);
-        self.open_request = Some(OpenRequest { has_key });
+        self.open_request = Some(OpenRequest {
+            has_key: has_key(inventory),
+        });
     }
 }
 
+fn has_key(inventory: Option<&Inventory>) -> bool {
+    inventory
+        .map(|i| i.item_count(ItemKind::MasterKey) > 0)
+        .unwrap_or(false)
+}
+
+/// Returns distance from given point to a line segment.
+fn distance_to_segment(point: Vector3<f32>, begin: Vector3<f32>, end: Vector3<f32>) -> f32 {
+    let segment = end - begin;
+    let length_squared = segment.norm_squared();
+    let t = if length_squared > f32::EPSILON {
+        ((point - begin).dot(&segment) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.metric_distance(&(begin + segment.scale(t)))
+}
+
 #[derive(Default, Visit)]
 pub struct DoorContainer {
     pub doors: Vec<Handle<Node>>,
+    /// Doors are registered at their initial positions which never change, so proximity
+    /// checks can use the grid instead of iterating over every door on the level.
+    #[visit(skip)]
+    grid: SpatialGrid<Handle<Node>>,
 }
 
 pub fn door_ref(handle: Handle<Node>, graph: &Graph) -> &Door {
@@ -437,9 +607,61 @@ impl DoorContainer {
     pub fn new() -> Self {
         Self {
             doors: Default::default(),
+            grid: Default::default(),
         }
     }
 
+    pub fn add(&mut self, door: Handle<Node>, initial_position: Vector3<f32>) {
+        if !self.doors.contains(&door) {
+            self.doors.push(door);
+        }
+        self.grid.insert(initial_position, door);
+    }
+
+    pub fn remove(&mut self, door: Handle<Node>) {
+        if let Some(position) = self.doors.iter().position(|d| *d == door) {
+            self.doors.remove(position);
+        }
+        self.grid.remove(door);
+    }
+
+    /// Returns every door which initial position is closer than `radius` to given point.
+    pub fn doors_near<'a>(
+        &'a self,
+        position: Vector3<f32>,
+        radius: f32,
+        graph: &'a Graph,
+    ) -> Box<dyn Iterator<Item = Handle<Node>> + 'a> {
+        if self.grid.len() == self.doors.len() {
+            Box::new(
+                self.grid
+                    .query(position, radius)
+                    .filter(move |(door_position, _)| {
+                        position.metric_distance(door_position) < radius
+                    })
+                    .map(|(_, door)| door),
+            )
+        } else {
+            // Grid is out of sync (e.g. right after deserialization), fallback to brute force.
+            Box::new(self.doors.iter().cloned().filter(move |&door| {
+                position.metric_distance(&door_ref(door, graph).initial_position()) < radius
+            }))
+        }
+    }
+
+    /// Returns every door that can be opened from given point, every door has its own interaction
+    /// radius.
+    pub fn doors_in_reach<'a>(
+        &'a self,
+        position: Vector3<f32>,
+        graph: &'a Graph,
+    ) -> impl Iterator<Item = Handle<Node>> + 'a {
+        self.doors.iter().cloned().filter(move |&door| {
+            let door = door_ref(door, graph);
+            position.metric_distance(&door.initial_position()) < door.interaction_radius()
+        })
+    }
+
     pub fn check_actor(
         &self,
         actor_position: Vector3<f32>,
@@ -447,10 +669,41 @@ impl DoorContainer {
         graph: &Graph,
         sender: &MessageSender,
     ) {
-        for &door_handle in &self.doors {
+        for door_handle in self.doors_in_reach(actor_position, graph) {
+            sender.send(Message::TryOpenDoor {
+                door: door_handle,
+                actor: actor_handle,
+            });
+        }
+    }
+
+    /// Opens every door in reach of an AI actor which is crossed by its path. Doors that the actor
+    /// is not able to open (locked doors without a key) are ignored and keep blocking the path.
+    pub fn check_path(
+        &self,
+        path: &[Vector3<f32>],
+        actor_handle: Handle<Actor>,
+        inventory: &Inventory,
+        graph: &Graph,
+        sender: &MessageSender,
+    ) {
+        let actor_position = match path.first() {
+            Some(position) => *position,
+            None => return,
+        };
+
+        for door_handle in self.doors_in_reach(actor_position, graph) {
             let door = door_ref(door_handle, graph);
-            let close_enough = actor_position.metric_distance(&door.initial_position()) < 1.25;
-            if close_enough {
+            if !door.can_be_opened_by(Some(inventory)) {
+                continue;
+            }
+
+            let door_position = door.initial_position();
+            let crossed = path.windows(2).any(|segment| {
+                distance_to_segment(door_position, segment[0], segment[1]) < PATH_CROSSING_DISTANCE
+            });
+
+            if crossed {
                 sender.send(Message::TryOpenDoor {
                     door: door_handle,
                     actor: actor_handle,
@@ -1,3 +1,4 @@
+use crate::sound::MaterialType;
 use fyrox::scene::light::{point::PointLightBuilder, BaseLightBuilder};
 
 use fyrox::scene::particle_system::particle::Particle;
@@ -31,6 +32,33 @@ pub enum EffectKind {
     BulletImpact,
     BloodSpray,
     Smoke,
+    /// Bloody burst that is made when a limb is torn off.
+    Gib,
+    /// Puff of dust that is made when a bullet hits stone, wood or ground.
+    Dust,
+}
+
+impl EffectKind {
+    /// Returns an impact effect for a surface of given material: sparks on metal, dust on
+    /// concrete and blood on flesh. Surfaces of unknown material make sparks.
+    pub fn impact_for(material: Option<MaterialType>) -> Self {
+        match material {
+            Some(MaterialType::Flesh) => EffectKind::BloodSpray,
+            Some(MaterialType::Stone | MaterialType::Grass | MaterialType::Wood) => {
+                EffectKind::Dust
+            }
+            Some(MaterialType::Metal | MaterialType::Chain) | None => EffectKind::BulletImpact,
+        }
+    }
+
+    /// Returns true if the effect is made by a hit. Amount of such effects is limited, because
+    /// automatic weapons can make lots of them in a short time.
+    pub fn is_impact(self) -> bool {
+        matches!(
+            self,
+            EffectKind::BulletImpact | EffectKind::BloodSpray | EffectKind::Dust
+        )
+    }
 }
 
 /// # Notes
@@ -47,6 +75,8 @@ pub fn create(
         EffectKind::BulletImpact => create_bullet_impact(graph, resource_manager, pos, orientation),
         EffectKind::BloodSpray => create_blood_spray(graph, resource_manager, pos, orientation),
         EffectKind::Smoke => create_smoke(graph, resource_manager, pos, orientation),
+        EffectKind::Gib => create_gib(graph, resource_manager, pos, orientation),
+        EffectKind::Dust => create_dust(graph, resource_manager, pos, orientation),
     }
 }
 
@@ -147,6 +177,92 @@ fn create_blood_spray(
     .build(graph)
 }
 
+fn create_gib(
+    graph: &mut Graph,
+    resource_manager: ResourceManager,
+    pos: Vector3<f32>,
+    orientation: UnitQuaternion<f32>,
+) -> Handle<Node> {
+    ParticleSystemBuilder::new(
+        BaseBuilder::new().with_lifetime(1.0).with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(pos)
+                .with_local_rotation(orientation)
+                .build(),
+        ),
+    )
+    .with_acceleration(Vector3::new(0.0, -0.0005, 0.0))
+    .with_color_over_lifetime_gradient({
+        let mut gradient = ColorGradient::new();
+        gradient.add_point(GradientPoint::new(0.00, Color::from_rgba(160, 0, 0, 255)));
+        gradient.add_point(GradientPoint::new(0.90, Color::from_rgba(120, 0, 0, 255)));
+        gradient.add_point(GradientPoint::new(1.00, Color::from_rgba(120, 0, 0, 0)));
+        gradient
+    })
+    .with_emitters(vec![SphereEmitterBuilder::new(
+        BaseEmitterBuilder::new()
+            .with_max_particles(400)
+            .with_spawn_rate(4000)
+            .with_size_modifier_range(-0.005..-0.0075)
+            .with_lifetime_range(0.3..1.0)
+            .with_size_range(0.015..0.03)
+            .with_x_velocity_range(-0.01..0.01)
+            .with_y_velocity_range(0.0..0.015)
+            .with_z_velocity_range(-0.01..0.01)
+            .resurrect_particles(false),
+    )
+    .with_radius(0.05)
+    .build()])
+    .with_texture(resource_manager.request_texture(Path::new("data/particles/dirt_01.png")))
+    .build(graph)
+}
+
+fn create_dust(
+    graph: &mut Graph,
+    resource_manager: ResourceManager,
+    pos: Vector3<f32>,
+    orientation: UnitQuaternion<f32>,
+) -> Handle<Node> {
+    ParticleSystemBuilder::new(
+        BaseBuilder::new().with_lifetime(0.8).with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(pos)
+                .with_local_rotation(orientation)
+                .build(),
+        ),
+    )
+    .with_acceleration(Vector3::new(0.0, -0.0002, 0.0))
+    .with_color_over_lifetime_gradient({
+        let mut gradient = ColorGradient::new();
+        gradient.add_point(GradientPoint::new(
+            0.00,
+            Color::from_rgba(140, 130, 115, 220),
+        ));
+        gradient.add_point(GradientPoint::new(
+            0.60,
+            Color::from_rgba(120, 112, 100, 160),
+        ));
+        gradient.add_point(GradientPoint::new(1.00, Color::from_rgba(120, 112, 100, 0)));
+        gradient
+    })
+    .with_emitters(vec![SphereEmitterBuilder::new(
+        BaseEmitterBuilder::new()
+            .with_max_particles(60)
+            .with_spawn_rate(600)
+            .with_size_modifier_range(0.0025..0.005)
+            .with_lifetime_range(0.3..0.8)
+            .with_size_range(0.01..0.025)
+            .with_x_velocity_range(-0.004..0.004)
+            .with_y_velocity_range(-0.004..0.004)
+            .with_z_velocity_range(0.004..0.012)
+            .resurrect_particles(false),
+    )
+    .with_radius(0.02)
+    .build()])
+    .with_texture(resource_manager.request_texture(Path::new("data/particles/smoke_04.tga")))
+    .build(graph)
+}
+
 fn create_smoke(
     graph: &mut Graph,
     resource_manager: ResourceManager,
@@ -236,3 +352,48 @@ pub fn create_rail(
     .with_texture(resource_manager.request_texture(Path::new("data/particles/circle_05.png")))
     .build(graph)
 }
+
+/// Creates a lingering cloud of smoke. Unlike other effects it is not oriented.
+pub fn create_smoke_cloud(
+    graph: &mut Graph,
+    resource_manager: ResourceManager,
+    pos: Vector3<f32>,
+    radius: f32,
+) -> Handle<Node> {
+    ParticleSystemBuilder::new(
+        BaseBuilder::new().with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(pos + Vector3::new(0.0, radius * 0.5, 0.0))
+                .build(),
+        ),
+    )
+    .with_acceleration(Vector3::new(0.0, 0.0, 0.0))
+    .with_color_over_lifetime_gradient({
+        let mut gradient = ColorGradient::new();
+        gradient.add_point(GradientPoint::new(0.00, Color::from_rgba(180, 180, 180, 0)));
+        gradient.add_point(GradientPoint::new(
+            0.15,
+            Color::from_rgba(180, 180, 180, 255),
+        ));
+        gradient.add_point(GradientPoint::new(
+            0.85,
+            Color::from_rgba(200, 200, 200, 255),
+        ));
+        gradient.add_point(GradientPoint::new(1.00, Color::from_rgba(200, 200, 200, 0)));
+        gradient
+    })
+    .with_emitters(vec![SphereEmitterBuilder::new(
+        BaseEmitterBuilder::new()
+            .with_max_particles(300)
+            .with_spawn_rate(100)
+            .with_lifetime_range(3.0..5.0)
+            .with_size_range(0.5..0.9)
+            .with_x_velocity_range(-0.002..0.002)
+            .with_y_velocity_range(0.0..0.002)
+            .with_z_velocity_range(-0.002..0.002),
+    )
+    .with_radius(radius)
+    .build()])
+    .with_texture(resource_manager.request_texture(Path::new("data/particles/smoke_04.tga")))
+    .build(graph)
+}
@@ -135,7 +135,26 @@ impl CallButtonContainer {
                     .to_string(),
                 );
 
-                ui.set_floor_text(format!("Floor {}", call_button_ref.floor));
+                let floor = call_button_ref.floor;
+                ui.set_floor_text(match call_button_ref.kind {
+                    CallButtonKind::EndPoint => format!("Floor {}", floor),
+                    // Arrows show in which directions the selection can be changed.
+                    CallButtonKind::FloorSelector => {
+                        let last_floor = (elevator.points.len() as u32).saturating_sub(1);
+                        format!(
+                            "{} Floor {} {}",
+                            if floor > 0 { "<" } else { " " },
+                            floor,
+                            if floor < last_floor { ">" } else { " " }
+                        )
+                    }
+                });
+
+                ui.set_elevator_text(if elevator.current_floor != elevator.dest_floor {
+                    format!("{} -> {}", elevator.current_floor, elevator.dest_floor)
+                } else {
+                    format!("At {}", elevator.current_floor)
+                });
             }
         }
     }
@@ -6,13 +6,28 @@ use fyrox::{
         pool::{Handle, Pool},
         visitor::prelude::*,
     },
-    scene::{node::Node, Scene},
+    scene::{base::PropertyValue, node::Node, Scene},
 };
 use std::ops::{Index, IndexMut};
 
 pub mod call_button;
 pub mod ui;
 
+/// Distance at which elevators and call buttons can be used, unless a node specifies its own
+/// distance by `InteractionRadius` property.
+const DEFAULT_INTERACTION_RADIUS: f32 = 0.75;
+
+/// Returns distance at which an elevator or a call button represented by given node can be used.
+pub fn interaction_radius(node: &Node) -> f32 {
+    match node
+        .find_first_property_ref("InteractionRadius")
+        .map(|p| &p.value)
+    {
+        Some(PropertyValue::F32(radius)) => *radius,
+        _ => DEFAULT_INTERACTION_RADIUS,
+    }
+}
+
 #[derive(Default, Debug, Visit)]
 pub struct Elevator {
     pub current_floor: u32,
@@ -21,6 +21,7 @@ pub struct CallButtonUi {
     pub render_target: Texture,
     floor_text: Handle<UiNode>,
     text: Handle<UiNode>,
+    elevator_text: Handle<UiNode>,
 }
 
 impl InteractiveUi for CallButtonUi {
@@ -53,6 +54,7 @@ impl CallButtonUi {
 
         let text;
         let floor_text;
+        let elevator_text;
 
         BorderBuilder::new(
             WidgetBuilder::new()
@@ -66,7 +68,7 @@ impl CallButtonUi {
                                     WidgetBuilder::new()
                                         .on_row(0)
                                         .on_column(0)
-                                        .with_margin(Thickness::top(25.0)),
+                                        .with_margin(Thickness::top(10.0)),
                                 )
                                 .with_font(font.clone())
                                 .with_horizontal_text_alignment(HorizontalAlignment::Center)
@@ -84,14 +86,26 @@ impl CallButtonUi {
                                 )
                                 .with_text("Call?")
                                 .with_horizontal_text_alignment(HorizontalAlignment::Center)
-                                .with_font(font)
+                                .with_font(font.clone())
                                 .build(ctx);
                                 text
+                            })
+                            .with_child({
+                                elevator_text = TextBuilder::new(
+                                    WidgetBuilder::new().on_row(2).on_column(0).with_foreground(
+                                        Brush::Solid(Color::opaque(200, 200, 200)),
+                                    ),
+                                )
+                                .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                                .with_font(font)
+                                .build(ctx);
+                                elevator_text
                             }),
                     )
                     .add_column(Column::stretch())
                     .add_row(Row::stretch())
                     .add_row(Row::stretch())
+                    .add_row(Row::stretch())
                     .build(ctx),
                 ),
         )
@@ -102,6 +116,7 @@ impl CallButtonUi {
             render_target,
             text,
             floor_text,
+            elevator_text,
         }
     }
 
@@ -120,6 +135,15 @@ impl CallButtonUi {
             text,
         ));
     }
+
+    /// Sets text that describes where the elevator is right now.
+    pub fn set_elevator_text(&mut self, text: String) {
+        self.ui.send_message(TextMessage::text(
+            self.elevator_text,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
 }
 
 pub type CallButtonUiContainer = UiContainer<CallButton, CallButtonUi>;
@@ -0,0 +1,66 @@
+//! Factions decide who can damage whom. Every character belongs to a faction, members of the same
+//! faction and of allied factions are friends to each other. Friends do not hurt each other unless
+//! friendly fire is enabled, in which case a stray shot makes the victim retaliate and friends
+//! start to fight. Relations are stored in `data/configs/factions.ron`.
+
+use fyrox::{
+    core::visitor::{Visit, VisitResult, Visitor},
+    lazy_static::lazy_static,
+};
+use serde::Deserialize;
+use std::fs::File;
+
+#[derive(Deserialize, Copy, Clone, PartialEq, Eq, Hash, Debug, Visit)]
+pub enum Faction {
+    Crew,
+    Mutants,
+    Parasites,
+    Undead,
+    /// Members of the faction are not friends to anyone, not even to each other, so only
+    /// hostility of bots decides whom they attack.
+    Unaligned,
+}
+
+impl Default for Faction {
+    fn default() -> Self {
+        Self::Crew
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FactionDefinition {
+    /// If `true`, friends can damage each other.
+    pub friendly_fire: bool,
+    /// Pairs of different factions that are friends to each other.
+    pub alliances: Vec<(Faction, Faction)>,
+}
+
+impl FactionDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/factions.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    pub static ref FACTIONS: FactionDefinition = FactionDefinition::load();
+}
+
+impl Faction {
+    pub fn is_friend_of(self, other: Faction) -> bool {
+        if self == Faction::Unaligned || other == Faction::Unaligned {
+            return false;
+        }
+
+        self == other
+            || FACTIONS
+                .alliances
+                .iter()
+                .any(|&(a, b)| a == self && b == other || a == other && b == self)
+    }
+
+    /// Checks whether a member of the faction can damage a member of given faction.
+    pub fn can_damage(self, other: Faction) -> bool {
+        FACTIONS.friendly_fire || !self.is_friend_of(other)
+    }
+}
@@ -0,0 +1,76 @@
+//! Gamepad input. Only looking and aiming are supported for now, everything else is still done
+//! with keyboard and mouse.
+
+use fyrox::core::{
+    algebra::Vector2,
+    log::{Log, MessageKind},
+};
+use gilrs::{Axis, Button, GamepadId, Gilrs};
+
+/// Stick deflection below this value is ignored, worn out sticks never return exactly to zero.
+const STICK_DEAD_ZONE: f32 = 0.15;
+
+/// State of a gamepad sampled once per update.
+#[derive(Default, Copy, Clone, Debug)]
+pub struct GamepadInput {
+    /// Deflection of the right stick, up and right are positive.
+    pub look: Vector2<f32>,
+    /// Whether the left trigger is held.
+    pub aim: bool,
+}
+
+pub struct Gamepad {
+    gilrs: Option<Gilrs>,
+    /// Gamepad that sent the last event, its input is used.
+    active: Option<GamepadId>,
+}
+
+impl Gamepad {
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(e) => {
+                Log::writeln(
+                    MessageKind::Warning,
+                    format!("Gamepads are not available. Reason: {:?}", e),
+                );
+                None
+            }
+        };
+
+        Self {
+            gilrs,
+            active: None,
+        }
+    }
+
+    /// Processes pending events and returns current state of the active gamepad, if any.
+    pub fn poll(&mut self) -> Option<GamepadInput> {
+        let gilrs = self.gilrs.as_mut()?;
+
+        while let Some(event) = gilrs.next_event() {
+            self.active = Some(event.id);
+        }
+
+        let gamepad = gilrs.connected_gamepad(self.active?)?;
+
+        let mut look = Vector2::new(
+            gamepad.value(Axis::RightStickX),
+            gamepad.value(Axis::RightStickY),
+        );
+        if look.norm() < STICK_DEAD_ZONE {
+            look = Vector2::default();
+        }
+
+        Some(GamepadInput {
+            look,
+            aim: gamepad.is_pressed(Button::LeftTrigger2),
+        })
+    }
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,7 +3,15 @@ use crate::{
     control_scheme::{ControlButton, ControlScheme},
     item::{Item, ItemKind},
     message::Message,
-    player::Player,
+    player::{
+        hotbar::{Hotbar, HOTBAR_SIZE},
+        Player,
+    },
+    weapon::{
+        attachment::attachment_definition,
+        definition::{WeaponKind, WeaponStats},
+        Weapon, WeaponContainer,
+    },
     MessageSender,
 };
 use fyrox::{
@@ -40,7 +48,19 @@ pub struct InventoryInterface {
     is_enabled: bool,
     sender: MessageSender,
     item_description: Handle<UiNode>,
+    item_actions: Handle<UiNode>,
     scroll_viewer: Handle<UiNode>,
+    /// Names of buttons that use and drop the selected item.
+    use_button: String,
+    drop_button: String,
+    detach_button: String,
+    hotbar_buttons: String,
+    /// Physical state of the hotbar buttons, it is used to ignore key repeats.
+    hotbar_pressed: [bool; HOTBAR_SIZE],
+    /// Stats of weapons carried by the player, attachments are taken into account.
+    weapon_stats: Vec<(WeaponKind, WeaponStats)>,
+    /// Attachments mounted on weapons carried by the player.
+    weapon_attachments: Vec<(WeaponKind, Vec<ItemKind>)>,
 }
 
 #[derive(Debug, Clone)]
@@ -218,6 +238,7 @@ impl InventoryInterface {
 
         let items_panel;
         let item_description;
+        let item_actions;
         let scroll_viewer;
         BorderBuilder::new(
             WidgetBuilder::new()
@@ -287,6 +308,26 @@ impl InventoryInterface {
                                                                         .with_wrap(WrapMode::Word)
                                                                         .build(&mut ui.build_ctx());
                                                                     item_description
+                                                                })
+                                                                .with_child({
+                                                                    item_actions = TextBuilder::new(
+                                                                        WidgetBuilder::new()
+                                                                            .with_margin(
+                                                                                Thickness::top(
+                                                                                    4.0,
+                                                                                ),
+                                                                            )
+                                                                            .with_foreground(
+                                                                                Brush::Solid(
+                                                                                    Color::opaque(
+                                                                                        0, 162, 232,
+                                                                                    ),
+                                                                                ),
+                                                                            ),
+                                                                    )
+                                                                    .with_wrap(WrapMode::Word)
+                                                                    .build(&mut ui.build_ctx());
+                                                                    item_actions
                                                                 }),
                                                         )
                                                         .build(&mut ui.build_ctx()),
@@ -316,16 +357,68 @@ impl InventoryInterface {
             is_enabled: true,
             sender,
             item_description,
+            item_actions,
             scroll_viewer,
+            use_button: Default::default(),
+            drop_button: Default::default(),
+            detach_button: Default::default(),
+            hotbar_buttons: Default::default(),
+            hotbar_pressed: Default::default(),
+            weapon_stats: Default::default(),
+            weapon_attachments: Default::default(),
         }
     }
 
-    pub fn sync_to_model(&mut self, resource_manager: ResourceManager, player: &Player) {
+    /// Rebuilds the item list from the inventory of the player, selection is kept on the same
+    /// kind of item if it is still in the inventory.
+    pub fn sync_to_model(
+        &mut self,
+        resource_manager: ResourceManager,
+        player: &Player,
+        weapons: &WeaponContainer,
+        control_scheme: &ControlScheme,
+    ) {
+        self.use_button = control_scheme.action.button.name().to_owned();
+        self.drop_button = control_scheme.drop_item.button.name().to_owned();
+        self.detach_button = control_scheme.detach_attachments.button.name().to_owned();
+        self.hotbar_buttons = control_scheme
+            .hotbar_slots()
+            .iter()
+            .map(|slot| slot.button.name())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        self.weapon_stats = player
+            .weapons()
+            .iter()
+            .filter_map(|&handle| weapons.try_get(handle))
+            .map(|weapon| (weapon.kind(), weapon.stats()))
+            .collect();
+
+        self.weapon_attachments = player
+            .weapons()
+            .iter()
+            .filter_map(|&handle| weapons.try_get(handle))
+            .filter(|weapon| !weapon.attachments().is_empty())
+            .map(|weapon| (weapon.kind(), weapon.attachments().to_vec()))
+            .collect();
+
+        let selection = self.selection();
+        let selected_kind = if selection.is_some() {
+            self.ui
+                .node(selection)
+                .cast::<InventoryItem>()
+                .map(|item| item.item)
+        } else {
+            None
+        };
+
         for &child in self.ui.node(self.items_panel).children() {
             self.ui
                 .send_message(WidgetMessage::remove(child, MessageDirection::ToWidget));
         }
 
+        let mut selection = Handle::NONE;
         for item in player.inventory().items() {
             let ctx = &mut self.ui.build_ctx();
 
@@ -343,7 +436,91 @@ impl InventoryInterface {
                 MessageDirection::ToWidget,
                 self.items_panel,
             ));
+
+            if selection.is_none() || selected_kind == Some(item.kind()) {
+                selection = widget;
+            }
+        }
+
+        if selection.is_some() {
+            self.ui.send_message(InventoryItemMessage::select(
+                selection,
+                MessageDirection::ToWidget,
+                true,
+            ));
+        } else {
+            self.ui.send_message(TextMessage::text(
+                self.item_description,
+                MessageDirection::ToWidget,
+                "Inventory is empty.".to_owned(),
+            ));
+            self.ui.send_message(TextMessage::text(
+                self.item_actions,
+                MessageDirection::ToWidget,
+                Default::default(),
+            ));
+        }
+    }
+
+    /// Returns a hint with buttons that can be used with given item.
+    fn actions_hint(&self, item: ItemKind) -> String {
+        let definition = Item::get_definition(item);
+        let mut hint = String::new();
+        if definition.consumable {
+            hint += &format!("[{}] Use\n", self.use_button);
+        } else if let Some(kind) = item.associated_weapon() {
+            hint += &format!("[{}] Equip\n", self.use_button);
+            if let Some((_, attachments)) = self
+                .weapon_attachments
+                .iter()
+                .find(|(weapon_kind, _)| *weapon_kind == kind)
+            {
+                hint += &format!(
+                    "[{}] Detach ({})\n",
+                    self.detach_button,
+                    attachments
+                        .iter()
+                        .map(|&attachment| Item::get_definition(attachment).name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        } else if let Some(attachment) = attachment_definition(item) {
+            hint += &format!(
+                "[{}] Attach ({})\n",
+                self.use_button,
+                attachment.slot.name()
+            );
+        }
+        if Hotbar::can_hold(item) {
+            hint += &format!("[{}] Hotbar\n", self.hotbar_buttons);
         }
+        hint += &format!("[{}] Drop", self.drop_button);
+        hint
+    }
+
+    /// Returns a summary of weapon characteristics that is shown under the description of
+    /// a weapon.
+    fn stats_summary(stats: &WeaponStats) -> String {
+        let mut summary = format!("Damage: {:.0}", stats.damage);
+        if let Some(radius) = stats.splash_radius {
+            summary += &format!(" (splash {:.1} m)", radius);
+        }
+        summary += &format!(
+            "\nRate of fire: {:.0} rpm\nRecoil: {:.1} deg\nCritical chance: {:.0}%\n\
+             Ammo per shot: {}\nModes: {}",
+            stats.rate_of_fire,
+            stats.recoil,
+            stats.critical_shot_probability * 100.0,
+            stats.ammo_per_shot,
+            stats
+                .fire_modes
+                .iter()
+                .map(|mode| mode.name())
+                .collect::<Vec<_>>()
+                .join("/")
+        );
+        summary
     }
 
     pub fn selection(&self) -> Handle<UiNode> {
@@ -472,6 +649,12 @@ impl InventoryInterface {
                                             kind: associated_weapon,
                                             actor: player_handle,
                                         });
+                                    } else if attachment_definition(item.item).is_some() {
+                                        self.sender.send(Message::AttachItem {
+                                            actor: player_handle,
+                                            item: item.item,
+                                        });
+                                        self.sender.send(Message::SyncInventory);
                                     }
                                 } else {
                                     unreachable!()
@@ -479,6 +662,25 @@ impl InventoryInterface {
                             }
                         }
                     }
+                    if let ControlButton::Key(key) = control_scheme.detach_attachments.button {
+                        if fyrox::utils::translate_key(key) == button {
+                            let selection = self.selection();
+                            if selection.is_some() {
+                                if let Some(weapon) = self
+                                    .ui
+                                    .node(selection)
+                                    .cast::<InventoryItem>()
+                                    .and_then(|item| item.item.associated_weapon())
+                                {
+                                    self.sender.send(Message::DetachAttachments {
+                                        actor: player_handle,
+                                        weapon,
+                                    });
+                                    self.sender.send(Message::SyncInventory);
+                                }
+                            }
+                        }
+                    }
                     if let ControlButton::Key(key) = control_scheme.drop_item.button {
                         if fyrox::utils::translate_key(key) == button {
                             let selection = self.selection();
@@ -498,6 +700,27 @@ impl InventoryInterface {
                         }
                     }
                 }
+
+                for (index, slot) in control_scheme.hotbar_slots().iter().enumerate() {
+                    if let ControlButton::Key(key) = slot.button {
+                        if fyrox::utils::translate_key(key) == button {
+                            // Key repeats of a held button must not reassign the slot.
+                            let pressed = state == ButtonState::Pressed;
+                            if pressed && !self.hotbar_pressed[index] && player.is_inventory_open()
+                            {
+                                let selection = self.selection();
+                                if selection.is_some() {
+                                    if let Some(item) =
+                                        self.ui.node(selection).cast::<InventoryItem>()
+                                    {
+                                        player.hotbar_mut().assign(index, item.item);
+                                    }
+                                }
+                            }
+                            self.hotbar_pressed[index] = pressed;
+                        }
+                    }
+                }
             }
         }
     }
@@ -521,10 +744,28 @@ impl InventoryInterface {
                             }
                         }
 
+                        let mut description = definition.description.clone();
+                        if let Some(kind) = item.item.associated_weapon() {
+                            // Prefer stats of the carried weapon, they include its attachments.
+                            let stats = self
+                                .weapon_stats
+                                .iter()
+                                .find(|(weapon_kind, _)| *weapon_kind == kind)
+                                .map(|(_, stats)| stats.clone())
+                                .unwrap_or_else(|| Weapon::definition(kind).stats());
+                            description += "\n\n";
+                            description += &Self::stats_summary(&stats);
+                        }
+
                         self.ui.send_message(TextMessage::text(
                             self.item_description,
                             MessageDirection::ToWidget,
-                            definition.description.clone(),
+                            description,
+                        ));
+                        self.ui.send_message(TextMessage::text(
+                            self.item_actions,
+                            MessageDirection::ToWidget,
+                            self.actions_hint(item.item),
                         ));
                     } else {
                         unreachable!();
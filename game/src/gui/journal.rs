@@ -1,29 +1,39 @@
 #![allow(dead_code)] // TODO
 
-use crate::control_scheme::{ControlButton, ControlScheme};
+use crate::{
+    control_scheme::{ControlButton, ControlScheme},
+    message::Message,
+    MessageSender,
+};
 use fyrox::{
     core::{
-        algebra::Vector2,
+        algebra::{Vector2, Vector3},
         pool::Handle,
         visitor::{Visit, VisitResult, Visitor},
     },
+    event::{ElementState, Event, WindowEvent},
     gui::{
         border::BorderBuilder,
         decorator::DecoratorBuilder,
         formatted_text::WrapMode,
         grid::{Column, GridBuilder, Row},
         list_view::{ListViewBuilder, ListViewMessage},
-        message::{ButtonState, MessageDirection, OsEvent},
+        message::{ButtonState, KeyCode, MessageDirection, OsEvent},
         scroll_viewer::ScrollViewerBuilder,
         text::{TextBuilder, TextMessage},
-        widget::WidgetBuilder,
-        UiNode, UserInterface,
+        text_box::TextBoxBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
+        Thickness, UiNode, UserInterface, VerticalAlignment,
     },
     lazy_static::lazy_static,
     resource::texture::Texture,
 };
 use serde::Deserialize;
-use std::{collections::HashMap, fs::File};
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+};
 
 #[derive(Deserialize, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Visit)]
 #[repr(u32)]
@@ -37,10 +47,41 @@ impl Default for JournalEntryKind {
     }
 }
 
+#[derive(Deserialize, Copy, Clone, PartialEq, Eq, Debug, Visit)]
+#[repr(u32)]
+pub enum JournalCategory {
+    Story,
+    Notes,
+    Objectives,
+}
+
+impl Default for JournalCategory {
+    fn default() -> Self {
+        Self::Story
+    }
+}
+
+impl JournalCategory {
+    const ALL: [JournalCategory; 3] = [
+        JournalCategory::Story,
+        JournalCategory::Notes,
+        JournalCategory::Objectives,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            JournalCategory::Story => "Story",
+            JournalCategory::Notes => "Notes",
+            JournalCategory::Objectives => "Objectives",
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct JournalEntryDefinition {
     pub title: String,
     pub text: String,
+    pub category: JournalCategory,
 }
 
 #[derive(Deserialize, Default)]
@@ -55,9 +96,35 @@ impl JournalEntryDefinitionContainer {
     }
 }
 
+#[derive(Deserialize)]
+pub struct AudioLogDefinition {
+    pub title: String,
+    /// Recording of the log, logs without it have the transcript only.
+    #[serde(default)]
+    pub sound: Option<PathBuf>,
+    pub transcript: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct AudioLogDefinitionContainer {
+    map: HashMap<String, AudioLogDefinition>,
+}
+
+impl AudioLogDefinitionContainer {
+    pub fn new() -> Self {
+        let file = File::open("data/configs/audio_logs.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
 lazy_static! {
     static ref DEFINITIONS: JournalEntryDefinitionContainer =
         JournalEntryDefinitionContainer::new();
+    static ref AUDIO_LOGS: AudioLogDefinitionContainer = AudioLogDefinitionContainer::new();
+}
+
+pub fn audio_log_definition(id: &str) -> Option<&'static AudioLogDefinition> {
+    AUDIO_LOGS.map.get(id)
 }
 
 impl JournalEntryKind {
@@ -66,44 +133,219 @@ impl JournalEntryKind {
     }
 }
 
-#[derive(Default, Visit)]
+#[derive(Visit, Clone, Debug)]
+pub enum JournalEntry {
+    /// Entry defined in the journal config.
+    Predefined(JournalEntryKind),
+    /// Entry created while playing, for example when an objective was completed.
+    Custom {
+        title: String,
+        text: String,
+        category: JournalCategory,
+    },
+    /// Audio log collected from a data pad, stores id of a log from the audio logs config.
+    AudioLog(String),
+}
+
+impl Default for JournalEntry {
+    fn default() -> Self {
+        Self::Predefined(Default::default())
+    }
+}
+
+impl JournalEntry {
+    pub fn title(&self) -> &str {
+        match self {
+            JournalEntry::Predefined(kind) => &kind.get_definition().title,
+            JournalEntry::Custom { title, .. } => title,
+            JournalEntry::AudioLog(id) => audio_log_definition(id).map_or(id, |d| &d.title),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            JournalEntry::Predefined(kind) => &kind.get_definition().text,
+            JournalEntry::Custom { text, .. } => text,
+            JournalEntry::AudioLog(id) => {
+                audio_log_definition(id).map_or("", |d| d.transcript.as_str())
+            }
+        }
+    }
+
+    pub fn category(&self) -> JournalCategory {
+        match self {
+            JournalEntry::Predefined(kind) => kind.get_definition().category,
+            JournalEntry::Custom { category, .. } => *category,
+            JournalEntry::AudioLog(_) => JournalCategory::Notes,
+        }
+    }
+
+    /// Returns path to a sound file of the entry if it is an audio log.
+    pub fn sound(&self) -> Option<&Path> {
+        match self {
+            JournalEntry::AudioLog(id) => audio_log_definition(id).and_then(|d| d.sound.as_deref()),
+            _ => None,
+        }
+    }
+
+    /// Checks whether title or text of the entry contains given lowercase string.
+    fn matches(&self, pattern: &str) -> bool {
+        pattern.is_empty()
+            || self.title().to_lowercase().contains(pattern)
+            || self.text().to_lowercase().contains(pattern)
+    }
+}
+
+#[derive(Default, Visit, Clone, Debug)]
+pub struct Objective {
+    pub id: String,
+    pub description: String,
+    pub completed: bool,
+    /// A point in the world where the objective should be done, the marker points to it.
+    pub target: Option<Vector3<f32>>,
+}
+
+#[derive(Default, Visit, Clone)]
 pub struct Journal {
+    /// Saves made by older versions store only kinds of predefined entries, such entries are
+    /// moved to `entries` on load.
+    #[visit(optional)]
     messages: Vec<JournalEntryKind>,
+    #[visit(optional)]
+    entries: Vec<JournalEntry>,
+    #[visit(optional)]
+    objectives: Vec<Objective>,
 }
 
 impl Journal {
     pub fn new() -> Self {
         Self {
-            messages: vec![JournalEntryKind::CurrentSituation],
+            messages: Default::default(),
+            entries: vec![JournalEntry::Predefined(JournalEntryKind::CurrentSituation)],
+            objectives: Default::default(),
+        }
+    }
+
+    pub fn add_entry(&mut self, entry: JournalEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Adds an audio log to the journal, returns `false` if the log was already collected.
+    pub fn add_audio_log(&mut self, id: &str) -> bool {
+        let collected = self
+            .entries
+            .iter()
+            .any(|entry| matches!(entry, JournalEntry::AudioLog(log) if log == id));
+        if collected {
+            false
+        } else {
+            self.add_entry(JournalEntry::AudioLog(id.to_owned()));
+            true
+        }
+    }
+
+    /// Adds new objective, does nothing if there is an objective with the same id already.
+    pub fn add_objective(
+        &mut self,
+        id: &str,
+        description: &str,
+        target: Option<Vector3<f32>>,
+    ) -> bool {
+        if self.objectives.iter().any(|o| o.id == id) {
+            return false;
+        }
+
+        self.objectives.push(Objective {
+            id: id.to_owned(),
+            description: description.to_owned(),
+            completed: false,
+            target,
+        });
+
+        true
+    }
+
+    /// Marks objective as completed and adds a journal entry about it.
+    pub fn complete_objective(&mut self, id: &str) -> bool {
+        if let Some(objective) = self
+            .objectives
+            .iter_mut()
+            .find(|o| o.id == id && !o.completed)
+        {
+            objective.completed = true;
+            let entry = JournalEntry::Custom {
+                title: "Objective Completed".to_owned(),
+                text: objective.description.clone(),
+                category: JournalCategory::Objectives,
+            };
+            self.add_entry(entry);
+            true
+        } else {
+            false
         }
     }
+
+    /// Returns a copy of the journal that can be carried over to another level. Objective markers
+    /// are dropped, because they point to places on the current level.
+    pub fn for_next_level(&self) -> Self {
+        let mut journal = self.clone();
+        for objective in journal.objectives.iter_mut() {
+            objective.target = None;
+        }
+        journal
+    }
+
+    /// Converts entries of a save made by an older version.
+    pub fn resolve(&mut self) {
+        let legacy = std::mem::take(&mut self.messages);
+        self.entries
+            .splice(0..0, legacy.into_iter().map(JournalEntry::Predefined));
+    }
+
+    /// Returns the first objective that is not completed yet.
+    pub fn active_objective(&self) -> Option<&Objective> {
+        self.objectives.iter().find(|o| !o.completed)
+    }
 }
 
+/// Shown when there is no active objective.
+const MAIN_OBJECTIVE: &str = "Investigate the reasons why connection with the station was lost.";
+
 pub struct JournalDisplay {
     pub ui: UserInterface,
     pub render_target: Texture,
     objective: Handle<UiNode>,
+    category: Handle<UiNode>,
+    search: Handle<UiNode>,
     messages: Handle<UiNode>,
     message_text: Handle<UiNode>,
     current_message: Option<usize>,
-}
-
-impl Default for JournalDisplay {
-    fn default() -> Self {
-        Self::new()
-    }
+    /// Indices of journal entries that are shown in the list.
+    visible_entries: Vec<usize>,
+    /// `None` means that entries of every category are shown.
+    category_filter: Option<JournalCategory>,
+    /// Lowercase search pattern.
+    search_pattern: String,
+    /// Sound of the selected entry if it is an audio log.
+    selected_sound: Option<PathBuf>,
+    sender: MessageSender,
+    /// `true` if the list of entries must be rebuilt because filters were changed.
+    need_sync: bool,
+    is_open: bool,
 }
 
 impl JournalDisplay {
     pub const WIDTH: f32 = 400.0;
     pub const HEIGHT: f32 = 300.0;
 
-    pub fn new() -> Self {
+    pub fn new(sender: MessageSender) -> Self {
         let mut ui = UserInterface::new(Vector2::new(Self::WIDTH, Self::HEIGHT));
 
         let render_target = Texture::new_render_target(Self::WIDTH as u32, Self::HEIGHT as u32);
 
         let objective;
+        let category;
+        let search;
         let messages;
         let message_text;
         BorderBuilder::new(
@@ -116,7 +358,7 @@ impl JournalDisplay {
                             .with_child({
                                 objective =
                                     TextBuilder::new(WidgetBuilder::new().on_row(0).on_column(0))
-                                        .with_text("Investigate the reasons why connection with the station was lost.")
+                                        .with_text(MAIN_OBJECTIVE)
                                         .with_wrap(WrapMode::Word)
                                         .build(&mut ui.build_ctx());
                                 objective
@@ -126,6 +368,39 @@ impl JournalDisplay {
                                     WidgetBuilder::new()
                                         .on_row(1)
                                         .on_column(0)
+                                        .with_child({
+                                            category = TextBuilder::new(
+                                                WidgetBuilder::new()
+                                                    .on_column(0)
+                                                    .with_vertical_alignment(
+                                                        VerticalAlignment::Center,
+                                                    ),
+                                            )
+                                            .with_text(category_text(None))
+                                            .build(&mut ui.build_ctx());
+                                            category
+                                        })
+                                        .with_child({
+                                            search = TextBoxBuilder::new(
+                                                WidgetBuilder::new()
+                                                    .on_column(1)
+                                                    .with_margin(Thickness::uniform(1.0)),
+                                            )
+                                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                                            .build(&mut ui.build_ctx());
+                                            search
+                                        }),
+                                )
+                                .add_row(Row::stretch())
+                                .add_column(Column::strict(150.0))
+                                .add_column(Column::stretch())
+                                .build(&mut ui.build_ctx()),
+                            )
+                            .with_child(
+                                GridBuilder::new(
+                                    WidgetBuilder::new()
+                                        .on_row(2)
+                                        .on_column(0)
                                         .with_child({
                                             messages = ListViewBuilder::new(
                                                 WidgetBuilder::new().on_column(0).on_row(0),
@@ -154,6 +429,7 @@ impl JournalDisplay {
                             ),
                     )
                     .add_row(Row::strict(60.0))
+                    .add_row(Row::strict(24.0))
                     .add_row(Row::stretch())
                     .add_column(Column::stretch())
                     .build(&mut ui.build_ctx()),
@@ -166,21 +442,103 @@ impl JournalDisplay {
             ui,
             render_target,
             objective,
+            category,
+            search,
             messages,
             message_text,
+            visible_entries: Default::default(),
+            category_filter: None,
+            search_pattern: Default::default(),
+            selected_sound: None,
+            sender,
+            need_sync: false,
+            is_open: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Must be called when the journal is opened or closed. Search box takes keyboard focus
+    /// while the journal is open.
+    pub fn set_open(&mut self, open: bool) {
+        self.is_open = open;
+        if open {
+            self.ui.send_message(WidgetMessage::focus(
+                self.search,
+                MessageDirection::ToWidget,
+            ));
+        } else {
+            self.ui.send_message(WidgetMessage::unfocus(
+                self.search,
+                MessageDirection::ToWidget,
+            ));
+            self.sender.send(Message::StopAudioLog);
+        }
+    }
+
+    /// Returns `true` if the event must not be passed to the game, because it is used to type
+    /// a search request. Journal button is passed anyway so the journal can be closed.
+    pub fn captures_input(&self, event: &Event<()>, control_scheme: &ControlScheme) -> bool {
+        if !self.is_open {
+            return false;
+        }
+
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::ReceivedCharacter(_),
+                ..
+            } => true,
+            // Releases are passed through, otherwise keys that were held when the journal was
+            // opened would stay pressed after it is closed.
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { input, .. },
+                ..
+            } => {
+                input.state == ElementState::Pressed
+                    && input.virtual_keycode.map_or(true, |key| {
+                        ControlButton::Key(key) != control_scheme.journal.button
+                    })
+            }
+            _ => false,
         }
     }
 
     pub fn sync_to_model(&mut self, journal: &Journal) {
-        let items = journal
-            .messages
+        self.need_sync = false;
+
+        self.ui.send_message(TextMessage::text(
+            self.objective,
+            MessageDirection::ToWidget,
+            journal
+                .active_objective()
+                .map_or(MAIN_OBJECTIVE, |o| o.description.as_str())
+                .to_owned(),
+        ));
+
+        self.visible_entries = journal
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                self.category_filter
+                    .map_or(true, |category| entry.category() == category)
+                    && entry.matches(&self.search_pattern)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        self.current_message = None;
+        self.selected_sound = None;
+
+        let items = self
+            .visible_entries
             .iter()
-            .map(|i| {
-                let definition = i.get_definition();
+            .map(|&index| {
                 DecoratorBuilder::new(BorderBuilder::new(
                     WidgetBuilder::new().with_child(
                         TextBuilder::new(WidgetBuilder::new())
-                            .with_text(&definition.title)
+                            .with_text(journal.entries[index].title())
                             .build(&mut self.ui.build_ctx()),
                     ),
                 ))
@@ -192,9 +550,45 @@ impl JournalDisplay {
             MessageDirection::ToWidget,
             items,
         ));
+        self.ui.send_message(TextMessage::text(
+            self.message_text,
+            MessageDirection::ToWidget,
+            Default::default(),
+        ));
+    }
+
+    fn cycle_category(&mut self, forward: bool) {
+        let all = JournalCategory::ALL;
+        let position = self
+            .category_filter
+            .and_then(|category| all.iter().position(|c| *c == category));
+        // `None` (all categories) goes before the first category and after the last.
+        self.category_filter = if forward {
+            match position {
+                None => all.first().cloned(),
+                Some(n) => all.get(n + 1).cloned(),
+            }
+        } else {
+            match position {
+                None => all.last().cloned(),
+                Some(0) => None,
+                Some(n) => all.get(n - 1).cloned(),
+            }
+        };
+
+        self.ui.send_message(TextMessage::text(
+            self.category,
+            MessageDirection::ToWidget,
+            category_text(self.category_filter),
+        ));
+        self.need_sync = true;
     }
 
     pub fn process_os_event(&mut self, os_event: &OsEvent, control_scheme: &ControlScheme) {
+        if !self.is_open {
+            return;
+        }
+
         self.ui.process_os_event(os_event);
 
         if let OsEvent::KeyboardInput { button, state } = *os_event {
@@ -216,7 +610,9 @@ impl JournalDisplay {
                     if fyrox::utils::translate_key(key) == button {
                         self.current_message = match self.current_message {
                             None => Some(0),
-                            Some(n) => Some(n + 1),
+                            Some(n) => {
+                                Some((n + 1).min(self.visible_entries.len().saturating_sub(1)))
+                            }
                         };
                         self.ui.send_message(ListViewMessage::selection(
                             self.messages,
@@ -225,6 +621,21 @@ impl JournalDisplay {
                         ));
                     }
                 }
+                if let ControlButton::Key(key) = control_scheme.cursor_left.button {
+                    if fyrox::utils::translate_key(key) == button {
+                        self.cycle_category(false);
+                    }
+                }
+                if let ControlButton::Key(key) = control_scheme.cursor_right.button {
+                    if fyrox::utils::translate_key(key) == button {
+                        self.cycle_category(true);
+                    }
+                }
+                if button == KeyCode::Return {
+                    if let Some(path) = self.selected_sound.clone() {
+                        self.sender.send(Message::ToggleAudioLog { path });
+                    }
+                }
             }
         }
     }
@@ -234,17 +645,46 @@ impl JournalDisplay {
             .update(Vector2::new(Self::WIDTH, Self::HEIGHT), delta);
 
         while let Some(message) = self.ui.poll_message() {
+            if message.direction() != MessageDirection::FromWidget {
+                continue;
+            }
+
             if let Some(ListViewMessage::SelectionChanged(Some(value))) = message.data() {
-                if message.direction() == MessageDirection::FromWidget {
-                    if let Some(entry) = journal.messages.get(*value) {
-                        self.ui.send_message(TextMessage::text(
-                            self.message_text,
-                            MessageDirection::ToWidget,
-                            entry.get_definition().text.clone(),
-                        ));
-                    }
+                if let Some(entry) = self
+                    .visible_entries
+                    .get(*value)
+                    .and_then(|&index| journal.entries.get(index))
+                {
+                    self.selected_sound = entry.sound().map(|path| path.to_path_buf());
+
+                    let text = if let Some(path) = self.selected_sound.clone() {
+                        // Selecting an audio log starts it immediately.
+                        self.sender.send(Message::PlayAudioLog { path });
+                        format!("[Enter] - Stop/Replay\n\n{}", entry.text())
+                    } else {
+                        entry.text().to_owned()
+                    };
+
+                    self.ui.send_message(TextMessage::text(
+                        self.message_text,
+                        MessageDirection::ToWidget,
+                        text,
+                    ));
+                }
+            } else if let Some(TextMessage::Text(text)) = message.data() {
+                if message.destination() == self.search {
+                    self.search_pattern = text.to_lowercase();
+                    self.need_sync = true;
                 }
             }
         }
+
+        if self.need_sync {
+            self.sync_to_model(journal);
+        }
     }
 }
+
+fn category_text(category: Option<JournalCategory>) -> String {
+    format!("< {} >", category.map_or("All", |c| c.name()))
+}
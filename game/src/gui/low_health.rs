@@ -0,0 +1,175 @@
+//! Low health feedback: below a health threshold the screen is framed by a red vignette and
+//! a heartbeat is heard, both intensify as health drops. Tunables are stored in
+//! `data/configs/low_health.ron`.
+
+use fyrox::{
+    core::{futures::executor::block_on, pool::Handle},
+    engine::resource_manager::ResourceManager,
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        core::color::Color,
+        message::MessageDirection,
+        widget::{WidgetBuilder, WidgetMessage},
+        Thickness, UiNode, UserInterface,
+    },
+    lazy_static::lazy_static,
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        node::Node,
+        sound::{SoundBuilder, Status},
+    },
+    utils::log::{Log, MessageKind},
+};
+use serde::Deserialize;
+use std::{fs::File, path::PathBuf};
+
+/// Amount of nested frames the vignette consists of, inner frames are more transparent.
+const VIGNETTE_LAYERS: usize = 5;
+
+#[derive(Deserialize)]
+pub struct LowHealthDefinition {
+    /// Feedback starts below this amount of health.
+    pub threshold: f32,
+    pub max_vignette_opacity: f32,
+    /// Width of the vignette as a fraction of the screen height.
+    pub vignette_size: f32,
+    pub heartbeat_sound: PathBuf,
+    pub heartbeat_gain: f32,
+}
+
+impl LowHealthDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/low_health.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    pub static ref LOW_HEALTH: LowHealthDefinition = LowHealthDefinition::load();
+}
+
+pub struct LowHealthFeedback {
+    pub root: Handle<UiNode>,
+    layers: Vec<Handle<UiNode>>,
+    intensity: f32,
+    heartbeat: Handle<Node>,
+}
+
+impl LowHealthFeedback {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let thickness = ui.screen_size().y * LOW_HEALTH.vignette_size / VIGNETTE_LAYERS as f32;
+
+        // Layers are built from the innermost one, the outermost one is the root.
+        let mut layers = Vec::new();
+        let mut inner = Handle::NONE;
+        for i in 0..VIGNETTE_LAYERS {
+            let mut builder = WidgetBuilder::new().with_hit_test_visibility(false);
+            if inner.is_some() {
+                builder = builder.with_child(inner);
+            }
+            if i == VIGNETTE_LAYERS - 1 {
+                builder = builder
+                    .with_visibility(false)
+                    .with_width(ui.screen_size().x)
+                    .with_height(ui.screen_size().y);
+            }
+            inner = BorderBuilder::new(builder)
+                .with_stroke_thickness(Thickness::uniform(thickness))
+                .build(&mut ui.build_ctx());
+            layers.push(inner);
+        }
+        layers.reverse();
+        let root = inner;
+
+        Self {
+            root,
+            layers,
+            intensity: 0.0,
+            heartbeat: Handle::NONE,
+        }
+    }
+
+    pub fn resize(&self, ui: &UserInterface, width: f32, height: f32) {
+        ui.send_message(WidgetMessage::width(
+            self.root,
+            MessageDirection::ToWidget,
+            width,
+        ));
+        ui.send_message(WidgetMessage::height(
+            self.root,
+            MessageDirection::ToWidget,
+            height,
+        ));
+    }
+
+    fn set_heartbeat(&mut self, graph: &mut Graph, resource_manager: &ResourceManager) {
+        if self.intensity <= 0.0 {
+            if graph.is_valid_handle(self.heartbeat) {
+                graph.remove_node(self.heartbeat);
+            }
+            self.heartbeat = Handle::NONE;
+            return;
+        }
+
+        if !graph.is_valid_handle(self.heartbeat) {
+            match block_on(resource_manager.request_sound_buffer(&LOW_HEALTH.heartbeat_sound)) {
+                Ok(buffer) => {
+                    self.heartbeat = SoundBuilder::new(BaseBuilder::new())
+                        .with_buffer(buffer.into())
+                        .with_looping(true)
+                        .with_status(Status::Playing)
+                        .build(graph);
+                }
+                Err(_) => {
+                    Log::writeln(
+                        MessageKind::Error,
+                        format!(
+                            "Unable to load heartbeat sound {:?}",
+                            LOW_HEALTH.heartbeat_sound
+                        ),
+                    );
+                    return;
+                }
+            }
+        }
+
+        graph[self.heartbeat]
+            .as_sound_mut()
+            .set_gain(LOW_HEALTH.heartbeat_gain * (0.3 + 0.7 * self.intensity));
+    }
+
+    /// Sets intensity of the feedback in `[0; 1]` range, zero turns it off. The heartbeat is
+    /// played in given graph, it must not be affected by pausing of a level.
+    pub fn set_intensity(
+        &mut self,
+        ui: &UserInterface,
+        graph: &mut Graph,
+        resource_manager: &ResourceManager,
+        intensity: f32,
+    ) {
+        if self.intensity == intensity {
+            return;
+        }
+
+        self.intensity = intensity;
+
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            intensity > 0.0,
+        ));
+        for (i, &layer) in self.layers.iter().enumerate() {
+            let falloff = 1.0 - i as f32 / VIGNETTE_LAYERS as f32;
+            let alpha = intensity * LOW_HEALTH.max_vignette_opacity * falloff * falloff;
+            ui.send_message(WidgetMessage::foreground(
+                layer,
+                MessageDirection::ToWidget,
+                Brush::Solid(Color::from_rgba(160, 0, 0, (alpha * 255.0) as u8)),
+            ));
+        }
+
+        self.set_heartbeat(graph, resource_manager);
+    }
+}
@@ -2,30 +2,45 @@
 //! However most of the styles are used from dark theme of rg3d-ui library so there
 //! is not much.
 
-use crate::{message::Message, MessageSender};
+use crate::{
+    gui::{inventory::InventoryInterface, journal::JournalDisplay, weapon_display::WeaponDisplay},
+    item::{Item, ItemKind},
+    message::Message,
+    status::StatusEffectKind,
+    weapon::definition::{PictureInPictureDefinition, ScopeDefinition},
+    MessageSender,
+};
 use fyrox::{
-    core::pool::Handle,
+    core::{algebra::Vector2, pool::Handle},
+    engine::resource_manager::ResourceManager,
     gui::{
         border::BorderBuilder,
         brush::Brush,
         button::{ButtonBuilder, ButtonMessage},
+        canvas::CanvasBuilder,
         check_box::CheckBoxBuilder,
         core::color::Color,
         grid::{Column, GridBuilder, Row},
+        image::{ImageBuilder, ImageMessage},
         message::{MessageDirection, UiMessage},
         scroll_bar::ScrollBarBuilder,
         stack_panel::StackPanelBuilder,
-        text::TextBuilder,
+        text::{TextBuilder, TextMessage},
         ttf::SharedFont,
         widget::{WidgetBuilder, WidgetMessage},
         BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
         VerticalAlignment,
     },
+    resource::texture::Texture,
+    utils,
 };
 
 pub mod inventory;
 pub mod item_display;
 pub mod journal;
+pub mod low_health;
+pub mod speed_lines;
+pub mod terminal;
 pub mod weapon_display;
 
 pub struct ScrollBarData {
@@ -83,6 +98,7 @@ pub fn create_check_box(
 
 pub struct DeathScreen {
     pub root: Handle<UiNode>,
+    stats: Handle<UiNode>,
     load_game: Handle<UiNode>,
     exit_to_menu: Handle<UiNode>,
     exit_game: Handle<UiNode>,
@@ -91,6 +107,7 @@ pub struct DeathScreen {
 
 impl DeathScreen {
     pub fn new(ui: &mut UserInterface, font: SharedFont, sender: MessageSender) -> Self {
+        let stats;
         let load_game;
         let exit_to_menu;
         let exit_game;
@@ -122,6 +139,17 @@ impl DeathScreen {
                                         .with_vertical_alignment(VerticalAlignment::Top)
                                         .on_row(1)
                                         .on_column(1)
+                                        .with_child({
+                                            stats = TextBuilder::new(
+                                                WidgetBuilder::new()
+                                                    .with_margin(Thickness::uniform(2.0)),
+                                            )
+                                            .with_horizontal_text_alignment(
+                                                HorizontalAlignment::Center,
+                                            )
+                                            .build(&mut ui.build_ctx());
+                                            stats
+                                        })
                                         .with_child({
                                             load_game = ButtonBuilder::new(
                                                 WidgetBuilder::new()
@@ -168,6 +196,7 @@ impl DeathScreen {
 
         Self {
             root,
+            stats,
             load_game,
             exit_to_menu,
             exit_game,
@@ -195,13 +224,902 @@ impl DeathScreen {
         ));
     }
 
+    /// Sets text with statistics of the finished session.
+    pub fn set_stats(&self, ui: &UserInterface, text: String) {
+        ui.send_message(TextMessage::text(
+            self.stats,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
+
     pub fn is_visible(&self, ui: &UserInterface) -> bool {
         ui.node(self.root).visibility()
     }
 }
 
+/// Full-screen white overlay which is shown when the player is blinded by a flashbang.
+pub struct FlashOverlay {
+    pub root: Handle<UiNode>,
+    intensity: f32,
+}
+
+impl FlashOverlay {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let root = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_width(ui.screen_size().x)
+                .with_height(ui.screen_size().y)
+                .with_background(Brush::Solid(Color::WHITE)),
+        )
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            intensity: 0.0,
+        }
+    }
+
+    pub fn set_intensity(&mut self, ui: &UserInterface, intensity: f32) {
+        if self.intensity == intensity {
+            return;
+        }
+
+        self.intensity = intensity;
+
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            intensity > 0.0,
+        ));
+        ui.send_message(WidgetMessage::background(
+            self.root,
+            MessageDirection::ToWidget,
+            Brush::Solid(Color::from_rgba(255, 255, 255, (intensity * 255.0) as u8)),
+        ));
+    }
+}
+
+/// Shows active objective of the player at the top-left corner of the screen.
+pub struct ObjectiveHud {
+    pub root: Handle<UiNode>,
+    text: String,
+}
+
+impl ObjectiveHud {
+    pub fn new(ui: &mut UserInterface, font: SharedFont) -> Self {
+        let root = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness::uniform(10.0))
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .with_vertical_alignment(VerticalAlignment::Top)
+                .with_foreground(Brush::Solid(Color::opaque(0, 162, 232))),
+        )
+        .with_font(font)
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            text: Default::default(),
+        }
+    }
+
+    /// Sets description of active objective and distance to its target (if any).
+    pub fn set_objective(&mut self, ui: &UserInterface, objective: Option<(&str, Option<f32>)>) {
+        let text = match objective {
+            Some((description, Some(distance))) => {
+                format!("Objective: {} ({:.0} m)", description, distance)
+            }
+            Some((description, None)) => format!("Objective: {}", description),
+            None => Default::default(),
+        };
+
+        if self.text == text {
+            return;
+        }
+
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            !text.is_empty(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.root,
+            MessageDirection::ToWidget,
+            text.clone(),
+        ));
+
+        self.text = text;
+    }
+}
+
+/// Shows what will happen if the player presses the action button.
+pub struct InteractionPrompt {
+    pub root: Handle<UiNode>,
+    text: String,
+}
+
+impl InteractionPrompt {
+    pub fn new(ui: &mut UserInterface, font: SharedFont) -> Self {
+        let root = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness::bottom(120.0))
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Bottom)
+                .with_foreground(Brush::Solid(Color::opaque(220, 220, 220))),
+        )
+        .with_font(font)
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            text: Default::default(),
+        }
+    }
+
+    /// Sets text of the prompt, `None` hides the prompt.
+    pub fn set_text(&mut self, ui: &UserInterface, text: Option<String>) {
+        let text = text.unwrap_or_default();
+
+        if self.text == text {
+            return;
+        }
+
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            !text.is_empty(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.root,
+            MessageDirection::ToWidget,
+            text.clone(),
+        ));
+
+        self.text = text;
+    }
+}
+
+/// Warns the player that they are inside a hazard zone.
+pub struct HazardWarning {
+    pub root: Handle<UiNode>,
+    text: String,
+}
+
+impl HazardWarning {
+    pub fn new(ui: &mut UserInterface, font: SharedFont) -> Self {
+        let root = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness::top(60.0))
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Top)
+                .with_foreground(Brush::Solid(Color::opaque(230, 190, 0))),
+        )
+        .with_font(font)
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            text: Default::default(),
+        }
+    }
+
+    /// Sets name of the hazard the player is in, `None` hides the warning.
+    pub fn set_hazard(&mut self, ui: &UserInterface, hazard: Option<&str>) {
+        let text = hazard
+            .map(|hazard| format!("Warning: {}!", hazard))
+            .unwrap_or_default();
+
+        if self.text == text {
+            return;
+        }
+
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            !text.is_empty(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.root,
+            MessageDirection::ToWidget,
+            text.clone(),
+        ));
+
+        self.text = text;
+    }
+}
+
+/// Shows items that are assigned to the hotbar slots together with buttons of the slots.
+pub struct HotbarHud {
+    pub root: Handle<UiNode>,
+    text: String,
+}
+
+impl HotbarHud {
+    pub fn new(ui: &mut UserInterface, font: SharedFont) -> Self {
+        let root = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness::bottom(20.0))
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Bottom)
+                .with_foreground(Brush::Solid(Color::opaque(220, 220, 220))),
+        )
+        .with_font(font)
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            text: Default::default(),
+        }
+    }
+
+    /// Sets button names and contents of the slots, empty list hides the hotbar.
+    pub fn set_slots(&mut self, ui: &UserInterface, slots: &[(&str, Option<(ItemKind, u32)>)]) {
+        let text = slots
+            .iter()
+            .map(|(button, content)| match content {
+                Some((item, count)) => format!(
+                    "[{}] {} x{}",
+                    button,
+                    Item::get_definition(*item).name,
+                    count
+                ),
+                None => format!("[{}] -", button),
+            })
+            .collect::<Vec<_>>()
+            .join("   ");
+
+        if self.text == text {
+            return;
+        }
+
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            !text.is_empty(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.root,
+            MessageDirection::ToWidget,
+            text.clone(),
+        ));
+
+        self.text = text;
+    }
+}
+
+/// Screen-space panels that show the displays of the player when diegetic displays are disabled,
+/// the panels show the same render targets as the displays in the world.
+pub struct ScreenDisplays {
+    weapon: Handle<UiNode>,
+    inventory: Handle<UiNode>,
+    journal: Handle<UiNode>,
+    visibility: [bool; 3],
+}
+
+impl ScreenDisplays {
+    pub fn new(
+        ui: &mut UserInterface,
+        weapon_texture: Texture,
+        inventory_texture: Texture,
+        journal_texture: Texture,
+    ) -> Self {
+        let mut make_panel = |texture: Texture,
+                              width: f32,
+                              height: f32,
+                              horizontal_alignment: HorizontalAlignment,
+                              vertical_alignment: VerticalAlignment| {
+            ImageBuilder::new(
+                WidgetBuilder::new()
+                    .with_visibility(false)
+                    .with_hit_test_visibility(false)
+                    .with_width(width)
+                    .with_height(height)
+                    .with_margin(Thickness::uniform(20.0))
+                    .with_horizontal_alignment(horizontal_alignment)
+                    .with_vertical_alignment(vertical_alignment),
+            )
+            // Render targets are upside down.
+            .with_flip(true)
+            .with_texture(utils::into_gui_texture(texture))
+            .build(&mut ui.build_ctx())
+        };
+
+        let weapon = make_panel(
+            weapon_texture,
+            WeaponDisplay::WIDTH,
+            WeaponDisplay::HEIGHT,
+            HorizontalAlignment::Right,
+            VerticalAlignment::Bottom,
+        );
+        let inventory = make_panel(
+            inventory_texture,
+            InventoryInterface::WIDTH,
+            InventoryInterface::HEIGHT,
+            HorizontalAlignment::Center,
+            VerticalAlignment::Center,
+        );
+        let journal = make_panel(
+            journal_texture,
+            JournalDisplay::WIDTH,
+            JournalDisplay::HEIGHT,
+            HorizontalAlignment::Center,
+            VerticalAlignment::Center,
+        );
+
+        Self {
+            weapon,
+            inventory,
+            journal,
+            visibility: [false; 3],
+        }
+    }
+
+    /// Sets visibility of the weapon display, the inventory and the journal panels.
+    pub fn set_visibility(&mut self, ui: &UserInterface, visibility: [bool; 3]) {
+        if self.visibility == visibility {
+            return;
+        }
+
+        for (panel, visible) in [self.weapon, self.inventory, self.journal]
+            .into_iter()
+            .zip(visibility)
+        {
+            ui.send_message(WidgetMessage::visibility(
+                panel,
+                MessageDirection::ToWidget,
+                visible,
+            ));
+        }
+
+        self.visibility = visibility;
+    }
+}
+
+/// Time (in seconds) that an entry stays in the kill feed.
+const KILL_FEED_ENTRY_LIFETIME: f32 = 4.0;
+/// Time (in seconds) at the end of the lifetime of an entry during which it fades out.
+const KILL_FEED_FADE_TIME: f32 = 1.0;
+const KILL_FEED_MAX_ENTRIES: usize = 5;
+
+struct KillFeedEntry {
+    text: Handle<UiNode>,
+    lifetime: f32,
+}
+
+/// Lists recent kills of the player at the top-right corner of the screen.
+pub struct KillFeed {
+    pub root: Handle<UiNode>,
+    font: SharedFont,
+    entries: Vec<KillFeedEntry>,
+}
+
+impl KillFeed {
+    pub fn new(ui: &mut UserInterface, font: SharedFont) -> Self {
+        let root = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness::uniform(10.0))
+                .with_horizontal_alignment(HorizontalAlignment::Right)
+                .with_vertical_alignment(VerticalAlignment::Top),
+        )
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            font,
+            entries: Default::default(),
+        }
+    }
+
+    fn entry_brush(alpha: f32) -> Brush {
+        Brush::Solid(Color::from_rgba(220, 60, 60, (alpha * 255.0) as u8))
+    }
+
+    pub fn add_entry(&mut self, ui: &mut UserInterface, text: String) {
+        if self.entries.len() >= KILL_FEED_MAX_ENTRIES {
+            let oldest = self.entries.remove(0);
+            ui.send_message(WidgetMessage::remove(
+                oldest.text,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        let text = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_horizontal_alignment(HorizontalAlignment::Right)
+                .with_foreground(Self::entry_brush(1.0)),
+        )
+        .with_text(text)
+        .with_font(self.font.clone())
+        .build(&mut ui.build_ctx());
+
+        ui.send_message(WidgetMessage::link(
+            text,
+            MessageDirection::ToWidget,
+            self.root,
+        ));
+
+        self.entries.push(KillFeedEntry {
+            text,
+            lifetime: KILL_FEED_ENTRY_LIFETIME,
+        });
+    }
+
+    pub fn update(&mut self, ui: &UserInterface, dt: f32) {
+        self.entries.retain_mut(|entry| {
+            entry.lifetime -= dt;
+
+            if entry.lifetime <= 0.0 {
+                ui.send_message(WidgetMessage::remove(
+                    entry.text,
+                    MessageDirection::ToWidget,
+                ));
+                false
+            } else {
+                if entry.lifetime < KILL_FEED_FADE_TIME {
+                    ui.send_message(WidgetMessage::foreground(
+                        entry.text,
+                        MessageDirection::ToWidget,
+                        Self::entry_brush(entry.lifetime / KILL_FEED_FADE_TIME),
+                    ));
+                }
+                true
+            }
+        });
+    }
+}
+
+struct StatusEffectBadge {
+    kind: StatusEffectKind,
+    border: Handle<UiNode>,
+    text: Handle<UiNode>,
+}
+
+/// Row of badges at the bottom-left corner of the screen that shows active status effects of
+/// the player together with their remaining time.
+pub struct StatusEffectHud {
+    pub root: Handle<UiNode>,
+    font: SharedFont,
+    badges: Vec<StatusEffectBadge>,
+}
+
+impl StatusEffectHud {
+    pub fn new(ui: &mut UserInterface, font: SharedFont) -> Self {
+        let root = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness {
+                    left: 10.0,
+                    top: 0.0,
+                    right: 0.0,
+                    bottom: 140.0,
+                })
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .with_vertical_alignment(VerticalAlignment::Bottom),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            font,
+            badges: Default::default(),
+        }
+    }
+
+    fn badge_color(kind: StatusEffectKind) -> Color {
+        match kind {
+            StatusEffectKind::Burning => Color::from_rgba(220, 110, 0, 200),
+            StatusEffectKind::Bleeding => Color::from_rgba(170, 0, 0, 200),
+            StatusEffectKind::Poisoned => Color::from_rgba(60, 150, 30, 200),
+        }
+    }
+
+    /// Syncs badges with given list of active effects and their remaining time (in seconds).
+    pub fn set_effects(&mut self, ui: &mut UserInterface, effects: &[(StatusEffectKind, f32)]) {
+        self.badges.retain(|badge| {
+            let active = effects.iter().any(|(kind, _)| *kind == badge.kind);
+            if !active {
+                ui.send_message(WidgetMessage::remove(
+                    badge.border,
+                    MessageDirection::ToWidget,
+                ));
+            }
+            active
+        });
+
+        for &(kind, time_left) in effects {
+            let badge = match self.badges.iter().position(|badge| badge.kind == kind) {
+                Some(index) => &self.badges[index],
+                None => {
+                    let text = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_hit_test_visibility(false)
+                            .with_margin(Thickness::uniform(4.0))
+                            .with_foreground(Brush::Solid(Color::opaque(240, 240, 240))),
+                    )
+                    .with_font(self.font.clone())
+                    .build(&mut ui.build_ctx());
+
+                    let border = BorderBuilder::new(
+                        WidgetBuilder::new()
+                            .with_hit_test_visibility(false)
+                            .with_margin(Thickness::right(4.0))
+                            .with_background(Brush::Solid(Self::badge_color(kind)))
+                            .with_child(text),
+                    )
+                    .build(&mut ui.build_ctx());
+
+                    ui.send_message(WidgetMessage::link(
+                        border,
+                        MessageDirection::ToWidget,
+                        self.root,
+                    ));
+
+                    self.badges.push(StatusEffectBadge { kind, border, text });
+                    self.badges.last().unwrap()
+                }
+            };
+
+            ui.send_message(TextMessage::text(
+                badge.text,
+                MessageDirection::ToWidget,
+                format!("{} {}s", kind.name(), time_left.ceil() as u32),
+            ));
+        }
+    }
+}
+
+/// Distance (in pixels) from the center of the screen to damage indicators.
+const DAMAGE_INDICATOR_RADIUS: f32 = 120.0;
+const DAMAGE_INDICATOR_SIZE: f32 = 14.0;
+
+/// Markers around the crosshair that point towards recent attackers of the player.
+pub struct DamageIndicatorHud {
+    pub root: Handle<UiNode>,
+    markers: Vec<Handle<UiNode>>,
+}
+
+impl DamageIndicatorHud {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let root = CanvasBuilder::new(WidgetBuilder::new().with_hit_test_visibility(false))
+            .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            markers: Default::default(),
+        }
+    }
+
+    fn marker_brush(opacity: f32) -> Brush {
+        Brush::Solid(Color::from_rgba(200, 0, 0, (opacity * 220.0) as u8))
+    }
+
+    /// Syncs markers with given list of angles (in radians, clockwise from the top of the
+    /// screen) and opacities of indicators.
+    pub fn set_indicators(&mut self, ui: &mut UserInterface, indicators: &[(f32, f32)]) {
+        while self.markers.len() < indicators.len() {
+            let marker = BorderBuilder::new(
+                WidgetBuilder::new()
+                    .with_hit_test_visibility(false)
+                    .with_width(DAMAGE_INDICATOR_SIZE)
+                    .with_height(DAMAGE_INDICATOR_SIZE),
+            )
+            .build(&mut ui.build_ctx());
+
+            ui.send_message(WidgetMessage::link(
+                marker,
+                MessageDirection::ToWidget,
+                self.root,
+            ));
+
+            self.markers.push(marker);
+        }
+
+        while self.markers.len() > indicators.len() {
+            let marker = self.markers.pop().unwrap();
+            ui.send_message(WidgetMessage::remove(marker, MessageDirection::ToWidget));
+        }
+
+        let center = ui.screen_size().scale(0.5);
+        for (&marker, &(angle, opacity)) in self.markers.iter().zip(indicators) {
+            let position = center
+                + Vector2::new(angle.sin(), -angle.cos()) * DAMAGE_INDICATOR_RADIUS
+                - Vector2::repeat(DAMAGE_INDICATOR_SIZE * 0.5);
+
+            ui.send_message(WidgetMessage::desired_position(
+                marker,
+                MessageDirection::ToWidget,
+                position,
+            ));
+            ui.send_message(WidgetMessage::background(
+                marker,
+                MessageDirection::ToWidget,
+                Self::marker_brush(opacity),
+            ));
+        }
+    }
+}
+
+/// Distance (in pixels) from the center of the screen to the grenade indicator.
+const GRENADE_INDICATOR_RADIUS: f32 = 160.0;
+const GRENADE_INDICATOR_SIZE: f32 = 28.0;
+
+/// Blinking marker that points towards a live grenade near the player.
+pub struct GrenadeIndicatorHud {
+    pub root: Handle<UiNode>,
+    marker: Handle<UiNode>,
+    blink_phase: f32,
+}
+
+impl GrenadeIndicatorHud {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let marker = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_width(GRENADE_INDICATOR_SIZE)
+                .with_height(GRENADE_INDICATOR_SIZE)
+                .with_foreground(Brush::Solid(Color::opaque(255, 255, 255)))
+                .with_child(
+                    TextBuilder::new(WidgetBuilder::new())
+                        .with_text("!")
+                        .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                        .build(&mut ui.build_ctx()),
+                ),
+        )
+        .with_stroke_thickness(Thickness::uniform(2.0))
+        .build(&mut ui.build_ctx());
+
+        let root = CanvasBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_child(marker),
+        )
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            marker,
+            blink_phase: 0.0,
+        }
+    }
+
+    /// Shows the marker at given angle (in radians, clockwise from the top of the screen), it
+    /// blinks faster as the remaining fuse time of the grenade runs out.
+    pub fn update(&mut self, ui: &UserInterface, indicator: Option<(f32, f32)>, dt: f32) {
+        ui.send_message(WidgetMessage::visibility(
+            self.marker,
+            MessageDirection::ToWidget,
+            indicator.is_some(),
+        ));
+
+        let (angle, fuse) = match indicator {
+            Some(indicator) => indicator,
+            None => {
+                self.blink_phase = 0.0;
+                return;
+            }
+        };
+
+        let frequency = 2.0 + 8.0 / fuse.max(0.25);
+        self.blink_phase = (self.blink_phase + frequency * dt).fract();
+        let opacity = 0.5 + 0.5 * (self.blink_phase * std::f32::consts::TAU).cos();
+
+        let position = ui.screen_size().scale(0.5)
+            + Vector2::new(angle.sin(), -angle.cos()) * GRENADE_INDICATOR_RADIUS
+            - Vector2::repeat(GRENADE_INDICATOR_SIZE * 0.5);
+        ui.send_message(WidgetMessage::desired_position(
+            self.marker,
+            MessageDirection::ToWidget,
+            position,
+        ));
+        ui.send_message(WidgetMessage::background(
+            self.marker,
+            MessageDirection::ToWidget,
+            Brush::Solid(Color::from_rgba(
+                255,
+                140,
+                0,
+                (80.0 + opacity * 175.0) as u8,
+            )),
+        ));
+    }
+}
+
+/// Full-screen overlay that is shown while the player is looking through a weapon scope.
+pub struct ScopeOverlay {
+    pub root: Handle<UiNode>,
+    image: Handle<UiNode>,
+    left: Handle<UiNode>,
+    right: Handle<UiNode>,
+    /// Frame around the view of a picture-in-picture scope.
+    frame: Handle<UiNode>,
+    /// Path to the overlay texture, darkening flag and picture-in-picture panel of the shown
+    /// scope.
+    current: Option<(String, bool, Option<PictureInPictureDefinition>)>,
+}
+
+impl ScopeOverlay {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let screen_size = ui.screen_size();
+
+        let image;
+        let left;
+        let right;
+        let root = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_width(screen_size.x)
+                .with_height(screen_size.y)
+                .with_child({
+                    left = BorderBuilder::new(WidgetBuilder::new().on_column(0))
+                        .build(&mut ui.build_ctx());
+                    left
+                })
+                .with_child({
+                    image = ImageBuilder::new(
+                        WidgetBuilder::new()
+                            .on_column(1)
+                            .with_width(screen_size.y)
+                            .with_height(screen_size.y),
+                    )
+                    .build(&mut ui.build_ctx());
+                    image
+                })
+                .with_child({
+                    right = BorderBuilder::new(WidgetBuilder::new().on_column(2))
+                        .build(&mut ui.build_ctx());
+                    right
+                }),
+        )
+        .add_column(Column::stretch())
+        .add_column(Column::auto())
+        .add_column(Column::stretch())
+        .add_row(Row::stretch())
+        .build(&mut ui.build_ctx());
+
+        let frame = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_foreground(Brush::Solid(Color::opaque(30, 30, 30))),
+        )
+        .with_stroke_thickness(Thickness::uniform(4.0))
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            image,
+            left,
+            right,
+            frame,
+            current: None,
+        }
+    }
+
+    fn layout_frame(&self, ui: &UserInterface, width: f32, height: f32) {
+        if let Some((_, _, Some(pip))) = self.current.as_ref() {
+            ui.send_message(WidgetMessage::desired_position(
+                self.frame,
+                MessageDirection::ToWidget,
+                Vector2::new(pip.position.0 * width, pip.position.1 * height),
+            ));
+            ui.send_message(WidgetMessage::width(
+                self.frame,
+                MessageDirection::ToWidget,
+                pip.size.0 * width,
+            ));
+            ui.send_message(WidgetMessage::height(
+                self.frame,
+                MessageDirection::ToWidget,
+                pip.size.1 * height,
+            ));
+        }
+    }
+
+    pub fn resize(&self, ui: &UserInterface, width: f32, height: f32) {
+        ui.send_message(WidgetMessage::width(
+            self.root,
+            MessageDirection::ToWidget,
+            width,
+        ));
+        ui.send_message(WidgetMessage::height(
+            self.root,
+            MessageDirection::ToWidget,
+            height,
+        ));
+        // Scope overlay is always square.
+        ui.send_message(WidgetMessage::width(
+            self.image,
+            MessageDirection::ToWidget,
+            height,
+        ));
+        ui.send_message(WidgetMessage::height(
+            self.image,
+            MessageDirection::ToWidget,
+            height,
+        ));
+        self.layout_frame(ui, width, height);
+    }
+
+    pub fn set_scope(
+        &mut self,
+        ui: &UserInterface,
+        resource_manager: &ResourceManager,
+        scope: Option<&ScopeDefinition>,
+    ) {
+        let new = scope.map(|scope| {
+            (
+                scope.overlay.clone(),
+                scope.darken_screen,
+                scope.picture_in_picture,
+            )
+        });
+        if self.current == new {
+            return;
+        }
+
+        // Picture-in-picture scopes are framed only, the rest of the screen stays visible.
+        let picture_in_picture = matches!(new, Some((_, _, Some(_))));
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            new.is_some() && !picture_in_picture,
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            self.frame,
+            MessageDirection::ToWidget,
+            picture_in_picture,
+        ));
+
+        if let Some((overlay, darken_screen, _)) = new.as_ref() {
+            ui.send_message(ImageMessage::texture(
+                self.image,
+                MessageDirection::ToWidget,
+                Some(utils::into_gui_texture(
+                    resource_manager.request_texture(overlay),
+                )),
+            ));
+
+            let brush = if *darken_screen {
+                Brush::Solid(Color::BLACK)
+            } else {
+                Brush::Solid(Color::TRANSPARENT)
+            };
+            for side in [self.left, self.right] {
+                ui.send_message(WidgetMessage::background(
+                    side,
+                    MessageDirection::ToWidget,
+                    brush.clone(),
+                ));
+            }
+        }
+
+        self.current = new;
+
+        let screen_size = ui.screen_size();
+        self.layout_frame(ui, screen_size.x, screen_size.y);
+    }
+}
+
 pub struct FinalScreen {
     root: Handle<UiNode>,
+    stats: Handle<UiNode>,
     exit_to_menu: Handle<UiNode>,
     exit_game: Handle<UiNode>,
     sender: MessageSender,
@@ -209,6 +1127,7 @@ pub struct FinalScreen {
 
 impl FinalScreen {
     pub fn new(ui: &mut UserInterface, font: SharedFont, sender: MessageSender) -> Self {
+        let stats;
         let exit_to_menu;
         let exit_game;
         let root = BorderBuilder::new(
@@ -239,6 +1158,17 @@ impl FinalScreen {
                                         .with_vertical_alignment(VerticalAlignment::Top)
                                         .on_row(1)
                                         .on_column(0)
+                                        .with_child({
+                                            stats = TextBuilder::new(
+                                                WidgetBuilder::new()
+                                                    .with_margin(Thickness::uniform(2.0)),
+                                            )
+                                            .with_horizontal_text_alignment(
+                                                HorizontalAlignment::Center,
+                                            )
+                                            .build(&mut ui.build_ctx());
+                                            stats
+                                        })
                                         .with_child({
                                             exit_to_menu = ButtonBuilder::new(
                                                 WidgetBuilder::new()
@@ -275,6 +1205,7 @@ impl FinalScreen {
 
         Self {
             root,
+            stats,
             exit_to_menu,
             exit_game,
             sender,
@@ -299,6 +1230,15 @@ impl FinalScreen {
         ));
     }
 
+    /// Sets text with statistics of the finished session.
+    pub fn set_stats(&self, ui: &UserInterface, text: String) {
+        ui.send_message(TextMessage::text(
+            self.stats,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
+
     pub fn is_visible(&self, ui: &UserInterface) -> bool {
         ui.node(self.root).visibility()
     }
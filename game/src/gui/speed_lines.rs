@@ -0,0 +1,122 @@
+//! Speed lines are thin streaks that radiate from the center of the screen while the player
+//! sprints, they become more opaque as the sprint intensifies and flicker to sell the speed.
+
+use fyrox::{
+    core::{
+        algebra::Vector2,
+        pool::Handle,
+        rand::{thread_rng, Rng},
+    },
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        core::color::Color,
+        message::MessageDirection,
+        vector_image::{Primitive, VectorImageBuilder},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+
+const LINE_COUNT: usize = 48;
+/// Lines start at this distance (in pixels) from the center of the screen, so the area around
+/// the crosshair stays clear.
+const INNER_RADIUS: f32 = 380.0;
+/// Lines end beyond the edges of the screen, everything outside of the screen is clipped.
+const OUTER_RADIUS: f32 = 1400.0;
+const MAX_OPACITY: f32 = 0.35;
+/// Frequency (in Hz) of the flicker of the lines.
+const FLICKER_FREQUENCY: f32 = 12.0;
+
+pub struct SpeedLines {
+    pub root: Handle<UiNode>,
+    image: Handle<UiNode>,
+    intensity: f32,
+    time: f32,
+}
+
+impl SpeedLines {
+    pub fn new(ui: &mut UserInterface) -> Self {
+        let mut rng = thread_rng();
+        let center = Vector2::new(OUTER_RADIUS, OUTER_RADIUS);
+        let primitives = (0..LINE_COUNT)
+            .map(|_| {
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let direction = Vector2::new(angle.cos(), angle.sin());
+                Primitive::Line {
+                    begin: center + direction.scale(INNER_RADIUS * rng.gen_range(1.0..1.5)),
+                    end: center + direction.scale(OUTER_RADIUS),
+                    thickness: rng.gen_range(1.0..3.0),
+                }
+            })
+            .collect();
+
+        let image = VectorImageBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_width(2.0 * OUTER_RADIUS)
+                .with_height(2.0 * OUTER_RADIUS)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_foreground(Brush::Solid(Color::TRANSPARENT)),
+        )
+        .with_primitives(primitives)
+        .build(&mut ui.build_ctx());
+
+        let root = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_hit_test_visibility(false)
+                .with_width(ui.screen_size().x)
+                .with_height(ui.screen_size().y)
+                .with_child(image),
+        )
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            image,
+            intensity: 0.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn resize(&self, ui: &UserInterface, width: f32, height: f32) {
+        ui.send_message(WidgetMessage::width(
+            self.root,
+            MessageDirection::ToWidget,
+            width,
+        ));
+        ui.send_message(WidgetMessage::height(
+            self.root,
+            MessageDirection::ToWidget,
+            height,
+        ));
+    }
+
+    /// Sets intensity of the lines in `[0; 1]` range, zero hides them.
+    pub fn update(&mut self, ui: &UserInterface, intensity: f32, dt: f32) {
+        if self.intensity == 0.0 && intensity == 0.0 {
+            return;
+        }
+
+        if (self.intensity > 0.0) != (intensity > 0.0) {
+            ui.send_message(WidgetMessage::visibility(
+                self.root,
+                MessageDirection::ToWidget,
+                intensity > 0.0,
+            ));
+        }
+
+        self.intensity = intensity;
+        self.time += dt;
+
+        let flicker = 0.75 + 0.25 * (self.time * FLICKER_FREQUENCY * std::f32::consts::TAU).sin();
+        let alpha = intensity * MAX_OPACITY * flicker;
+        ui.send_message(WidgetMessage::foreground(
+            self.image,
+            MessageDirection::ToWidget,
+            Brush::Solid(Color::from_rgba(255, 255, 255, (alpha * 255.0) as u8)),
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+//! Screen of a computer terminal. It asks for a password if the terminal is protected by one,
+//! then shows the log of the terminal and actions that the terminal can perform.
+
+use crate::{level::terminal::Terminal, message::Message, MessageSender};
+use fyrox::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        button::{ButtonBuilder, ButtonMessage},
+        formatted_text::WrapMode,
+        grid::{Column, GridBuilder, Row},
+        message::{MessageDirection, UiMessage},
+        scroll_viewer::ScrollViewerBuilder,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        text_box::TextBoxBuilder,
+        ttf::SharedFont,
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+    scene::node::Node,
+};
+use std::path::PathBuf;
+
+pub struct TerminalScreen {
+    pub root: Handle<UiNode>,
+    title: Handle<UiNode>,
+    login_panel: Handle<UiNode>,
+    password_box: Handle<UiNode>,
+    log_in: Handle<UiNode>,
+    login_status: Handle<UiNode>,
+    log_panel: Handle<UiNode>,
+    log_text: Handle<UiNode>,
+    unlock_door: Handle<UiNode>,
+    run_program: Handle<UiNode>,
+    close: Handle<UiNode>,
+    sender: MessageSender,
+    password: Option<String>,
+    entered_password: String,
+    door: Handle<Node>,
+    objective: Option<String>,
+}
+
+fn make_button(ui: &mut UserInterface, font: SharedFont, text: &str) -> Handle<UiNode> {
+    ButtonBuilder::new(
+        WidgetBuilder::new()
+            .with_width(140.0)
+            .with_margin(Thickness::uniform(2.0)),
+    )
+    .with_text(text)
+    .with_font(font)
+    .build(&mut ui.build_ctx())
+}
+
+impl TerminalScreen {
+    pub fn new(ui: &mut UserInterface, font: SharedFont, sender: MessageSender) -> Self {
+        let title = TextBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .with_margin(Thickness::uniform(4.0))
+                .with_foreground(Brush::Solid(Color::opaque(0, 200, 0)))
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center),
+        )
+        .with_font(font.clone())
+        .build(&mut ui.build_ctx());
+
+        let password_box = TextBoxBuilder::new(
+            WidgetBuilder::new()
+                .with_height(30.0)
+                .with_margin(Thickness::uniform(2.0)),
+        )
+        .with_vertical_text_alignment(VerticalAlignment::Center)
+        .build(&mut ui.build_ctx());
+        let log_in = make_button(ui, font.clone(), "Log In");
+        let login_status = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(2.0))
+                .with_foreground(Brush::Solid(Color::opaque(200, 0, 0))),
+        )
+        .build(&mut ui.build_ctx());
+        let login_panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .on_row(1)
+                .with_width(300.0)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_child(
+                    TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(2.0)))
+                        .with_text("Enter password:")
+                        .build(&mut ui.build_ctx()),
+                )
+                .with_child(password_box)
+                .with_child(log_in)
+                .with_child(login_status),
+        )
+        .build(&mut ui.build_ctx());
+
+        let log_text = TextBuilder::new(WidgetBuilder::new())
+            .with_wrap(WrapMode::Word)
+            .build(&mut ui.build_ctx());
+        let log_panel = ScrollViewerBuilder::new(
+            WidgetBuilder::new()
+                .on_row(1)
+                .with_margin(Thickness::uniform(4.0)),
+        )
+        .with_content(log_text)
+        .build(&mut ui.build_ctx());
+
+        let unlock_door = make_button(ui, font.clone(), "Unlock Door");
+        let run_program = make_button(ui, font.clone(), "Run Program");
+        let close = make_button(ui, font, "Close");
+        let buttons = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .on_row(2)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_child(unlock_door)
+                .with_child(run_program)
+                .with_child(close),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .build(&mut ui.build_ctx());
+
+        let root = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_width(600.0)
+                .with_height(400.0)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_background(Brush::Solid(Color::from_rgba(0, 20, 0, 230)))
+                .with_foreground(Brush::Solid(Color::opaque(0, 200, 0)))
+                .with_child(
+                    GridBuilder::new(
+                        WidgetBuilder::new()
+                            .with_child(title)
+                            .with_child(login_panel)
+                            .with_child(log_panel)
+                            .with_child(buttons),
+                    )
+                    .add_row(Row::strict(40.0))
+                    .add_row(Row::stretch())
+                    .add_row(Row::strict(40.0))
+                    .add_column(Column::stretch())
+                    .build(&mut ui.build_ctx()),
+                ),
+        )
+        .with_stroke_thickness(Thickness::uniform(2.0))
+        .build(&mut ui.build_ctx());
+
+        Self {
+            root,
+            title,
+            login_panel,
+            password_box,
+            log_in,
+            login_status,
+            log_panel,
+            log_text,
+            unlock_door,
+            run_program,
+            close,
+            sender,
+            password: None,
+            entered_password: Default::default(),
+            door: Default::default(),
+            objective: None,
+        }
+    }
+
+    /// Shows the screen for given terminal, the password will be asked if the terminal has one.
+    pub fn open(&mut self, ui: &UserInterface, terminal: &Terminal) {
+        self.password = terminal.password().map(|p| p.to_owned());
+        self.entered_password.clear();
+        self.door = terminal.door();
+        self.objective = terminal.objective().map(|o| o.to_owned());
+
+        ui.send_message(TextMessage::text(
+            self.title,
+            MessageDirection::ToWidget,
+            terminal.title().to_owned(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.log_text,
+            MessageDirection::ToWidget,
+            terminal.log().to_owned(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.password_box,
+            MessageDirection::ToWidget,
+            Default::default(),
+        ));
+        ui.send_message(TextMessage::text(
+            self.login_status,
+            MessageDirection::ToWidget,
+            Default::default(),
+        ));
+
+        self.set_access_granted(ui, self.password.is_none());
+        self.set_visible(ui, true);
+    }
+
+    fn set_access_granted(&self, ui: &UserInterface, granted: bool) {
+        for (widget, visible) in [
+            (self.login_panel, !granted),
+            (self.log_panel, granted),
+            (self.unlock_door, granted && self.door.is_some()),
+            (self.run_program, granted && self.objective.is_some()),
+        ] {
+            ui.send_message(WidgetMessage::visibility(
+                widget,
+                MessageDirection::ToWidget,
+                visible,
+            ));
+        }
+
+        ui.send_message(WidgetMessage::focus(
+            if granted {
+                self.close
+            } else {
+                self.password_box
+            },
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    fn play_sound(&self, path: &str) {
+        self.sender.send(Message::Play2DSound {
+            path: PathBuf::from(path),
+            gain: 1.0,
+        });
+    }
+
+    pub fn handle_ui_message(&mut self, ui: &UserInterface, message: &UiMessage) {
+        if message.direction() != MessageDirection::FromWidget {
+            return;
+        }
+
+        if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.log_in {
+                if self.password.as_ref() == Some(&self.entered_password) {
+                    self.play_sound("data/sounds/access_granted.ogg");
+                    self.set_access_granted(ui, true);
+                } else {
+                    self.play_sound("data/sounds/door_deny.ogg");
+                    ui.send_message(TextMessage::text(
+                        self.login_status,
+                        MessageDirection::ToWidget,
+                        "Access denied".to_owned(),
+                    ));
+                }
+            } else if message.destination() == self.unlock_door {
+                self.play_sound("data/sounds/access_granted.ogg");
+                self.sender.send(Message::UnlockDoor { door: self.door });
+            } else if message.destination() == self.run_program {
+                if let Some(id) = self.objective.clone() {
+                    self.sender.send(Message::CompleteObjective { id });
+                }
+            } else if message.destination() == self.close {
+                self.set_visible(ui, false);
+            }
+        } else if let Some(TextMessage::Text(text)) = message.data() {
+            if message.destination() == self.password_box {
+                self.entered_password = text.clone();
+            }
+        }
+    }
+
+    pub fn set_visible(&self, ui: &UserInterface, state: bool) {
+        ui.send_message(WidgetMessage::visibility(
+            self.root,
+            MessageDirection::ToWidget,
+            state,
+        ));
+    }
+
+    pub fn is_visible(&self, ui: &UserInterface) -> bool {
+        ui.node(self.root).visibility()
+    }
+}
@@ -9,7 +9,7 @@ use fyrox::{
         message::MessageDirection,
         text::{TextBuilder, TextMessage},
         ttf::SharedFont,
-        widget::WidgetBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
         UiNode, UserInterface, VerticalAlignment,
     },
     resource::texture::Texture,
@@ -17,11 +17,70 @@ use fyrox::{
 };
 use std::path::Path;
 
+const NORMAL_COLOR: Color = Color::opaque(0, 162, 232);
+const LOW_AMMO_COLOR: Color = Color::opaque(220, 0, 0);
+/// Battery charge below which the battery indicator turns red.
+const LOW_BATTERY_CHARGE: f32 = 0.2;
+/// Amount of flashes per second of the empty ammo indicator.
+const EMPTY_FLASH_FREQUENCY: f32 = 3.0;
+
+/// Returns short name of a grenade type that fits the display.
+fn grenade_label(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::SmokeGrenade => "SMK",
+        ItemKind::Flashbang => "FLS",
+        _ => "FRG",
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AmmoStatus {
+    Normal,
+    Low,
+    Empty,
+}
+
+impl AmmoStatus {
+    pub fn new(shots_left: u32, low_ammo_shots: u32) -> Self {
+        if shots_left == 0 {
+            AmmoStatus::Empty
+        } else if shots_left <= low_ammo_shots {
+            AmmoStatus::Low
+        } else {
+            AmmoStatus::Normal
+        }
+    }
+
+    /// Returns `true` if the empty ammo indicator should be lit at given moment of time.
+    pub fn is_lit(self, time: f32) -> bool {
+        self != AmmoStatus::Empty || (time * EMPTY_FLASH_FREQUENCY).fract() < 0.5
+    }
+
+    /// Returns color of the ammo indicator at given moment of time, empty indicator flashes.
+    pub fn color(self, time: f32) -> Color {
+        match self {
+            AmmoStatus::Normal => NORMAL_COLOR,
+            AmmoStatus::Low => LOW_AMMO_COLOR,
+            AmmoStatus::Empty => {
+                if self.is_lit(time) {
+                    LOW_AMMO_COLOR
+                } else {
+                    Color::opaque(60, 0, 0)
+                }
+            }
+        }
+    }
+}
+
 pub struct WeaponDisplay {
     pub ui: UserInterface,
     pub render_target: Texture,
     ammo: Handle<UiNode>,
     grenades: Handle<UiNode>,
+    fire_mode: Handle<UiNode>,
+    battery: Handle<UiNode>,
+    ammo_status: AmmoStatus,
+    time: f32,
 }
 
 impl WeaponDisplay {
@@ -35,6 +94,8 @@ impl WeaponDisplay {
 
         let ammo;
         let grenades;
+        let fire_mode;
+        let battery;
         GridBuilder::new(
             WidgetBuilder::new()
                 .with_width(Self::WIDTH)
@@ -56,7 +117,7 @@ impl WeaponDisplay {
                     ammo = TextBuilder::new(
                         WidgetBuilder::new()
                             .with_vertical_alignment(VerticalAlignment::Center)
-                            .with_foreground(Brush::Solid(Color::opaque(0, 162, 232)))
+                            .with_foreground(Brush::Solid(NORMAL_COLOR))
                             .on_row(0)
                             .on_column(1),
                     )
@@ -81,19 +142,45 @@ impl WeaponDisplay {
                     grenades = TextBuilder::new(
                         WidgetBuilder::new()
                             .with_vertical_alignment(VerticalAlignment::Center)
-                            .with_foreground(Brush::Solid(Color::opaque(0, 162, 232)))
+                            .with_foreground(Brush::Solid(NORMAL_COLOR))
                             .on_row(1)
                             .on_column(1),
                     )
-                    .with_font(font)
+                    .with_font(font.clone())
                     .build(&mut ui.build_ctx());
                     grenades
+                })
+                .with_child({
+                    fire_mode = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_foreground(Brush::Solid(NORMAL_COLOR))
+                            .on_row(2)
+                            .on_column(1),
+                    )
+                    .with_font(font.clone())
+                    .build(&mut ui.build_ctx());
+                    fire_mode
+                })
+                .with_child({
+                    battery = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_foreground(Brush::Solid(NORMAL_COLOR))
+                            .on_row(3)
+                            .on_column(1),
+                    )
+                    .with_font(font)
+                    .build(&mut ui.build_ctx());
+                    battery
                 }),
         )
         .add_column(Column::auto())
         .add_column(Column::stretch())
         .add_row(Row::auto())
         .add_row(Row::auto())
+        .add_row(Row::auto())
+        .add_row(Row::auto())
         .add_row(Row::stretch())
         .build(&mut ui.build_ctx());
 
@@ -102,34 +189,71 @@ impl WeaponDisplay {
             render_target,
             ammo,
             grenades,
+            fire_mode,
+            battery,
+            ammo_status: AmmoStatus::Normal,
+            time: 0.0,
         }
     }
 
-    pub fn sync_to_model(&self, player: &Player, weapons: &WeaponContainer) {
-        let ammo = if player.current_weapon().is_some() {
-            let total_ammo = player.inventory().item_count(ItemKind::Ammo);
-            total_ammo
-                / weapons[player.current_weapon()]
-                    .definition
-                    .ammo_consumption_per_shot
-        } else {
-            0
-        };
+    pub fn sync_to_model(&mut self, player: &Player, weapons: &WeaponContainer) {
         self.ui.send_message(TextMessage::text(
             self.ammo,
             MessageDirection::ToWidget,
-            format!("{}", ammo),
+            format!("{}", player.shots_left(weapons)),
         ));
+        self.ammo_status = player.ammo_status(weapons);
 
-        let grenades = player.inventory().item_count(ItemKind::Grenade);
+        let grenade = player.selected_grenade();
+        let grenades = player.inventory().item_count(grenade);
         self.ui.send_message(TextMessage::text(
             self.grenades,
             MessageDirection::ToWidget,
-            format!("{}", grenades),
+            format!("{} {}", grenades, grenade_label(grenade)),
         ));
+
+        let fire_mode = weapons
+            .try_get(player.current_weapon())
+            .map(|weapon| weapon.fire_mode().name())
+            .unwrap_or_default();
+        self.ui.send_message(TextMessage::text(
+            self.fire_mode,
+            MessageDirection::ToWidget,
+            fire_mode,
+        ));
+
+        let charge = player.flash_light_charge();
+        self.ui.send_message(WidgetMessage::visibility(
+            self.battery,
+            MessageDirection::ToWidget,
+            charge.is_some(),
+        ));
+        if let Some(charge) = charge {
+            self.ui.send_message(TextMessage::text(
+                self.battery,
+                MessageDirection::ToWidget,
+                format!("BAT {}%", (charge * 100.0).round()),
+            ));
+            self.ui.send_message(WidgetMessage::foreground(
+                self.battery,
+                MessageDirection::ToWidget,
+                Brush::Solid(if charge < LOW_BATTERY_CHARGE {
+                    LOW_AMMO_COLOR
+                } else {
+                    NORMAL_COLOR
+                }),
+            ));
+        }
     }
 
     pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+        self.ui.send_message(WidgetMessage::foreground(
+            self.ammo,
+            MessageDirection::ToWidget,
+            Brush::Solid(self.ammo_status.color(self.time)),
+        ));
+
         self.ui.update(
             Vector2::new(WeaponDisplay::WIDTH, WeaponDisplay::HEIGHT),
             delta,
@@ -1,4 +1,4 @@
-use crate::item::ItemKind;
+use crate::item::{Item, ItemKind};
 use fyrox::core::visitor::{Visit, VisitResult, Visitor};
 
 #[derive(Default, Debug, Clone, Visit)]
@@ -31,35 +31,51 @@ impl Inventory {
         Self { items }
     }
 
-    pub fn add_item(&mut self, item: ItemKind, count: u32) {
+    /// Adds items to existing stacks of the item until they're full, the rest is put in new
+    /// stacks. Items that do not fit in the reserve cap of the item are refused. Returns amount
+    /// of items that were actually added.
+    pub fn add_item(&mut self, item: ItemKind, count: u32) -> u32 {
         assert_ne!(count, 0);
 
-        if let Some(item) = self.entry_mut(item) {
-            item.amount += count;
-        } else {
-            self.items.push(ItemEntry {
-                kind: item,
-                amount: count,
-            })
+        let count = count.min(self.free_space(item));
+        let max_stack = Item::get_definition(item)
+            .max_stack
+            .unwrap_or(u32::MAX)
+            .max(1);
+
+        let mut remaining = count;
+        for entry in self.items.iter_mut().filter(|i| i.kind == item) {
+            let added = remaining.min(max_stack.saturating_sub(entry.amount));
+            entry.amount += added;
+            remaining -= added;
         }
-    }
 
-    pub fn try_extract_exact_items(&mut self, item: ItemKind, amount: u32) -> u32 {
-        if let Some(position) = self.items.iter().position(|i| i.kind == item) {
-            let item = &mut self.items[position];
+        while remaining > 0 {
+            let amount = remaining.min(max_stack);
+            self.items.push(ItemEntry { kind: item, amount });
+            remaining -= amount;
+        }
 
-            if item.amount >= amount {
-                item.amount -= amount;
+        count
+    }
 
-                if item.amount == 0 {
-                    self.items.remove(position);
-                }
+    /// Takes exactly given amount of the item from all of its stacks, nothing is taken if there
+    /// is not enough items.
+    pub fn try_extract_exact_items(&mut self, item: ItemKind, amount: u32) -> u32 {
+        if self.item_count(item) < amount {
+            return 0;
+        }
 
-                return amount;
-            }
+        // Take from the last stacks first, so the first ones stay full.
+        let mut remaining = amount;
+        for entry in self.items.iter_mut().rev().filter(|i| i.kind == item) {
+            let taken = remaining.min(entry.amount);
+            entry.amount -= taken;
+            remaining -= taken;
         }
+        self.items.retain(|i| i.amount > 0);
 
-        0
+        amount
     }
 
     pub fn items(&self) -> &[ItemEntry] {
@@ -67,18 +83,19 @@ impl Inventory {
     }
 
     pub fn item_count(&self, item: ItemKind) -> u32 {
-        if let Some(item) = self.entry(item) {
-            item.amount
-        } else {
-            0
-        }
+        self.items
+            .iter()
+            .filter(|i| i.kind == item)
+            .map(|i| i.amount)
+            .sum()
     }
 
-    fn entry(&self, item: ItemKind) -> Option<&ItemEntry> {
-        self.items.iter().find(|i| i.kind == item)
-    }
-
-    fn entry_mut(&mut self, item: ItemKind) -> Option<&mut ItemEntry> {
-        self.items.iter_mut().find(|i| i.kind == item)
+    /// Returns amount of given item that can be added before the reserve cap of the item is
+    /// reached.
+    pub fn free_space(&self, item: ItemKind) -> u32 {
+        match Item::get_definition(item).reserve_cap {
+            Some(cap) => cap.saturating_sub(self.item_count(item)),
+            None => u32::MAX,
+        }
     }
 }
@@ -1,21 +1,39 @@
-use crate::weapon::definition::WeaponKind;
+use crate::{
+    status::StatusEffectKind,
+    utils::spatial_grid::SpatialGrid,
+    weapon::{definition::WeaponKind, projectile::ProjectileKind},
+};
 use fyrox::scene::pivot::PivotBuilder;
 use fyrox::{
     core::{
         algebra::Vector3,
         color::Color,
+        parking_lot::Mutex,
         pool::{Handle, Pool},
+        sstorage::ImmutableString,
         visitor::{Visit, VisitResult, Visitor},
     },
     engine::resource_manager::ResourceManager,
     lazy_static::lazy_static,
+    material::{Material, PropertyValue},
     scene::{
-        base::BaseBuilder, graph::Graph, node::Node, sprite::SpriteBuilder,
+        base::BaseBuilder, graph::Graph, mesh::Mesh, node::Node, sprite::SpriteBuilder,
         transform::TransformBuilder, Scene,
     },
+    utils::log::Log,
 };
 use serde::Deserialize;
-use std::{collections::HashMap, fs::File};
+use std::{collections::HashMap, fs::File, sync::Arc};
+
+/// Items closer than this to the player can be picked up, unless a definition of an item
+/// specifies its own radius.
+const DEFAULT_INTERACTION_RADIUS: f32 = 0.75;
+
+/// Sound of picking up an item, unless a definition of an item specifies its own sound.
+const DEFAULT_PICKUP_SOUND: &str = "data/sounds/item_pickup.ogg";
+
+/// Emission strength of a highlighted item, it should be barely noticeable.
+const HIGHLIGHT_EMISSION: f32 = 0.35;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Deserialize, Hash, Visit)]
 pub enum ItemKind {
@@ -25,6 +43,10 @@ pub enum ItemKind {
     // Ammo
     Ammo,
     Grenade,
+    SmokeGrenade,
+    Flashbang,
+    Mine,
+    ThrowingKnife,
 
     // Weapons
     PlasmaGun,
@@ -35,6 +57,25 @@ pub enum ItemKind {
 
     // Keys
     MasterKey,
+
+    // Data pad with an audio log.
+    DataPad,
+
+    Armor,
+
+    // Charge for the flash light.
+    Battery,
+
+    // Restores condition of weapons.
+    RepairKit,
+
+    // Quick injection that heals a bit and cures poisoning.
+    Stim,
+
+    // Weapon attachments
+    Scope,
+    Suppressor,
+    Grip,
 }
 
 impl Default for ItemKind {
@@ -55,7 +96,28 @@ impl ItemKind {
             | ItemKind::Medpack
             | ItemKind::Ammo
             | ItemKind::Grenade
-            | ItemKind::MasterKey => None,
+            | ItemKind::SmokeGrenade
+            | ItemKind::Flashbang
+            | ItemKind::Mine
+            | ItemKind::ThrowingKnife
+            | ItemKind::MasterKey
+            | ItemKind::DataPad
+            | ItemKind::Armor
+            | ItemKind::Battery
+            | ItemKind::RepairKit
+            | ItemKind::Stim
+            | ItemKind::Scope
+            | ItemKind::Suppressor
+            | ItemKind::Grip => None,
+        }
+    }
+
+    pub fn associated_grenade(&self) -> Option<ProjectileKind> {
+        match self {
+            ItemKind::Grenade => Some(ProjectileKind::Grenade),
+            ItemKind::SmokeGrenade => Some(ProjectileKind::Smoke),
+            ItemKind::Flashbang => Some(ProjectileKind::Flashbang),
+            _ => None,
         }
     }
 }
@@ -68,8 +130,34 @@ pub struct Item {
     spark: Handle<Node>,
     spark_size_change_dir: f32,
     pub stack_size: u32,
+    /// Saves made before stack sizes were taken from definitions have a stack of one for every
+    /// item, such stacks are replaced with the stack size of the definition on load.
+    #[visit(optional)]
+    stack_size_defined: bool,
+    /// Id of an audio log that is added to the journal when a data pad is picked up.
+    #[visit(optional)]
+    pub audio_log: Option<String>,
+    /// Item is stuck in a moving object (like a body of an actor), such items are not put in
+    /// the spatial grid.
+    #[visit(optional)]
+    pub attached: bool,
     #[visit(skip)]
     pub definition: &'static ItemDefinition,
+    #[visit(skip)]
+    highlighted: bool,
+    /// Built lazily on first highlight.
+    #[visit(skip)]
+    highlight_surfaces: Vec<HighlightSurface>,
+}
+
+/// A surface of the model of an item with its original material and a glowing copy of it.
+/// Materials are shared between all instances of a model, so the copy is used to highlight
+/// a single item.
+struct HighlightSurface {
+    mesh: Handle<Node>,
+    index: usize,
+    original: Arc<Mutex<Material>>,
+    highlighted: Arc<Mutex<Material>>,
 }
 
 impl Default for Item {
@@ -81,7 +169,12 @@ impl Default for Item {
             spark: Default::default(),
             spark_size_change_dir: 1.0,
             stack_size: 1,
+            stack_size_defined: false,
+            audio_log: None,
+            attached: false,
             definition: Self::get_definition(ItemKind::Medkit),
+            highlighted: false,
+            highlight_surfaces: Default::default(),
         }
     }
 }
@@ -94,6 +187,62 @@ pub struct ItemDefinition {
     pub name: String,
     pub consumable: bool,
     pub preview: String,
+    /// Amount of the item in a single pickup placed on a level.
+    #[serde(default = "default_stack_size")]
+    pub stack_size: u32,
+    /// Maximum amount of the item that can be carried, `None` means no limit.
+    #[serde(default)]
+    pub reserve_cap: Option<u32>,
+    /// Maximum amount of the item in a single inventory stack, `None` means no limit.
+    #[serde(default)]
+    pub max_stack: Option<u32>,
+    /// Maximum distance at which the item can be picked up.
+    #[serde(default = "default_interaction_radius")]
+    pub interaction_radius: f32,
+    /// Sound that is played when the item is picked up, `None` means the default sound.
+    #[serde(default)]
+    pub pickup_sound: Option<String>,
+    /// Effect of a consumable item on the character that uses it.
+    #[serde(default)]
+    pub use_effect: Option<UseEffect>,
+    /// Items that still use placeholder models are not spawned from tags of level nodes.
+    #[serde(default = "default_placeable")]
+    pub placeable: bool,
+}
+
+/// Describes what happens with a character that uses a consumable item.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct UseEffect {
+    /// Amount of restored health.
+    #[serde(default)]
+    pub heal: f32,
+    /// Amount of added armor.
+    #[serde(default)]
+    pub armor: f32,
+    /// Status effects that are removed from the character.
+    #[serde(default)]
+    pub cure: Vec<StatusEffectKind>,
+    /// Whether the item gets a downed player back on their feet.
+    #[serde(default)]
+    pub revive: bool,
+}
+
+fn default_stack_size() -> u32 {
+    1
+}
+
+fn default_interaction_radius() -> f32 {
+    DEFAULT_INTERACTION_RADIUS
+}
+
+fn default_placeable() -> bool {
+    true
+}
+
+impl ItemDefinition {
+    pub fn pickup_sound(&self) -> &str {
+        self.pickup_sound.as_deref().unwrap_or(DEFAULT_PICKUP_SOUND)
+    }
 }
 
 #[derive(Deserialize, Default)]
@@ -165,6 +314,9 @@ impl Item {
             kind,
             model,
             spark,
+            stack_size: definition.stack_size,
+            stack_size_defined: true,
+            definition,
             ..Default::default()
         }
     }
@@ -200,12 +352,100 @@ impl Item {
 
     pub fn resolve(&mut self) {
         self.definition = Self::get_definition(self.kind);
+        if !self.stack_size_defined {
+            self.stack_size = self.definition.stack_size;
+            self.stack_size_defined = true;
+        }
+    }
+
+    fn build_highlight_surfaces(&mut self, graph: &mut Graph, resource_manager: &ResourceManager) {
+        let meshes = graph.traverse_handle_iter(self.model).collect::<Vec<_>>();
+        for mesh_handle in meshes {
+            if let Some(mesh) = graph[mesh_handle].cast_mut::<Mesh>() {
+                for (index, surface) in mesh.surfaces_mut().iter().enumerate() {
+                    let original = surface.material().clone();
+
+                    let mut material = original.lock().clone();
+                    Log::verify(material.set_property(
+                        &ImmutableString::new("emissionTexture"),
+                        PropertyValue::Sampler {
+                            value: Some(
+                                resource_manager.request_texture("data/ui/white_pixel.bmp"),
+                            ),
+                            fallback: Default::default(),
+                        },
+                    ));
+                    Log::verify(material.set_property(
+                        &ImmutableString::new("emissionStrength"),
+                        PropertyValue::Vector3(Vector3::repeat(HIGHLIGHT_EMISSION)),
+                    ));
+
+                    self.highlight_surfaces.push(HighlightSurface {
+                        mesh: mesh_handle,
+                        index,
+                        original,
+                        highlighted: Arc::new(Mutex::new(material)),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Makes the item glow slightly, it is used to show which item will be picked up.
+    pub fn set_highlighted(
+        &mut self,
+        highlighted: bool,
+        graph: &mut Graph,
+        resource_manager: &ResourceManager,
+    ) {
+        if self.highlighted == highlighted {
+            return;
+        }
+        self.highlighted = highlighted;
+
+        if self.highlight_surfaces.is_empty() {
+            self.build_highlight_surfaces(graph, resource_manager);
+        }
+
+        for surface in self.highlight_surfaces.iter() {
+            if let Some(mesh) = graph
+                .try_get_mut(surface.mesh)
+                .and_then(|node| node.cast_mut::<Mesh>())
+            {
+                if let Some(mesh_surface) = mesh.surfaces_mut().get_mut(surface.index) {
+                    mesh_surface.set_material(if highlighted {
+                        surface.highlighted.clone()
+                    } else {
+                        surface.original.clone()
+                    });
+                }
+            }
+        }
     }
 }
 
+/// State of an item that lies on a level. It is used to restore items of a level that the player
+/// has left, so picked up items do not reappear and dropped ones stay where they were left.
+#[derive(Clone, Default, Debug, Visit)]
+pub struct ItemState {
+    pub kind: ItemKind,
+    pub position: Vector3<f32>,
+    pub stack_size: u32,
+    pub audio_log: Option<String>,
+}
+
 #[derive(Visit)]
 pub struct ItemContainer {
     pool: Pool<Item>,
+    /// Items do not move, so they're put in a grid to speed up proximity checks.
+    #[visit(skip)]
+    grid: SpatialGrid<Handle<Item>>,
+    /// `true` if the grid must be rebuilt, it is done lazily because positions of items
+    /// are stored in the scene graph.
+    #[visit(skip)]
+    grid_dirty: bool,
+    #[visit(skip)]
+    highlighted: Handle<Item>,
 }
 
 impl Default for ItemContainer {
@@ -216,10 +456,16 @@ impl Default for ItemContainer {
 
 impl ItemContainer {
     pub fn new() -> Self {
-        Self { pool: Pool::new() }
+        Self {
+            pool: Pool::new(),
+            grid: Default::default(),
+            grid_dirty: true,
+            highlighted: Default::default(),
+        }
     }
 
     pub fn add(&mut self, item: Item) -> Handle<Item> {
+        self.grid_dirty = true;
         self.pool.spawn(item)
     }
 
@@ -246,12 +492,143 @@ impl ItemContainer {
     pub fn remove(&mut self, item: Handle<Item>, graph: &mut Graph) {
         self.pool[item].cleanup(graph);
         self.pool.free(item);
+        self.grid.remove(item);
+    }
+
+    /// Returns states of items that lie on the level. Items stuck in actors are not included,
+    /// they are gone together with the bodies.
+    pub fn world_state(&self, graph: &Graph) -> Vec<ItemState> {
+        self.pool
+            .iter()
+            .filter(|item| !item.attached)
+            .map(|item| ItemState {
+                kind: item.kind,
+                position: item.position(graph),
+                stack_size: item.stack_size,
+                audio_log: item.audio_log.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the first item (in order of addition) which is closer than `radius` to given
+    /// point.
+    pub fn first_in_range(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+        graph: &Graph,
+    ) -> Option<(Handle<Item>, &Item)> {
+        self.first_filtered(position, radius, graph, |_, distance| distance < radius)
+    }
+
+    /// Returns the first item (in order of addition) that can be picked up from given point,
+    /// every kind of items has its own interaction radius.
+    pub fn first_interactable(
+        &self,
+        position: Vector3<f32>,
+        graph: &Graph,
+    ) -> Option<(Handle<Item>, &Item)> {
+        let max_radius = DEFINITIONS
+            .map
+            .values()
+            .map(|definition| definition.interaction_radius)
+            .fold(DEFAULT_INTERACTION_RADIUS, f32::max);
+
+        self.first_filtered(position, max_radius, graph, |item, distance| {
+            distance < item.definition.interaction_radius
+        })
+    }
+
+    fn first_filtered(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+        graph: &Graph,
+        filter: impl Fn(&Item, f32) -> bool,
+    ) -> Option<(Handle<Item>, &Item)> {
+        let candidates: Box<dyn Iterator<Item = Handle<Item>>> = if self.grid_dirty {
+            Box::new(self.pool.pair_iter().map(|(handle, _)| handle))
+        } else {
+            Box::new(
+                self.grid
+                    .query(position, radius)
+                    .map(|(_, handle)| handle)
+                    .chain(
+                        self.pool
+                            .pair_iter()
+                            .filter(|(_, item)| item.attached)
+                            .map(|(handle, _)| handle),
+                    ),
+            )
+        };
+
+        candidates
+            .filter(|handle| self.pool.is_valid_handle(*handle))
+            .map(|handle| {
+                let item = &self.pool[handle];
+                (
+                    handle,
+                    item,
+                    item.position(graph).metric_distance(&position),
+                )
+            })
+            .filter(|(_, item, distance)| filter(item, *distance))
+            // The grid returns items in arbitrary order, so the first item is picked by its
+            // place in the pool.
+            .min_by_key(|(handle, _, _)| handle.index())
+            .map(|(handle, item, _)| (handle, item))
     }
 
     pub fn update(&mut self, dt: f32, graph: &mut Graph) {
+        // Attached items are removed together with the object they're attached to.
+        let orphans = self
+            .pool
+            .pair_iter()
+            .filter(|(_, item)| !graph.is_valid_handle(item.pivot))
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in orphans {
+            self.pool.free(handle);
+            self.grid.remove(handle);
+        }
+
         for item in self.pool.iter_mut() {
             item.update(dt, graph);
         }
+
+        if self.grid_dirty {
+            self.grid.clear();
+            for (handle, item) in self.pool.pair_iter().filter(|(_, item)| !item.attached) {
+                self.grid.insert(item.position(graph), handle);
+            }
+            self.grid_dirty = false;
+        }
+    }
+
+    /// Highlights the item that is in pickup range of given position (position of the player),
+    /// the previously highlighted item is returned to normal.
+    pub fn update_highlight(
+        &mut self,
+        position: Option<Vector3<f32>>,
+        graph: &mut Graph,
+        resource_manager: &ResourceManager,
+    ) {
+        let target = position
+            .and_then(|position| self.first_interactable(position, graph))
+            .map(|(handle, _)| handle)
+            .unwrap_or_default();
+
+        if target == self.highlighted {
+            return;
+        }
+
+        if let Some(item) = self.pool.try_borrow_mut(self.highlighted) {
+            item.set_highlighted(false, graph, resource_manager);
+        }
+        if let Some(item) = self.pool.try_borrow_mut(target) {
+            item.set_highlighted(true, graph, resource_manager);
+        }
+        self.highlighted = target;
     }
 
     pub fn resolve(&mut self) {
@@ -0,0 +1,285 @@
+//! Explosive barrels placed on a level. A barrel is defined in the editor by assigning
+//! `ExplosiveBarrel` script to a rigid body with a collider. Barrels are damaged by shots and
+//! explosions, a destroyed barrel explodes after a short delay and damages everything around,
+//! including other barrels, so barrels placed close to each other explode in a chain.
+
+use crate::{
+    actor::Actor, current_level_mut, current_level_ref, effects::EffectKind, game_ref,
+    message::Message, GameConstructor,
+};
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        inspect::prelude::*,
+        pool::Handle,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        graph::{map::NodeHandleMap, Graph},
+        node::{Node, NodeHandle, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+use std::path::PathBuf;
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct ExplosiveBarrel {
+    #[inspect(
+        description = "Amount of damage that the barrel can take before it explodes.",
+        min_value = "0.0"
+    )]
+    health: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Fraction of health that must be lost to make the barrel look damaged.",
+        min_value = "0.0"
+    )]
+    damaged_threshold: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "A node (fire or sparks, for example) that is shown when the barrel is damaged."
+    )]
+    damaged_effect: NodeHandle,
+
+    #[inspect(
+        description = "Delay (in seconds) between destruction of the barrel and its explosion.",
+        min_value = "0.0"
+    )]
+    fuse: InheritableVariable<f32>,
+
+    #[inspect(description = "Radius of the explosion.", min_value = "0.0")]
+    explosion_radius: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Damage dealt by the explosion to everything in its radius.",
+        min_value = "0.0"
+    )]
+    explosion_damage: InheritableVariable<f32>,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(optional)]
+    damage_taken: f32,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(optional)]
+    damaged: bool,
+
+    /// Time left before the explosion, `None` if the barrel is not destroyed yet.
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(optional)]
+    fuse_timer: Option<f32>,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(optional)]
+    exploded: bool,
+
+    /// The last actor that damaged the barrel, the explosion is attributed to it.
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(optional)]
+    attacker: Handle<Actor>,
+}
+
+impl Default for ExplosiveBarrel {
+    fn default() -> Self {
+        Self {
+            health: InheritableVariable::new(40.0),
+            damaged_threshold: InheritableVariable::new(0.5),
+            damaged_effect: Default::default(),
+            fuse: InheritableVariable::new(0.3),
+            explosion_radius: InheritableVariable::new(4.0),
+            explosion_damage: InheritableVariable::new(80.0),
+            damage_taken: 0.0,
+            damaged: false,
+            fuse_timer: None,
+            exploded: false,
+            attacker: Default::default(),
+        }
+    }
+}
+
+impl_component_provider!(ExplosiveBarrel);
+
+impl TypeUuidProvider for ExplosiveBarrel {
+    fn type_uuid() -> Uuid {
+        uuid!("d3a7c5e1-9f20-4b6d-8e4a-51c0b7f2a396")
+    }
+}
+
+impl ScriptTrait for ExplosiveBarrel {
+    fn on_init(&mut self, context: ScriptContext) {
+        current_level_mut(context.plugin)
+            .barrels
+            .add(context.handle);
+    }
+
+    fn on_deinit(&mut self, context: ScriptDeinitContext) {
+        current_level_mut(context.plugin)
+            .barrels
+            .remove(context.node_handle);
+    }
+
+    fn on_update(&mut self, context: ScriptContext) {
+        if self.exploded || current_level_ref(context.plugin).is_photo_mode_active() {
+            return;
+        }
+
+        let sender = &game_ref(context.plugin).message_sender;
+        let graph = &mut context.scene.graph;
+        let position = graph[context.handle].global_position();
+
+        if !self.damaged && self.damage_taken >= *self.health * *self.damaged_threshold {
+            self.damaged = true;
+
+            sender.send(Message::CreateEffect {
+                kind: EffectKind::Smoke,
+                position,
+                orientation: Default::default(),
+            });
+        }
+
+        if let Some(effect) = graph.try_get_mut(*self.damaged_effect) {
+            effect.set_visibility(self.damaged);
+        }
+
+        if self.fuse_timer.is_none() && self.damage_taken >= *self.health {
+            self.fuse_timer = Some(*self.fuse);
+        }
+
+        if let Some(fuse_timer) = self.fuse_timer.as_mut() {
+            *fuse_timer -= context.dt;
+            if *fuse_timer <= 0.0 {
+                self.exploded = true;
+
+                sender.send(Message::CreateEffect {
+                    kind: EffectKind::BulletImpact,
+                    position,
+                    orientation: Default::default(),
+                });
+                sender.send(Message::CreateEffect {
+                    kind: EffectKind::Smoke,
+                    position,
+                    orientation: Default::default(),
+                });
+                sender.send(Message::PlaySound {
+                    path: PathBuf::from("data/sounds/explosion.wav"),
+                    position,
+                    gain: 1.0,
+                    rolloff_factor: 4.0,
+                    radius: 3.0,
+                });
+                sender.send(Message::ApplySplashDamage {
+                    amount: *self.explosion_damage,
+                    radius: *self.explosion_radius,
+                    center: position,
+                    who: self.attacker,
+                    critical_shot_probability: 0.0,
+                });
+
+                shatter(context.handle, graph);
+            }
+        }
+    }
+
+    fn remap_handles(&mut self, old_new_mapping: &NodeHandleMap) {
+        old_new_mapping.try_map(&mut self.damaged_effect);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+/// Hides every part of the barrel and removes its colliders.
+fn shatter(barrel: Handle<Node>, graph: &mut Graph) {
+    // Removing a collider removes its children too, so split the nodes before touching them.
+    let (colliders, others): (Vec<_>, Vec<_>) = graph
+        .traverse_handle_iter(barrel)
+        .partition(|&descendant| graph[descendant].is_collider());
+
+    for node in others {
+        graph[node].set_visibility(false);
+    }
+
+    for collider in colliders {
+        if graph.is_valid_handle(collider) {
+            graph.remove_node(collider);
+        }
+    }
+}
+
+impl ExplosiveBarrel {
+    /// Accumulates damage, the barrel will explode after its fuse time once the damage has
+    /// exceeded its health.
+    pub fn damage(&mut self, amount: f32, who: Handle<Actor>) {
+        if !self.exploded {
+            self.damage_taken += amount.max(0.0);
+            if who.is_some() {
+                self.attacker = who;
+            }
+        }
+    }
+}
+
+pub fn barrel_mut(handle: Handle<Node>, graph: &mut Graph) -> &mut ExplosiveBarrel {
+    graph[handle]
+        .script_mut()
+        .and_then(|s| s.cast_mut::<ExplosiveBarrel>())
+        .unwrap()
+}
+
+#[derive(Default, Visit)]
+pub struct BarrelContainer {
+    barrels: Vec<Handle<Node>>,
+}
+
+impl BarrelContainer {
+    pub fn add(&mut self, barrel: Handle<Node>) {
+        if !self.barrels.contains(&barrel) {
+            self.barrels.push(barrel);
+        }
+    }
+
+    pub fn remove(&mut self, barrel: Handle<Node>) {
+        if let Some(position) = self.barrels.iter().position(|b| *b == barrel) {
+            self.barrels.remove(position);
+        }
+    }
+
+    /// Returns a barrel which the collider belongs to.
+    pub fn find_by_collider(&self, collider: Handle<Node>, graph: &Graph) -> Option<Handle<Node>> {
+        // Colliders are attached to the rigid body of the barrel.
+        graph
+            .try_get(collider)
+            .map(|collider| collider.parent())
+            .filter(|body| self.barrels.contains(body))
+    }
+
+    pub fn barrels_near(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+        graph: &Graph,
+    ) -> Vec<Handle<Node>> {
+        self.barrels
+            .iter()
+            .filter(|&&barrel| {
+                graph.is_valid_handle(barrel)
+                    && graph[barrel].global_position().metric_distance(&position) <= radius
+            })
+            .copied()
+            .collect()
+    }
+}
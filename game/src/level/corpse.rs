@@ -0,0 +1,133 @@
+//! Corpse budget. Dead bots are kept on a level for some time, but a long fight would fill the
+//! level with bodies, so once there are too many of them the oldest corpses fade out and are
+//! removed. The budget is defined in `data/configs/corpses.ron`.
+
+use crate::{
+    actor::{Actor, ActorContainer},
+    level::trail::set_alpha,
+    message::Message,
+    MessageSender,
+};
+use fyrox::{
+    core::{parking_lot::Mutex, pool::Handle, visitor::prelude::*},
+    lazy_static::lazy_static,
+    scene::{mesh::Mesh, Scene},
+};
+use serde::Deserialize;
+use std::{fs::File, sync::Arc};
+
+#[derive(Deserialize)]
+pub struct CorpseDefinition {
+    /// Maximum amount of corpses on a level.
+    pub max_corpses: usize,
+    /// Duration (in seconds) of fading of a corpse.
+    pub fade_time: f32,
+    /// Maximum amount of decals on a level.
+    pub max_decals: usize,
+}
+
+impl CorpseDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/corpses.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    pub static ref CORPSES: CorpseDefinition = CorpseDefinition::load();
+}
+
+#[derive(Default, Visit)]
+struct Corpse {
+    actor: Handle<Actor>,
+    /// Time left before removal, `None` if the corpse is not fading yet.
+    fade_timer: Option<f32>,
+    /// Materials of the corpse are shared with living bots of the same kind, so they're
+    /// replaced by copies before fading.
+    #[visit(skip)]
+    own_materials: bool,
+}
+
+impl Corpse {
+    fn fade(&mut self, scene: &mut Scene, actors: &ActorContainer, dt: f32) -> bool {
+        let fade_timer = match self.fade_timer.as_mut() {
+            Some(fade_timer) => fade_timer,
+            None => return false,
+        };
+        if *fade_timer <= 0.0 {
+            // Faded already, waiting for removal.
+            return false;
+        }
+
+        let pivot = actors.get(self.actor).pivot;
+        let nodes = scene.graph.traverse_handle_iter(pivot).collect::<Vec<_>>();
+
+        if !self.own_materials {
+            for &node in nodes.iter() {
+                if let Some(mesh) = scene.graph[node].cast_mut::<Mesh>() {
+                    for surface in mesh.surfaces_mut() {
+                        let material = surface.material().lock().clone();
+                        surface.set_material(Arc::new(Mutex::new(material)));
+                    }
+                }
+            }
+            self.own_materials = true;
+        }
+
+        *fade_timer -= dt;
+        let k = (*fade_timer / CORPSES.fade_time.max(f32::EPSILON)).clamp(0.0, 1.0);
+        for node in nodes {
+            set_alpha(&mut scene.graph, node, (255.0 * k) as u8);
+        }
+
+        *fade_timer <= 0.0
+    }
+}
+
+#[derive(Default, Visit)]
+pub struct CorpseContainer {
+    /// Corpses in order of death, the oldest is the first.
+    corpses: Vec<Corpse>,
+}
+
+impl CorpseContainer {
+    pub fn update(
+        &mut self,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        sender: &MessageSender,
+        dt: f32,
+    ) {
+        self.corpses.retain(|corpse| actors.contains(corpse.actor));
+
+        // A bot becomes a corpse when it has finished dying (its ragdoll settled, for example).
+        for (handle, actor) in actors.pair_iter() {
+            if let Actor::Bot(_) = actor {
+                if actor.is_dead()
+                    && actor.can_be_removed(scene)
+                    && !self.corpses.iter().any(|c| c.actor == handle)
+                {
+                    self.corpses.push(Corpse {
+                        actor: handle,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        let excess = self.corpses.len().saturating_sub(CORPSES.max_corpses);
+        for corpse in self.corpses.iter_mut().take(excess) {
+            if corpse.fade_timer.is_none() {
+                corpse.fade_timer = Some(CORPSES.fade_time);
+            }
+        }
+
+        for corpse in self.corpses.iter_mut() {
+            if corpse.fade(scene, actors, dt) {
+                sender.send(Message::RemoveActor {
+                    actor: corpse.actor,
+                });
+            }
+        }
+    }
+}
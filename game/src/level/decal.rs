@@ -1,3 +1,4 @@
+use crate::{level::corpse::CORPSES, sound::MaterialType};
 use fyrox::core::algebra::Point3;
 use fyrox::resource::texture::Texture;
 use fyrox::{
@@ -10,11 +11,38 @@ use fyrox::{
         VecExtensions,
     },
     engine::resource_manager::ResourceManager,
+    lazy_static::lazy_static,
     scene::{
         base::BaseBuilder, decal::DecalBuilder, graph::Graph, node::Node,
         transform::TransformBuilder,
     },
 };
+use serde::Deserialize;
+use std::{collections::HashMap, fs::File};
+
+#[derive(Deserialize)]
+pub struct DecalDefinition {
+    pub default_bullet_hole: String,
+    pub bullet_holes: HashMap<MaterialType, String>,
+    pub blood_splatter: String,
+}
+
+impl DecalDefinition {
+    pub fn new() -> Self {
+        let file = File::open("data/configs/decals.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+
+    pub fn bullet_hole_texture(&self, material: Option<MaterialType>) -> &str {
+        material
+            .and_then(|material| self.bullet_holes.get(&material))
+            .unwrap_or(&self.default_bullet_hole)
+    }
+}
+
+lazy_static! {
+    pub static ref DEFINITION: DecalDefinition = DecalDefinition::new();
+}
 
 #[derive(Default, Visit)]
 pub struct Decal {
@@ -91,6 +119,7 @@ impl Decal {
         face_towards: Vector3<f32>,
         parent: Handle<Node>,
         color: Color,
+        material: Option<MaterialType>,
     ) -> Self {
         let default_scale = Vector3::new(0.05, 0.05, 0.05);
 
@@ -101,7 +130,24 @@ impl Decal {
             parent,
             color,
             default_scale,
-            resource_manager.request_texture("data/textures/decals/BulletImpact_BaseColor.png"),
+            resource_manager.request_texture(DEFINITION.bullet_hole_texture(material)),
+        )
+    }
+
+    pub fn new_blood_splatter(
+        resource_manager: ResourceManager,
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        face_towards: Vector3<f32>,
+    ) -> Self {
+        Self::new(
+            graph,
+            position,
+            face_towards,
+            Handle::NONE,
+            Color::opaque(255, 255, 255),
+            Vector3::new(0.45, 0.45, 0.2),
+            resource_manager.request_texture(&DEFINITION.blood_splatter),
         )
     }
 }
@@ -112,7 +158,15 @@ pub struct DecalContainer {
 }
 
 impl DecalContainer {
-    pub fn add(&mut self, decal: Decal) {
+    pub fn add(&mut self, graph: &mut Graph, decal: Decal) {
+        // Oldest decals are removed first when the limit is reached.
+        while !self.decals.is_empty() && self.decals.len() >= CORPSES.max_decals {
+            let oldest = self.decals.remove(0);
+            if graph.is_valid_handle(oldest.decal) {
+                graph.remove_node(oldest.decal);
+            }
+        }
+
         self.decals.push(decal);
     }
 
@@ -128,7 +182,12 @@ impl DecalContainer {
                 1.0
             };
 
-            let decal_node = graph[decal.decal].as_decal_mut();
+            // Decal could be removed together with its parent (a dead actor for example).
+            let decal_node = if let Some(decal_node) = graph.try_get_mut(decal.decal) {
+                decal_node.as_decal_mut()
+            } else {
+                return false;
+            };
 
             decal_node.set_color(decal_node.color().with_new_alpha((255.0 * alpha) as u8));
 
@@ -0,0 +1,161 @@
+//! Hazard zones are areas filled with radiation, toxic gas and so on. A zone is defined in the
+//! editor by assigning `HazardZone` script to a node, every actor inside the volume of the zone
+//! takes continuous damage until it leaves the zone.
+
+use crate::{
+    actor::ActorContainer, current_level_mut, message::Message, GameConstructor, MessageSender,
+};
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        inspect::prelude::*,
+        pool::Handle,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        graph::Graph,
+        node::{Node, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct HazardZone {
+    #[inspect(description = "Name of the hazard that is shown to the player inside the zone.")]
+    name: InheritableVariable<String>,
+
+    #[inspect(description = "Radius of the volume of the zone.", min_value = "0.0")]
+    radius: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Damage per second dealt to every actor inside the zone.",
+        min_value = "0.0"
+    )]
+    damage_per_second: InheritableVariable<f32>,
+}
+
+impl Default for HazardZone {
+    fn default() -> Self {
+        Self {
+            name: InheritableVariable::new("Radiation".to_owned()),
+            radius: InheritableVariable::new(3.0),
+            damage_per_second: InheritableVariable::new(5.0),
+        }
+    }
+}
+
+impl_component_provider!(HazardZone);
+
+impl TypeUuidProvider for HazardZone {
+    fn type_uuid() -> Uuid {
+        uuid!("5b9e02d7-c314-4f8a-9d61-e7a2f40b83c5")
+    }
+}
+
+impl ScriptTrait for HazardZone {
+    fn on_init(&mut self, context: ScriptContext) {
+        current_level_mut(context.plugin)
+            .hazards
+            .add(context.handle);
+    }
+
+    fn on_deinit(&mut self, context: ScriptDeinitContext) {
+        current_level_mut(context.plugin)
+            .hazards
+            .remove(context.node_handle);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+impl HazardZone {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn contains(&self, zone_position: Vector3<f32>, position: Vector3<f32>) -> bool {
+        zone_position.metric_distance(&position) <= *self.radius
+    }
+}
+
+pub fn hazard_zone_ref(handle: Handle<Node>, graph: &Graph) -> &HazardZone {
+    graph[handle]
+        .script()
+        .and_then(|s| s.cast::<HazardZone>())
+        .unwrap()
+}
+
+#[derive(Default, Visit)]
+pub struct HazardZoneContainer {
+    zones: Vec<Handle<Node>>,
+}
+
+impl HazardZoneContainer {
+    pub fn add(&mut self, zone: Handle<Node>) {
+        if !self.zones.contains(&zone) {
+            self.zones.push(zone);
+        }
+    }
+
+    pub fn remove(&mut self, zone: Handle<Node>) {
+        if let Some(position) = self.zones.iter().position(|z| *z == zone) {
+            self.zones.remove(position);
+        }
+    }
+
+    /// Returns the first zone that has given position inside its volume.
+    pub fn find(&self, position: Vector3<f32>, graph: &Graph) -> Option<&HazardZone> {
+        self.zones
+            .iter()
+            .filter(|&&handle| graph.is_valid_handle(handle))
+            .map(|&handle| {
+                (
+                    hazard_zone_ref(handle, graph),
+                    graph[handle].global_position(),
+                )
+            })
+            .find(|(zone, zone_position)| zone.contains(*zone_position, position))
+            .map(|(zone, _)| zone)
+    }
+
+    /// Damages every living actor inside the zones, zones do not stack - an actor inside of
+    /// several zones takes damage of the strongest one.
+    pub fn update(&self, graph: &Graph, actors: &ActorContainer, sender: &MessageSender, dt: f32) {
+        for (handle, actor) in actors.pair_iter() {
+            if actor.is_dead() {
+                continue;
+            }
+
+            let position = actor.position(graph);
+            let damage_per_second = self
+                .zones
+                .iter()
+                .filter(|&&zone| graph.is_valid_handle(zone))
+                .map(|&zone| (hazard_zone_ref(zone, graph), graph[zone].global_position()))
+                .filter(|(zone, zone_position)| zone.contains(*zone_position, position))
+                .map(|(zone, _)| *zone.damage_per_second)
+                .fold(0.0f32, f32::max);
+
+            if damage_per_second > 0.0 {
+                sender.send(Message::DamageActor {
+                    actor: handle,
+                    who: Default::default(),
+                    weapon: Default::default(),
+                    hitbox: None,
+                    amount: damage_per_second * dt,
+                    critical_shot_probability: 0.0,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Hit-stop is a short slowdown of the game that happens when a shot of the player hits an actor,
+//! it makes hits feel punchier. Hit-stops can't happen more often than the cooldown allows, so
+//! rapid fire does not stack them.
+
+use fyrox::lazy_static::lazy_static;
+use serde::Deserialize;
+use std::fs::File;
+
+#[derive(Deserialize)]
+pub struct HitStopDefinition {
+    pub enabled: bool,
+    /// Speed of the game during hit-stop, 1.0 means no slowdown.
+    pub time_scale: f32,
+    /// Duration of hit-stop in seconds of real time.
+    pub duration: f32,
+    /// Minimum time (in seconds) between two hit-stops.
+    pub cooldown: f32,
+    /// Distance at which the camera is pushed back on a hit.
+    pub camera_kick: f32,
+}
+
+impl HitStopDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/hit_stop.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    pub static ref HIT_STOP: HitStopDefinition = HitStopDefinition::load();
+}
+
+#[derive(Default)]
+pub struct HitStop {
+    timer: f32,
+    cooldown: f32,
+}
+
+impl HitStop {
+    /// Starts hit-stop, returns `false` if it is disabled or still on cooldown.
+    pub fn request(&mut self) -> bool {
+        if !HIT_STOP.enabled || self.cooldown > 0.0 {
+            return false;
+        }
+
+        self.timer = HIT_STOP.duration;
+        self.cooldown = HIT_STOP.duration + HIT_STOP.cooldown;
+        true
+    }
+
+    /// Advances hit-stop by given amount of real time and returns current time scale.
+    pub fn update(&mut self, dt: f32) -> f32 {
+        self.cooldown = (self.cooldown - dt).max(0.0);
+        if self.timer > 0.0 {
+            self.timer -= dt;
+            HIT_STOP.time_scale.clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Ladders placed on a level. A ladder is defined in the editor by assigning `Ladder` script to a
+//! node at the bottom of the ladder, look vector of the node must point from the ladder towards
+//! the place where the climber stands.
+
+use crate::{current_level_mut, GameConstructor};
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        inspect::prelude::*,
+        pool::Handle,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        graph::Graph,
+        node::{Node, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct Ladder {
+    #[inspect(
+        description = "Height of the ladder, the top of it must be at the level of the floor above.",
+        min_value = "0.0"
+    )]
+    height: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Maximum distance from the ladder at which it can be grabbed.",
+        min_value = "0.0"
+    )]
+    grab_distance: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Speed (in units per second) of climbing the ladder.",
+        min_value = "0.0"
+    )]
+    climb_speed: InheritableVariable<f32>,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(skip)]
+    bottom: Vector3<f32>,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(skip)]
+    normal: Vector3<f32>,
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Self {
+            height: InheritableVariable::new(3.0),
+            grab_distance: InheritableVariable::new(0.6),
+            climb_speed: InheritableVariable::new(1.2),
+            bottom: Default::default(),
+            normal: Vector3::z(),
+        }
+    }
+}
+
+impl_component_provider!(Ladder);
+
+impl TypeUuidProvider for Ladder {
+    fn type_uuid() -> Uuid {
+        uuid!("7c1e5a92-3b4d-4f08-a6e2-91d8c0b5f347")
+    }
+}
+
+impl ScriptTrait for Ladder {
+    fn on_init(&mut self, context: ScriptContext) {
+        // Ladders never move, so it is enough to fetch their placement once.
+        let node = &context.scene.graph[context.handle];
+        self.bottom = node.global_position();
+        let look_vector = node.look_vector();
+        self.normal = Vector3::new(look_vector.x, 0.0, look_vector.z)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::z);
+
+        current_level_mut(context.plugin)
+            .ladders
+            .add(context.handle);
+    }
+
+    fn on_deinit(&mut self, context: ScriptDeinitContext) {
+        current_level_mut(context.plugin)
+            .ladders
+            .remove(context.node_handle);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+impl Ladder {
+    pub fn bottom(&self) -> Vector3<f32> {
+        self.bottom
+    }
+
+    pub fn top(&self) -> Vector3<f32> {
+        self.bottom + Vector3::new(0.0, *self.height, 0.0)
+    }
+
+    /// Horizontal direction from the ladder towards the climber.
+    pub fn normal(&self) -> Vector3<f32> {
+        self.normal
+    }
+
+    pub fn climb_speed(&self) -> f32 {
+        *self.climb_speed
+    }
+
+    /// Checks whether someone with feet at given position is able to grab the ladder, either at
+    /// the bottom or from the floor at the top of it.
+    pub fn can_be_grabbed(&self, feet_position: Vector3<f32>) -> bool {
+        let horizontal_distance = Vector3::new(
+            feet_position.x - self.bottom.x,
+            0.0,
+            feet_position.z - self.bottom.z,
+        )
+        .norm();
+
+        horizontal_distance <= *self.grab_distance
+            && feet_position.y >= self.bottom.y - 0.3
+            && feet_position.y <= self.top().y + 0.3
+    }
+}
+
+pub fn ladder_ref(handle: Handle<Node>, graph: &Graph) -> &Ladder {
+    graph[handle]
+        .script()
+        .and_then(|s| s.cast::<Ladder>())
+        .unwrap()
+}
+
+#[derive(Default, Visit)]
+pub struct LadderContainer {
+    ladders: Vec<Handle<Node>>,
+}
+
+impl LadderContainer {
+    pub fn add(&mut self, ladder: Handle<Node>) {
+        if !self.ladders.contains(&ladder) {
+            self.ladders.push(ladder);
+        }
+    }
+
+    pub fn remove(&mut self, ladder: Handle<Node>) {
+        if let Some(position) = self.ladders.iter().position(|l| *l == ladder) {
+            self.ladders.remove(position);
+        }
+    }
+
+    /// Returns the closest ladder that can be grabbed by someone with feet at given position.
+    /// There are just a few ladders on each level, so there is no need for a spatial grid.
+    pub fn find_grabbable<'a>(
+        &self,
+        feet_position: Vector3<f32>,
+        graph: &'a Graph,
+    ) -> Option<&'a Ladder> {
+        self.ladders
+            .iter()
+            .filter(|&&handle| graph.is_valid_handle(handle))
+            .map(|&handle| ladder_ref(handle, graph))
+            .filter(|ladder| ladder.can_be_grabbed(feet_position))
+            .min_by(|a, b| {
+                let da = a.bottom().xz().metric_distance(&feet_position.xz());
+                let db = b.bottom().xz().metric_distance(&feet_position.xz());
+                da.total_cmp(&db)
+            })
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{
+    actor::{Actor, ActorContainer},
+    effects::EffectKind,
+    message::Message,
+    MessageSender,
+};
+use fyrox::{
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        color::Color,
+        math::vector_to_quat,
+        parking_lot::Mutex,
+        pool::{Handle, Pool},
+        sstorage::ImmutableString,
+        visitor::prelude::*,
+    },
+    material::{Material, PropertyValue},
+    scene::{
+        base::BaseBuilder,
+        light::{point::PointLightBuilder, BaseLight, BaseLightBuilder},
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData},
+            MeshBuilder,
+        },
+        node::Node,
+        transform::TransformBuilder,
+        Scene,
+    },
+    utils::log::Log,
+};
+use std::{path::PathBuf, sync::Arc};
+
+/// Maximum amount of active mines that can be placed by a single actor.
+pub const MAX_MINES_PER_ACTOR: usize = 3;
+
+const TRIGGER_RADIUS: f32 = 1.25;
+const SPLASH_RADIUS: f32 = 3.0;
+const DAMAGE: f32 = 150.0;
+/// Time after placement until mine starts reacting on actors.
+const ARMING_TIME: f32 = 1.5;
+/// Time after placement during which mine ignores its owner.
+const OWNER_GRACE_PERIOD: f32 = 5.0;
+
+const ARMING_COLOR: Color = Color::opaque(255, 200, 0);
+const ARMED_COLOR: Color = Color::opaque(255, 0, 0);
+
+#[derive(Default, Visit)]
+pub struct Mine {
+    model: Handle<Node>,
+    light: Handle<Node>,
+    pub owner: Handle<Actor>,
+    lifetime: f32,
+    blink_timer: f32,
+    exploded: bool,
+}
+
+impl Mine {
+    pub fn new(
+        scene: &mut Scene,
+        position: Vector3<f32>,
+        normal: Vector3<f32>,
+        owner: Handle<Actor>,
+    ) -> Self {
+        let light;
+        let model = MeshBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .with_local_rotation(
+                            vector_to_quat(normal)
+                                * UnitQuaternion::from_axis_angle(
+                                    &Vector3::x_axis(),
+                                    90.0f32.to_radians(),
+                                ),
+                        )
+                        .build(),
+                )
+                .with_children(&[{
+                    light = PointLightBuilder::new(
+                        BaseLightBuilder::new(
+                            BaseBuilder::new().with_local_transform(
+                                TransformBuilder::new()
+                                    .with_local_position(Vector3::new(0.0, 0.05, 0.0))
+                                    .build(),
+                            ),
+                        )
+                        .cast_shadows(false)
+                        .with_scatter_enabled(false)
+                        .with_color(ARMING_COLOR),
+                    )
+                    .with_radius(0.25)
+                    .build(&mut scene.graph);
+                    light
+                }]),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(Arc::new(Mutex::new(
+            SurfaceData::make_cylinder(12, 0.08, 0.04, true, &Matrix4::identity()),
+        )))
+        .with_material(Arc::new(Mutex::new({
+            let mut material = Material::standard();
+            Log::verify(material.set_property(
+                &ImmutableString::new("diffuseColor"),
+                PropertyValue::Color(Color::opaque(60, 60, 60)),
+            ));
+            material
+        })))
+        .build()])
+        .build(&mut scene.graph);
+
+        Self {
+            model,
+            light,
+            owner,
+            lifetime: 0.0,
+            blink_timer: 0.0,
+            exploded: false,
+        }
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.lifetime >= ARMING_TIME
+    }
+
+    fn update(
+        &mut self,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        sender: &MessageSender,
+        dt: f32,
+    ) {
+        self.lifetime += dt;
+        self.blink_timer -= dt;
+
+        // Arming mine blinks fast, armed mine - slowly.
+        if self.blink_timer <= 0.0 {
+            let (color, interval) = if self.is_armed() {
+                (ARMED_COLOR, 1.0)
+            } else {
+                (ARMING_COLOR, 0.15)
+            };
+
+            let light = &mut scene.graph[self.light];
+            let visibility = !light.visibility();
+            light.set_visibility(visibility);
+            light
+                .query_component_mut::<BaseLight>()
+                .unwrap()
+                .set_color(color);
+
+            self.blink_timer = interval;
+        }
+
+        if !self.is_armed() {
+            return;
+        }
+
+        let position = scene.graph[self.model].global_position();
+
+        for (actor_handle, actor) in actors.pair_iter() {
+            if actor.is_dead() || (actor_handle == self.owner && self.lifetime < OWNER_GRACE_PERIOD)
+            {
+                continue;
+            }
+
+            if actor.position(&scene.graph).metric_distance(&position) < TRIGGER_RADIUS {
+                self.explode(position, sender);
+                break;
+            }
+        }
+    }
+
+    fn explode(&mut self, position: Vector3<f32>, sender: &MessageSender) {
+        self.exploded = true;
+
+        sender.send(Message::CreateEffect {
+            kind: EffectKind::BulletImpact,
+            position,
+            orientation: Default::default(),
+        });
+
+        sender.send(Message::PlaySound {
+            path: PathBuf::from("data/sounds/explosion.wav"),
+            position,
+            gain: 1.0,
+            rolloff_factor: 4.0,
+            radius: 3.0,
+        });
+
+        sender.send(Message::ApplySplashDamage {
+            amount: DAMAGE,
+            radius: SPLASH_RADIUS,
+            center: position,
+            who: self.owner,
+            critical_shot_probability: 0.0,
+        });
+    }
+
+    fn clean_up(&mut self, scene: &mut Scene) {
+        scene.graph.remove_node(self.model);
+    }
+}
+
+#[derive(Default, Visit)]
+pub struct MineContainer {
+    pool: Pool<Mine>,
+}
+
+impl MineContainer {
+    pub fn add(&mut self, mine: Mine) -> Handle<Mine> {
+        self.pool.spawn(mine)
+    }
+
+    pub fn count_of(&self, owner: Handle<Actor>) -> usize {
+        self.pool.iter().filter(|m| m.owner == owner).count()
+    }
+
+    pub fn update(
+        &mut self,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        sender: &MessageSender,
+        dt: f32,
+    ) {
+        for mine in self.pool.iter_mut() {
+            mine.update(scene, actors, sender, dt);
+            if mine.exploded {
+                mine.clean_up(scene);
+            }
+        }
+
+        self.pool.retain(|mine| !mine.exploded);
+    }
+}
@@ -1,33 +1,58 @@
 use crate::{
-    actor::{Actor, ActorContainer},
-    bot::{Bot, BotKind},
-    character::HitBox,
-    config::SoundConfig,
-    control_scheme::ControlScheme,
+    actor::{Actor, ActorContainer, TargetKind},
+    bot::{
+        cover::CoverPointContainer,
+        patrol::{Patrol, PatrolRoute},
+        Bot, BotKind,
+    },
+    character::{GiveResult, HitBox, ARMOR_DEFINITION, KNOCKBACK},
+    config::{
+        CameraConfig, DoubleJumpConfig, DownedStateConfig, HealthRegenerationConfig,
+        LaserSightConfig, SoundConfig, WeaponJamConfig,
+    },
+    control_scheme::{ControlButton, ControlScheme},
     door::{door_mut, DoorContainer},
     effects::{self, EffectKind},
     elevator::{
         call_button::{CallButton, CallButtonContainer, CallButtonKind},
         Elevator, ElevatorContainer,
     },
-    item::{Item, ItemContainer, ItemKind},
+    gamepad::GamepadInput,
+    gui::journal::Journal,
+    item::{Item, ItemContainer, ItemKind, ItemState},
     level::{
+        barrel::{barrel_mut, BarrelContainer},
+        corpse::CorpseContainer,
         decal::{Decal, DecalContainer},
+        hazard::HazardZoneContainer,
+        hit_stop::{HitStop, HIT_STOP},
+        ladder::LadderContainer,
+        mine::{Mine, MineContainer, MAX_MINES_PER_ACTOR},
+        music::MusicDirector,
+        photo_mode::PhotoMode,
+        smoke::{SmokeCloud, SmokeCloudContainer},
+        terminal::{terminal_ref, TerminalContainer},
         trail::{ShotTrail, ShotTrailContainer},
+        trajectory::GrenadeTrajectory,
+        transition::LevelTransitionContainer,
         trigger::{Trigger, TriggerContainer, TriggerKind},
         turret::{Hostility, ShootMode, Turret, TurretContainer},
+        wave::WaveSpawnerContainer,
     },
     light::{Light, LightContainer},
+    loading_screen::LoadingStage,
     message::Message,
     player::{Player, PlayerPersistentData},
-    sound::{SoundKind, SoundManager},
+    sound::{MaterialType, NoiseKind, SoundKind, SoundManager},
+    stats::SessionStats,
+    status::StatusEffectKind,
     utils::{is_probability_event_occurred, use_hrtf},
     weapon::{
         definition::{ShotEffect, WeaponKind},
         projectile::{Damage, Projectile, ProjectileContainer, ProjectileKind, Shooter},
         ray_hit,
         sight::SightReaction,
-        Weapon, WeaponContainer,
+        Weapon, WeaponContainer, WeaponPersistentData, JAM_SOUND,
     },
     CallButtonUiContainer, GameTime, MessageSender,
 };
@@ -44,7 +69,7 @@ use fyrox::{
         visitor::prelude::*,
     },
     engine::resource_manager::ResourceManager,
-    event::Event,
+    event::{DeviceEvent, ElementState, Event, WindowEvent},
     material::{Material, PropertyValue},
     plugin::PluginContext,
     rand,
@@ -53,13 +78,14 @@ use fyrox::{
         self, base,
         base::BaseBuilder,
         collider::ColliderShape,
-        graph::physics::RayCastOptions,
+        graph::{physics::RayCastOptions, Graph},
         mesh::{
             surface::{SurfaceBuilder, SurfaceData},
             MeshBuilder, RenderPath,
         },
         node::Node,
-        rigidbody::RigidBody,
+        rigidbody::{RigidBody, RigidBodyType},
+        sprite::SpriteBuilder,
         transform::TransformBuilder,
         Scene,
     },
@@ -69,18 +95,36 @@ use fyrox::{
     },
 };
 use std::{
+    collections::{HashMap, VecDeque},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
+pub mod barrel;
+pub mod corpse;
 pub mod decal;
+pub mod hazard;
+pub mod hit_stop;
+pub mod ladder;
+pub mod mine;
+pub mod music;
+pub mod objective;
+pub mod photo_mode;
+pub mod smoke;
+pub mod terminal;
 pub mod trail;
+pub mod trajectory;
+pub mod transition;
 pub mod trigger;
 pub mod turret;
+pub mod wave;
 
 #[derive(Default, Visit)]
 pub struct Level {
     pub map_path: String,
+    /// States of items of levels that were visited before, keyed by path of a level.
+    #[visit(optional)]
+    world_items: HashMap<String, Vec<ItemState>>,
     pub scene: Handle<Scene>,
     player: Handle<Actor>,
     projectiles: ProjectileContainer,
@@ -102,8 +146,54 @@ pub struct Level {
     turrets: TurretContainer,
     triggers: TriggerContainer,
     decals: DecalContainer,
+    #[visit(optional)]
+    corpses: CorpseContainer,
     pub elevators: ElevatorContainer,
     pub call_buttons: CallButtonContainer,
+    #[visit(optional)]
+    mines: MineContainer,
+    #[visit(optional)]
+    smoke_clouds: SmokeCloudContainer,
+    /// A sprite that hangs over the target of the active objective.
+    #[visit(optional)]
+    objective_marker: Handle<Node>,
+    #[visit(optional)]
+    grenade_trajectory: GrenadeTrajectory,
+    /// Impact effects that are still alive, the oldest ones are removed when there are too many.
+    #[visit(skip)]
+    impact_effects: VecDeque<Handle<Node>>,
+    #[visit(optional)]
+    music: MusicDirector,
+    #[visit(optional)]
+    pub ladders: LadderContainer,
+    #[visit(optional)]
+    pub terminals: TerminalContainer,
+    #[visit(optional)]
+    pub cover_points: CoverPointContainer,
+    #[visit(optional)]
+    pub transitions: LevelTransitionContainer,
+    #[visit(optional)]
+    pub wave_spawners: WaveSpawnerContainer,
+    #[visit(optional)]
+    pub barrels: BarrelContainer,
+    #[visit(optional)]
+    pub hazards: HazardZoneContainer,
+    #[visit(skip)]
+    laser_sight_config: LaserSightConfig,
+    #[visit(skip)]
+    health_regeneration_config: HealthRegenerationConfig,
+    #[visit(skip)]
+    camera_config: CameraConfig,
+    #[visit(skip)]
+    downed_state_config: DownedStateConfig,
+    #[visit(skip)]
+    weapon_jam_config: WeaponJamConfig,
+    #[visit(skip)]
+    double_jump_config: DoubleJumpConfig,
+    #[visit(skip)]
+    hit_stop: HitStop,
+    #[visit(skip)]
+    photo_mode: Option<PhotoMode>,
 }
 
 #[derive(Visit)]
@@ -124,11 +214,21 @@ pub struct UpdateContext<'a> {
     pub scene: &'a mut Scene,
     pub items: &'a ItemContainer,
     pub doors: &'a DoorContainer,
+    pub ladders: &'a LadderContainer,
+    pub projectiles: &'a ProjectileContainer,
+    pub terminals: &'a TerminalContainer,
+    pub transitions: &'a LevelTransitionContainer,
     pub navmesh: Handle<Navmesh>,
     pub weapons: &'a WeaponContainer,
     pub sender: &'a MessageSender,
     pub elevators: &'a ElevatorContainer,
     pub call_buttons: &'a CallButtonContainer,
+    pub smoke_clouds: &'a SmokeCloudContainer,
+    pub cover_points: &'a CoverPointContainer,
+    pub health_regeneration: &'a HealthRegenerationConfig,
+    pub camera: &'a CameraConfig,
+    pub downed_state: &'a DownedStateConfig,
+    pub double_jump: &'a DoubleJumpConfig,
 }
 
 #[derive(Default)]
@@ -193,10 +293,62 @@ fn make_beam() -> Arc<Mutex<SurfaceData>> {
     )))
 }
 
-pub async fn analyze(scene: &mut Scene, resource_manager: ResourceManager) -> AnalysisResult {
+fn patrol_of(node: &Node, graph: &Graph) -> Patrol {
+    node.script()
+        .and_then(|s| s.cast::<PatrolRoute>())
+        .map(|route| route.make_patrol(graph))
+        .unwrap_or_default()
+}
+
+/// Data pads are tagged as `DataPad:<audio log id>` in the editor.
+const DATA_PAD_TAG_PREFIX: &str = "DataPad:";
+
+/// Bots closer than this to a flying bullet are suppressed by it.
+const SUPPRESSION_RADIUS: f32 = 1.5;
+/// Time (in seconds) that a bot stays in cover after a bullet flew by.
+const SHOT_SUPPRESSION: f32 = 1.0;
+/// Time (in seconds) that a bot stays in cover after it was hit.
+const DAMAGE_SUPPRESSION: f32 = 2.0;
+/// Impulse of shots of turrets and other shooters without a weapon definition.
+const DEFAULT_IMPACT_FORCE: f32 = 30.0;
+/// Condition that a repair kit restores to every weapon.
+const REPAIR_KIT_CONDITION: f32 = 0.5;
+/// Max amount of impact effects (sparks, dust, blood) that can exist at the same time.
+const MAX_IMPACT_EFFECTS: usize = 32;
+
+/// Pushes an actor by given impulse, knockback of the player is limited by its own scale.
+fn knock_back(actor: &mut Actor, impulse: Vector3<f32>, graph: &mut Graph) {
+    let scale = match actor {
+        Actor::Bot(_) => 1.0,
+        Actor::Player(_) => KNOCKBACK.player_scale,
+    };
+    if scale > 0.0 {
+        actor.knock_back(impulse.scale(scale / KNOCKBACK.actor_mass), graph);
+    }
+}
+
+/// Impulse of an explosion at given position, it is directed away from the center of the
+/// explosion (and slightly upwards) and falls off with inverse distance.
+fn explosion_impulse(center: Vector3<f32>, position: Vector3<f32>, damage: f32) -> Vector3<f32> {
+    let offset = position - center;
+    let distance = offset.norm().max(1.0);
+    let direction = (offset.try_normalize(f32::EPSILON).unwrap_or_default() + Vector3::y())
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector3::y);
+    direction.scale(KNOCKBACK.explosion_impulse * damage / distance)
+}
+
+/// Analyzes the scene of a level. Items of a level that was visited before are restored from
+/// `saved_items` instead of being created from the tags of the scene.
+pub async fn analyze(
+    scene: &mut Scene,
+    resource_manager: ResourceManager,
+    saved_items: Option<Vec<ItemState>>,
+) -> AnalysisResult {
     let mut result = AnalysisResult::default();
 
     let mut items = Vec::new();
+    let mut data_pads = Vec::new();
     let mut spawn_points = Vec::new();
     let mut death_zones = Vec::new();
     let mut player_spawn_position = Default::default();
@@ -217,6 +369,7 @@ pub async fn analyze(scene: &mut Scene, resource_manager: ResourceManager) -> An
                 bot_kind: BotKind::Zombie,
                 spawned: false,
                 with_gun: false,
+                patrol: patrol_of(node, &scene.graph),
             })
         } else if name.starts_with("Mutant") {
             spawn_points.push(SpawnPoint {
@@ -225,6 +378,7 @@ pub async fn analyze(scene: &mut Scene, resource_manager: ResourceManager) -> An
                 bot_kind: BotKind::Mutant,
                 with_gun: false,
                 spawned: false,
+                patrol: patrol_of(node, &scene.graph),
             })
         } else if name.starts_with("Parasite") {
             spawn_points.push(SpawnPoint {
@@ -233,6 +387,7 @@ pub async fn analyze(scene: &mut Scene, resource_manager: ResourceManager) -> An
                 bot_kind: BotKind::Parasite,
                 spawned: false,
                 with_gun: false,
+                patrol: patrol_of(node, &scene.graph),
             })
         } else if name.starts_with("PlayerSpawnPoint") {
             player_spawn_position = node.global_position();
@@ -295,12 +450,26 @@ pub async fn analyze(scene: &mut Scene, resource_manager: ResourceManager) -> An
             "Medpack" => items.push((ItemKind::Medpack, position)),
             "Ammo" => items.push((ItemKind::Ammo, position)),
             "Grenade" => items.push((ItemKind::Grenade, position)),
+            "SmokeGrenade" => items.push((ItemKind::SmokeGrenade, position)),
+            "Flashbang" => items.push((ItemKind::Flashbang, position)),
+            "Mine" => items.push((ItemKind::Mine, position)),
+            "ThrowingKnife" => items.push((ItemKind::ThrowingKnife, position)),
             "PlasmaGun" => items.push((ItemKind::PlasmaGun, position)),
             "Ak47" => items.push((ItemKind::Ak47, position)),
             "M4" => items.push((ItemKind::M4, position)),
             "Glock" => items.push((ItemKind::Glock, position)),
             "RailGun" => items.push((ItemKind::RailGun, position)),
             "MasterKey" => items.push((ItemKind::MasterKey, position)),
+            "Armor" => items.push((ItemKind::Armor, position)),
+            "Battery" => items.push((ItemKind::Battery, position)),
+            "RepairKit" => items.push((ItemKind::RepairKit, position)),
+            "Stim" => items.push((ItemKind::Stim, position)),
+            "Scope" => items.push((ItemKind::Scope, position)),
+            "Suppressor" => items.push((ItemKind::Suppressor, position)),
+            "Grip" => items.push((ItemKind::Grip, position)),
+            tag if tag.starts_with(DATA_PAD_TAG_PREFIX) => {
+                data_pads.push((tag[DATA_PAD_TAG_PREFIX.len()..].to_owned(), position))
+            }
             "Turret" => {
                 turrets
                     .add(Turret::new(handle, scene, ShootMode::Consecutive, Hostility::All).await);
@@ -313,15 +482,52 @@ pub async fn analyze(scene: &mut Scene, resource_manager: ResourceManager) -> An
                 bot_kind: BotKind::Zombie,
                 spawned: false,
                 with_gun: true,
+                patrol: patrol_of(node, &scene.graph),
             }),
             _ => (),
         }
     }
 
-    for (kind, position) in items {
-        result
-            .items
-            .add(spawn_item(scene, resource_manager.clone(), kind, position, true).await);
+    if let Some(saved_items) = saved_items {
+        for state in saved_items {
+            let mut item = spawn_item(
+                scene,
+                resource_manager.clone(),
+                state.kind,
+                state.position,
+                false,
+            )
+            .await;
+            item.stack_size = state.stack_size;
+            item.audio_log = state.audio_log;
+            result.items.add(item);
+        }
+    } else {
+        for (kind, position) in items {
+            if !Item::get_definition(kind).placeable {
+                Log::writeln(
+                    MessageKind::Warning,
+                    format!("{:?} cannot be placed on a level yet, skipping it.", kind),
+                );
+                continue;
+            }
+
+            result
+                .items
+                .add(spawn_item(scene, resource_manager.clone(), kind, position, true).await);
+        }
+        for (audio_log, position) in data_pads {
+            let mut item = spawn_item(
+                scene,
+                resource_manager.clone(),
+                ItemKind::DataPad,
+                position,
+                true,
+            )
+            .await;
+            item.audio_log = Some(audio_log);
+            result.items.add(item);
+        }
     }
     for handle in death_zones {
         let node = &mut scene.graph[handle];
@@ -375,12 +581,12 @@ async fn spawn_player(
     let weapons_to_give = if let Some(data) = persistent_data {
         data.weapons
     } else {
-        vec![WeaponKind::Glock]
+        vec![WeaponPersistentData::new(WeaponKind::Glock)]
     };
 
-    for (i, &weapon) in weapons_to_give.iter().enumerate() {
-        give_new_weapon(
-            weapon,
+    for (i, data) in weapons_to_give.iter().enumerate() {
+        let weapon = give_new_weapon(
+            data.kind,
             player,
             resource_manager.clone(),
             i == weapons_to_give.len() - 1,
@@ -390,6 +596,9 @@ async fn spawn_player(
             sender,
         )
         .await;
+        if let Some(weapon) = weapons.try_get_mut(weapon) {
+            weapon.restore(data, &mut scene.graph);
+        }
     }
 
     player
@@ -404,7 +613,7 @@ async fn give_new_weapon(
     actors: &mut ActorContainer,
     scene: &mut Scene,
     sender: &MessageSender,
-) {
+) -> Handle<Weapon> {
     if actors.contains(actor) {
         let mut weapon = Weapon::new(kind, resource_manager, scene).await;
         weapon.set_owner(actor);
@@ -415,6 +624,9 @@ async fn give_new_weapon(
         actor.add_weapon(weapon_handle, sender);
         scene.graph.link_nodes(weapon_model, actor.weapon_pivot());
         actor.inventory_mut().add_item(kind.associated_item(), 1);
+        weapon_handle
+    } else {
+        Handle::NONE
     }
 }
 
@@ -439,6 +651,10 @@ async fn spawn_bot(
     )
     .await;
 
+    if let Actor::Bot(bot_ref) = actors.get_mut(bot) {
+        bot_ref.set_patrol(spawn_point.patrol.clone());
+    }
+
     if let Some(weapon) = weapon {
         give_new_weapon(
             weapon,
@@ -450,7 +666,7 @@ async fn spawn_bot(
             scene,
             sender,
         )
-        .await
+        .await;
     }
 
     bot
@@ -550,7 +766,7 @@ impl Level {
             triggers,
             elevators,
             call_buttons,
-        } = block_on(analyze(scene, resource_manager.clone()));
+        } = block_on(analyze(scene, resource_manager.clone(), None));
         let mut actors = ActorContainer::new();
         let mut weapons = WeaponContainer::new();
 
@@ -561,11 +777,7 @@ impl Level {
                 resource_manager.clone(),
                 &sender,
                 scene,
-                if pt.with_gun {
-                    Some(WeaponKind::Ak47)
-                } else {
-                    None
-                },
+                pt.weapon(),
                 &mut weapons,
             ));
         }
@@ -594,18 +806,45 @@ impl Level {
             turrets,
             triggers,
             decals: Default::default(),
+            corpses: Default::default(),
             navmesh: scene.navmeshes.handle_from_index(0),
             scene: scene_handle,
             sender: Some(sender),
             time: 0.0,
             projectiles: ProjectileContainer::new(),
-            sound_manager: SoundManager::new(scene),
+            sound_manager: SoundManager::new(scene, sound_config.sfx_volume),
             beam: Some(make_beam()),
             trails: Default::default(),
             doors,
             elevators,
             call_buttons,
+            mines: Default::default(),
+            smoke_clouds: Default::default(),
+            objective_marker: Default::default(),
+            grenade_trajectory: Default::default(),
+            impact_effects: Default::default(),
+            ladders: Default::default(),
+            terminals: Default::default(),
+            cover_points: Default::default(),
+            transitions: Default::default(),
+            wave_spawners: Default::default(),
+            barrels: Default::default(),
+            hazards: Default::default(),
+            laser_sight_config: Default::default(),
+            health_regeneration_config: Default::default(),
+            camera_config: Default::default(),
+            downed_state_config: Default::default(),
+            weapon_jam_config: Default::default(),
+            double_jump_config: Default::default(),
+            hit_stop: Default::default(),
+            photo_mode: None,
+            music: block_on(MusicDirector::new(
+                &mut scene.graph,
+                resource_manager,
+                sound_config.music_volume,
+            )),
             map_path: Default::default(),
+            world_items: Default::default(),
         }
     }
 
@@ -619,7 +858,12 @@ impl Level {
         journal_texture: Texture,
         sound_config: SoundConfig, // Using copy, instead of reference because of async.
         persistent_data: Option<PlayerPersistentData>,
+        world_items: HashMap<String, Vec<ItemState>>,
+        spawn_point: Option<String>,
+        report_stage: impl Fn(LoadingStage),
     ) -> (Self, Scene) {
+        report_stage(LoadingStage::Map);
+
         let mut scene = Scene::new();
 
         if sound_config.use_hrtf {
@@ -645,18 +889,44 @@ impl Level {
             items,
             death_zones,
             mut spawn_points,
-            player_spawn_position,
-            player_spawn_orientation,
+            mut player_spawn_position,
+            mut player_spawn_orientation,
             doors,
             lights,
             turrets,
             triggers,
             elevators,
             call_buttons,
-        } = analyze(&mut scene, resource_manager.clone()).await;
+        } = analyze(
+            &mut scene,
+            resource_manager.clone(),
+            world_items.get(&map).cloned(),
+        )
+        .await;
+
+        // Level transitions could specify where exactly the player should appear.
+        if let Some(spawn_point) = spawn_point {
+            let handle = scene
+                .graph
+                .find_by_name(scene.graph.get_root(), &spawn_point);
+            if handle.is_some() {
+                player_spawn_position = scene.graph[handle].global_position();
+                player_spawn_orientation = scene.graph.global_rotation(handle);
+            } else {
+                Log::writeln(
+                    MessageKind::Warning,
+                    format!(
+                        "There is no spawn point {} in {}, default one is used.",
+                        spawn_point, map
+                    ),
+                );
+            }
+        }
+
         let mut actors = ActorContainer::new();
         let mut weapons = WeaponContainer::new();
 
+        report_stage(LoadingStage::Actors);
         for pt in spawn_points.iter_mut() {
             spawn_bot(
                 pt,
@@ -664,16 +934,13 @@ impl Level {
                 resource_manager.clone(),
                 &sender,
                 &mut scene,
-                if pt.with_gun {
-                    Some(WeaponKind::Ak47)
-                } else {
-                    None
-                },
+                pt.weapon(),
                 &mut weapons,
             )
             .await;
         }
 
+        report_stage(LoadingStage::Player);
         let level = Self {
             player: spawn_player(
                 player_spawn_position,
@@ -699,18 +966,46 @@ impl Level {
             turrets,
             triggers,
             decals: Default::default(),
+            corpses: Default::default(),
             navmesh: scene.navmeshes.handle_from_index(0),
             scene: Handle::NONE, // Filled when scene will be moved to engine.
             sender: Some(sender),
             time: 0.0,
             projectiles: ProjectileContainer::new(),
-            sound_manager: SoundManager::new(&mut scene),
+            sound_manager: SoundManager::new(&mut scene, sound_config.sfx_volume),
             beam: Some(make_beam()),
             trails: Default::default(),
             doors,
             elevators,
             call_buttons,
+            mines: Default::default(),
+            smoke_clouds: Default::default(),
+            objective_marker: Default::default(),
+            grenade_trajectory: Default::default(),
+            impact_effects: Default::default(),
+            ladders: Default::default(),
+            terminals: Default::default(),
+            cover_points: Default::default(),
+            transitions: Default::default(),
+            wave_spawners: Default::default(),
+            barrels: Default::default(),
+            hazards: Default::default(),
+            laser_sight_config: Default::default(),
+            health_regeneration_config: Default::default(),
+            camera_config: Default::default(),
+            downed_state_config: Default::default(),
+            weapon_jam_config: Default::default(),
+            double_jump_config: Default::default(),
+            hit_stop: Default::default(),
+            photo_mode: None,
+            music: MusicDirector::new(
+                &mut scene.graph,
+                resource_manager,
+                sound_config.music_volume,
+            )
+            .await,
             map_path: map,
+            world_items,
         };
 
         (level, scene)
@@ -743,6 +1038,20 @@ impl Level {
         self.player
     }
 
+    /// Passes state of a gamepad to the player, gamepads do nothing in photo mode.
+    pub fn process_gamepad_input(
+        &mut self,
+        input: Option<GamepadInput>,
+        control_scheme: &ControlScheme,
+    ) {
+        let input = input.filter(|_| self.photo_mode.is_none());
+        if self.player.is_some() {
+            if let Actor::Player(player) = self.actors.get_mut(self.player) {
+                player.process_gamepad_input(input, control_scheme);
+            }
+        }
+    }
+
     pub fn process_input_event(
         &mut self,
         event: &Event<()>,
@@ -751,17 +1060,79 @@ impl Level {
         control_scheme: &ControlScheme,
         sender: &MessageSender,
     ) {
-        if self.player.is_some() {
+        let photo_mode_pressed = match event {
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { input, .. },
+                ..
+            } => {
+                input.state == ElementState::Pressed
+                    && input.virtual_keycode.map(ControlButton::Key)
+                        == Some(control_scheme.photo_mode.button)
+            }
+            Event::DeviceEvent {
+                event: DeviceEvent::Button { button, state },
+                ..
+            } => {
+                *state == ElementState::Pressed
+                    && ControlButton::Mouse(*button as u16) == control_scheme.photo_mode.button
+            }
+            _ => false,
+        };
+        if photo_mode_pressed {
+            let active = self.photo_mode.is_none();
+            self.set_photo_mode(active, scene);
+            return;
+        }
+
+        if let Some(photo_mode) = self.photo_mode.as_mut() {
+            photo_mode.process_input_event(event, dt, &mut scene.graph, control_scheme);
+        } else if self.player.is_some() {
             if let Actor::Player(player) = self.actors.get_mut(self.player) {
                 player.process_input_event(event, dt, scene, &self.weapons, control_scheme, sender);
             }
         }
     }
 
+    /// Enters or leaves photo mode, the game is frozen while photo mode is active.
+    pub fn set_photo_mode(&mut self, active: bool, scene: &mut Scene) {
+        if active && self.photo_mode.is_none() {
+            if let Some(Actor::Player(player)) = self.actors.try_get(self.player) {
+                let camera = player.camera_controller().camera();
+                // Parts of the HUD that are placed in the world are shown again by the next
+                // update after photo mode is left.
+                player.hide_world_hud(&mut scene.graph);
+                if scene.graph.is_valid_handle(self.objective_marker) {
+                    scene.graph[self.objective_marker].set_visibility(false);
+                }
+                self.photo_mode = Some(PhotoMode::new(camera, scene));
+                // The player won't receive releases of buttons that are held right now.
+                self.reset_player_input();
+            }
+        } else if !active {
+            if let Some(photo_mode) = self.photo_mode.take() {
+                photo_mode.exit(scene);
+            }
+        }
+    }
+
+    pub fn is_photo_mode_active(&self) -> bool {
+        self.photo_mode.is_some()
+    }
+
+    pub fn reset_player_input(&mut self) {
+        if let Some(Actor::Player(player)) = self.actors.try_get_mut(self.player) {
+            player.reset_input();
+        }
+    }
+
     pub fn actors(&self) -> &ActorContainer {
         &self.actors
     }
 
+    pub fn projectiles(&self) -> &ProjectileContainer {
+        &self.projectiles
+    }
+
     pub fn actors_mut(&mut self) -> &mut ActorContainer {
         &mut self.actors
     }
@@ -780,6 +1151,15 @@ impl Level {
             }
         }
 
+        // Attachments outlive the weapon, the owner gets them back.
+        let owner = self.weapons[weapon].owner();
+        let attachments = self.weapons[weapon].detach_all();
+        if let Some(owner) = self.actors.try_get_mut(owner) {
+            for attachment in attachments {
+                owner.inventory_mut().add_item(attachment, 1);
+            }
+        }
+
         let scene = &mut engine.scenes[self.scene];
 
         for actor in self.actors.iter_mut() {
@@ -819,6 +1199,11 @@ impl Level {
 
     async fn remove_actor(&mut self, engine: &mut PluginContext<'_>, actor: Handle<Actor>) {
         if self.actors.contains(actor) {
+            // Weapons are attached to the actor, so they must be removed with it.
+            for weapon in self.actors.get(actor).weapons.clone() {
+                self.remove_weapon(engine, weapon);
+            }
+
             let scene = &mut engine.scenes[self.scene];
             self.actors.get_mut(actor).clean_up(scene);
             self.actors.free(actor);
@@ -864,23 +1249,115 @@ impl Level {
         }
     }
 
-    async fn use_item(&mut self, actor: Handle<Actor>, kind: ItemKind) {
+    async fn use_item(
+        &mut self,
+        engine: &mut PluginContext<'_>,
+        actor: Handle<Actor>,
+        kind: ItemKind,
+    ) {
         if self.actors.contains(actor) {
             let character = self.actors.get_mut(actor);
-            match kind {
-                ItemKind::Medkit => character.heal(40.0),
-                ItemKind::Medpack => character.heal(20.0),
-                // Non-consumable items.
-                ItemKind::Ak47
-                | ItemKind::PlasmaGun
-                | ItemKind::M4
-                | ItemKind::Glock
-                | ItemKind::Ammo
-                | ItemKind::RailGun
-                | ItemKind::Grenade
-                | ItemKind::MasterKey => (),
+            if let Some(effect) = Item::get_definition(kind).use_effect.as_ref() {
+                character.heal(effect.heal);
+                if effect.armor > 0.0 {
+                    character.add_armor(effect.armor);
+                }
+                for &status_effect in effect.cure.iter() {
+                    character.status_effects.cure(status_effect);
+                }
+                if effect.revive {
+                    if let Actor::Player(player) = character {
+                        player.revive();
+                    }
+                }
+            }
+
+            // Items which effect can't be described by data.
+            if kind == ItemKind::Mine {
+                self.place_mine(engine, actor);
+            }
+        }
+    }
+
+    /// Mounts an attachment from the inventory of an actor on its current weapon, the attachment
+    /// that occupied the same slot goes back to the inventory.
+    fn attach_item(&mut self, actor: Handle<Actor>, item: ItemKind) {
+        if let Some(character) = self.actors.try_get_mut(actor) {
+            if let Some(weapon) = self.weapons.try_get_mut(character.current_weapon()) {
+                if weapon.can_attach(item)
+                    && character.inventory_mut().try_extract_exact_items(item, 1) == 1
+                {
+                    if let Some(replaced) = weapon.attach(item) {
+                        character.inventory_mut().add_item(replaced, 1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Takes every attachment off a weapon of given kind that is carried by an actor, the
+    /// attachments go back to the inventory.
+    fn detach_attachments(&mut self, actor: Handle<Actor>, kind: WeaponKind) {
+        if let Some(character) = self.actors.try_get_mut(actor) {
+            for handle in character.weapons.clone() {
+                if let Some(weapon) = self.weapons.try_get_mut(handle) {
+                    if weapon.kind() == kind {
+                        for attachment in weapon.detach_all() {
+                            character.inventory_mut().add_item(attachment, 1);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn place_mine(&mut self, engine: &mut PluginContext, actor: Handle<Actor>) {
+        let scene = &mut engine.scenes[self.scene];
+        let character = self.actors.get_mut(actor);
+
+        let mut placed = false;
+        if self.mines.count_of(actor) < MAX_MINES_PER_ACTOR {
+            // Stick the mine to a surface in front of the actor.
+            let pivot = &scene.graph[character.pivot];
+            let origin = pivot.global_position()
+                + pivot
+                    .look_vector()
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default()
+                    .scale(0.75)
+                + Vector3::new(0.0, 0.5, 0.0);
+
+            let mut query_buffer = Vec::new();
+            scene.graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::from(origin),
+                    ray_direction: Vector3::new(0.0, -1.0, 0.0),
+                    max_len: 2.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                },
+                &mut query_buffer,
+            );
+
+            if let Some(intersection) = query_buffer
+                .iter()
+                .find(|i| i.collider != character.capsule_collider)
+            {
+                self.mines.add(Mine::new(
+                    scene,
+                    intersection.position.coords,
+                    intersection.normal,
+                    actor,
+                ));
+                placed = true;
             }
         }
+
+        if !placed {
+            // Give the mine back, so it won't be lost.
+            character.inventory_mut().add_item(ItemKind::Mine, 1);
+            self.sender.as_ref().unwrap().send(Message::SyncInventory);
+        }
     }
 
     async fn pickup_item(
@@ -896,10 +1373,23 @@ impl Level {
             let position = item.position(&scene.graph);
             let kind = item.get_kind();
 
-            self.items.remove(item_handle, &mut scene.graph);
+            // Reserve is full, the item stays in the world.
+            let free_space = self.actors.get(actor).inventory().free_space(kind);
+            if free_space == 0 {
+                return;
+            }
+
+            let amount = item.stack_size.min(free_space);
+            let audio_log = item.audio_log.take();
+            if amount < item.stack_size {
+                // Take as much as the reserve can hold and leave the rest.
+                item.stack_size -= amount;
+            } else {
+                self.items.remove(item_handle, &mut scene.graph);
+            }
 
             self.sender.as_ref().unwrap().send(Message::PlaySound {
-                path: PathBuf::from("data/sounds/item_pickup.ogg"),
+                path: PathBuf::from(Item::get_definition(kind).pickup_sound()),
                 position,
                 gain: 1.0,
                 rolloff_factor: 3.0,
@@ -909,8 +1399,21 @@ impl Level {
             let character = self.actors.get_mut(actor);
 
             match kind {
-                ItemKind::Medkit => character.inventory_mut().add_item(ItemKind::Medkit, 1),
-                ItemKind::Medpack => character.inventory_mut().add_item(ItemKind::Medpack, 1),
+                ItemKind::Medkit
+                | ItemKind::Medpack
+                | ItemKind::Stim
+                | ItemKind::Ammo
+                | ItemKind::Grenade
+                | ItemKind::SmokeGrenade
+                | ItemKind::Flashbang
+                | ItemKind::Mine
+                | ItemKind::ThrowingKnife
+                | ItemKind::MasterKey
+                | ItemKind::Scope
+                | ItemKind::Suppressor
+                | ItemKind::Grip => {
+                    character.inventory_mut().add_item(kind, amount);
+                }
                 ItemKind::Ak47
                 | ItemKind::PlasmaGun
                 | ItemKind::M4
@@ -918,29 +1421,35 @@ impl Level {
                 | ItemKind::RailGun => {
                     let weapon_kind = kind.associated_weapon().unwrap();
 
-                    let mut found = false;
-                    for weapon_handle in character.weapons() {
-                        let weapon = &mut self.weapons[*weapon_handle];
-                        if weapon.kind() == weapon_kind {
-                            found = true;
-                            break;
-                        }
-                    }
-                    if found {
-                        character.inventory_mut().add_item(ItemKind::Ammo, 24);
-                    } else {
-                        // Finally if actor does not have such weapon, give new one to him.
+                    if character.give_weapon(weapon_kind, &self.weapons) == GiveResult::NewWeapon {
                         self.give_new_weapon(engine, actor, weapon_kind).await;
                     }
                 }
-                ItemKind::Ammo => {
-                    character.inventory_mut().add_item(ItemKind::Ammo, 24);
+                ItemKind::Armor => {
+                    // Armor is put on immediately.
+                    character.add_armor(ARMOR_DEFINITION.pickup_amount);
+                }
+                ItemKind::Battery => {
+                    // Batteries are used immediately.
+                    if let Actor::Player(player) = character {
+                        player.pick_up_battery();
+                    }
                 }
-                ItemKind::Grenade => {
-                    character.inventory_mut().add_item(ItemKind::Grenade, 1);
+                ItemKind::RepairKit => {
+                    // Repair kits are used immediately on every weapon of the character.
+                    for &weapon in character.weapons.iter() {
+                        if let Some(weapon) = self.weapons.try_get_mut(weapon) {
+                            weapon.repair(REPAIR_KIT_CONDITION);
+                        }
+                    }
                 }
-                ItemKind::MasterKey => {
-                    character.inventory_mut().add_item(ItemKind::MasterKey, 1);
+                ItemKind::DataPad => {
+                    // Data pads are not stored in the inventory, only their logs are kept.
+                    if let (Actor::Player(player), Some(audio_log)) = (character, audio_log) {
+                        if player.journal.add_audio_log(&audio_log) {
+                            self.sender.as_ref().unwrap().send(Message::SyncJournal);
+                        }
+                    }
                 }
             }
         }
@@ -954,8 +1463,15 @@ impl Level {
         direction: Vector3<f32>,
         initial_velocity: Vector3<f32>,
         owner: Shooter,
-    ) {
+    ) -> Handle<Projectile> {
         let scene = &mut engine.scenes[self.scene];
+        let reused =
+            self.projectiles
+                .try_reuse(kind, scene, direction, position, owner, initial_velocity);
+        if reused.is_some() {
+            return reused;
+        }
+
         let projectile = Projectile::new(
             kind,
             engine.resource_manager.clone(),
@@ -966,7 +1482,32 @@ impl Level {
             initial_velocity,
         )
         .await;
-        self.projectiles.add(projectile);
+        self.projectiles.add(projectile)
+    }
+
+    async fn throw_back_grenade(
+        &mut self,
+        engine: &mut PluginContext<'_>,
+        projectile: Handle<Projectile>,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        initial_velocity: Vector3<f32>,
+        owner: Shooter,
+    ) {
+        let (kind, fuse) = match self.projectiles.try_get(projectile) {
+            Some(grenade) if grenade.can_be_thrown_back() => (grenade.kind(), grenade.fuse()),
+            _ => return,
+        };
+
+        self.projectiles
+            .remove(projectile, &mut engine.scenes[self.scene]);
+
+        let thrown = self
+            .create_projectile(engine, kind, position, direction, initial_velocity, owner)
+            .await;
+        if let Some(thrown) = self.projectiles.try_get_mut(thrown) {
+            thrown.set_fuse(fuse);
+        }
     }
 
     async fn shoot_weapon(
@@ -978,6 +1519,12 @@ impl Level {
     ) {
         if self.weapons.contains(weapon_handle) {
             let scene = &mut engine.scenes[self.scene];
+
+            let is_player_weapon = self.weapons[weapon_handle].owner() == self.player;
+            if is_player_weapon {
+                self.update_player_stats(|stats| stats.shots_fired += 1);
+            }
+
             let weapon = &mut self.weapons[weapon_handle];
             weapon.shoot(
                 weapon_handle,
@@ -987,7 +1534,63 @@ impl Level {
                 direction,
                 self.sender.as_ref().unwrap(),
             );
+
+            // Only weapons of the player jam, because bots cannot clear jams. The next round is
+            // the one that jams, so the player does not spend ammo on a shot that never happens.
+            if self.weapon_jam_config.enabled
+                && is_player_weapon
+                && weapon.wear(&self.weapon_jam_config)
+            {
+                self.sender.as_ref().unwrap().send(Message::PlaySound {
+                    path: PathBuf::from(JAM_SOUND),
+                    position: weapon.shot_position(&scene.graph),
+                    gain: 1.0,
+                    rolloff_factor: 1.0,
+                    radius: 1.0,
+                });
+            }
+        }
+    }
+
+    async fn spawn_wave_enemy(
+        &mut self,
+        engine: &mut PluginContext<'_>,
+        spawner: Handle<Node>,
+        kind: BotKind,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        health_factor: f32,
+    ) {
+        let scene = &mut engine.scenes[self.scene];
+        let bot = add_bot(
+            kind,
+            position,
+            rotation,
+            &mut self.actors,
+            engine.resource_manager.clone(),
+            scene,
+        )
+        .await;
+
+        if let Actor::Bot(bot_ref) = self.actors.get_mut(bot) {
+            bot_ref.health *= health_factor;
+        }
+
+        if let Some(ranged_attack) = Bot::get_definition(kind).ranged_attack.as_ref() {
+            give_new_weapon(
+                ranged_attack.weapon,
+                bot,
+                engine.resource_manager.clone(),
+                true,
+                &mut self.weapons,
+                &mut self.actors,
+                scene,
+                self.sender.as_ref().unwrap(),
+            )
+            .await;
         }
+
+        self.wave_spawners.add_enemy(spawner, bot);
     }
 
     fn show_weapon(
@@ -1004,12 +1607,14 @@ impl Level {
         engine: &mut PluginContext,
         actor_handle: Handle<Actor>,
         who: Handle<Actor>,
+        weapon: Handle<Weapon>,
         mut amount: f32,
         hitbox: Option<HitBox>,
         critical_shot_probability: f32,
     ) {
         if self.actors.contains(actor_handle)
             && (who.is_none() || who.is_some() && self.actors.contains(who))
+            && self.actors.can_damage(who, actor_handle)
         {
             let scene = &mut engine.scenes[self.scene];
 
@@ -1023,8 +1628,9 @@ impl Level {
             if !actor.is_dead() {
                 if let Actor::Bot(bot) = actor {
                     if let Some(who_position) = who_position {
-                        bot.set_target(actor_handle, who_position);
+                        bot.set_target(who, who_position);
                     }
+                    bot.suppress(DAMAGE_SUPPRESSION);
                 }
 
                 if let Some(hitbox) = hitbox {
@@ -1041,39 +1647,137 @@ impl Level {
                     }
                 }
 
+                let health_before = actor.health;
                 actor.damage(amount);
+                let damage_done = (health_before - actor.health).max(0.0);
+
+                if let Actor::Player(player) = &mut *actor {
+                    if player.is_dead() && self.downed_state_config.enabled {
+                        player.go_down(&self.downed_state_config);
+                    }
+                }
+
+                let confirmed_hit = who.is_some()
+                    && who == self.player
+                    && who != actor_handle
+                    && self.weapons.try_get(weapon).is_some();
+
+                if let Actor::Player(player) = &mut *actor {
+                    player.flinch(damage_done);
+                    if let Some(who_position) = who_position.filter(|_| who != actor_handle) {
+                        player.add_damage_indicator(who_position);
+                    }
+                }
+
+                let mut killed_by_player = false;
+                if actor.is_dead() && who.is_some() && who == self.player {
+                    if let Actor::Bot(bot) = actor {
+                        killed_by_player = true;
+                        self.sender.as_ref().unwrap().send(Message::ReportKill {
+                            victim: bot.kind,
+                            weapon: self.weapons.try_get(weapon).map(|w| w.kind()),
+                        });
+                    }
+                }
+
+                if let (Actor::Bot(bot), Some(hitbox)) = (&mut *actor, hitbox.as_ref()) {
+                    if let Some(position) = bot.dismember(hitbox, amount, &mut scene.graph) {
+                        self.sender.as_ref().unwrap().send(Message::CreateEffect {
+                            kind: EffectKind::Gib,
+                            position,
+                            orientation: Default::default(),
+                        });
+                    }
+                }
 
-                // Prevent spamming with grunt sounds.
                 if actor.last_health - actor.health > 20.0 {
                     actor.last_health = actor.health;
-                    match actor {
-                        Actor::Bot(bot) => {
-                            bot.restoration_time = 0.8;
+                    if let Actor::Bot(bot) = actor {
+                        bot.restoration_time = 0.8;
+                    }
+                }
 
-                            if let Some(grunt_sound) =
-                                bot.definition.pain_sounds.choose(&mut rand::thread_rng())
-                            {
-                                self.sender.as_ref().unwrap().send(Message::PlaySound {
-                                    path: PathBuf::from(grunt_sound.clone()),
-                                    position: actor.position(&scene.graph),
-                                    gain: 0.8,
-                                    rolloff_factor: 1.0,
-                                    radius: 0.6,
-                                });
-                            }
-                        }
-                        Actor::Player(_) => {
-                            // TODO: Add player sounds.
-                        }
+                let sound = if actor.is_dead() {
+                    actor.death_sounds().choose(&mut rand::thread_rng())
+                } else if actor.try_start_pain_sound(damage_done) {
+                    actor.pain_sounds().choose(&mut rand::thread_rng())
+                } else {
+                    None
+                };
+                if let Some(sound) = sound {
+                    self.sender.as_ref().unwrap().send(Message::PlaySound {
+                        path: PathBuf::from(sound),
+                        position: actor.position(&scene.graph),
+                        gain: 0.8,
+                        rolloff_factor: 1.0,
+                        radius: 0.6,
+                    });
+                }
+
+                if confirmed_hit && self.hit_stop.request() {
+                    if let Actor::Player(player) = self.actors.get_mut(self.player) {
+                        player.kick_camera(HIT_STOP.camera_kick);
                     }
                 }
+
+                let player = self.player;
+                self.update_player_stats(|stats| {
+                    if actor_handle == player {
+                        stats.damage_taken += damage_done;
+                    } else if who == player {
+                        stats.damage_dealt += damage_done;
+                    }
+                    if confirmed_hit {
+                        stats.hits += 1;
+                    }
+                    if killed_by_player {
+                        stats.kills += 1;
+                    }
+                });
             }
         }
     }
 
-    async fn spawn_item(
-        &mut self,
-        engine: &mut PluginContext<'_>,
+    fn takedown(&mut self, attacker: Handle<Actor>, victim: Handle<Actor>) {
+        if !self.actors.contains(attacker) || !self.actors.contains(victim) {
+            return;
+        }
+
+        if let Actor::Bot(bot) = self.actors.get_mut(victim) {
+            // The bot could notice the attacker in the meantime.
+            if !bot.is_dead() && !bot.is_aware() {
+                // Kill the bot without any sounds, so nobody around will notice it.
+                bot.health = 0.0;
+
+                if attacker == self.player {
+                    self.sender.as_ref().unwrap().send(Message::ReportKill {
+                        victim: bot.kind,
+                        weapon: None,
+                    });
+                    self.update_player_stats(|stats| stats.kills += 1);
+                }
+            }
+        }
+    }
+
+    /// Applies given change to statistics of the player, if there is the player.
+    fn update_player_stats<F: FnOnce(&mut SessionStats)>(&mut self, update: F) {
+        if let Some(Actor::Player(player)) = self.actors.try_get_mut(self.player) {
+            update(player.stats_mut());
+        }
+    }
+
+    /// Returns statistics of the current session, `None` if there is no player.
+    pub fn session_stats(&self) -> Option<&SessionStats> {
+        match self.actors.try_get(self.player) {
+            Some(Actor::Player(player)) => Some(player.stats()),
+            _ => None,
+        }
+    }
+
+    async fn spawn_item(
+        &mut self,
+        engine: &mut PluginContext<'_>,
         kind: ItemKind,
         position: Vector3<f32>,
         adjust_height: bool,
@@ -1091,6 +1795,36 @@ impl Level {
         );
     }
 
+    async fn embed_item(
+        &mut self,
+        engine: &mut PluginContext<'_>,
+        kind: ItemKind,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        parent: Handle<Node>,
+    ) {
+        let scene = &mut engine.scenes[self.scene];
+        let mut item = spawn_item(
+            scene,
+            engine.resource_manager.clone(),
+            kind,
+            position,
+            false,
+        )
+        .await;
+
+        let pivot = item.get_pivot();
+        scene.graph[pivot]
+            .local_transform_mut()
+            .set_rotation(vector_to_quat(direction));
+        if scene.graph.is_valid_handle(parent) {
+            scene.graph.link_nodes_keep_global_transform(pivot, parent);
+            item.attached = true;
+        }
+
+        self.items.add(item);
+    }
+
     fn update_death_zones(&mut self, scene: &Scene) {
         for (handle, actor) in self.actors.pair_iter_mut() {
             for death_zone in self.death_zones.iter() {
@@ -1101,6 +1835,7 @@ impl Level {
                     self.sender.as_ref().unwrap().send(Message::DamageActor {
                         actor: handle,
                         who: Default::default(),
+                        weapon: Default::default(),
                         hitbox: None,
                         amount: 99999.0,
                         critical_shot_probability: 0.0,
@@ -1110,6 +1845,117 @@ impl Level {
         }
     }
 
+    fn is_player_in_combat(&self) -> bool {
+        self.actors.iter().any(|actor| match actor {
+            Actor::Bot(bot) => !bot.is_dead() && bot.is_hunting(self.player),
+            Actor::Player(_) => false,
+        })
+    }
+
+    pub fn set_music_volume(&mut self, scene: &mut Scene, volume: f32) {
+        self.music.set_volume(&mut scene.graph, volume);
+    }
+
+    /// Returns the highlighted item to normal, the highlight swaps materials of the scene, so it
+    /// must not get into a save. It is applied again on next update.
+    pub fn clear_item_highlight(&mut self, scene: &mut Scene, resource_manager: &ResourceManager) {
+        self.items
+            .update_highlight(None, &mut scene.graph, resource_manager);
+    }
+
+    pub fn set_sfx_volume(&mut self, volume: f32) {
+        self.sound_manager.set_sfx_volume(volume);
+    }
+
+    pub fn set_camera_config(&mut self, config: CameraConfig) {
+        self.camera_config = config;
+    }
+
+    pub fn set_downed_state_config(&mut self, config: DownedStateConfig) {
+        self.downed_state_config = config;
+    }
+
+    pub fn set_weapon_jam_config(&mut self, config: WeaponJamConfig) {
+        self.weapon_jam_config = config;
+    }
+
+    pub fn set_double_jump_config(&mut self, config: DoubleJumpConfig) {
+        self.double_jump_config = config;
+    }
+
+    pub fn set_laser_sight_config(&mut self, config: LaserSightConfig) {
+        self.laser_sight_config = config;
+    }
+
+    pub fn set_health_regeneration_config(&mut self, config: HealthRegenerationConfig) {
+        self.health_regeneration_config = config;
+    }
+
+    fn update_objective_marker(&mut self, scene: &mut Scene, resource_manager: &ResourceManager) {
+        let target = match self.actors.try_get(self.player) {
+            Some(Actor::Player(player)) => player
+                .journal
+                .active_objective()
+                .and_then(|objective| objective.target),
+            _ => None,
+        };
+
+        if target.is_some() && !scene.graph.is_valid_handle(self.objective_marker) {
+            self.objective_marker = SpriteBuilder::new(BaseBuilder::new())
+                .with_size(0.1)
+                .with_color(Color::from_rgba(255, 200, 0, 220))
+                .with_texture(resource_manager.request_texture("data/particles/circle_05.png"))
+                .build(&mut scene.graph);
+        }
+
+        if scene.graph.is_valid_handle(self.objective_marker) {
+            let marker = &mut scene.graph[self.objective_marker];
+            marker.set_visibility(target.is_some());
+            if let Some(target) = target {
+                // Make the marker float a bit to make it more noticeable.
+                let offset = 1.0 + 0.1 * (self.time * 2.0).sin();
+                marker
+                    .local_transform_mut()
+                    .set_position(target + Vector3::new(0.0, offset, 0.0));
+            }
+        }
+    }
+
+    /// Returns states of items of every visited level including this one, it should be passed
+    /// to the next level to keep items of the levels as the player left them.
+    pub fn world_items(&self, graph: &Graph) -> HashMap<String, Vec<ItemState>> {
+        let mut world_items = self.world_items.clone();
+        world_items.insert(self.map_path.clone(), self.items.world_state(graph));
+        world_items
+    }
+
+    fn player_journal_mut(&mut self) -> Option<&mut Journal> {
+        if !self.actors.contains(self.player) {
+            return None;
+        }
+
+        match self.actors.get_mut(self.player) {
+            Actor::Player(player) => Some(&mut player.journal),
+            _ => None,
+        }
+    }
+
+    fn add_objective(&mut self, id: &str, description: &str, target: Option<Vector3<f32>>) {
+        if let Some(journal) = self.player_journal_mut() {
+            if journal.add_objective(id, description, target) {
+                self.sender.as_ref().unwrap().send(Message::SyncJournal);
+            }
+        }
+    }
+
+    fn complete_objective(&mut self, id: &str) {
+        if let Some(journal) = self.player_journal_mut() {
+            if journal.complete_objective(id) {
+                self.sender.as_ref().unwrap().send(Message::SyncJournal);
+            }
+        }
+    }
+
     fn update_game_ending(&self, scene: &Scene) {
         if let Actor::Player(player) = self.actors.get(self.player) {
             if player.is_completely_dead(scene) {
@@ -1124,11 +1970,28 @@ impl Level {
         time: GameTime,
         call_button_ui_container: &mut CallButtonUiContainer,
     ) {
+        if let Some(photo_mode) = self.photo_mode.as_mut() {
+            let scene = &mut engine.scenes[self.scene];
+            // Freeze physics, only the free camera moves in photo mode.
+            scene.graph.physics.integration_parameters.dt = 0.0;
+            photo_mode.update(&mut scene.graph, time.delta);
+            return;
+        }
+
+        let time_scale = self.hit_stop.update(time.delta);
+        let time = GameTime {
+            delta: time.delta * time_scale,
+            ..time
+        };
+
         self.time += time.delta;
         let scene = &mut engine.scenes[self.scene];
+        // Physics runs at the same fixed rate as the game, so it has to be slowed down too.
+        scene.graph.physics.integration_parameters.dt = time.delta;
 
         self.update_death_zones(scene);
-        self.weapons.update(scene, &self.actors, time.delta);
+        self.weapons
+            .update(scene, &self.actors, &self.laser_sight_config, time.delta);
         self.projectiles.update(
             scene,
             &self.actors,
@@ -1142,6 +2005,12 @@ impl Level {
             self.sender.as_ref().unwrap(),
             time.delta,
         );
+        self.mines.update(
+            scene,
+            &self.actors,
+            self.sender.as_ref().unwrap(),
+            time.delta,
+        );
         self.elevators.update(time.delta, scene);
         self.call_buttons
             .update(&self.elevators, call_button_ui_container);
@@ -1150,10 +2019,20 @@ impl Level {
             scene,
             items: &self.items,
             doors: &self.doors,
+            ladders: &self.ladders,
+            projectiles: &self.projectiles,
+            terminals: &self.terminals,
+            transitions: &self.transitions,
             navmesh: self.navmesh,
             weapons: &self.weapons,
             elevators: &self.elevators,
             call_buttons: &self.call_buttons,
+            smoke_clouds: &self.smoke_clouds,
+            cover_points: &self.cover_points,
+            health_regeneration: &self.health_regeneration_config,
+            camera: &self.camera_config,
+            downed_state: &self.downed_state_config,
+            double_jump: &self.double_jump_config,
             sender: self.sender.as_ref().unwrap(),
         };
 
@@ -1161,16 +2040,77 @@ impl Level {
         self.trails.update(time.delta, scene);
         self.update_game_ending(scene);
         self.decals.update(&mut scene.graph, time.delta);
+        self.corpses.update(
+            scene,
+            &self.actors,
+            self.sender.as_ref().unwrap(),
+            time.delta,
+        );
+        self.smoke_clouds.update(&mut scene.graph, time.delta);
         self.lights.update(scene, time.delta);
         self.items.update(time.delta, &mut scene.graph);
+        let player_position = self
+            .actors
+            .try_get(self.player)
+            .filter(|player| !player.is_dead())
+            .map(|player| player.position(&scene.graph));
+        self.items
+            .update_highlight(player_position, &mut scene.graph, &engine.resource_manager);
         self.triggers
             .update(scene, &self.actors, self.sender.as_ref().unwrap());
+        self.wave_spawners.update(
+            &scene.graph,
+            &self.actors,
+            self.player,
+            self.sender.as_ref().unwrap(),
+            time.delta,
+        );
+        self.hazards.update(
+            &scene.graph,
+            &self.actors,
+            self.sender.as_ref().unwrap(),
+            time.delta,
+        );
+        self.update_objective_marker(scene, &engine.resource_manager);
+        let grenade_throw = match self.actors.try_get(self.player) {
+            Some(Actor::Player(player)) => player.grenade_throw_preview(&scene.graph),
+            _ => None,
+        };
+        self.grenade_trajectory
+            .update(&mut scene.graph, &engine.resource_manager, grenade_throw);
+        let in_combat = self.is_player_in_combat();
+        self.music.update(&mut scene.graph, in_combat, time.delta);
         // Make sure to clear unused animation events, because they might be used
         // in next frames which might cause unwanted side effects (like multiple
         // queued attack events can result in huge damage at single frame).
         scene.animations.clear_animation_events();
     }
 
+    /// Bullets that fly close to bots (or land nearby) make them look for a cover.
+    fn suppress_bots(
+        &mut self,
+        graph: &Graph,
+        shooter: Handle<Actor>,
+        begin: Vector3<f32>,
+        end: Vector3<f32>,
+    ) {
+        let segment = end - begin;
+        let length_squared = segment.norm_squared().max(f32::EPSILON);
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            if handle == shooter {
+                continue;
+            }
+            if let Actor::Bot(bot) = actor {
+                let position = bot.position(graph);
+                let t = ((position - begin).dot(&segment) / length_squared).clamp(0.0, 1.0);
+                let closest = begin + segment.scale(t);
+                if closest.metric_distance(&position) < SUPPRESSION_RADIUS {
+                    bot.suppress(SHOT_SUPPRESSION);
+                }
+            }
+        }
+    }
+
     fn shoot_ray(
         &mut self,
         engine: &mut PluginContext,
@@ -1178,7 +2118,7 @@ impl Level {
         begin: Vector3<f32>,
         end: Vector3<f32>,
         damage: Damage,
-        shot_effect: ShotEffect,
+        shot_effect: Option<ShotEffect>,
     ) {
         let scene = &mut engine.scenes[self.scene];
 
@@ -1194,13 +2134,15 @@ impl Level {
         ) {
             let sender = self.sender.as_ref().unwrap();
 
+            let material = if hit.actor.is_some() {
+                Some(MaterialType::Flesh)
+            } else {
+                self.sound_manager.material_of(hit.collider, hit.feature)
+            };
+
             // Just send new messages, instead of doing everything manually here.
             sender.send(Message::CreateEffect {
-                kind: if hit.actor.is_some() {
-                    EffectKind::BloodSpray
-                } else {
-                    EffectKind::BulletImpact
-                },
+                kind: EffectKind::impact_for(material),
                 position: hit.position,
                 orientation: vector_to_quat(hit.normal),
             });
@@ -1235,6 +2177,10 @@ impl Level {
             sender.send(Message::DamageActor {
                 actor: hit.actor,
                 who: hit.who,
+                weapon: match shooter {
+                    Shooter::Weapon(weapon) => weapon,
+                    _ => Handle::NONE,
+                },
                 hitbox: hit.hit_box,
                 amount: damage
                     .scale(hit.hit_box.map_or(1.0, |h| h.damage_factor))
@@ -1242,33 +2188,48 @@ impl Level {
                 critical_shot_probability,
             });
 
+            if hit.actor.is_none() {
+                sender.send(Message::DamageDoor {
+                    collider: hit.collider,
+                    amount: damage.amount(),
+                });
+                sender.send(Message::DamageBarrel {
+                    collider: hit.collider,
+                    amount: damage.amount(),
+                    who: hit.who,
+                });
+            }
+
             let dir = hit.position - begin;
+            let impulse = dir
+                .try_normalize(std::f32::EPSILON)
+                .unwrap_or_default()
+                .scale(match shooter {
+                    Shooter::Weapon(weapon) => self.weapons[weapon].definition.impact_force,
+                    _ => DEFAULT_IMPACT_FORCE,
+                });
 
             let hit_collider_body = scene.graph[hit.collider].parent();
             let parent = if let Some(collider_parent) =
                 scene.graph[hit_collider_body].cast_mut::<RigidBody>()
             {
-                collider_parent.apply_force_at_point(
-                    dir.try_normalize(std::f32::EPSILON)
-                        .unwrap_or_default()
-                        .scale(30.0),
-                    hit.position,
-                );
+                collider_parent.apply_force_at_point(impulse, hit.position);
                 hit_collider_body
             } else {
                 Default::default()
             };
 
             if hit.actor.is_some() {
-                if let Actor::Bot(actor) = self.actors.get_mut(hit.actor) {
+                let actor = self.actors.get_mut(hit.actor);
+                if let Actor::Bot(bot) = actor {
                     let body = scene.graph[hit.collider].parent();
-                    actor
-                        .impact_handler
+                    bot.impact_handler
                         .handle_impact(scene, body, hit.position, dir);
                 }
+                knock_back(actor, impulse, &mut scene.graph);
             }
 
-            self.decals.add(Decal::new_bullet_hole(
+            let bullet_hole = Decal::new_bullet_hole(
                 engine.resource_manager.clone(),
                 &mut scene.graph,
                 hit.position,
@@ -1279,7 +2240,9 @@ impl Level {
                 } else {
                     Color::opaque(20, 20, 20)
                 },
-            ));
+                material,
+            );
+            self.decals.add(&mut scene.graph, bullet_hole);
 
             // Add blood splatter on a surface behind an actor that was shot.
             if hit.actor.is_some() && !self.actors.get(hit.actor).is_dead() {
@@ -1289,17 +2252,13 @@ impl Level {
                         ColliderShape::Trimesh(_)
                     ) {
                         if intersection.position.coords.metric_distance(&hit.position) < 2.0 {
-                            self.decals.add(Decal::new(
+                            let blood_splatter = Decal::new_blood_splatter(
+                                engine.resource_manager.clone(),
                                 &mut scene.graph,
                                 intersection.position.coords,
                                 dir,
-                                Handle::NONE,
-                                Color::opaque(255, 255, 255),
-                                Vector3::new(0.45, 0.45, 0.2),
-                                engine.resource_manager.request_texture(
-                                    "data/textures/decals/BloodSplatter_BaseColor.png",
-                                ),
-                            ));
+                            );
+                            self.decals.add(&mut scene.graph, blood_splatter);
 
                             break;
                         }
@@ -1312,8 +2271,18 @@ impl Level {
             (30.0, end)
         };
 
+        let shooter_actor = match shooter {
+            Shooter::Weapon(weapon) => self
+                .weapons
+                .try_get(weapon)
+                .map_or(Handle::NONE, |weapon| weapon.owner()),
+            _ => Handle::NONE,
+        };
+        self.suppress_bots(&scene.graph, shooter_actor, begin, hit_point);
+
         match shot_effect {
-            ShotEffect::Smoke => {
+            None => (),
+            Some(ShotEffect::Smoke) => {
                 self.trails.add(ShotTrail::new(
                     crate::effects::create(
                         EffectKind::Smoke,
@@ -1325,43 +2294,49 @@ impl Level {
                     5.0,
                 ));
             }
-            ShotEffect::Beam => {
+            Some(ShotEffect::Beam) => {
                 let trail_radius = 0.0014;
-
-                let trail = MeshBuilder::new(
-                    BaseBuilder::new()
-                        .with_cast_shadows(false)
-                        .with_local_transform(
-                            TransformBuilder::new()
-                                .with_local_position(begin)
-                                .with_local_scale(Vector3::new(
-                                    trail_radius,
-                                    trail_radius,
-                                    trail_len,
-                                ))
-                                .with_local_rotation(UnitQuaternion::face_towards(
-                                    &(end - begin),
-                                    &Vector3::y(),
-                                ))
-                                .build(),
-                        ),
-                )
-                .with_surfaces(vec![SurfaceBuilder::new(self.beam.clone().unwrap())
-                    .with_material(Arc::new(Mutex::new({
-                        let mut material = Material::standard();
-                        Log::verify(material.set_property(
-                            &ImmutableString::new("diffuseColor"),
-                            PropertyValue::Color(Color::from_rgba(255, 255, 255, 120)),
-                        ));
-                        material
-                    })))
-                    .build()])
-                .with_render_path(RenderPath::Forward)
-                .build(&mut scene.graph);
-
-                self.trails.add(ShotTrail::new(trail, 0.2));
+                let scale = Vector3::new(trail_radius, trail_radius, trail_len);
+                let rotation = UnitQuaternion::face_towards(&(end - begin), &Vector3::y());
+
+                // Beams are spawned at the rate of fire, so reuse expired ones when possible.
+                let trail = if let Some(trail) = self.trails.take_free(&mut scene.graph) {
+                    scene.graph[trail]
+                        .local_transform_mut()
+                        .set_position(begin)
+                        .set_scale(scale)
+                        .set_rotation(rotation);
+                    trail
+                } else {
+                    MeshBuilder::new(
+                        BaseBuilder::new()
+                            .with_cast_shadows(false)
+                            .with_local_transform(
+                                TransformBuilder::new()
+                                    .with_local_position(begin)
+                                    .with_local_scale(scale)
+                                    .with_local_rotation(rotation)
+                                    .build(),
+                            ),
+                    )
+                    .with_surfaces(vec![SurfaceBuilder::new(self.beam.clone().unwrap())
+                        .with_material(Arc::new(Mutex::new({
+                            let mut material = Material::standard();
+                            Log::verify(material.set_property(
+                                &ImmutableString::new("diffuseColor"),
+                                PropertyValue::Color(Color::from_rgba(255, 255, 255, 120)),
+                            ));
+                            material
+                        })))
+                        .build()])
+                    .with_render_path(RenderPath::Forward)
+                    .build(&mut scene.graph)
+                };
+
+                self.trails
+                    .add(ShotTrail::new(trail, 0.2).with_reusable(true));
             }
-            ShotEffect::Rail => {
+            Some(ShotEffect::Rail) => {
                 self.trails.add(ShotTrail::new(
                     crate::effects::create_rail(
                         &mut scene.graph,
@@ -1387,13 +2362,20 @@ impl Level {
     ) {
         let scene = &mut engine.scenes[self.scene];
         // Just find out actors which must be damaged and re-cast damage message for each.
-        for (actor_handle, actor) in self.actors.pair_iter() {
+        for (actor_handle, actor) in self.actors.pair_iter_mut() {
             // TODO: Add occlusion test. This will hit actors through walls.
             let position = actor.position(&scene.graph);
             if position.metric_distance(&center) <= radius {
+                knock_back(
+                    actor,
+                    explosion_impulse(center, position, amount),
+                    &mut scene.graph,
+                );
+
                 self.sender.as_ref().unwrap().send(Message::DamageActor {
                     actor: actor_handle,
                     who,
+                    weapon: Handle::NONE,
                     hitbox: None,
                     /// TODO: Maybe collect all hitboxes?
                     amount,
@@ -1401,6 +2383,133 @@ impl Level {
                 });
             }
         }
+
+        // Explosions can break doors too.
+        let doors = self
+            .doors
+            .doors_near(center, radius, &scene.graph)
+            .collect::<Vec<_>>();
+        for door in doors {
+            door_mut(door, &mut scene.graph).damage(amount);
+        }
+
+        // Barrels that are caught by an explosion explode too.
+        for barrel in self.barrels.barrels_near(center, radius, &scene.graph) {
+            barrel_mut(barrel, &mut scene.graph).damage(amount, who);
+        }
+
+        // Throw loose physical objects around. Actors (including their ragdolls) are pushed by
+        // knockback above.
+        let mut actor_bodies = Vec::new();
+        for actor in self.actors.iter() {
+            actor_bodies.push(actor.body);
+            for hit_box in actor.hit_boxes.iter() {
+                if let Some(collider) = scene.graph.try_get(hit_box.collider) {
+                    actor_bodies.push(collider.parent());
+                }
+            }
+        }
+        for (handle, node) in scene.graph.pair_iter_mut() {
+            if let Some(body) = node.cast_mut::<RigidBody>() {
+                let position = body.global_position();
+                if body.body_type() == RigidBodyType::Dynamic
+                    && !actor_bodies.contains(&handle)
+                    && position.metric_distance(&center) <= radius
+                {
+                    body.apply_impulse(explosion_impulse(center, position, amount));
+                }
+            }
+        }
+    }
+
+    fn handle_noise(
+        &mut self,
+        engine: &mut PluginContext,
+        position: Vector3<f32>,
+        radius: f32,
+        kind: NoiseKind,
+        source: Handle<Actor>,
+    ) {
+        let (source_kind, source_faction) = match self.actors.try_get(source) {
+            Some(Actor::Bot(bot)) => (TargetKind::Bot(bot.kind), bot.faction),
+            Some(Actor::Player(player)) => (TargetKind::Player, player.faction),
+            None => return,
+        };
+
+        let graph = &engine.scenes[self.scene].graph;
+        for (handle, actor) in self.actors.pair_iter_mut() {
+            if handle == source || actor.is_dead() {
+                continue;
+            }
+
+            if let Actor::Bot(bot) = actor {
+                let distance = bot.position(graph).metric_distance(&position);
+                if distance <= radius.min(bot.definition.hearing_radius)
+                    && !bot.faction.is_friend_of(source_faction)
+                    && bot
+                        .definition
+                        .hostility
+                        .is_hostile_to(bot.kind, &source_kind)
+                {
+                    bot.hear_noise(source, position, kind);
+                }
+            }
+        }
+    }
+
+    fn detonate_flashbang(
+        &mut self,
+        engine: &mut PluginContext,
+        position: Vector3<f32>,
+        range: f32,
+        duration: f32,
+    ) {
+        let scene = &mut engine.scenes[self.scene];
+
+        if let Some(Actor::Player(player)) = self.actors.try_get(self.player) {
+            if player.is_dead() {
+                return;
+            }
+
+            let eye_position = scene.graph[player.camera_controller().camera()].global_position();
+            if eye_position.metric_distance(&position) > range
+                || self
+                    .smoke_clouds
+                    .is_line_of_sight_blocked(position, eye_position)
+            {
+                return;
+            }
+
+            let ray = Ray::from_two_points(position, eye_position);
+            let mut query_buffer = Vec::new();
+            scene.graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::from(ray.origin),
+                    ray_direction: ray.dir,
+                    max_len: ray.dir.norm(),
+                    groups: Default::default(),
+                    sort_results: false,
+                },
+                &mut query_buffer,
+            );
+
+            // Actors do not occlude the flash, only level geometry does.
+            let occluded = query_buffer.iter().any(|intersection| {
+                !self.actors.iter().any(|actor| {
+                    actor.capsule_collider == intersection.collider
+                        || actor
+                            .hit_boxes
+                            .iter()
+                            .any(|hit_box| hit_box.collider == intersection.collider)
+                })
+            });
+
+            if !occluded {
+                if let Actor::Player(player) = self.actors.get_mut(self.player) {
+                    player.flash(duration);
+                }
+            }
+        }
     }
 
     fn try_open_door(
@@ -1414,6 +2523,69 @@ impl Level {
         door_mut(door, graph).try_open(inventory);
     }
 
+    fn unlock_door(&mut self, engine: &mut PluginContext, door: Handle<Node>) {
+        let graph = &mut engine.scenes[self.scene].graph;
+        if self.doors.doors.contains(&door) {
+            door_mut(door, graph).unlock();
+        }
+    }
+
+    fn use_terminal(
+        &mut self,
+        engine: &mut PluginContext,
+        terminal: Handle<Node>,
+        actor: Handle<Actor>,
+    ) {
+        let graph = &engine.scenes[self.scene].graph;
+        if !graph.is_valid_handle(terminal) {
+            return;
+        }
+
+        let has_key = self
+            .actors
+            .try_get(actor)
+            .map_or(false, |a| a.inventory.item_count(ItemKind::MasterKey) > 0);
+
+        if terminal_ref(terminal, graph).requires_key() && !has_key {
+            self.sender.as_ref().unwrap().send(Message::PlaySound {
+                path: PathBuf::from("data/sounds/door_deny.ogg"),
+                position: graph[terminal].global_position(),
+                gain: 1.0,
+                rolloff_factor: 1.0,
+                radius: 1.0,
+            });
+        } else if actor == self.player {
+            self.sender
+                .as_ref()
+                .unwrap()
+                .send(Message::ShowTerminal { terminal });
+        }
+    }
+
+    fn damage_door(&mut self, engine: &mut PluginContext, collider: Handle<Node>, amount: f32) {
+        let graph = &mut engine.scenes[self.scene].graph;
+        if let Some(collider_ref) = graph.try_get(collider) {
+            // Colliders are attached to the rigid body of the door.
+            let body = collider_ref.parent();
+            if self.doors.doors.contains(&body) {
+                door_mut(body, graph).damage(amount);
+            }
+        }
+    }
+
+    fn damage_barrel(
+        &mut self,
+        engine: &mut PluginContext,
+        collider: Handle<Node>,
+        amount: f32,
+        who: Handle<Actor>,
+    ) {
+        let graph = &mut engine.scenes[self.scene].graph;
+        if let Some(barrel) = self.barrels.find_by_collider(collider, graph) {
+            barrel_mut(barrel, graph).damage(amount, who);
+        }
+    }
+
     fn call_elevator(&mut self, elevator: Handle<Elevator>, floor: u32) {
         self.elevators[elevator].call_to(floor);
     }
@@ -1428,9 +2600,14 @@ impl Level {
         message: &Message,
         time: GameTime,
     ) {
+        let listener = match self.actors.try_get(self.player) {
+            Some(Actor::Player(player)) => player.camera_controller().camera(),
+            _ => Handle::NONE,
+        };
         self.sound_manager
             .handle_message(
                 &mut engine.scenes[self.scene].graph,
+                listener,
                 engine.resource_manager.clone(),
                 &message,
             )
@@ -1447,6 +2624,22 @@ impl Level {
             &Message::TryOpenDoor { door, actor } => {
                 self.try_open_door(engine, door, actor);
             }
+            &Message::UnlockDoor { door } => {
+                self.unlock_door(engine, door);
+            }
+            &Message::UseTerminal { terminal, actor } => {
+                self.use_terminal(engine, terminal, actor);
+            }
+            &Message::DamageDoor { collider, amount } => {
+                self.damage_door(engine, collider, amount);
+            }
+            &Message::DamageBarrel {
+                collider,
+                amount,
+                who,
+            } => {
+                self.damage_barrel(engine, collider, amount, who);
+            }
             &Message::GiveNewWeapon { actor, kind } => {
                 self.give_new_weapon(engine, actor, kind).await;
             }
@@ -1459,7 +2652,7 @@ impl Level {
             }
             &Message::RemoveActor { actor } => self.remove_actor(engine, actor).await,
             &Message::UseItem { actor, kind } => {
-                self.use_item(actor, kind).await;
+                self.use_item(engine, actor, kind).await;
             }
             &Message::PickUpItem { actor, item } => {
                 self.pickup_item(engine, actor, item).await;
@@ -1475,6 +2668,32 @@ impl Level {
                 shooter: owner,
             } => {
                 self.create_projectile(engine, kind, position, direction, initial_velocity, owner)
+                    .await;
+            }
+            &Message::ThrowBackGrenade {
+                projectile,
+                position,
+                direction,
+                initial_velocity,
+                shooter,
+            } => {
+                self.throw_back_grenade(
+                    engine,
+                    projectile,
+                    position,
+                    direction,
+                    initial_velocity,
+                    shooter,
+                )
+                .await;
+            }
+            &Message::EmbedItem {
+                item,
+                position,
+                direction,
+                parent,
+            } => {
+                self.embed_item(engine, item, position, direction, parent)
                     .await
             }
             &Message::ShowWeapon { weapon, state } => self.show_weapon(engine, weapon, state),
@@ -1492,6 +2711,16 @@ impl Level {
                     .await;
                 }
             }
+            &Message::SpawnWaveEnemy {
+                spawner,
+                kind,
+                position,
+                rotation,
+                health_factor,
+            } => {
+                self.spawn_wave_enemy(engine, spawner, kind, position, rotation, health_factor)
+                    .await
+            }
             &Message::ApplySplashDamage {
                 amount,
                 radius,
@@ -1509,6 +2738,7 @@ impl Level {
             &Message::DamageActor {
                 actor,
                 who,
+                weapon,
                 amount,
                 hitbox,
                 critical_shot_probability,
@@ -1517,11 +2747,23 @@ impl Level {
                     engine,
                     actor,
                     who,
+                    weapon,
                     amount,
                     hitbox,
                     critical_shot_probability,
                 );
             }
+            &Message::ApplyStatusEffect { actor, who, effect } => {
+                if self.actors.contains(actor) {
+                    let actor = self.actors.get_mut(actor);
+                    if !actor.is_dead() {
+                        actor.status_effects.apply(&effect, who);
+                    }
+                }
+            }
+            &Message::Takedown { attacker, victim } => {
+                self.takedown(attacker, victim);
+            }
             &Message::SightReaction { weapon, reaction } => {
                 self.weapons[weapon]
                     .laser_sight_mut()
@@ -1532,19 +2774,58 @@ impl Level {
                 position,
                 orientation,
             } => {
-                effects::create(
+                let graph = &mut engine.scenes[self.scene].graph;
+                if kind.is_impact() {
+                    self.impact_effects.retain(|&e| graph.is_valid_handle(e));
+                    if self.impact_effects.len() >= MAX_IMPACT_EFFECTS {
+                        if let Some(oldest) = self.impact_effects.pop_front() {
+                            graph.remove_node(oldest);
+                        }
+                    }
+                }
+
+                let effect = effects::create(
                     kind,
-                    &mut engine.scenes[self.scene].graph,
+                    graph,
                     engine.resource_manager.clone(),
                     position,
                     orientation,
                 );
+
+                if kind.is_impact() {
+                    self.impact_effects.push_back(effect);
+                }
             }
             &Message::SpawnItem {
                 kind,
                 position,
                 adjust_height,
             } => self.spawn_item(engine, kind, position, adjust_height).await,
+            &Message::CreateSmokeCloud {
+                position,
+                radius,
+                lifetime,
+            } => {
+                let node = effects::create_smoke_cloud(
+                    &mut engine.scenes[self.scene].graph,
+                    engine.resource_manager.clone(),
+                    position,
+                    radius,
+                );
+                self.smoke_clouds
+                    .add(SmokeCloud::new(node, position, radius, lifetime));
+            }
+            &Message::MakeNoise {
+                position,
+                radius,
+                kind,
+                source,
+            } => self.handle_noise(engine, position, radius, kind, source),
+            &Message::DetonateFlashbang {
+                position,
+                range,
+                duration,
+            } => self.detonate_flashbang(engine, position, range, duration),
             Message::ShootRay {
                 shooter: weapon,
                 begin,
@@ -1552,7 +2833,7 @@ impl Level {
                 damage,
                 shot_effect,
             } => {
-                self.shoot_ray(engine, *weapon, *begin, *end, *damage, shot_effect.clone());
+                self.shoot_ray(engine, *weapon, *begin, *end, *damage, *shot_effect);
             }
             &Message::GrabWeapon { kind, actor } => {
                 if self.actors.contains(actor) {
@@ -1565,9 +2846,51 @@ impl Level {
                     self.weapons[weapon].switch_flash_light(&mut engine.scenes[self.scene].graph);
                 }
             }
+            &Message::AttachItem { actor, item } => self.attach_item(actor, item),
+            &Message::DetachAttachments { actor, weapon } => self.detach_attachments(actor, weapon),
+            &Message::ToggleSilencer { weapon } => {
+                if self.weapons.contains(weapon) {
+                    let weapon = &mut self.weapons[weapon];
+                    let silenced = !weapon.is_silenced();
+                    weapon.set_silenced(silenced, &mut engine.scenes[self.scene].graph);
+                }
+            }
+            &Message::CycleFireMode { weapon } => {
+                if self.weapons.contains(weapon) {
+                    self.weapons[weapon].cycle_fire_mode();
+                }
+            }
+            &Message::ClearJam { weapon } => {
+                if self.weapons.contains(weapon) {
+                    let weapon = &mut self.weapons[weapon];
+                    if weapon.is_jammed() && !weapon.is_clearing_jam() {
+                        weapon.clear_jam(self.weapon_jam_config.clear_time);
+                        self.sender.as_ref().unwrap().send(Message::PlaySound {
+                            path: PathBuf::from(JAM_SOUND),
+                            position: weapon.shot_position(&engine.scenes[self.scene].graph),
+                            gain: 1.0,
+                            rolloff_factor: 1.0,
+                            radius: 1.0,
+                        });
+                    }
+                }
+            }
+            &Message::ToggleLaserSight { weapon } => {
+                if self.weapons.contains(weapon) {
+                    let weapon = &mut self.weapons[weapon];
+                    let enabled = !weapon.is_laser_sight_enabled();
+                    weapon.set_laser_sight_enabled(enabled);
+                }
+            }
             &Message::DropItems { actor, item, count } => {
                 self.drop_items(engine, actor, item, count).await;
             }
+            Message::AddObjective {
+                id,
+                description,
+                target,
+            } => self.add_objective(id, description, *target),
+            Message::CompleteObjective { id } => self.complete_objective(id),
             _ => (),
         }
     }
@@ -1647,6 +2970,22 @@ pub struct SpawnPoint {
     bot_kind: BotKind,
     spawned: bool,
     with_gun: bool,
+    #[visit(optional)]
+    patrol: Patrol,
+}
+
+impl SpawnPoint {
+    /// Returns kind of the weapon which the bot will be armed with, if any.
+    fn weapon(&self) -> Option<WeaponKind> {
+        if self.with_gun {
+            Bot::get_definition(self.bot_kind)
+                .ranged_attack
+                .as_ref()
+                .map(|ranged_attack| ranged_attack.weapon)
+        } else {
+            None
+        }
+    }
 }
 
 impl Default for SpawnPoint {
@@ -1657,6 +2996,7 @@ impl Default for SpawnPoint {
             bot_kind: BotKind::Zombie,
             spawned: false,
             with_gun: false,
+            patrol: Default::default(),
         }
     }
 }
@@ -0,0 +1,143 @@
+//! Music director crossfades between ambient and combat tracks depending on whether the player
+//! is being hunted by someone. Without a combat track the ambient track speeds up instead.
+
+use fyrox::{
+    core::{pool::Handle, visitor::prelude::*},
+    engine::resource_manager::ResourceManager,
+    lazy_static::lazy_static,
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        node::Node,
+        sound::{SoundBuilder, Status},
+    },
+    utils::log::{Log, MessageKind},
+};
+use serde::Deserialize;
+use std::{fs::File, path::PathBuf};
+
+#[derive(Deserialize)]
+pub struct MusicDefinition {
+    pub ambient: PathBuf,
+    /// Track that is played while the player is hunted. If it is not set, the ambient track keeps
+    /// playing and its pitch is raised to `combat_pitch` instead.
+    #[serde(default)]
+    pub combat: Option<PathBuf>,
+    #[serde(default = "default_combat_pitch")]
+    pub combat_pitch: f32,
+    /// Time (in seconds) to fully switch to combat track.
+    pub fade_in_time: f32,
+    /// Time (in seconds) to fully switch back to ambient track.
+    pub fade_out_time: f32,
+    /// Time (in seconds) after the end of combat before ambient track starts fading in.
+    pub combat_cooldown: f32,
+}
+
+fn default_combat_pitch() -> f32 {
+    1.0
+}
+
+impl MusicDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/music.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    static ref DEFINITION: MusicDefinition = MusicDefinition::load();
+}
+
+#[derive(Default, Visit)]
+pub struct MusicDirector {
+    ambient: Handle<Node>,
+    combat: Handle<Node>,
+    /// 0.0 - only ambient track is heard, 1.0 - only combat track is heard.
+    mix: f32,
+    cooldown: f32,
+    volume: f32,
+}
+
+async fn create_track(
+    graph: &mut Graph,
+    resource_manager: &ResourceManager,
+    path: &PathBuf,
+    gain: f32,
+) -> Handle<Node> {
+    match resource_manager.request_sound_buffer(path).await {
+        Ok(buffer) => SoundBuilder::new(BaseBuilder::new())
+            .with_buffer(buffer.into())
+            .with_looping(true)
+            .with_status(Status::Playing)
+            .with_gain(gain)
+            .build(graph),
+        Err(_) => {
+            Log::writeln(
+                MessageKind::Error,
+                format!("Unable to load music track {:?}", path),
+            );
+            Handle::NONE
+        }
+    }
+}
+
+impl MusicDirector {
+    pub async fn new(graph: &mut Graph, resource_manager: ResourceManager, volume: f32) -> Self {
+        let definition = &*DEFINITION;
+
+        Self {
+            ambient: create_track(graph, &resource_manager, &definition.ambient, volume).await,
+            combat: match definition.combat.as_ref() {
+                Some(combat) => create_track(graph, &resource_manager, combat, 0.0).await,
+                None => Handle::NONE,
+            },
+            mix: 0.0,
+            cooldown: 0.0,
+            volume,
+        }
+    }
+
+    pub fn set_volume(&mut self, graph: &mut Graph, volume: f32) {
+        self.volume = volume;
+        self.apply_mix(graph);
+    }
+
+    fn apply_mix(&self, graph: &mut Graph) {
+        if self.combat.is_none() {
+            // The ambient track is streamed, so it cannot be played by a second sound at the same
+            // time, it speeds up instead.
+            if let Some(track) = graph.try_get_mut(self.ambient) {
+                let pitch = 1.0 + (DEFINITION.combat_pitch - 1.0) * self.mix;
+                let track = track.as_sound_mut();
+                track.set_gain(self.volume);
+                track.set_pitch(pitch as f64);
+            }
+            return;
+        }
+
+        for (track, gain) in [
+            (self.ambient, self.volume * (1.0 - self.mix)),
+            (self.combat, self.volume * self.mix),
+        ] {
+            if let Some(track) = graph.try_get_mut(track) {
+                track.as_sound_mut().set_gain(gain);
+            }
+        }
+    }
+
+    pub fn update(&mut self, graph: &mut Graph, in_combat: bool, dt: f32) {
+        let definition = &*DEFINITION;
+
+        if in_combat {
+            self.cooldown = definition.combat_cooldown;
+            self.mix += dt / definition.fade_in_time.max(f32::EPSILON);
+        } else if self.cooldown > 0.0 {
+            self.cooldown -= dt;
+        } else {
+            self.mix -= dt / definition.fade_out_time.max(f32::EPSILON);
+        }
+        self.mix = self.mix.clamp(0.0, 1.0);
+
+        self.apply_mix(graph);
+    }
+}
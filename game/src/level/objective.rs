@@ -0,0 +1,104 @@
+//! Objectives placed on a level. Each objective point adds an objective to the journal of the
+//! player when the level starts and completes it when the player reaches the point.
+
+use crate::{actor::Actor, game_ref, message::Message, GameConstructor};
+use fyrox::{
+    core::{
+        inspect::prelude::*,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::node::TypeUuidProvider,
+    script::{ScriptContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct ObjectivePoint {
+    #[inspect(description = "Unique id of the objective.")]
+    id: InheritableVariable<String>,
+
+    #[inspect(description = "Description of the objective that will be shown to the player.")]
+    description: InheritableVariable<String>,
+
+    #[inspect(
+        description = "Objective is completed when the player is closer than the radius to the point.",
+        min_value = "0.0"
+    )]
+    completion_radius: InheritableVariable<f32>,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(skip)]
+    added: bool,
+
+    #[inspect(skip)]
+    #[reflect(hidden)]
+    #[visit(skip)]
+    completed: bool,
+}
+
+impl Default for ObjectivePoint {
+    fn default() -> Self {
+        Self {
+            id: Default::default(),
+            description: Default::default(),
+            completion_radius: InheritableVariable::new(1.5),
+            added: false,
+            completed: false,
+        }
+    }
+}
+
+impl_component_provider!(ObjectivePoint);
+
+impl TypeUuidProvider for ObjectivePoint {
+    fn type_uuid() -> Uuid {
+        uuid!("e2b7f1c4-8d39-4a51-b6e0-5c3a9f7d2e18")
+    }
+}
+
+impl ScriptTrait for ObjectivePoint {
+    fn on_update(&mut self, context: ScriptContext) {
+        let game = game_ref(context.plugin);
+        let position = context.scene.graph[context.handle].global_position();
+
+        // Journal ignores objectives that were added already, so it is safe to do this after
+        // loading a saved game.
+        if !self.added {
+            game.message_sender.send(Message::AddObjective {
+                id: (*self.id).clone(),
+                description: (*self.description).clone(),
+                target: Some(position),
+            });
+            self.added = true;
+        }
+
+        if !self.completed {
+            if let Some(level) = game.level.as_ref() {
+                if let Some(Actor::Player(player)) = level.actors.try_get(level.get_player()) {
+                    if player
+                        .position(&context.scene.graph)
+                        .metric_distance(&position)
+                        < *self.completion_radius
+                    {
+                        game.message_sender.send(Message::CompleteObjective {
+                            id: (*self.id).clone(),
+                        });
+                        self.completed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
@@ -0,0 +1,308 @@
+//! Photo mode freezes the game and detaches the camera from the player, the camera flies around
+//! freely, so the player could take screenshots. Gameplay state is not touched, animations and
+//! particle systems of the scene are paused, cameras of the player are restored on exit.
+
+use crate::control_scheme::{ControlButton, ControlScheme};
+use fyrox::{
+    animation::Animation,
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+    },
+    event::{DeviceEvent, ElementState, Event, MouseScrollDelta, WindowEvent},
+    scene::{
+        camera::{Camera, PerspectiveProjection, Projection},
+        graph::Graph,
+        node::Node,
+        particle_system::ParticleSystem,
+        Scene,
+    },
+};
+
+/// Speed of the camera in meters per second.
+const CAMERA_SPEED: f32 = 3.0;
+/// Multiplier of the speed of the camera while the run button is held.
+const FAST_SPEED_FACTOR: f32 = 4.0;
+/// Speed of the roll of the camera in degrees per second.
+const ROLL_SPEED: f32 = 45.0;
+/// Change of field of view (in degrees) per step of the mouse wheel.
+const FOV_STEP: f32 = 5.0;
+const MIN_FOV: f32 = 10.0;
+const MAX_FOV: f32 = 120.0;
+
+#[derive(Default)]
+struct Controller {
+    move_forward: bool,
+    move_backward: bool,
+    move_left: bool,
+    move_right: bool,
+    move_up: bool,
+    move_down: bool,
+    fast: bool,
+    roll_left: bool,
+    roll_right: bool,
+}
+
+pub struct PhotoMode {
+    camera: Handle<Node>,
+    /// Cameras that were enabled before photo mode was entered.
+    disabled_cameras: Vec<Handle<Node>>,
+    /// Animations and particle systems that were running before photo mode was entered.
+    paused_animations: Vec<Handle<Animation>>,
+    paused_particle_systems: Vec<Handle<Node>>,
+    yaw: f32,
+    pitch: f32,
+    roll: f32,
+    controller: Controller,
+}
+
+impl PhotoMode {
+    /// Creates a free camera at the place of given camera, every other camera is disabled while
+    /// photo mode is active.
+    pub fn new(player_camera: Handle<Node>, scene: &mut Scene) -> Self {
+        let paused_animations = scene
+            .animations
+            .pair_iter_mut()
+            .filter_map(|(handle, animation)| {
+                if animation.is_enabled() {
+                    animation.set_enabled(false);
+                    Some(handle)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let graph = &mut scene.graph;
+
+        let paused_particle_systems = graph
+            .pair_iter_mut()
+            .filter_map(|(handle, node)| {
+                let particle_system = node.cast_mut::<ParticleSystem>()?;
+                if particle_system.is_enabled() {
+                    particle_system.set_enabled(false);
+                    Some(handle)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let position = graph[player_camera].global_position();
+        let look = graph[player_camera]
+            .look_vector()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::z);
+
+        let disabled_cameras = graph
+            .pair_iter_mut()
+            .filter_map(|(handle, node)| {
+                let camera = node.cast_mut::<Camera>()?;
+                if camera.is_enabled() {
+                    camera.set_enabled(false);
+                    Some(handle)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let (camera, _) = graph.copy_node_inplace(player_camera, &mut |_, _| true);
+        let root = graph.get_root();
+        graph.link_nodes(camera, root);
+        graph[camera]
+            .local_transform_mut()
+            .set_position(position)
+            .set_scale(Vector3::new(1.0, 1.0, 1.0));
+        if let Some(camera) = graph[camera].cast_mut::<Camera>() {
+            camera.set_enabled(true);
+        }
+
+        let mut photo_mode = Self {
+            camera,
+            disabled_cameras,
+            paused_animations,
+            paused_particle_systems,
+            yaw: look.x.atan2(look.z),
+            pitch: (-look.y).asin(),
+            roll: 0.0,
+            controller: Default::default(),
+        };
+        photo_mode.apply_rotation(graph);
+        photo_mode
+    }
+
+    fn apply_rotation(&self, graph: &mut Graph) {
+        graph[self.camera].local_transform_mut().set_rotation(
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw)
+                * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch)
+                * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), self.roll),
+        );
+    }
+
+    fn change_fov(&self, graph: &mut Graph, delta: f32) {
+        if let Some(camera) = graph[self.camera].cast_mut::<Camera>() {
+            if let Projection::Perspective(perspective) = camera.projection().clone() {
+                let fov = (perspective.fov.to_degrees() + delta).clamp(MIN_FOV, MAX_FOV);
+                camera.set_projection(Projection::Perspective(PerspectiveProjection {
+                    fov: fov.to_radians(),
+                    ..perspective
+                }));
+            }
+        }
+    }
+
+    pub fn process_input_event(
+        &mut self,
+        event: &Event<()>,
+        dt: f32,
+        graph: &mut Graph,
+        control_scheme: &ControlScheme,
+    ) {
+        let button_state = match event {
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { input, .. },
+                ..
+            } => input
+                .virtual_keycode
+                .map(|vk| (ControlButton::Key(vk), input.state)),
+            Event::DeviceEvent { event, .. } => match event {
+                &DeviceEvent::MouseWheel { delta } => {
+                    let y = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => y,
+                        MouseScrollDelta::PixelDelta(delta) => delta.y as f32,
+                    };
+                    // Scrolling up narrows the view.
+                    self.change_fov(graph, -y.signum() * FOV_STEP);
+                    None
+                }
+                &DeviceEvent::Button { button, state } => {
+                    Some((ControlButton::Mouse(button as u16), state))
+                }
+                DeviceEvent::MouseMotion { delta } => {
+                    let mouse_sens = control_scheme.mouse_sens * dt;
+                    let pitch_direction = if control_scheme.mouse_y_inverse {
+                        -1.0
+                    } else {
+                        1.0
+                    };
+                    self.yaw -= (delta.0 as f32) * mouse_sens;
+                    self.pitch = (self.pitch + pitch_direction * (delta.1 as f32) * mouse_sens)
+                        .clamp(-90.0f32.to_radians(), 90.0f32.to_radians());
+                    None
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some((button, state)) = button_state {
+            let pressed = state == ElementState::Pressed;
+            if button == control_scheme.move_forward.button {
+                self.controller.move_forward = pressed;
+            } else if button == control_scheme.move_backward.button {
+                self.controller.move_backward = pressed;
+            } else if button == control_scheme.move_left.button {
+                self.controller.move_left = pressed;
+            } else if button == control_scheme.move_right.button {
+                self.controller.move_right = pressed;
+            } else if button == control_scheme.jump.button {
+                self.controller.move_up = pressed;
+            } else if button == control_scheme.crouch.button {
+                self.controller.move_down = pressed;
+            } else if button == control_scheme.run.button {
+                self.controller.fast = pressed;
+            } else if button == control_scheme.cursor_left.button {
+                self.controller.roll_left = pressed;
+            } else if button == control_scheme.cursor_right.button {
+                self.controller.roll_right = pressed;
+            } else if button == control_scheme.cursor_up.button && pressed {
+                self.change_fov(graph, -FOV_STEP);
+            } else if button == control_scheme.cursor_down.button && pressed {
+                self.change_fov(graph, FOV_STEP);
+            }
+        }
+    }
+
+    pub fn update(&mut self, graph: &mut Graph, dt: f32) {
+        // Nodes with limited lifetime (like effects) must not disappear while the game is frozen.
+        for node in graph.linear_iter_mut() {
+            if let Some(lifetime) = node.lifetime() {
+                node.set_lifetime(Some(lifetime + dt));
+            }
+        }
+
+        if self.controller.roll_left {
+            self.roll -= ROLL_SPEED.to_radians() * dt;
+        }
+        if self.controller.roll_right {
+            self.roll += ROLL_SPEED.to_radians() * dt;
+        }
+        self.apply_rotation(graph);
+
+        let node = &graph[self.camera];
+        let mut direction = Vector3::default();
+        if self.controller.move_forward {
+            direction += node.look_vector();
+        }
+        if self.controller.move_backward {
+            direction -= node.look_vector();
+        }
+        if self.controller.move_left {
+            direction += node.side_vector();
+        }
+        if self.controller.move_right {
+            direction -= node.side_vector();
+        }
+        if self.controller.move_up {
+            direction += Vector3::y();
+        }
+        if self.controller.move_down {
+            direction -= Vector3::y();
+        }
+
+        if let Some(direction) = direction.try_normalize(f32::EPSILON) {
+            let speed = if self.controller.fast {
+                CAMERA_SPEED * FAST_SPEED_FACTOR
+            } else {
+                CAMERA_SPEED
+            };
+            graph[self.camera]
+                .local_transform_mut()
+                .offset(direction.scale(speed * dt));
+        }
+    }
+
+    /// Removes the free camera and resumes everything that was paused by photo mode.
+    pub fn exit(self, scene: &mut Scene) {
+        for (handle, animation) in scene.animations.pair_iter_mut() {
+            if self.paused_animations.contains(&handle) {
+                animation.set_enabled(true);
+            }
+        }
+
+        let graph = &mut scene.graph;
+
+        for particle_system in self.paused_particle_systems {
+            if let Some(particle_system) = graph
+                .try_get_mut(particle_system)
+                .and_then(|n| n.cast_mut::<ParticleSystem>())
+            {
+                particle_system.set_enabled(true);
+            }
+        }
+
+        if graph.is_valid_handle(self.camera) {
+            graph.remove_node(self.camera);
+        }
+
+        for camera in self.disabled_cameras {
+            if let Some(camera) = graph
+                .try_get_mut(camera)
+                .and_then(|n| n.cast_mut::<Camera>())
+            {
+                camera.set_enabled(true);
+            }
+        }
+    }
+}
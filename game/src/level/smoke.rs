@@ -0,0 +1,70 @@
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        pool::Handle,
+        visitor::{Visit, VisitResult, Visitor},
+        VecExtensions,
+    },
+    scene::{graph::Graph, node::Node},
+};
+
+#[derive(Default, Visit)]
+pub struct SmokeCloud {
+    node: Handle<Node>,
+    position: Vector3<f32>,
+    radius: f32,
+    lifetime: f32,
+}
+
+impl SmokeCloud {
+    pub fn new(node: Handle<Node>, position: Vector3<f32>, radius: f32, lifetime: f32) -> Self {
+        Self {
+            node,
+            position,
+            radius,
+            lifetime,
+        }
+    }
+}
+
+#[derive(Default, Visit)]
+pub struct SmokeCloudContainer {
+    clouds: Vec<SmokeCloud>,
+}
+
+impl SmokeCloudContainer {
+    pub fn add(&mut self, cloud: SmokeCloud) {
+        self.clouds.push(cloud);
+    }
+
+    /// Checks whether a segment between two points passes through any smoke cloud.
+    pub fn is_line_of_sight_blocked(&self, begin: Vector3<f32>, end: Vector3<f32>) -> bool {
+        let dir = end - begin;
+        let sqr_len = dir.norm_squared();
+        self.clouds.iter().any(|cloud| {
+            // Find closest point on the segment to the center of the cloud.
+            let t = if sqr_len > f32::EPSILON {
+                ((cloud.position - begin).dot(&dir) / sqr_len).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            (begin + dir.scale(t)).metric_distance(&cloud.position) < cloud.radius
+        })
+    }
+
+    pub fn update(&mut self, graph: &mut Graph, dt: f32) {
+        self.clouds.retain_mut_ext(|cloud| {
+            cloud.lifetime -= dt;
+
+            if cloud.lifetime <= 0.0 {
+                if graph.is_valid_handle(cloud.node) {
+                    graph.remove_node(cloud.node);
+                }
+
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
@@ -0,0 +1,174 @@
+//! Computer terminals placed on a level. A terminal shows a log and can unlock a door or complete
+//! an objective, access to it could be restricted by a master key or a password.
+
+use crate::{current_level_mut, GameConstructor};
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        inspect::prelude::*,
+        pool::Handle,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        graph::{map::NodeHandleMap, Graph},
+        node::{Node, NodeHandle, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct Terminal {
+    #[inspect(description = "Title of the terminal that is shown at the top of its screen.")]
+    title: InheritableVariable<String>,
+
+    #[inspect(description = "Text of a log that can be read on the terminal.")]
+    log: InheritableVariable<String>,
+
+    #[inspect(
+        description = "Password that must be entered to use the terminal, empty means no password."
+    )]
+    password: InheritableVariable<String>,
+
+    #[inspect(description = "Whether the terminal can be used only by someone with a master key.")]
+    requires_key: InheritableVariable<bool>,
+
+    #[inspect(description = "A locked door that can be unlocked using the terminal.")]
+    door: NodeHandle,
+
+    #[inspect(description = "Id of an objective that will be completed using the terminal.")]
+    objective: InheritableVariable<String>,
+
+    #[inspect(
+        description = "Maximum distance from which the terminal can be used.",
+        min_value = "0.0"
+    )]
+    use_distance: InheritableVariable<f32>,
+}
+
+impl Default for Terminal {
+    fn default() -> Self {
+        Self {
+            title: InheritableVariable::new("Terminal".to_owned()),
+            log: Default::default(),
+            password: Default::default(),
+            requires_key: InheritableVariable::new(false),
+            door: Default::default(),
+            objective: Default::default(),
+            use_distance: InheritableVariable::new(1.0),
+        }
+    }
+}
+
+impl_component_provider!(Terminal);
+
+impl TypeUuidProvider for Terminal {
+    fn type_uuid() -> Uuid {
+        uuid!("3f9d62b1-0c7e-4e2a-9b85-d41a6e0c7f29")
+    }
+}
+
+impl ScriptTrait for Terminal {
+    fn on_init(&mut self, context: ScriptContext) {
+        current_level_mut(context.plugin)
+            .terminals
+            .add(context.handle);
+    }
+
+    fn on_deinit(&mut self, context: ScriptDeinitContext) {
+        current_level_mut(context.plugin)
+            .terminals
+            .remove(context.node_handle);
+    }
+
+    fn remap_handles(&mut self, old_new_mapping: &NodeHandleMap) {
+        old_new_mapping.try_map(&mut self.door);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+impl Terminal {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn log(&self) -> &str {
+        &self.log
+    }
+
+    /// Returns `None` if the terminal is not protected by a password.
+    pub fn password(&self) -> Option<&str> {
+        if self.password.is_empty() {
+            None
+        } else {
+            Some(&self.password)
+        }
+    }
+
+    pub fn requires_key(&self) -> bool {
+        *self.requires_key
+    }
+
+    pub fn door(&self) -> Handle<Node> {
+        *self.door
+    }
+
+    /// Returns `None` if the terminal does not complete any objective.
+    pub fn objective(&self) -> Option<&str> {
+        if self.objective.is_empty() {
+            None
+        } else {
+            Some(&self.objective)
+        }
+    }
+}
+
+pub fn terminal_ref(handle: Handle<Node>, graph: &Graph) -> &Terminal {
+    graph[handle]
+        .script()
+        .and_then(|s| s.cast::<Terminal>())
+        .unwrap()
+}
+
+#[derive(Default, Visit)]
+pub struct TerminalContainer {
+    terminals: Vec<Handle<Node>>,
+}
+
+impl TerminalContainer {
+    pub fn add(&mut self, terminal: Handle<Node>) {
+        if !self.terminals.contains(&terminal) {
+            self.terminals.push(terminal);
+        }
+    }
+
+    pub fn remove(&mut self, terminal: Handle<Node>) {
+        if let Some(position) = self.terminals.iter().position(|t| *t == terminal) {
+            self.terminals.remove(position);
+        }
+    }
+
+    /// Returns the closest terminal that can be used by someone at given position.
+    pub fn find_usable(&self, position: Vector3<f32>, graph: &Graph) -> Option<Handle<Node>> {
+        self.terminals
+            .iter()
+            .filter(|&&handle| graph.is_valid_handle(handle))
+            .map(|&handle| {
+                let distance = graph[handle].global_position().metric_distance(&position);
+                (handle, distance)
+            })
+            .filter(|&(handle, distance)| distance <= *terminal_ref(handle, graph).use_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(handle, _)| handle)
+    }
+}
@@ -5,14 +5,22 @@ use fyrox::scene::sprite::Sprite;
 use fyrox::utils::log::Log;
 use fyrox::{
     core::{pool::Handle, visitor::prelude::*, VecExtensions},
-    scene::{node::Node, Scene},
+    scene::{graph::Graph, node::Node, Scene},
 };
 
+/// Max amount of expired trails that are kept hidden in the scene for reuse. The rest will be
+/// removed from the scene as usual.
+const MAX_FREE_TRAILS: usize = 64;
+
 #[derive(Default, Visit)]
 pub struct ShotTrail {
     node: Handle<Node>,
     lifetime: f32,
     max_lifetime: f32,
+    /// Reusable trails are hidden on expiration instead of removal so they can be taken back
+    /// from the container by [`ShotTrailContainer::take_free`].
+    #[visit(optional)]
+    reusable: bool,
 }
 
 impl ShotTrail {
@@ -21,42 +29,61 @@ impl ShotTrail {
             node,
             lifetime: 0.0,
             max_lifetime,
+            reusable: false,
+        }
+    }
+
+    pub fn with_reusable(mut self, reusable: bool) -> Self {
+        self.reusable = reusable;
+        self
+    }
+}
+
+pub fn set_alpha(graph: &mut Graph, node: Handle<Node>, alpha: u8) {
+    let trait_node = &mut graph[node];
+    if let Some(mesh) = trait_node.cast_mut::<Mesh>() {
+        for surface in mesh.surfaces_mut() {
+            let mut material = surface.material().lock();
+            let color = material
+                .property_ref(&ImmutableString::new("diffuseColor"))
+                .unwrap()
+                .as_color()
+                .unwrap();
+            Log::verify(material.set_property(
+                &ImmutableString::new("diffuseColor"),
+                PropertyValue::Color(color.with_new_alpha(alpha)),
+            ));
         }
+    } else if let Some(sprite) = trait_node.cast_mut::<Sprite>() {
+        sprite.set_color(sprite.color().with_new_alpha(alpha));
     }
 }
 
 #[derive(Default, Visit)]
 pub struct ShotTrailContainer {
     container: Vec<ShotTrail>,
+    /// Hidden nodes of expired reusable trails.
+    #[visit(optional)]
+    free: Vec<Handle<Node>>,
 }
 
 impl ShotTrailContainer {
     pub fn update(&mut self, dt: f32, scene: &mut Scene) {
+        let free = &mut self.free;
         self.container.retain_mut_ext(|trail| {
             trail.lifetime = (trail.lifetime + dt).min(trail.max_lifetime);
             let k = 1.0 - trail.lifetime / trail.max_lifetime;
             let new_alpha = (255.0 * k) as u8;
 
-            let trait_node = &mut scene.graph[trail.node];
-            if let Some(mesh) = trait_node.cast_mut::<Mesh>() {
-                for surface in mesh.surfaces_mut() {
-                    let mut material = surface.material().lock();
-                    let color = material
-                        .property_ref(&ImmutableString::new("diffuseColor"))
-                        .unwrap()
-                        .as_color()
-                        .unwrap();
-                    Log::verify(material.set_property(
-                        &ImmutableString::new("diffuseColor"),
-                        PropertyValue::Color(color.with_new_alpha(new_alpha)),
-                    ));
-                }
-            } else if let Some(sprite) = trait_node.cast_mut::<Sprite>() {
-                sprite.set_color(sprite.color().with_new_alpha(new_alpha));
-            }
+            set_alpha(&mut scene.graph, trail.node, new_alpha);
 
             if trail.lifetime >= trail.max_lifetime {
-                scene.remove_node(trail.node);
+                if trail.reusable && free.len() < MAX_FREE_TRAILS {
+                    scene.graph[trail.node].set_visibility(false);
+                    free.push(trail.node);
+                } else {
+                    scene.remove_node(trail.node);
+                }
             }
             trail.lifetime < trail.max_lifetime
         });
@@ -65,4 +92,17 @@ impl ShotTrailContainer {
     pub fn add(&mut self, trail: ShotTrail) {
         self.container.push(trail);
     }
+
+    /// Returns a node of an expired reusable trail (if any). The node is made visible and fully
+    /// opaque, so the caller just needs to set its transform and add the trail back.
+    pub fn take_free(&mut self, graph: &mut Graph) -> Option<Handle<Node>> {
+        while let Some(node) = self.free.pop() {
+            if graph.is_valid_handle(node) {
+                set_alpha(graph, node, 255);
+                graph[node].set_visibility(true);
+                return Some(node);
+            }
+        }
+        None
+    }
 }
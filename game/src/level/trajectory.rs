@@ -0,0 +1,120 @@
+//! Predicted flight path of a grenade, shown while the player holds the toss button. The path is
+//! simulated with the same initial velocity as the actual throw and is cut at the first obstacle.
+
+use crate::CollisionGroups;
+use fyrox::{
+    core::{
+        algebra::{Point3, Vector3},
+        arrayvec::ArrayVec,
+        color::Color,
+        pool::Handle,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    scene::{
+        base::BaseBuilder,
+        collider::{BitMask, InteractionGroups},
+        graph::{physics::RayCastOptions, Graph},
+        node::Node,
+        sprite::SpriteBuilder,
+    },
+};
+
+/// Amount of points the path consists of.
+const POINT_COUNT: usize = 32;
+/// Time (in seconds) of flight between two adjacent points.
+const TIME_STEP: f32 = 0.05;
+const POINT_COLOR: Color = Color::from_rgba(255, 255, 255, 180);
+const IMPACT_COLOR: Color = Color::from_rgba(255, 120, 0, 220);
+
+#[derive(Default, Visit)]
+pub struct GrenadeTrajectory {
+    points: Vec<Handle<Node>>,
+}
+
+impl GrenadeTrajectory {
+    fn create_points(&mut self, graph: &mut Graph, resource_manager: &ResourceManager) {
+        let texture = resource_manager.request_texture("data/particles/circle_05.png");
+        self.points = (0..POINT_COUNT)
+            .map(|_| {
+                SpriteBuilder::new(BaseBuilder::new().with_visibility(false))
+                    .with_size(0.03)
+                    .with_color(POINT_COLOR)
+                    .with_texture(texture.clone())
+                    .build(graph)
+            })
+            .collect();
+    }
+
+    /// Shows the path of a grenade thrown from given position with given velocity, `None` hides
+    /// the path.
+    pub fn update(
+        &mut self,
+        graph: &mut Graph,
+        resource_manager: &ResourceManager,
+        throw: Option<(Vector3<f32>, Vector3<f32>)>,
+    ) {
+        let (mut position, mut velocity) = match throw {
+            Some(throw) => throw,
+            None => {
+                for &point in self.points.iter() {
+                    if let Some(point) = graph.try_get_mut(point) {
+                        point.set_visibility(false);
+                    }
+                }
+                return;
+            }
+        };
+
+        if self.points.iter().any(|&p| !graph.is_valid_handle(p)) {
+            for &point in self.points.iter() {
+                if graph.is_valid_handle(point) {
+                    graph.remove_node(point);
+                }
+            }
+            self.create_points(graph, resource_manager);
+        }
+
+        let gravity = graph.physics.gravity;
+        let mut impact = false;
+        for &point in self.points.iter() {
+            if impact {
+                graph[point].set_visibility(false);
+                continue;
+            }
+
+            let next_velocity = velocity + gravity.scale(TIME_STEP);
+            let step = (velocity + next_velocity).scale(0.5 * TIME_STEP);
+
+            let mut intersections = ArrayVec::<_, 8>::new();
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::from(position),
+                    ray_direction: step,
+                    max_len: step.norm(),
+                    groups: InteractionGroups::new(
+                        BitMask(0xFFFF),
+                        BitMask(!(CollisionGroups::ActorCapsule as u32)),
+                    ),
+                    sort_results: true,
+                },
+                &mut intersections,
+            );
+
+            let color = if let Some(hit) = intersections.first() {
+                impact = true;
+                position = hit.position.coords;
+                IMPACT_COLOR
+            } else {
+                position += step;
+                velocity = next_velocity;
+                POINT_COLOR
+            };
+
+            let node = &mut graph[point];
+            node.set_visibility(true);
+            node.local_transform_mut().set_position(position);
+            node.as_sprite_mut().set_color(color);
+        }
+    }
+}
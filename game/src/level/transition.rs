@@ -0,0 +1,139 @@
+//! Level transitions allow the player to move between levels. A transition is defined in the
+//! editor by assigning `LevelTransition` script to a node placed at a passage to another level,
+//! the player must press the action button inside the volume of the transition to use it.
+
+use crate::{current_level_mut, GameConstructor};
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        inspect::prelude::*,
+        pool::Handle,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        graph::Graph,
+        node::{Node, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct LevelTransition {
+    #[inspect(description = "Path to a level (.rgs) that will be loaded.")]
+    destination: InheritableVariable<String>,
+
+    #[inspect(
+        description = "Name of a node in the destination level at which the player will appear, \
+        empty means the default spawn point of the level."
+    )]
+    spawn_point: InheritableVariable<String>,
+
+    #[inspect(
+        description = "Radius of the volume in which the transition can be used.",
+        min_value = "0.0"
+    )]
+    radius: InheritableVariable<f32>,
+}
+
+impl Default for LevelTransition {
+    fn default() -> Self {
+        Self {
+            destination: Default::default(),
+            spawn_point: Default::default(),
+            radius: InheritableVariable::new(1.5),
+        }
+    }
+}
+
+impl_component_provider!(LevelTransition);
+
+impl TypeUuidProvider for LevelTransition {
+    fn type_uuid() -> Uuid {
+        uuid!("8c41f0d2-6a7b-4e59-b3d8-2f1e9a05c6d4")
+    }
+}
+
+impl ScriptTrait for LevelTransition {
+    fn on_init(&mut self, context: ScriptContext) {
+        current_level_mut(context.plugin)
+            .transitions
+            .add(context.handle);
+    }
+
+    fn on_deinit(&mut self, context: ScriptDeinitContext) {
+        current_level_mut(context.plugin)
+            .transitions
+            .remove(context.node_handle);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+impl LevelTransition {
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// Returns `None` if the player should appear at the default spawn point.
+    pub fn spawn_point(&self) -> Option<&str> {
+        if self.spawn_point.is_empty() {
+            None
+        } else {
+            Some(&self.spawn_point)
+        }
+    }
+}
+
+pub fn level_transition_ref(handle: Handle<Node>, graph: &Graph) -> &LevelTransition {
+    graph[handle]
+        .script()
+        .and_then(|s| s.cast::<LevelTransition>())
+        .unwrap()
+}
+
+#[derive(Default, Visit)]
+pub struct LevelTransitionContainer {
+    transitions: Vec<Handle<Node>>,
+}
+
+impl LevelTransitionContainer {
+    pub fn add(&mut self, transition: Handle<Node>) {
+        if !self.transitions.contains(&transition) {
+            self.transitions.push(transition);
+        }
+    }
+
+    pub fn remove(&mut self, transition: Handle<Node>) {
+        if let Some(position) = self.transitions.iter().position(|t| *t == transition) {
+            self.transitions.remove(position);
+        }
+    }
+
+    /// Returns the closest transition with a destination, that has given position inside its
+    /// volume.
+    pub fn find_usable(&self, position: Vector3<f32>, graph: &Graph) -> Option<Handle<Node>> {
+        self.transitions
+            .iter()
+            .filter(|&&handle| graph.is_valid_handle(handle))
+            .map(|&handle| {
+                let distance = graph[handle].global_position().metric_distance(&position);
+                (handle, distance)
+            })
+            .filter(|&(handle, distance)| {
+                let transition = level_transition_ref(handle, graph);
+                !transition.destination.is_empty() && distance <= *transition.radius
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(handle, _)| handle)
+    }
+}
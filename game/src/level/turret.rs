@@ -98,7 +98,7 @@ impl Barrel {
             begin: shoot_point.global_position(),
             end: target_position,
             damage: Damage::Point(10.0),
-            shot_effect: ShotEffect::Smoke,
+            shot_effect: Some(ShotEffect::Smoke),
         });
 
         let sounds = [
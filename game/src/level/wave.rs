@@ -0,0 +1,289 @@
+//! Wave spawners are used to make arenas. A wave spawner is defined in the editor by assigning
+//! `WaveSpawner` script to a node, child nodes of the spawner are used as spawn points. Once the
+//! player comes close, the spawner spawns successive waves of enemies with a pause between them,
+//! every next wave is bigger and tougher. A wave is cleared when all of its enemies are dead.
+
+use crate::{
+    actor::{Actor, ActorContainer},
+    bot::BotKind,
+    current_level_mut,
+    message::Message,
+    GameConstructor, MessageSender,
+};
+use fyrox::{
+    core::{
+        inspect::prelude::*,
+        pool::Handle,
+        reflect::Reflect,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        graph::Graph,
+        node::{Node, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+    utils::log::{Log, MessageKind},
+};
+
+#[derive(Visit, Reflect, Inspect, Debug, Clone)]
+pub struct WaveSpawner {
+    #[inspect(
+        description = "Comma-separated list of enemies (Mutant, Parasite, Zombie) that waves \
+        consist of, enemies are spawned in the order of the list."
+    )]
+    enemies: InheritableVariable<String>,
+
+    #[inspect(description = "Total amount of waves.", min_value = "1.0")]
+    wave_count: InheritableVariable<u32>,
+
+    #[inspect(
+        description = "Amount of enemies in the first wave.",
+        min_value = "1.0"
+    )]
+    first_wave_size: InheritableVariable<u32>,
+
+    #[inspect(description = "Amount of enemies that is added to every next wave.")]
+    wave_size_growth: InheritableVariable<u32>,
+
+    #[inspect(
+        description = "Fraction of health that is added to enemies of every next wave.",
+        min_value = "0.0"
+    )]
+    health_growth: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Pause (in seconds) between the end of a wave and the start of the next one.",
+        min_value = "0.0"
+    )]
+    pause: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "The first wave starts when the player comes closer than this distance.",
+        min_value = "0.0"
+    )]
+    activation_radius: InheritableVariable<f32>,
+
+    #[inspect(
+        description = "Id of an objective that will be completed when all waves are cleared."
+    )]
+    objective: InheritableVariable<String>,
+}
+
+impl Default for WaveSpawner {
+    fn default() -> Self {
+        Self {
+            enemies: InheritableVariable::new("Mutant".to_owned()),
+            wave_count: InheritableVariable::new(3),
+            first_wave_size: InheritableVariable::new(3),
+            wave_size_growth: InheritableVariable::new(2),
+            health_growth: InheritableVariable::new(0.25),
+            pause: InheritableVariable::new(5.0),
+            activation_radius: InheritableVariable::new(10.0),
+            objective: Default::default(),
+        }
+    }
+}
+
+impl_component_provider!(WaveSpawner);
+
+impl TypeUuidProvider for WaveSpawner {
+    fn type_uuid() -> Uuid {
+        uuid!("e27a94c3-51d8-4b0f-a6e2-7c3d9b18f045")
+    }
+}
+
+impl ScriptTrait for WaveSpawner {
+    fn on_init(&mut self, context: ScriptContext) {
+        if self.enemies().is_empty() {
+            Log::writeln(
+                MessageKind::Warning,
+                format!(
+                    "Wave spawner {} has no valid enemies, Mutant will be used.",
+                    context.scene.graph[context.handle].name()
+                ),
+            );
+        }
+
+        current_level_mut(context.plugin)
+            .wave_spawners
+            .add(context.handle);
+    }
+
+    fn on_deinit(&mut self, context: ScriptDeinitContext) {
+        current_level_mut(context.plugin)
+            .wave_spawners
+            .remove(context.node_handle);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn plugin_uuid(&self) -> Uuid {
+        GameConstructor::type_uuid()
+    }
+}
+
+impl WaveSpawner {
+    /// Returns kinds of enemies that waves consist of, unknown names are ignored.
+    pub fn enemies(&self) -> Vec<BotKind> {
+        self.enemies
+            .split(',')
+            .filter_map(|name| BotKind::from_description(name.trim()))
+            .collect()
+    }
+
+    /// Returns `None` if the spawner does not complete any objective.
+    pub fn objective(&self) -> Option<&str> {
+        if self.objective.is_empty() {
+            None
+        } else {
+            Some(&self.objective)
+        }
+    }
+}
+
+pub fn wave_spawner_ref(handle: Handle<Node>, graph: &Graph) -> &WaveSpawner {
+    graph[handle]
+        .script()
+        .and_then(|s| s.cast::<WaveSpawner>())
+        .unwrap()
+}
+
+/// Runtime state of a wave spawner.
+#[derive(Default, Visit)]
+struct Arena {
+    spawner: Handle<Node>,
+    active: bool,
+    finished: bool,
+    /// Amount of waves that were spawned already.
+    wave: u32,
+    pause_timer: f32,
+    /// Amount of enemies that were requested to spawn, but not spawned yet.
+    pending: u32,
+    enemies: Vec<Handle<Actor>>,
+}
+
+impl Arena {
+    fn update(
+        &mut self,
+        graph: &Graph,
+        actors: &ActorContainer,
+        player: Handle<Actor>,
+        sender: &MessageSender,
+        dt: f32,
+    ) {
+        if self.finished {
+            return;
+        }
+
+        let spawner = wave_spawner_ref(self.spawner, graph);
+        let position = graph[self.spawner].global_position();
+
+        if !self.active {
+            if actors.contains(player)
+                && actors
+                    .get(player)
+                    .position(graph)
+                    .metric_distance(&position)
+                    <= *spawner.activation_radius
+            {
+                self.active = true;
+            }
+            return;
+        }
+
+        self.enemies
+            .retain(|&enemy| actors.contains(enemy) && !actors.get(enemy).is_dead());
+        if self.pending > 0 || !self.enemies.is_empty() {
+            return;
+        }
+
+        if self.wave >= *spawner.wave_count {
+            self.finished = true;
+            if let Some(id) = spawner.objective() {
+                sender.send(Message::CompleteObjective { id: id.to_owned() });
+            }
+            return;
+        }
+
+        self.pause_timer -= dt;
+        if self.pause_timer > 0.0 {
+            return;
+        }
+
+        let mut spawn_points = graph[self.spawner].children().to_vec();
+        if spawn_points.is_empty() {
+            spawn_points.push(self.spawner);
+        }
+        let mut kinds = spawner.enemies();
+        if kinds.is_empty() {
+            kinds.push(BotKind::Mutant);
+        }
+
+        let size = *spawner.first_wave_size + self.wave * *spawner.wave_size_growth;
+        let health_factor = 1.0 + self.wave as f32 * *spawner.health_growth;
+        for i in 0..size as usize {
+            let spawn_point = spawn_points[i % spawn_points.len()];
+            sender.send(Message::SpawnWaveEnemy {
+                spawner: self.spawner,
+                kind: kinds[i % kinds.len()],
+                position: graph[spawn_point].global_position(),
+                rotation: graph.global_rotation(spawn_point),
+                health_factor,
+            });
+        }
+
+        self.pending = size;
+        self.wave += 1;
+        self.pause_timer = *spawner.pause;
+    }
+}
+
+#[derive(Default, Visit)]
+pub struct WaveSpawnerContainer {
+    arenas: Vec<Arena>,
+}
+
+impl WaveSpawnerContainer {
+    pub fn add(&mut self, spawner: Handle<Node>) {
+        if !self.arenas.iter().any(|a| a.spawner == spawner) {
+            self.arenas.push(Arena {
+                spawner,
+                ..Default::default()
+            });
+        }
+    }
+
+    pub fn remove(&mut self, spawner: Handle<Node>) {
+        if let Some(position) = self.arenas.iter().position(|a| a.spawner == spawner) {
+            self.arenas.remove(position);
+        }
+    }
+
+    /// Registers an enemy that was spawned by given spawner.
+    pub fn add_enemy(&mut self, spawner: Handle<Node>, enemy: Handle<Actor>) {
+        if let Some(arena) = self.arenas.iter_mut().find(|a| a.spawner == spawner) {
+            arena.pending = arena.pending.saturating_sub(1);
+            arena.enemies.push(enemy);
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        graph: &Graph,
+        actors: &ActorContainer,
+        player: Handle<Actor>,
+        sender: &MessageSender,
+        dt: f32,
+    ) {
+        for arena in self.arenas.iter_mut() {
+            if graph.is_valid_handle(arena.spawner) {
+                arena.update(graph, actors, player, sender, dt);
+            }
+        }
+    }
+}
@@ -8,6 +8,8 @@ pub mod control_scheme;
 pub mod door;
 pub mod effects;
 pub mod elevator;
+pub mod faction;
+pub mod gamepad;
 pub mod gui;
 pub mod inventory;
 pub mod item;
@@ -18,27 +20,44 @@ pub mod menu;
 pub mod message;
 pub mod options_menu;
 pub mod player;
+pub mod ragdoll;
 pub mod sound;
+pub mod stats;
+pub mod status;
 pub mod ui_container;
 pub mod utils;
 pub mod weapon;
 
+use crate::bot::{cover::CoverPoint, patrol::PatrolRoute};
 use crate::door::Door;
+use crate::level::{
+    barrel::ExplosiveBarrel, hazard::HazardZone, ladder::Ladder, objective::ObjectivePoint,
+    terminal::Terminal, transition::LevelTransition, wave::WaveSpawner,
+};
 use crate::{
     actor::Actor,
-    config::{Config, SoundConfig},
+    config::{
+        CameraConfig, Config, DoubleJumpConfig, DownedStateConfig, HealthRegenerationConfig,
+        LaserSightConfig, SoundConfig, WeaponJamConfig,
+    },
     control_scheme::ControlScheme,
     door::ui::DoorUiContainer,
     elevator::ui::CallButtonUiContainer,
+    gamepad::Gamepad,
     gui::{
         inventory::InventoryInterface, item_display::ItemDisplay, journal::JournalDisplay,
-        weapon_display::WeaponDisplay, DeathScreen, FinalScreen,
+        low_health::LowHealthFeedback, speed_lines::SpeedLines, terminal::TerminalScreen,
+        weapon_display::WeaponDisplay, DamageIndicatorHud, DeathScreen, FinalScreen, FlashOverlay,
+        GrenadeIndicatorHud, HazardWarning, HotbarHud, InteractionPrompt, KillFeed, ObjectiveHud,
+        ScopeOverlay, ScreenDisplays, StatusEffectHud,
     },
-    level::Level,
-    loading_screen::LoadingScreen,
+    item::ItemState,
+    level::{terminal::terminal_ref, Level},
+    loading_screen::{LoadingScreen, LoadingStage},
     menu::Menu,
     message::Message,
     player::PlayerPersistentData,
+    stats::SessionRecords,
     utils::use_hrtf,
 };
 use fyrox::{
@@ -67,7 +86,7 @@ use fyrox::{
     resource::texture::Texture,
     scene::{
         base::BaseBuilder,
-        node::TypeUuidProvider,
+        node::{Node, TypeUuidProvider},
         sound::{SoundBuilder, Status},
         Scene, SceneLoader,
     },
@@ -78,6 +97,7 @@ use fyrox::{
     window::CursorGrabMode,
 };
 use std::{
+    collections::HashMap,
     fs::File,
     io::Write,
     path::{Path, PathBuf},
@@ -89,6 +109,10 @@ use std::{
 };
 
 const FIXED_FPS: f32 = 60.0;
+/// Maximum amount of time (in seconds) that is simulated in one frame. A hitch longer than this
+/// slows the game down instead of running a burst of updates, the player and physics must not
+/// jump forward by a big step.
+const MAX_FRAME_TIME: f64 = 0.25;
 
 pub struct Game {
     menu: Menu,
@@ -97,6 +121,7 @@ pub struct Game {
     debug_string: String,
     running: bool,
     control_scheme: ControlScheme,
+    gamepad: Gamepad,
     time: GameTime,
     message_receiver: Receiver<Message>,
     message_sender: MessageSender,
@@ -104,16 +129,41 @@ pub struct Game {
     loading_screen: LoadingScreen,
     death_screen: DeathScreen,
     final_screen: FinalScreen,
+    terminal_screen: TerminalScreen,
+    objective_hud: ObjectiveHud,
+    interaction_prompt: InteractionPrompt,
+    hazard_warning: HazardWarning,
+    hotbar_hud: HotbarHud,
+    screen_displays: ScreenDisplays,
+    kill_feed: KillFeed,
+    damage_indicator_hud: DamageIndicatorHud,
+    status_effect_hud: StatusEffectHud,
+    grenade_indicator_hud: GrenadeIndicatorHud,
+    scope_overlay: ScopeOverlay,
+    flash_overlay: FlashOverlay,
+    low_health_feedback: LowHealthFeedback,
+    speed_lines: SpeedLines,
     weapon_display: WeaponDisplay,
     inventory_interface: InventoryInterface,
     item_display: ItemDisplay,
     journal_display: JournalDisplay,
+    /// Audio log that is played from the journal. It lives in the menu scene, because
+    /// it is not positional.
+    audio_log: Option<(PathBuf, Handle<Node>)>,
     door_ui_container: DoorUiContainer,
     call_button_ui_container: CallButtonUiContainer,
     // We're storing sound config separately because we can adjust sound
     // setting in the options but don't have a level loaded. This field
     // is data-model for options menu.
     sound_config: SoundConfig,
+    laser_sight_config: LaserSightConfig,
+    health_regeneration_config: HealthRegenerationConfig,
+    camera_config: CameraConfig,
+    downed_state_config: DownedStateConfig,
+    weapon_jam_config: WeaponJamConfig,
+    double_jump_config: DoubleJumpConfig,
+    /// The best results of finished sessions.
+    session_records: SessionRecords,
     update_duration: Duration,
     show_debug_info: bool,
     smaller_font: SharedFont,
@@ -145,11 +195,13 @@ pub struct GameTime {
 #[repr(u16)]
 pub enum CollisionGroups {
     ActorCapsule = 1 << 0,
+    Ragdoll = 1 << 1,
     All = std::u16::MAX,
 }
 
 pub struct LoadContext {
     level: Option<(Level, Scene)>,
+    stage: LoadingStage,
 }
 
 pub fn create_display_material(display_texture: Texture) -> Arc<Mutex<Material>> {
@@ -213,11 +265,23 @@ impl Game {
         let mut control_scheme = ControlScheme::default();
         let mut sound_config = SoundConfig::default();
         let mut show_debug_info = false;
+        let mut laser_sight_config = LaserSightConfig::default();
+        let mut health_regeneration_config = HealthRegenerationConfig::default();
+        let mut camera_config = CameraConfig::default();
+        let mut downed_state_config = DownedStateConfig::default();
+        let mut weapon_jam_config = WeaponJamConfig::default();
+        let mut double_jump_config = DoubleJumpConfig::default();
 
         match Config::load() {
             Ok(config) => {
                 show_debug_info = config.show_debug_info;
                 sound_config = config.sound;
+                laser_sight_config = config.laser_sight;
+                health_regeneration_config = config.health_regeneration;
+                camera_config = config.camera;
+                downed_state_config = config.downed_state;
+                weapon_jam_config = config.weapon_jam;
+                double_jump_config = config.double_jump;
 
                 match context
                     .renderer
@@ -266,7 +330,7 @@ impl Game {
         let weapon_display = WeaponDisplay::new(font.clone(), context.resource_manager.clone());
         let inventory_interface = InventoryInterface::new(message_sender.clone());
         let item_display = ItemDisplay::new(smaller_font.clone());
-        let journal_display = JournalDisplay::new();
+        let journal_display = JournalDisplay::new(message_sender.clone());
 
         let level = if override_scene.is_some() {
             let display_texture = weapon_display.render_target.clone();
@@ -275,7 +339,7 @@ impl Game {
             let journal_texture = journal_display.render_target.clone();
             let sound_config = sound_config.clone();
 
-            Some(Level::from_existing_scene(
+            let mut level = Level::from_existing_scene(
                 &mut context.scenes[override_scene],
                 override_scene,
                 context.resource_manager.clone(),
@@ -286,7 +350,14 @@ impl Game {
                 journal_texture,
                 sound_config,
                 None,
-            ))
+            );
+            level.set_laser_sight_config(laser_sight_config.clone());
+            level.set_health_regeneration_config(health_regeneration_config.clone());
+            level.set_camera_config(camera_config.clone());
+            level.set_downed_state_config(downed_state_config.clone());
+            level.set_weapon_jam_config(weapon_jam_config.clone());
+            level.set_double_jump_config(double_jump_config.clone());
+            Some(level)
         } else {
             None
         };
@@ -306,6 +377,12 @@ impl Game {
                 font.clone(),
                 show_debug_info,
                 &sound_config,
+                &laser_sight_config,
+                &health_regeneration_config,
+                &camera_config,
+                &downed_state_config,
+                &weapon_jam_config,
+                &double_jump_config,
             )),
             death_screen: DeathScreen::new(
                 &mut context.user_interface,
@@ -317,11 +394,36 @@ impl Game {
                 font.clone(),
                 message_sender.clone(),
             ),
+            terminal_screen: TerminalScreen::new(
+                &mut context.user_interface,
+                font.clone(),
+                message_sender.clone(),
+            ),
+            objective_hud: ObjectiveHud::new(&mut context.user_interface, font.clone()),
+            interaction_prompt: InteractionPrompt::new(&mut context.user_interface, font.clone()),
+            hazard_warning: HazardWarning::new(&mut context.user_interface, font.clone()),
+            hotbar_hud: HotbarHud::new(&mut context.user_interface, font.clone()),
+            screen_displays: ScreenDisplays::new(
+                &mut context.user_interface,
+                weapon_display.render_target.clone(),
+                inventory_interface.render_target.clone(),
+                journal_display.render_target.clone(),
+            ),
+            kill_feed: KillFeed::new(&mut context.user_interface, font.clone()),
+            damage_indicator_hud: DamageIndicatorHud::new(&mut context.user_interface),
+            status_effect_hud: StatusEffectHud::new(&mut context.user_interface, font),
+            grenade_indicator_hud: GrenadeIndicatorHud::new(&mut context.user_interface),
+            scope_overlay: ScopeOverlay::new(&mut context.user_interface),
+            flash_overlay: FlashOverlay::new(&mut context.user_interface),
+            low_health_feedback: LowHealthFeedback::new(&mut context.user_interface),
+            speed_lines: SpeedLines::new(&mut context.user_interface),
             control_scheme,
+            gamepad: Gamepad::new(),
             debug_text: Handle::NONE,
             weapon_display,
             item_display,
             journal_display,
+            audio_log: None,
             smaller_font,
             level,
             debug_string: String::new(),
@@ -331,6 +433,13 @@ impl Game {
             message_receiver: rx,
             message_sender,
             sound_config,
+            laser_sight_config,
+            health_regeneration_config,
+            camera_config,
+            downed_state_config,
+            weapon_jam_config,
+            double_jump_config,
+            session_records: SessionRecords::load().unwrap_or_default(),
             update_duration: Default::default(),
             door_ui_container: Default::default(),
             call_button_ui_container: Default::default(),
@@ -348,10 +457,18 @@ impl Game {
             &mut self.control_scheme,
             &mut self.show_debug_info,
             &self.sound_config,
+            &self.laser_sight_config,
+            &self.health_regeneration_config,
+            &self.camera_config,
+            &self.downed_state_config,
+            &self.weapon_jam_config,
+            &self.double_jump_config,
         );
 
         self.death_screen.handle_ui_message(message);
         self.final_screen.handle_ui_message(message);
+        self.terminal_screen
+            .handle_ui_message(&context.user_interface, message);
 
         let play_sound = if message.direction() == MessageDirection::FromWidget {
             if let Some(ButtonMessage::Click) = message.data() {
@@ -413,6 +530,7 @@ impl Game {
         if let Some(level) = self.level.as_mut() {
             let mut visitor = Visitor::new();
 
+            level.clear_item_highlight(&mut context.scenes[level.scene], &context.resource_manager);
             context.scenes[level.scene].save("Scene", &mut visitor)?;
             level.visit("Level", &mut visitor)?;
 
@@ -452,6 +570,14 @@ impl Game {
         let mut level = Level::default();
         level.visit("Level", &mut visitor)?;
         level.scene = context.scenes.add(scene);
+        level.set_laser_sight_config(self.laser_sight_config.clone());
+        level.set_health_regeneration_config(self.health_regeneration_config.clone());
+        level.set_camera_config(self.camera_config.clone());
+        level.set_downed_state_config(self.downed_state_config.clone());
+        level.set_weapon_jam_config(self.weapon_jam_config.clone());
+        level.set_double_jump_config(self.double_jump_config.clone());
+        // Sound manager is not serialized entirely, so volume must be restored manually.
+        level.set_sfx_volume(self.sound_config.sfx_volume);
         self.level = Some(level);
 
         Log::writeln(
@@ -465,6 +591,8 @@ impl Game {
             .set_visible(&context.user_interface, false);
         self.final_screen
             .set_visible(&context.user_interface, false);
+        self.terminal_screen
+            .set_visible(&context.user_interface, false);
         self.door_ui_container.clear();
         self.call_button_ui_container.clear();
 
@@ -486,10 +614,87 @@ impl Game {
         Ok(())
     }
 
+    fn is_audio_log_playing(&self, path: &Path, context: &PluginContext) -> bool {
+        self.audio_log.as_ref().map_or(false, |(current, sound)| {
+            current == path
+                && self.level.as_ref().map_or(false, |level| {
+                    context.scenes[level.scene].graph.is_valid_handle(*sound)
+                })
+        })
+    }
+
+    fn stop_audio_log(&mut self, context: &mut PluginContext) {
+        if let (Some((_, sound)), Some(level)) = (self.audio_log.take(), self.level.as_ref()) {
+            let graph = &mut context.scenes[level.scene].graph;
+            // Sound is removed automatically when it has finished playing.
+            if graph.is_valid_handle(sound) {
+                graph.remove_node(sound);
+            }
+        }
+    }
+
+    fn play_audio_log(&mut self, path: &Path, context: &mut PluginContext) {
+        self.stop_audio_log(context);
+
+        // Logs are listened during the game, the menu scene is disabled at this time.
+        let level_scene = match self.level.as_ref() {
+            Some(level) => level.scene,
+            None => return,
+        };
+
+        if let Ok(buffer) = fyrox::core::futures::executor::block_on(
+            context.resource_manager.request_sound_buffer(path),
+        ) {
+            let graph = &mut context.scenes[level_scene].graph;
+            // Recording is heard the same way regardless of player's position.
+            let sound = SoundBuilder::new(BaseBuilder::new())
+                .with_buffer(buffer.into())
+                .with_status(Status::Playing)
+                .with_play_once(true)
+                .with_spatial_blend_factor(0.0)
+                .build(graph);
+            self.audio_log = Some((path.to_path_buf(), sound));
+        } else {
+            Log::writeln(
+                MessageKind::Error,
+                format!("Unable to play audio log {:?}", path),
+            );
+        }
+    }
+
+    /// Submits statistics of the current session to the records and returns summary of both
+    /// of them. Must be called before the level is destroyed.
+    fn finish_session(&mut self) -> String {
+        let stats = match self.level.as_ref().and_then(|level| level.session_stats()) {
+            Some(stats) => stats.clone(),
+            None => return String::new(),
+        };
+
+        let new_record = self.session_records.submit(&stats);
+        if new_record {
+            if let Err(e) = self.session_records.save() {
+                Log::writeln(
+                    MessageKind::Error,
+                    format!("Failed to save records. Reason: {:?}", e),
+                );
+            }
+        }
+
+        format!(
+            "{}\n\n{}{}",
+            stats.summary(),
+            if new_record { "New record! " } else { "" },
+            self.session_records.summary()
+        )
+    }
+
     fn destroy_level(&mut self, context: &mut PluginContext) {
+        self.stop_audio_log(context);
+
         if let Some(ref mut level) = self.level.take() {
             self.door_ui_container.clear();
             self.call_button_ui_container.clear();
+            self.journal_display.set_open(false);
             level.destroy(context);
             Log::writeln(
                 MessageKind::Information,
@@ -502,14 +707,20 @@ impl Game {
         &mut self,
         map: S,
         persistent_data: Option<PlayerPersistentData>,
+        world_items: HashMap<String, Vec<ItemState>>,
+        spawn_point: Option<String>,
         context: &mut PluginContext,
     ) {
         self.destroy_level(context);
 
-        let ctx = Arc::new(Mutex::new(LoadContext { level: None }));
+        let ctx = Arc::new(Mutex::new(LoadContext {
+            level: None,
+            stage: LoadingStage::Map,
+        }));
 
         self.load_context = Some(ctx.clone());
 
+        self.loading_screen.reset(&context.user_interface);
         context
             .user_interface
             .send_message(WidgetMessage::visibility(
@@ -540,6 +751,9 @@ impl Game {
                     journal_texture,
                     sound_config,
                     persistent_data,
+                    world_items,
+                    spawn_point,
+                    |stage| ctx.lock().stage = stage,
                 ));
                 (arrival, scene)
             };
@@ -548,14 +762,43 @@ impl Game {
         });
     }
 
+    /// Returns state of the player on current level that should be kept on the next level.
+    fn player_persistent_data(&self, context: &PluginContext) -> Option<PlayerPersistentData> {
+        let level = self.level.as_ref()?;
+        if let Actor::Player(player) = level.actors().get(level.get_player()) {
+            Some(player.persistent_data(level.weapons(), &context.scenes[level.scene].graph))
+        } else {
+            None
+        }
+    }
+
+    /// Returns states of items of visited levels, they are restored when the player comes back.
+    fn world_items(&self, context: &PluginContext) -> HashMap<String, Vec<ItemState>> {
+        self.level
+            .as_ref()
+            .map(|level| level.world_items(&context.scenes[level.scene].graph))
+            .unwrap_or_default()
+    }
+
     pub fn set_menu_visible(&mut self, visible: bool, context: &mut PluginContext) {
         self.menu.set_visible(context, visible);
+
+        if visible {
+            // The game is paused while the menu is open, and the player won't receive releases
+            // of buttons that were held when it was opened. Photo mode is left, so the free
+            // camera won't end up in a saved game.
+            if let Some(level) = self.level.as_mut() {
+                level.reset_player_input();
+                level.set_photo_mode(false, &mut context.scenes[level.scene]);
+            }
+        }
     }
 
     pub fn is_any_menu_visible(&self, context: &mut PluginContext) -> bool {
         self.menu.is_visible(&context.user_interface)
             || self.death_screen.is_visible(&context.user_interface)
             || self.final_screen.is_visible(&context.user_interface)
+            || self.terminal_screen.is_visible(&context.user_interface)
     }
 
     pub fn update(&mut self, context: &mut PluginContext, time: GameTime) {
@@ -590,6 +833,12 @@ impl Game {
                     }
 
                     level.scene = context.scenes.add(scene);
+                    level.set_laser_sight_config(self.laser_sight_config.clone());
+                    level.set_health_regeneration_config(self.health_regeneration_config.clone());
+                    level.set_camera_config(self.camera_config.clone());
+                    level.set_downed_state_config(self.downed_state_config.clone());
+                    level.set_weapon_jam_config(self.weapon_jam_config.clone());
+                    level.set_double_jump_config(self.double_jump_config.clone());
 
                     self.level = Some(level);
                     self.load_context = None;
@@ -605,27 +854,165 @@ impl Game {
                 } else {
                     self.loading_screen.set_progress(
                         &context.user_interface,
+                        ctx.stage,
                         context.resource_manager.state().loading_progress() as f32 / 100.0,
                     );
                 }
             }
         }
 
+        let mut flash_intensity = 0.0;
+        let mut low_health_intensity = 0.0;
+        let mut sprint_intensity = 0.0;
+        let mut scope = None;
+        let mut objective = None;
+        let mut interaction = None;
+        let mut downed_time_left = None;
+        let mut jam = None;
+        let mut damage_indicators = Vec::new();
+        let mut status_effects = Vec::new();
+        let mut grenade_indicator = None;
+        let mut hazard = None;
+        let mut hotbar = Vec::new();
+        let mut screen_displays = [false; 3];
+        let mut photo_mode = false;
         if let Some(ref mut level) = self.level {
             let menu_visible = self.menu.is_visible(&context.user_interface);
             if !menu_visible {
+                level.process_gamepad_input(self.gamepad.poll(), &self.control_scheme);
                 level.update(context, time, &mut self.call_button_ui_container);
+                self.kill_feed.update(&context.user_interface, time.delta);
+                // HUD is hidden in photo mode.
+                photo_mode = level.is_photo_mode_active();
                 let player = level.get_player();
-                if player.is_some() {
+                if player.is_some() && !photo_mode {
                     if let Actor::Player(player) = level.actors().get(player) {
                         self.weapon_display.sync_to_model(player, level.weapons());
                         self.journal_display.update(time.delta, &player.journal);
+                        if !self.camera_config.diegetic_displays {
+                            screen_displays = [
+                                player.is_weapon_display_open(),
+                                player.is_inventory_open(),
+                                player.is_journal_open(),
+                            ];
+                        }
+                        flash_intensity = player.flash_intensity();
+                        low_health_intensity = player.low_health_intensity();
+                        if self.camera_config.sprint_effects && self.camera_config.speed_lines {
+                            sprint_intensity = player.sprint_intensity();
+                        }
+                        scope = player.active_scope(level.weapons());
+                        interaction = player.interaction();
+                        downed_time_left = player.downed_time_left();
+                        jam = level
+                            .weapons()
+                            .try_get(player.current_weapon())
+                            .filter(|weapon| weapon.is_jammed())
+                            .map(|weapon| weapon.is_clearing_jam());
+                        damage_indicators =
+                            player.damage_indicators(&context.scenes[level.scene].graph);
+                        status_effects = player.status_effects.active().collect();
+                        if !player.is_dead() {
+                            hotbar = self
+                                .control_scheme
+                                .hotbar_slots()
+                                .iter()
+                                .map(|slot| slot.button.name())
+                                .zip(player.hotbar().contents(player.inventory()))
+                                .collect();
+                        }
+                        grenade_indicator = player.grenade_indicator(
+                            level.projectiles(),
+                            &context.scenes[level.scene].graph,
+                        );
+
+                        let player_position = player.position(&context.scenes[level.scene].graph);
+                        if !player.is_dead() {
+                            hazard = level
+                                .hazards
+                                .find(player_position, &context.scenes[level.scene].graph)
+                                .map(|zone| zone.name().to_owned());
+                        }
+                        objective = player.journal.active_objective().map(|objective| {
+                            (
+                                objective.description.clone(),
+                                objective
+                                    .target
+                                    .map(|target| target.metric_distance(&player_position)),
+                            )
+                        });
                     }
                 }
             }
             context.scenes[level.scene].enabled = !menu_visible;
         }
 
+        self.objective_hud.set_objective(
+            &context.user_interface,
+            objective
+                .as_ref()
+                .map(|(description, distance)| (description.as_str(), *distance)),
+        );
+        self.interaction_prompt.set_text(
+            &context.user_interface,
+            match downed_time_left {
+                Some(time_left) => Some(format!(
+                    "You are down! Press [{}] to use a medpack ({}s)",
+                    self.control_scheme.action.button.name(),
+                    time_left.ceil() as u32
+                )),
+                None => match jam {
+                    Some(true) => Some("Clearing jam...".to_owned()),
+                    Some(false) => Some(format!(
+                        "Weapon jammed! Press [{}] to clear",
+                        self.control_scheme.clear_jam.button.name()
+                    )),
+                    None => interaction.map(|interaction| interaction.prompt(&self.control_scheme)),
+                },
+            },
+        );
+        if context
+            .user_interface
+            .node(self.kill_feed.root)
+            .visibility()
+            == photo_mode
+        {
+            context
+                .user_interface
+                .send_message(WidgetMessage::visibility(
+                    self.kill_feed.root,
+                    MessageDirection::ToWidget,
+                    !photo_mode,
+                ));
+        }
+        self.hazard_warning
+            .set_hazard(&context.user_interface, hazard.as_deref());
+        self.hotbar_hud.set_slots(&context.user_interface, &hotbar);
+        self.screen_displays
+            .set_visibility(&context.user_interface, screen_displays);
+        self.damage_indicator_hud
+            .set_indicators(&mut context.user_interface, &damage_indicators);
+        self.status_effect_hud
+            .set_effects(&mut context.user_interface, &status_effects);
+        self.grenade_indicator_hud
+            .update(&context.user_interface, grenade_indicator, time.delta);
+        self.scope_overlay
+            .set_scope(&context.user_interface, &context.resource_manager, scope);
+        self.flash_overlay
+            .set_intensity(&context.user_interface, flash_intensity);
+        self.low_health_feedback.set_intensity(
+            &context.user_interface,
+            &mut context.scenes[self.menu.scene.scene].graph,
+            &context.resource_manager,
+            low_health_intensity,
+        );
+        self.speed_lines
+            .update(&context.user_interface, sprint_intensity, time.delta);
+        // Flashbang muffles all sounds for a while.
+        context
+            .sound_engine
+            .set_sound_gain(self.sound_config.master_volume * (1.0 - 0.8 * flash_intensity));
+
         self.menu.scene.update(context, time.delta);
         self.weapon_display.update(time.delta);
         self.inventory_interface.update(time.delta);
@@ -648,10 +1035,10 @@ impl Game {
         while let Ok(message) = self.message_receiver.try_recv() {
             match &message {
                 Message::StartNewGame => {
-                    self.load_level(Level::ARRIVAL_PATH, None, context);
+                    self.load_level(Level::ARRIVAL_PATH, None, Default::default(), None, context);
                 }
                 Message::LoadTestbed => {
-                    self.load_level(Level::TESTBED_PATH, None, context);
+                    self.load_level(Level::TESTBED_PATH, None, Default::default(), None, context);
                 }
                 Message::SaveGame => match self.save_game(context) {
                     Ok(_) => {
@@ -678,35 +1065,58 @@ impl Game {
                         };
 
                         if let Some(kind) = kind {
-                            let persistent_data = if let Actor::Player(player) =
-                                level.actors().get(level.get_player())
-                            {
-                                player.persistent_data(&level.weapons())
-                            } else {
-                                unreachable!()
-                            };
-
-                            self.load_level(kind, Some(persistent_data), context)
+                            let persistent_data = self.player_persistent_data(context);
+                            let world_items = self.world_items(context);
+                            self.load_level(kind, persistent_data, world_items, None, context)
                         }
                     }
                 }
+                Message::LoadLevel { path, spawn_point } => {
+                    if self.load_context.is_some() {
+                        // Another level is being loaded already.
+                    } else if !Path::new(path).exists() {
+                        Log::writeln(
+                            MessageKind::Error,
+                            format!("Unable to load level {}, file does not exist!", path),
+                        );
+                    } else {
+                        let persistent_data = self.player_persistent_data(context);
+                        let world_items = self.world_items(context);
+                        self.load_level(
+                            path,
+                            persistent_data,
+                            world_items,
+                            spawn_point.clone(),
+                            context,
+                        )
+                    }
+                }
+                Message::ResumeGame => {
+                    self.set_menu_visible(false, context);
+                }
                 Message::QuitGame => {
                     self.destroy_level(context);
                     self.running = false;
                 }
                 Message::EndMatch => {
+                    let stats = self.finish_session();
+                    self.death_screen.set_stats(&context.user_interface, stats);
                     self.destroy_level(context);
                     self.death_screen.set_visible(&context.user_interface, true);
                     self.menu.sync_to_model(context, false);
                 }
                 Message::EndGame => {
+                    let stats = self.finish_session();
+                    self.final_screen.set_stats(&context.user_interface, stats);
                     self.destroy_level(context);
                     self.final_screen.set_visible(&context.user_interface, true);
                     self.menu.sync_to_model(context, false);
                 }
                 Message::SetMusicVolume(volume) => {
                     self.sound_config.music_volume = *volume;
-                    // TODO: Apply to sound manager of level when it will handle music!
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_music_volume(&mut context.scenes[level.scene], *volume);
+                    }
                     context.scenes[self.menu.scene.scene].graph[self.menu.scene.music]
                         .as_sound_mut()
                         .set_gain(*volume);
@@ -726,16 +1136,93 @@ impl Game {
                         }
                     }
                 }
+                Message::SetLaserSightColor(color) => {
+                    self.laser_sight_config.color = *color;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_laser_sight_config(self.laser_sight_config.clone());
+                    }
+                }
+                Message::SetLaserDotVisible(visible) => {
+                    self.laser_sight_config.show_dot = *visible;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_laser_sight_config(self.laser_sight_config.clone());
+                    }
+                }
+                Message::ReportKill { victim, weapon } => {
+                    let text = match weapon {
+                        Some(weapon) => {
+                            format!("Killed {} with {}", victim.description(), weapon.name())
+                        }
+                        None => format!("Killed {}", victim.description()),
+                    };
+                    self.kill_feed.add_entry(&mut context.user_interface, text);
+                }
+                Message::SetHealthRegenerationEnabled(enabled) => {
+                    self.health_regeneration_config.enabled = *enabled;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_health_regeneration_config(
+                            self.health_regeneration_config.clone(),
+                        );
+                    }
+                }
+                Message::SetWeaponJamEnabled(enabled) => {
+                    self.weapon_jam_config.enabled = *enabled;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_weapon_jam_config(self.weapon_jam_config.clone());
+                    }
+                }
+                Message::SetDoubleJumpEnabled(enabled) => {
+                    self.double_jump_config.enabled = *enabled;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_double_jump_config(self.double_jump_config.clone());
+                    }
+                }
+                Message::SetDownedStateEnabled(enabled) => {
+                    self.downed_state_config.enabled = *enabled;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_downed_state_config(self.downed_state_config.clone());
+                    }
+                }
                 Message::SetMasterVolume(volume) => {
                     self.sound_config.master_volume = *volume;
                     context.sound_engine.set_sound_gain(*volume);
                 }
+                Message::SetSfxVolume(volume) => {
+                    self.sound_config.sfx_volume = *volume;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_sfx_volume(*volume);
+                    }
+                }
+                Message::SetFieldOfView(field_of_view) => {
+                    self.camera_config.field_of_view = *field_of_view;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_camera_config(self.camera_config.clone());
+                    }
+                }
+                Message::SetSprintEffectsEnabled(enabled) => {
+                    self.camera_config.sprint_effects = *enabled;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_camera_config(self.camera_config.clone());
+                    }
+                }
+                Message::SetDiegeticDisplaysEnabled(enabled) => {
+                    self.camera_config.diegetic_displays = *enabled;
+                    if let Some(level) = self.level.as_mut() {
+                        level.set_camera_config(self.camera_config.clone());
+                    }
+                }
                 Message::SaveConfig => {
                     match Config::save(
                         context,
                         self.control_scheme.clone(),
                         self.sound_config.clone(),
                         self.show_debug_info,
+                        self.laser_sight_config.clone(),
+                        self.health_regeneration_config.clone(),
+                        self.camera_config.clone(),
+                        self.downed_state_config.clone(),
+                        self.weapon_jam_config.clone(),
+                        self.double_jump_config.clone(),
                     ) {
                         Ok(_) => {
                             Log::writeln(MessageKind::Information, "Settings saved!".to_string());
@@ -756,8 +1243,12 @@ impl Game {
                 Message::SyncInventory => {
                     if let Some(ref mut level) = self.level {
                         if let Actor::Player(player) = level.actors().get(level.get_player()) {
-                            self.inventory_interface
-                                .sync_to_model(context.resource_manager.clone(), player);
+                            self.inventory_interface.sync_to_model(
+                                context.resource_manager.clone(),
+                                player,
+                                level.weapons(),
+                                &self.control_scheme,
+                            );
                         }
                     }
                 }
@@ -768,10 +1259,45 @@ impl Game {
                         }
                     }
                 }
+                &Message::SetJournalVisible { visible } => {
+                    self.journal_display.set_open(visible);
+                    if visible {
+                        if let Some(ref mut level) = self.level {
+                            if let Actor::Player(player) = level.actors().get(level.get_player()) {
+                                self.journal_display.sync_to_model(&player.journal);
+                            }
+                        }
+                    }
+                }
+                &Message::ShowTerminal { terminal } => {
+                    if let Some(ref mut level) = self.level {
+                        let graph = &context.scenes[level.scene].graph;
+                        if graph.is_valid_handle(terminal) {
+                            self.terminal_screen
+                                .open(&context.user_interface, terminal_ref(terminal, graph));
+                            // The player won't receive releases of buttons that were held when
+                            // the terminal was opened.
+                            level.reset_player_input();
+                        }
+                    }
+                }
                 &Message::ShowItemDisplay { item, count } => {
                     self.item_display
                         .sync_to_model(context.resource_manager.clone(), item, count);
                 }
+                Message::PlayAudioLog { path } => {
+                    self.play_audio_log(path, context);
+                }
+                Message::ToggleAudioLog { path } => {
+                    if self.is_audio_log_playing(path, context) {
+                        self.stop_audio_log(context);
+                    } else {
+                        self.play_audio_log(path, context);
+                    }
+                }
+                Message::StopAudioLog => {
+                    self.stop_audio_log(context);
+                }
                 Message::Play2DSound { path, gain } => {
                     if let Ok(buffer) = fyrox::core::futures::executor::block_on(
                         context.resource_manager.request_sound_buffer(path),
@@ -823,12 +1349,17 @@ impl Game {
             ));
         }
 
+        // HUD is hidden in photo mode, debug info is a part of it.
+        let photo_mode = self
+            .level
+            .as_ref()
+            .map_or(false, |level| level.is_photo_mode_active());
         context
             .user_interface
             .send_message(WidgetMessage::visibility(
                 self.debug_text,
                 MessageDirection::ToWidget,
-                self.show_debug_info,
+                self.show_debug_info && !photo_mode,
             ));
     }
 
@@ -856,7 +1387,11 @@ impl Game {
             }
         }
 
-        if !self.is_any_menu_visible(context) {
+        if !self.is_any_menu_visible(context)
+            && !self
+                .journal_display
+                .captures_input(event, &self.control_scheme)
+        {
             if let Some(ref mut level) = self.level {
                 let scene = &mut context.scenes[level.scene];
                 level.process_input_event(
@@ -881,7 +1416,12 @@ impl Game {
             if let ElementState::Pressed = input.state {
                 if let Some(key) = input.virtual_keycode {
                     if key == VirtualKeyCode::Escape && self.level.is_some() {
-                        self.set_menu_visible(!self.is_any_menu_visible(context), context);
+                        if self.terminal_screen.is_visible(&context.user_interface) {
+                            self.terminal_screen
+                                .set_visible(&context.user_interface, false);
+                        } else {
+                            self.set_menu_visible(!self.is_any_menu_visible(context), context);
+                        }
                     }
                 }
             }
@@ -906,6 +1446,42 @@ impl PluginConstructor for GameConstructor {
             .serialization_context
             .script_constructors
             .add::<Door>("Door");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<PatrolRoute>("Patrol Route");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<ObjectivePoint>("Objective Point");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<Ladder>("Ladder");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<Terminal>("Terminal");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<CoverPoint>("Cover Point");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<LevelTransition>("Level Transition");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<WaveSpawner>("Wave Spawner");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<ExplosiveBarrel>("Explosive Barrel");
+        context
+            .serialization_context
+            .script_constructors
+            .add::<HazardZone>("Hazard Zone");
     }
 
     fn create_instance(
@@ -925,6 +1501,10 @@ impl Plugin for Game {
     fn update(&mut self, context: &mut PluginContext, control_flow: &mut ControlFlow) {
         let fixed_timestep = 1.0 / FIXED_FPS;
         let mut dt = self.time.clock.elapsed().as_secs_f64() - self.time.elapsed;
+        if dt > MAX_FRAME_TIME {
+            self.time.elapsed += dt - MAX_FRAME_TIME;
+            dt = MAX_FRAME_TIME;
+        }
         while dt >= fixed_timestep as f64 {
             dt -= fixed_timestep as f64;
             self.time.elapsed += fixed_timestep as f64;
@@ -976,6 +1556,44 @@ impl Plugin for Game {
                         MessageDirection::ToWidget,
                         new_size.height as f32,
                     ));
+
+                    self.scope_overlay.resize(
+                        &context.user_interface,
+                        new_size.width as f32,
+                        new_size.height as f32,
+                    );
+
+                    context.user_interface.send_message(WidgetMessage::width(
+                        self.flash_overlay.root,
+                        MessageDirection::ToWidget,
+                        new_size.width as f32,
+                    ));
+                    context.user_interface.send_message(WidgetMessage::height(
+                        self.flash_overlay.root,
+                        MessageDirection::ToWidget,
+                        new_size.height as f32,
+                    ));
+                    self.low_health_feedback.resize(
+                        &context.user_interface,
+                        new_size.width as f32,
+                        new_size.height as f32,
+                    );
+                    self.speed_lines.resize(
+                        &context.user_interface,
+                        new_size.width as f32,
+                        new_size.height as f32,
+                    );
+                }
+                WindowEvent::Focused(focused) => {
+                    if *focused {
+                        // Do not try to catch up with the time that has passed while the window
+                        // was in the background (it could be minimized and not updated at all).
+                        self.time.elapsed = self.time.clock.elapsed().as_secs_f64();
+                    } else if self.level.is_some() && !self.is_any_menu_visible(&mut context) {
+                        // Pause the game when the player switches to another window, the menu
+                        // also releases all held buttons of the player.
+                        self.set_menu_visible(true, &mut context);
+                    }
                 }
                 _ => (),
             },
@@ -4,20 +4,52 @@ use fyrox::{
         grid::{Column, GridBuilder, Row},
         message::MessageDirection,
         progress_bar::{ProgressBarBuilder, ProgressBarMessage},
-        text::TextBuilder,
+        text::{TextBuilder, TextMessage},
         widget::WidgetBuilder,
         BuildContext, HorizontalAlignment, UiNode, UserInterface, VerticalAlignment,
     },
 };
 
+/// Stages of level loading, in the order of execution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LoadingStage {
+    Map,
+    Actors,
+    Player,
+}
+
+impl LoadingStage {
+    const COUNT: usize = 3;
+
+    fn index(self) -> usize {
+        match self {
+            LoadingStage::Map => 0,
+            LoadingStage::Actors => 1,
+            LoadingStage::Player => 2,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            LoadingStage::Map => "Loading the map...",
+            LoadingStage::Actors => "Spawning enemies...",
+            LoadingStage::Player => "Preparing equipment...",
+        }
+    }
+}
+
 pub struct LoadingScreen {
     pub root: Handle<UiNode>,
     progress_bar: Handle<UiNode>,
+    stage_text: Handle<UiNode>,
+    /// Progress never goes back, even if new resources were requested on the current stage.
+    progress: f32,
 }
 
 impl LoadingScreen {
     pub fn new(ctx: &mut BuildContext, width: f32, height: f32) -> Self {
         let progress_bar;
+        let stage_text;
         let root = GridBuilder::new(
             WidgetBuilder::new()
                 .with_width(width)
@@ -34,13 +66,14 @@ impl LoadingScreen {
                                         .build(ctx);
                                 progress_bar
                             })
-                            .with_child(
-                                TextBuilder::new(WidgetBuilder::new().on_row(0))
+                            .with_child({
+                                stage_text = TextBuilder::new(WidgetBuilder::new().on_row(0))
                                     .with_horizontal_text_alignment(HorizontalAlignment::Center)
                                     .with_vertical_text_alignment(VerticalAlignment::Center)
                                     .with_text("Loading... Please wait.")
-                                    .build(ctx),
-                            ),
+                                    .build(ctx);
+                                stage_text
+                            }),
                     )
                     .add_row(Row::stretch())
                     .add_row(Row::strict(32.0))
@@ -55,14 +88,50 @@ impl LoadingScreen {
         .add_row(Row::strict(100.0))
         .add_row(Row::stretch())
         .build(ctx);
-        Self { root, progress_bar }
+        Self {
+            root,
+            progress_bar,
+            stage_text,
+            progress: 0.0,
+        }
+    }
+
+    /// Prepares the screen to show progress of new loading.
+    pub fn reset(&mut self, ui: &UserInterface) {
+        self.progress = 0.0;
+        ui.send_message(ProgressBarMessage::progress(
+            self.progress_bar,
+            MessageDirection::ToWidget,
+            0.0,
+        ));
+        ui.send_message(TextMessage::text(
+            self.stage_text,
+            MessageDirection::ToWidget,
+            "Loading... Please wait.".to_owned(),
+        ));
     }
 
-    pub fn set_progress(&self, ui: &UserInterface, progress: f32) {
+    /// Shows progress of given stage, `resource_progress` is a fraction of completed resource
+    /// requests. Every stage takes equal part of the progress bar.
+    pub fn set_progress(
+        &mut self,
+        ui: &UserInterface,
+        stage: LoadingStage,
+        resource_progress: f32,
+    ) {
+        let progress =
+            (stage.index() as f32 + resource_progress.clamp(0.0, 1.0)) / LoadingStage::COUNT as f32;
+        self.progress = self.progress.max(progress);
+
         ui.send_message(ProgressBarMessage::progress(
             self.progress_bar,
             MessageDirection::ToWidget,
-            progress,
+            self.progress,
+        ));
+        ui.send_message(TextMessage::text(
+            self.stage_text,
+            MessageDirection::ToWidget,
+            stage.description().to_owned(),
         ));
     }
 }
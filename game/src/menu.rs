@@ -1,6 +1,13 @@
 use crate::{
-    config::SoundConfig, control_scheme::ControlScheme, message::Message,
-    options_menu::OptionsMenu, utils::create_camera, MessageSender,
+    config::{
+        CameraConfig, DoubleJumpConfig, DownedStateConfig, HealthRegenerationConfig,
+        LaserSightConfig, SoundConfig, WeaponJamConfig,
+    },
+    control_scheme::ControlScheme,
+    message::Message,
+    options_menu::OptionsMenu,
+    utils::create_camera,
+    MessageSender,
 };
 use fyrox::{
     core::{
@@ -32,6 +39,7 @@ pub struct Menu {
     sender: MessageSender,
     root: Handle<UiNode>,
     btn_load_test_bed: Handle<UiNode>,
+    btn_resume: Handle<UiNode>,
     btn_new_game: Handle<UiNode>,
     btn_save_game: Handle<UiNode>,
     btn_settings: Handle<UiNode>,
@@ -116,6 +124,12 @@ impl Menu {
         font: SharedFont,
         show_debug_info: bool,
         sound_config: &SoundConfig,
+        laser_sight_config: &LaserSightConfig,
+        health_regeneration_config: &HealthRegenerationConfig,
+        camera_config: &CameraConfig,
+        downed_state_config: &DownedStateConfig,
+        weapon_jam_config: &WeaponJamConfig,
+        double_jump_config: &DoubleJumpConfig,
     ) -> Self {
         let frame_size = context.renderer.get_frame_size();
 
@@ -124,6 +138,7 @@ impl Menu {
         let ctx = &mut context.user_interface.build_ctx();
 
         let btn_load_test_bed;
+        let btn_resume;
         let btn_new_game;
         let btn_settings;
         let btn_save_game;
@@ -159,10 +174,23 @@ impl Menu {
                                 WidgetBuilder::new()
                                     .with_margin(Thickness::uniform(20.0))
                                     .with_child({
-                                        btn_new_game = ButtonBuilder::new(
+                                        btn_resume = ButtonBuilder::new(
                                             WidgetBuilder::new()
                                                 .on_column(0)
                                                 .on_row(0)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(4.0)),
+                                        )
+                                        .with_text("Resume")
+                                        .with_font(font.clone())
+                                        .build(ctx);
+                                        btn_resume
+                                    })
+                                    .with_child({
+                                        btn_new_game = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_column(0)
+                                                .on_row(1)
                                                 .with_margin(Thickness::uniform(4.0)),
                                         )
                                         .with_text("New Game")
@@ -174,7 +202,7 @@ impl Menu {
                                         btn_save_game = ButtonBuilder::new(
                                             WidgetBuilder::new()
                                                 .on_column(0)
-                                                .on_row(1)
+                                                .on_row(2)
                                                 .with_enabled(false)
                                                 .with_margin(Thickness::uniform(4.0)),
                                         )
@@ -187,7 +215,7 @@ impl Menu {
                                         btn_load_game = ButtonBuilder::new(
                                             WidgetBuilder::new()
                                                 .on_column(0)
-                                                .on_row(2)
+                                                .on_row(3)
                                                 .with_margin(Thickness::uniform(4.0)),
                                         )
                                         .with_text("Load Game")
@@ -199,7 +227,7 @@ impl Menu {
                                         btn_settings = ButtonBuilder::new(
                                             WidgetBuilder::new()
                                                 .on_column(0)
-                                                .on_row(3)
+                                                .on_row(4)
                                                 .with_margin(Thickness::uniform(4.0)),
                                         )
                                         .with_text("Settings")
@@ -211,7 +239,7 @@ impl Menu {
                                         btn_quit_game = ButtonBuilder::new(
                                             WidgetBuilder::new()
                                                 .on_column(0)
-                                                .on_row(4)
+                                                .on_row(5)
                                                 .with_margin(Thickness::uniform(4.0)),
                                         )
                                         .with_text("Quit")
@@ -226,13 +254,14 @@ impl Menu {
                             .add_row(Row::strict(75.0))
                             .add_row(Row::strict(75.0))
                             .add_row(Row::strict(75.0))
+                            .add_row(Row::strict(75.0))
                             .build(ctx),
                         )
                         .build(ctx),
                 ),
         )
         .add_row(Row::stretch())
-        .add_row(Row::strict(500.0))
+        .add_row(Row::strict(575.0))
         .add_column(Column::strict(400.0))
         .add_column(Column::stretch())
         .build(ctx);
@@ -241,6 +270,7 @@ impl Menu {
             scene,
             sender: sender.clone(),
             root,
+            btn_resume,
             btn_new_game,
             btn_settings,
             btn_save_game,
@@ -253,6 +283,12 @@ impl Menu {
                 sender,
                 show_debug_info,
                 sound_config,
+                laser_sight_config,
+                health_regeneration_config,
+                camera_config,
+                downed_state_config,
+                weapon_jam_config,
+                double_jump_config,
             ),
         }
     }
@@ -307,11 +343,13 @@ impl Menu {
     }
 
     pub fn sync_to_model(&mut self, engine: &mut PluginContext, level_loaded: bool) {
-        engine.user_interface.send_message(WidgetMessage::enabled(
-            self.btn_save_game,
-            MessageDirection::ToWidget,
-            level_loaded,
-        ));
+        for button in [self.btn_resume, self.btn_save_game] {
+            engine.user_interface.send_message(WidgetMessage::enabled(
+                button,
+                MessageDirection::ToWidget,
+                level_loaded,
+            ));
+        }
     }
 
     pub fn handle_ui_message(
@@ -321,9 +359,17 @@ impl Menu {
         control_scheme: &mut ControlScheme,
         show_debug_info: &mut bool,
         sound_config: &SoundConfig,
+        laser_sight_config: &LaserSightConfig,
+        health_regeneration_config: &HealthRegenerationConfig,
+        camera_config: &CameraConfig,
+        downed_state_config: &DownedStateConfig,
+        weapon_jam_config: &WeaponJamConfig,
+        double_jump_config: &DoubleJumpConfig,
     ) {
         if let Some(ButtonMessage::Click) = message.data() {
-            if message.destination() == self.btn_new_game {
+            if message.destination() == self.btn_resume {
+                self.sender.send(Message::ResumeGame);
+            } else if message.destination() == self.btn_new_game {
                 self.sender.send(Message::StartNewGame);
             } else if message.destination() == self.btn_save_game {
                 self.sender.send(Message::SaveGame);
@@ -360,6 +406,12 @@ impl Menu {
             control_scheme,
             show_debug_info,
             sound_config,
+            laser_sight_config,
+            health_regeneration_config,
+            camera_config,
+            downed_state_config,
+            weapon_jam_config,
+            double_jump_config,
         );
     }
 }
@@ -7,13 +7,15 @@ use crate::{
     actor::Actor,
     bot::BotKind,
     character::HitBox,
+    config::LaserSightColor,
     effects::EffectKind,
     elevator::{call_button::CallButton, Elevator},
     item::{Item, ItemKind},
-    sound::SoundKind,
+    sound::{NoiseKind, SoundKind},
+    status::StatusEffectDefinition,
     weapon::{
         definition::{ShotEffect, WeaponKind},
-        projectile::{Damage, ProjectileKind, Shooter},
+        projectile::{Damage, Projectile, ProjectileKind, Shooter},
         sight::SightReaction,
         Weapon,
     },
@@ -40,6 +42,33 @@ pub enum Message {
         door: Handle<Node>,
         actor: Handle<Actor>,
     },
+    /// Unlocks a locked door, does nothing if the door is not locked.
+    UnlockDoor {
+        door: Handle<Node>,
+    },
+    /// An actor tries to use a terminal. Access is denied if the terminal requires a master
+    /// key and the actor does not have it.
+    UseTerminal {
+        terminal: Handle<Node>,
+        actor: Handle<Actor>,
+    },
+    /// Opens terminal screen for the player.
+    ShowTerminal {
+        terminal: Handle<Node>,
+    },
+    /// Damages a breakable door which the collider belongs to. Does nothing if the collider is
+    /// not a part of a door.
+    DamageDoor {
+        collider: Handle<Node>,
+        amount: f32,
+    },
+    /// Damages an explosive barrel which the collider belongs to. Does nothing if the collider is
+    /// not a part of a barrel.
+    DamageBarrel {
+        collider: Handle<Node>,
+        amount: f32,
+        who: Handle<Actor>,
+    },
     GiveNewWeapon {
         actor: Handle<Actor>,
         kind: WeaponKind,
@@ -55,6 +84,15 @@ pub enum Message {
     SpawnBot {
         spawn_point_id: usize,
     },
+    /// Spawns an enemy of a wave, the enemy will be registered in the wave spawner.
+    SpawnWaveEnemy {
+        spawner: Handle<Node>,
+        kind: BotKind,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        /// Multiplier for the health of the enemy.
+        health_factor: f32,
+    },
     /// Gives item of specified kind to a given actor. Basically it means that actor will take
     /// item and consume it immediately (heal itself, add ammo, etc.)
     UseItem {
@@ -79,6 +117,15 @@ pub enum Message {
         initial_velocity: Vector3<f32>,
         shooter: Shooter,
     },
+    /// Throws a live grenade again, the grenade keeps its remaining fuse time. Does nothing if
+    /// the grenade is about to detonate.
+    ThrowBackGrenade {
+        projectile: Handle<Projectile>,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        initial_velocity: Vector3<f32>,
+        shooter: Shooter,
+    },
     ShootWeapon {
         weapon: Handle<Weapon>,
         direction: Option<Vector3<f32>>,
@@ -88,7 +135,8 @@ pub enum Message {
         begin: Vector3<f32>,
         end: Vector3<f32>,
         damage: Damage,
-        shot_effect: ShotEffect,
+        /// `None` means that the shot leaves no visible trail.
+        shot_effect: Option<ShotEffect>,
     },
     PlaySound {
         path: PathBuf,
@@ -123,12 +171,37 @@ pub enum Message {
     SwitchFlashLight {
         weapon: Handle<Weapon>,
     },
+    /// Mounts an attachment from the inventory of an actor on its current weapon.
+    AttachItem {
+        actor: Handle<Actor>,
+        item: ItemKind,
+    },
+    /// Takes every attachment off a weapon of an actor and puts them back to its inventory.
+    DetachAttachments {
+        actor: Handle<Actor>,
+        weapon: WeaponKind,
+    },
+    ToggleSilencer {
+        weapon: Handle<Weapon>,
+    },
+    CycleFireMode {
+        weapon: Handle<Weapon>,
+    },
+    ClearJam {
+        weapon: Handle<Weapon>,
+    },
+    ToggleLaserSight {
+        weapon: Handle<Weapon>,
+    },
     DamageActor {
         /// Which actor should be damaged.
         actor: Handle<Actor>,
         /// Actor who damaged target actor, can be Handle::NONE if damage came from environment
         /// or not from any actor.
         who: Handle<Actor>,
+        /// Weapon which was used to deal damage, can be Handle::NONE if damage was dealt by
+        /// something else (explosions, melee attacks, etc).
+        weapon: Handle<Weapon>,
         /// A body part which was hit.
         hitbox: Option<HitBox>,
         /// Numeric value of damage.
@@ -136,6 +209,23 @@ pub enum Message {
         /// Only takes effect iff damage was applied to a head hit box!
         critical_shot_probability: f32,
     },
+    /// Applies burning, bleeding, etc. to an actor, ignored if the actor is dead already.
+    ApplyStatusEffect {
+        actor: Handle<Actor>,
+        /// Actor who applied the effect, damage dealt by the effect is attributed to it.
+        who: Handle<Actor>,
+        effect: StatusEffectDefinition,
+    },
+    /// Silently kills an unaware bot in close combat.
+    Takedown {
+        attacker: Handle<Actor>,
+        victim: Handle<Actor>,
+    },
+    /// Adds an entry to the kill feed, sent when the player kills someone.
+    ReportKill {
+        victim: BotKind,
+        weapon: Option<WeaponKind>,
+    },
     CreateEffect {
         kind: EffectKind,
         position: Vector3<f32>,
@@ -149,6 +239,27 @@ pub enum Message {
         who: Handle<Actor>,
         critical_shot_probability: f32,
     },
+    /// Creates a cloud of smoke that blocks line of sight of bots.
+    CreateSmokeCloud {
+        position: Vector3<f32>,
+        radius: f32,
+        lifetime: f32,
+    },
+    /// Blinds the player (if it can see the detonation point) for some time.
+    DetonateFlashbang {
+        position: Vector3<f32>,
+        range: f32,
+        duration: f32,
+    },
+    /// Notifies bots about a noise, bots that can hear it will investigate its position.
+    MakeNoise {
+        position: Vector3<f32>,
+        /// Maximum distance at which the noise can be heard.
+        radius: f32,
+        kind: NoiseKind,
+        /// Actor that made the noise.
+        source: Handle<Actor>,
+    },
     /// Forces weapon's sight to react in given manner. It is used to indicate hits and
     /// moment when enemy dies.
     SightReaction {
@@ -162,12 +273,50 @@ pub enum Message {
     StartNewGame,
     LoadTestbed,
     QuitGame,
+    /// Closes the main menu that was opened during the game.
+    ResumeGame,
     LoadNextLevel,
+    /// Loads given level keeping the state of the player, the player appears at a node with given
+    /// name or at the default spawn point of the level.
+    LoadLevel {
+        path: String,
+        spawn_point: Option<String>,
+    },
     ToggleMainMenu,
     EndMatch,
     EndGame,
     SyncInventory,
     SyncJournal,
+    /// Journal display was opened or closed by the player.
+    SetJournalVisible {
+        visible: bool,
+    },
+    /// Starts playing an audio log, a log that is currently playing will be stopped.
+    PlayAudioLog {
+        path: PathBuf,
+    },
+    /// Stops the audio log if it is playing, otherwise plays it from the beginning.
+    ToggleAudioLog {
+        path: PathBuf,
+    },
+    StopAudioLog,
+    /// Creates an item stuck in a surface or in a body of an actor (if `parent` is not none).
+    EmbedItem {
+        item: ItemKind,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        parent: Handle<Node>,
+    },
+    /// Adds new objective to the journal of the player.
+    AddObjective {
+        id: String,
+        description: String,
+        /// A point in the world that will be marked while the objective is active.
+        target: Option<Vector3<f32>>,
+    },
+    CompleteObjective {
+        id: String,
+    },
     ShowItemDisplay {
         item: ItemKind,
         count: u32,
@@ -181,5 +330,15 @@ pub enum Message {
     // Sound-related messages.
     SetMusicVolume(f32),
     SetUseHrtf(bool),
+    SetLaserSightColor(LaserSightColor),
+    SetLaserDotVisible(bool),
+    SetHealthRegenerationEnabled(bool),
+    SetDownedStateEnabled(bool),
+    SetWeaponJamEnabled(bool),
+    SetDoubleJumpEnabled(bool),
     SetMasterVolume(f32),
+    SetSfxVolume(f32),
+    SetFieldOfView(f32),
+    SetSprintEffectsEnabled(bool),
+    SetDiegeticDisplaysEnabled(bool),
 }
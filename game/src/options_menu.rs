@@ -1,5 +1,8 @@
 use crate::{
-    config::SoundConfig,
+    config::{
+        CameraConfig, DoubleJumpConfig, DownedStateConfig, HealthRegenerationConfig,
+        LaserSightColor, LaserSightConfig, SoundConfig, WeaponJamConfig,
+    },
     control_scheme::{ControlButton, ControlScheme},
     gui::{create_check_box, create_scroll_bar, ScrollBarData},
     message::Message,
@@ -36,6 +39,7 @@ pub struct OptionsMenu {
     sender: MessageSender,
     sound_volume: Handle<UiNode>,
     pub music_volume: Handle<UiNode>,
+    sfx_volume: Handle<UiNode>,
     video_mode: Handle<UiNode>,
     spot_shadows: Handle<UiNode>,
     soft_spot_shadows: Handle<UiNode>,
@@ -51,12 +55,27 @@ pub struct OptionsMenu {
     active_control_button: Option<usize>,
     mouse_sens: Handle<UiNode>,
     mouse_y_inverse: Handle<UiNode>,
+    aim_toggle: Handle<UiNode>,
+    run_toggle: Handle<UiNode>,
+    crouch_toggle: Handle<UiNode>,
+    stick_sens: Handle<UiNode>,
+    aim_assist: Handle<UiNode>,
+    aim_assist_strength: Handle<UiNode>,
     reset_control_scheme: Handle<UiNode>,
     use_hrtf: Handle<UiNode>,
     reset_audio_settings: Handle<UiNode>,
     point_shadows_quality: Handle<UiNode>,
     spot_shadows_quality: Handle<UiNode>,
     show_debug_info: Handle<UiNode>,
+    laser_sight_color: Handle<UiNode>,
+    show_laser_dot: Handle<UiNode>,
+    health_regeneration: Handle<UiNode>,
+    field_of_view: Handle<UiNode>,
+    downed_state: Handle<UiNode>,
+    weapon_jam: Handle<UiNode>,
+    sprint_effects: Handle<UiNode>,
+    diegetic_displays: Handle<UiNode>,
+    double_jump: Handle<UiNode>,
 }
 
 fn make_text_mark(text: &str, row: usize, ctx: &mut BuildContext) -> Handle<UiNode> {
@@ -137,6 +156,43 @@ fn make_shadows_quality_drop_down(
     .build(ctx)
 }
 
+fn make_laser_sight_color_drop_down(
+    ctx: &mut BuildContext,
+    row: usize,
+    current: LaserSightColor,
+) -> Handle<UiNode> {
+    DropdownListBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(1)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_items({
+        LaserSightColor::ALL
+            .iter()
+            .map(|color| {
+                DecoratorBuilder::new(BorderBuilder::new(
+                    WidgetBuilder::new().with_child(
+                        TextBuilder::new(WidgetBuilder::new())
+                            .with_text(color.name())
+                            .build(ctx),
+                    ),
+                ))
+                .build(ctx)
+            })
+            .collect::<Vec<_>>()
+    })
+    .with_selected(laser_sight_color_index(current))
+    .build(ctx)
+}
+
+fn laser_sight_color_index(color: LaserSightColor) -> usize {
+    LaserSightColor::ALL
+        .iter()
+        .position(|c| *c == color)
+        .unwrap_or_default()
+}
+
 fn shadows_quality(size: usize) -> usize {
     if size < 256 {
         0
@@ -165,6 +221,12 @@ impl OptionsMenu {
         sender: MessageSender,
         show_debug_info_value: bool,
         sound_config: &SoundConfig,
+        laser_sight_config: &LaserSightConfig,
+        health_regeneration_config: &HealthRegenerationConfig,
+        camera_config: &CameraConfig,
+        downed_state_config: &DownedStateConfig,
+        weapon_jam_config: &WeaponJamConfig,
+        double_jump_config: &DoubleJumpConfig,
     ) -> Self {
         let video_modes: Vec<VideoMode> = if let Some(monitor) = engine.window.current_monitor() {
             monitor
@@ -187,6 +249,7 @@ impl OptionsMenu {
 
         let sound_volume;
         let music_volume;
+        let sfx_volume;
         let video_mode;
         let spot_shadows;
         let soft_spot_shadows;
@@ -196,6 +259,12 @@ impl OptionsMenu {
         let spot_shadow_distance;
         let mouse_sens;
         let mouse_y_inverse;
+        let aim_toggle;
+        let run_toggle;
+        let crouch_toggle;
+        let stick_sens;
+        let aim_assist;
+        let aim_assist_strength;
         let reset_control_scheme;
         let mut control_scheme_buttons = Vec::new();
         let use_hrtf;
@@ -206,6 +275,15 @@ impl OptionsMenu {
         let point_shadows_quality;
         let spot_shadows_quality;
         let show_debug_info;
+        let laser_sight_color;
+        let show_laser_dot;
+        let health_regeneration;
+        let field_of_view;
+        let downed_state;
+        let weapon_jam;
+        let sprint_effects;
+        let diegetic_displays;
+        let double_jump;
 
         let graphics_tab = TabDefinition {
             header: make_tab_header("Graphics", ctx),
@@ -347,6 +425,21 @@ impl OptionsMenu {
                                     show_debug_info =
                                         create_check_box(ctx, 12, 1, show_debug_info_value);
                                     show_debug_info
+                                })
+                                .with_child(make_text_mark("Laser Sight Color", 13, ctx))
+                                .with_child({
+                                    laser_sight_color = make_laser_sight_color_drop_down(
+                                        ctx,
+                                        13,
+                                        laser_sight_config.color,
+                                    );
+                                    laser_sight_color
+                                })
+                                .with_child(make_text_mark("Show Laser Dot", 14, ctx))
+                                .with_child({
+                                    show_laser_dot =
+                                        create_check_box(ctx, 14, 1, laser_sight_config.show_dot);
+                                    show_laser_dot
                                 }),
                         )
                         .add_row(common_row)
@@ -362,6 +455,8 @@ impl OptionsMenu {
                         .add_row(common_row)
                         .add_row(common_row)
                         .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
                         .add_column(Column::strict(250.0))
                         .add_column(Column::stretch())
                         .build(ctx),
@@ -413,14 +508,32 @@ impl OptionsMenu {
                                     );
                                     music_volume
                                 })
-                                .with_child(make_text_mark("Use HRTF", 2, ctx))
+                                .with_child(make_text_mark("SFX Volume", 2, ctx))
                                 .with_child({
-                                    use_hrtf = create_check_box(ctx, 2, 1, sound_config.use_hrtf);
+                                    sfx_volume = create_scroll_bar(
+                                        ctx,
+                                        ScrollBarData {
+                                            min: 0.0,
+                                            max: 1.0,
+                                            value: sound_config.sfx_volume,
+                                            step: 0.025,
+                                            row: 2,
+                                            column: 1,
+                                            margin,
+                                            show_value: true,
+                                            orientation: Orientation::Horizontal,
+                                        },
+                                    );
+                                    sfx_volume
+                                })
+                                .with_child(make_text_mark("Use HRTF", 3, ctx))
+                                .with_child({
+                                    use_hrtf = create_check_box(ctx, 3, 1, sound_config.use_hrtf);
                                     use_hrtf
                                 })
                                 .with_child({
                                     reset_audio_settings = ButtonBuilder::new(
-                                        WidgetBuilder::new().on_row(4).with_margin(margin),
+                                        WidgetBuilder::new().on_row(5).with_margin(margin),
                                     )
                                     .with_text("Reset")
                                     .build(ctx);
@@ -430,6 +543,7 @@ impl OptionsMenu {
                         .add_row(common_row)
                         .add_row(common_row)
                         .add_row(common_row)
+                        .add_row(common_row)
                         .add_row(Row::stretch())
                         .add_row(common_row)
                         .add_column(Column::strict(250.0))
@@ -440,6 +554,91 @@ impl OptionsMenu {
             },
         };
 
+        let gameplay_tab = TabDefinition {
+            header: make_tab_header("Gameplay", ctx),
+            content: {
+                ScrollViewerBuilder::new(WidgetBuilder::new())
+                    .with_content(
+                        GridBuilder::new(
+                            WidgetBuilder::new()
+                                .with_child(make_text_mark("Regenerating Health", 0, ctx))
+                                .with_child({
+                                    health_regeneration = create_check_box(
+                                        ctx,
+                                        0,
+                                        1,
+                                        health_regeneration_config.enabled,
+                                    );
+                                    health_regeneration
+                                })
+                                .with_child(make_text_mark("Field Of View", 1, ctx))
+                                .with_child({
+                                    field_of_view = create_scroll_bar(
+                                        ctx,
+                                        ScrollBarData {
+                                            min: 50.0,
+                                            max: 110.0,
+                                            value: camera_config.field_of_view,
+                                            step: 1.0,
+                                            row: 1,
+                                            column: 1,
+                                            margin,
+                                            show_value: true,
+                                            orientation: Orientation::Horizontal,
+                                        },
+                                    );
+                                    field_of_view
+                                })
+                                .with_child(make_text_mark("Downed State", 2, ctx))
+                                .with_child({
+                                    downed_state =
+                                        create_check_box(ctx, 2, 1, downed_state_config.enabled);
+                                    downed_state
+                                })
+                                .with_child(make_text_mark("Weapon Jamming", 3, ctx))
+                                .with_child({
+                                    weapon_jam =
+                                        create_check_box(ctx, 3, 1, weapon_jam_config.enabled);
+                                    weapon_jam
+                                })
+                                .with_child(make_text_mark("Sprint Effects", 4, ctx))
+                                .with_child({
+                                    sprint_effects =
+                                        create_check_box(ctx, 4, 1, camera_config.sprint_effects);
+                                    sprint_effects
+                                })
+                                .with_child(make_text_mark("Diegetic Displays", 5, ctx))
+                                .with_child({
+                                    diegetic_displays = create_check_box(
+                                        ctx,
+                                        5,
+                                        1,
+                                        camera_config.diegetic_displays,
+                                    );
+                                    diegetic_displays
+                                })
+                                .with_child(make_text_mark("Double Jump", 6, ctx))
+                                .with_child({
+                                    double_jump =
+                                        create_check_box(ctx, 6, 1, double_jump_config.enabled);
+                                    double_jump
+                                }),
+                        )
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_column(Column::strict(250.0))
+                        .add_column(Column::stretch())
+                        .build(ctx),
+                    )
+                    .build(ctx)
+            },
+        };
+
         let controls_tab = TabDefinition {
             header: make_tab_header("Controls", ctx),
             content: {
@@ -449,7 +648,7 @@ impl OptionsMenu {
 
                         for (row, button) in control_scheme.buttons().iter().enumerate() {
                             // Offset by total amount of rows that goes before
-                            let row = row + 2;
+                            let row = row + 8;
 
                             children.push(make_text_mark(button.description.as_str(), row, ctx));
 
@@ -491,10 +690,70 @@ impl OptionsMenu {
                                         create_check_box(ctx, 1, 1, control_scheme.mouse_y_inverse);
                                     mouse_y_inverse
                                 })
+                                .with_child(make_text_mark("Toggle Aim", 2, ctx))
+                                .with_child({
+                                    aim_toggle =
+                                        create_check_box(ctx, 2, 1, control_scheme.aim_toggle);
+                                    aim_toggle
+                                })
+                                .with_child(make_text_mark("Toggle Run", 3, ctx))
+                                .with_child({
+                                    run_toggle =
+                                        create_check_box(ctx, 3, 1, control_scheme.run_toggle);
+                                    run_toggle
+                                })
+                                .with_child(make_text_mark("Toggle Crouch", 4, ctx))
+                                .with_child({
+                                    crouch_toggle =
+                                        create_check_box(ctx, 4, 1, control_scheme.crouch_toggle);
+                                    crouch_toggle
+                                })
+                                .with_child(make_text_mark("Stick Sensitivity", 5, ctx))
+                                .with_child({
+                                    stick_sens = create_scroll_bar(
+                                        ctx,
+                                        ScrollBarData {
+                                            min: 0.5,
+                                            max: 6.0,
+                                            value: control_scheme.stick_sens,
+                                            step: 0.1,
+                                            row: 5,
+                                            column: 1,
+                                            margin,
+                                            show_value: true,
+                                            orientation: Orientation::Horizontal,
+                                        },
+                                    );
+                                    stick_sens
+                                })
+                                .with_child(make_text_mark("Aim Assist", 6, ctx))
+                                .with_child({
+                                    aim_assist =
+                                        create_check_box(ctx, 6, 1, control_scheme.aim_assist);
+                                    aim_assist
+                                })
+                                .with_child(make_text_mark("Aim Assist Strength", 7, ctx))
+                                .with_child({
+                                    aim_assist_strength = create_scroll_bar(
+                                        ctx,
+                                        ScrollBarData {
+                                            min: 0.0,
+                                            max: 1.0,
+                                            value: control_scheme.aim_assist_strength,
+                                            step: 0.05,
+                                            row: 7,
+                                            column: 1,
+                                            margin,
+                                            show_value: true,
+                                            orientation: Orientation::Horizontal,
+                                        },
+                                    );
+                                    aim_assist_strength
+                                })
                                 .with_child({
                                     reset_control_scheme = ButtonBuilder::new(
                                         WidgetBuilder::new()
-                                            .on_row(2 + control_scheme.buttons().len())
+                                            .on_row(8 + control_scheme.buttons().len())
                                             .with_margin(margin),
                                     )
                                     .with_text("Reset")
@@ -507,6 +766,12 @@ impl OptionsMenu {
                         .add_column(Column::stretch())
                         .add_row(common_row)
                         .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
+                        .add_row(common_row)
                         .add_rows(
                             (0..control_scheme.buttons().len())
                                 .map(|_| common_row)
@@ -522,6 +787,7 @@ impl OptionsMenu {
         let tab_control = TabControlBuilder::new(WidgetBuilder::new())
             .with_tab(graphics_tab)
             .with_tab(sound_tab)
+            .with_tab(gameplay_tab)
             .with_tab(controls_tab)
             .build(ctx);
 
@@ -541,6 +807,7 @@ impl OptionsMenu {
             window: options_window,
             sound_volume,
             music_volume,
+            sfx_volume,
             video_mode,
             spot_shadows,
             soft_spot_shadows,
@@ -553,6 +820,12 @@ impl OptionsMenu {
             active_control_button: None,
             mouse_sens,
             mouse_y_inverse,
+            aim_toggle,
+            run_toggle,
+            crouch_toggle,
+            stick_sens,
+            aim_assist,
+            aim_assist_strength,
             reset_control_scheme,
             use_hrtf,
             reset_audio_settings,
@@ -562,6 +835,15 @@ impl OptionsMenu {
             ssao,
             spot_shadows_quality,
             show_debug_info,
+            laser_sight_color,
+            show_laser_dot,
+            health_regeneration,
+            field_of_view,
+            downed_state,
+            weapon_jam,
+            sprint_effects,
+            diegetic_displays,
+            double_jump,
         }
     }
 
@@ -571,6 +853,12 @@ impl OptionsMenu {
         control_scheme: &ControlScheme,
         show_debug_info: bool,
         sound_config: &SoundConfig,
+        laser_sight_config: &LaserSightConfig,
+        health_regeneration_config: &HealthRegenerationConfig,
+        camera_config: &CameraConfig,
+        downed_state_config: &DownedStateConfig,
+        weapon_jam_config: &WeaponJamConfig,
+        double_jump_config: &DoubleJumpConfig,
     ) {
         let ui = &mut engine.user_interface;
         let settings = engine.renderer.get_quality_settings();
@@ -590,8 +878,25 @@ impl OptionsMenu {
         sync_check_box(self.ssao, settings.use_ssao);
         sync_check_box(self.fxaa, settings.fxaa);
         sync_check_box(self.mouse_y_inverse, control_scheme.mouse_y_inverse);
+        sync_check_box(self.aim_toggle, control_scheme.aim_toggle);
+        sync_check_box(self.run_toggle, control_scheme.run_toggle);
+        sync_check_box(self.crouch_toggle, control_scheme.crouch_toggle);
+        sync_check_box(self.aim_assist, control_scheme.aim_assist);
         sync_check_box(self.use_hrtf, sound_config.use_hrtf);
         sync_check_box(self.show_debug_info, show_debug_info);
+        sync_check_box(self.show_laser_dot, laser_sight_config.show_dot);
+        sync_check_box(self.health_regeneration, health_regeneration_config.enabled);
+        sync_check_box(self.downed_state, downed_state_config.enabled);
+        sync_check_box(self.weapon_jam, weapon_jam_config.enabled);
+        sync_check_box(self.sprint_effects, camera_config.sprint_effects);
+        sync_check_box(self.diegetic_displays, camera_config.diegetic_displays);
+        sync_check_box(self.double_jump, double_jump_config.enabled);
+
+        ui.send_message(DropdownListMessage::selection(
+            self.laser_sight_color,
+            MessageDirection::ToWidget,
+            Some(laser_sight_color_index(laser_sight_config.color)),
+        ));
 
         let sync_scroll_bar = |handle: Handle<UiNode>, value: f32| {
             ui.send_message(ScrollBarMessage::value(
@@ -603,8 +908,12 @@ impl OptionsMenu {
         sync_scroll_bar(self.point_shadow_distance, settings.point_shadows_distance);
         sync_scroll_bar(self.spot_shadow_distance, settings.spot_shadows_distance);
         sync_scroll_bar(self.mouse_sens, control_scheme.mouse_sens);
+        sync_scroll_bar(self.stick_sens, control_scheme.stick_sens);
+        sync_scroll_bar(self.aim_assist_strength, control_scheme.aim_assist_strength);
         sync_scroll_bar(self.sound_volume, sound_config.master_volume);
         sync_scroll_bar(self.music_volume, sound_config.music_volume);
+        sync_scroll_bar(self.sfx_volume, sound_config.sfx_volume);
+        sync_scroll_bar(self.field_of_view, camera_config.field_of_view);
 
         for (btn, def) in self
             .control_scheme_buttons
@@ -691,6 +1000,12 @@ impl OptionsMenu {
         control_scheme: &mut ControlScheme,
         show_debug_info: &mut bool,
         sound_config: &SoundConfig,
+        laser_sight_config: &LaserSightConfig,
+        health_regeneration_config: &HealthRegenerationConfig,
+        camera_config: &CameraConfig,
+        downed_state_config: &DownedStateConfig,
+        weapon_jam_config: &WeaponJamConfig,
+        double_jump_config: &DoubleJumpConfig,
     ) {
         let old_settings = context.renderer.get_quality_settings();
         let mut settings = old_settings;
@@ -711,9 +1026,21 @@ impl OptionsMenu {
                 } else if message.destination() == self.mouse_sens {
                     control_scheme.mouse_sens = *new_value;
                     changed = true;
+                } else if message.destination() == self.stick_sens {
+                    control_scheme.stick_sens = *new_value;
+                    changed = true;
+                } else if message.destination() == self.aim_assist_strength {
+                    control_scheme.aim_assist_strength = *new_value;
+                    changed = true;
                 } else if message.destination() == self.music_volume {
                     self.sender.send(Message::SetMusicVolume(*new_value));
                     changed = true;
+                } else if message.destination() == self.sfx_volume {
+                    self.sender.send(Message::SetSfxVolume(*new_value));
+                    changed = true;
+                } else if message.destination() == self.field_of_view {
+                    self.sender.send(Message::SetFieldOfView(*new_value));
+                    changed = true;
                 }
             }
         } else if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
@@ -744,6 +1071,11 @@ impl OptionsMenu {
                     settings.point_shadow_map_precision = ShadowMapPrecision::Half;
                 }
                 changed = true;
+            } else if message.destination() == self.laser_sight_color {
+                if let Some(color) = LaserSightColor::ALL.get(*index) {
+                    self.sender.send(Message::SetLaserSightColor(*color));
+                    changed = true;
+                }
             }
         } else if let Some(CheckBoxMessage::Check(value)) = message.data() {
             let value = value.unwrap_or(false);
@@ -762,6 +1094,18 @@ impl OptionsMenu {
             } else if message.destination() == self.mouse_y_inverse {
                 control_scheme.mouse_y_inverse = value;
                 changed = true;
+            } else if message.destination() == self.aim_toggle {
+                control_scheme.aim_toggle = value;
+                changed = true;
+            } else if message.destination() == self.run_toggle {
+                control_scheme.run_toggle = value;
+                changed = true;
+            } else if message.destination() == self.crouch_toggle {
+                control_scheme.crouch_toggle = value;
+                changed = true;
+            } else if message.destination() == self.aim_assist {
+                control_scheme.aim_assist = value;
+                changed = true;
             } else if message.destination() == self.use_light_scatter {
                 settings.light_scatter_enabled = value;
                 changed = true;
@@ -777,15 +1121,59 @@ impl OptionsMenu {
             } else if message.destination() == self.show_debug_info {
                 changed = true;
                 *show_debug_info = value;
+            } else if message.destination() == self.show_laser_dot {
+                changed = true;
+                self.sender.send(Message::SetLaserDotVisible(value));
+            } else if message.destination() == self.health_regeneration {
+                changed = true;
+                self.sender
+                    .send(Message::SetHealthRegenerationEnabled(value));
+            } else if message.destination() == self.downed_state {
+                changed = true;
+                self.sender.send(Message::SetDownedStateEnabled(value));
+            } else if message.destination() == self.weapon_jam {
+                changed = true;
+                self.sender.send(Message::SetWeaponJamEnabled(value));
+            } else if message.destination() == self.sprint_effects {
+                changed = true;
+                self.sender.send(Message::SetSprintEffectsEnabled(value));
+            } else if message.destination() == self.diegetic_displays {
+                changed = true;
+                self.sender.send(Message::SetDiegeticDisplaysEnabled(value));
+            } else if message.destination() == self.double_jump {
+                changed = true;
+                self.sender.send(Message::SetDoubleJumpEnabled(value));
             }
         } else if let Some(ButtonMessage::Click) = message.data() {
             if message.destination() == self.reset_control_scheme {
                 control_scheme.reset();
-                self.sync_to_model(context, control_scheme, *show_debug_info, sound_config);
+                self.sync_to_model(
+                    context,
+                    control_scheme,
+                    *show_debug_info,
+                    sound_config,
+                    laser_sight_config,
+                    health_regeneration_config,
+                    camera_config,
+                    downed_state_config,
+                    weapon_jam_config,
+                    double_jump_config,
+                );
                 changed = true;
             } else if message.destination() == self.reset_audio_settings {
                 context.sound_engine.set_sound_gain(1.0);
-                self.sync_to_model(context, control_scheme, *show_debug_info, sound_config);
+                self.sync_to_model(
+                    context,
+                    control_scheme,
+                    *show_debug_info,
+                    sound_config,
+                    laser_sight_config,
+                    health_regeneration_config,
+                    camera_config,
+                    downed_state_config,
+                    weapon_jam_config,
+                    double_jump_config,
+                );
                 changed = true;
             }
 
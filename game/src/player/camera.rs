@@ -1,3 +1,4 @@
+use crate::config::CameraConfig;
 use crate::utils::create_camera;
 use crate::GameTime;
 use fyrox::scene::graph::physics::{Intersection, RayCastOptions};
@@ -5,16 +6,39 @@ use fyrox::scene::pivot::PivotBuilder;
 use fyrox::{
     core::{
         algebra::{Point3, UnitQuaternion, Vector3},
-        math::{ray::Ray, Vector3Ext},
+        math::{ray::Ray, Rect, Vector3Ext},
         pool::Handle,
         rand::Rng,
         visitor::{Visit, VisitResult, Visitor},
     },
     engine::resource_manager::ResourceManager,
     rand,
-    scene::{base::BaseBuilder, graph::Graph, node::Node, transform::TransformBuilder, Scene},
+    scene::{
+        base::BaseBuilder,
+        camera::{Camera, PerspectiveProjection, Projection},
+        graph::Graph,
+        node::Node,
+        transform::TransformBuilder,
+        Scene,
+    },
 };
 
+/// Distance along the view direction between the camera hinge and the scope camera, it puts
+/// the scope camera in front of the player.
+const SCOPE_CAMERA_OFFSET: f32 = 0.5;
+
+/// Sets field of view of the camera, that is given field of view (in degrees) narrowed by given
+/// magnification.
+fn set_magnification(camera: &mut Camera, field_of_view: f32, magnification: f32) {
+    let fov = 2.0 * ((field_of_view.to_radians() * 0.5).tan() / magnification.max(1.0)).atan();
+    if let Projection::Perspective(perspective) = camera.projection().clone() {
+        camera.set_projection(Projection::Perspective(PerspectiveProjection {
+            fov,
+            ..perspective
+        }));
+    }
+}
+
 #[derive(Default, Visit)]
 pub struct CameraController {
     camera_pivot: Handle<Node>,
@@ -27,6 +51,23 @@ pub struct CameraController {
     shake_timer: f32,
     #[visit(skip)]
     query_buffer: Vec<Intersection>,
+    /// Current magnification of the camera, values less than 1.0 mean no zoom.
+    #[visit(skip)]
+    zoom: f32,
+    #[visit(skip)]
+    target_zoom: f32,
+    /// Fraction of the remaining zoom change that is made every frame.
+    #[visit(skip)]
+    zoom_speed: f32,
+    /// Camera of picture-in-picture scopes, it looks where the main camera looks.
+    #[visit(optional)]
+    scope_camera: Handle<Node>,
+    /// Magnification and viewport of the scope camera, `None` disables the camera.
+    #[visit(skip)]
+    scope_view: Option<(f32, Rect<f32>)>,
+    /// Intensity of sprint in `[0; 1]` range, the view widens with it.
+    #[visit(skip)]
+    sprint_factor: f32,
 }
 
 impl CameraController {
@@ -59,6 +100,18 @@ impl CameraController {
         }]))
         .build(graph);
 
+        let scope_camera = create_camera(
+            resource_manager.clone(),
+            Vector3::new(0.0, 0.0, SCOPE_CAMERA_OFFSET),
+            graph,
+            200.0,
+        )
+        .await;
+        graph.link_nodes(scope_camera, camera_hinge);
+        if let Some(camera) = graph[scope_camera].cast_mut::<Camera>() {
+            camera.set_enabled(false);
+        }
+
         Self {
             camera_pivot,
             camera_hinge,
@@ -69,6 +122,12 @@ impl CameraController {
             target_shake_offset: Default::default(),
             shake_timer: 0.0,
             query_buffer: Default::default(),
+            zoom: 1.0,
+            target_zoom: 1.0,
+            zoom_speed: 0.25,
+            scope_camera,
+            scope_view: None,
+            sprint_factor: 0.0,
         }
     }
 
@@ -76,10 +135,37 @@ impl CameraController {
         self.camera
     }
 
+    /// Sets desired magnification of the camera, it will be reached smoothly. Speed is a
+    /// multiplier for the default zoom speed.
+    pub fn set_target_zoom(&mut self, zoom: f32, speed: f32) {
+        self.target_zoom = zoom;
+        self.zoom_speed = (0.25 * speed).clamp(0.0, 1.0);
+    }
+
+    pub fn set_sprint_factor(&mut self, factor: f32) {
+        self.sprint_factor = factor.clamp(0.0, 1.0);
+    }
+
+    /// Sets magnification and viewport (in normalized screen coordinates) of the scope camera,
+    /// `None` disables the camera.
+    pub fn set_scope_view(&mut self, view: Option<(f32, Rect<f32>)>) {
+        self.scope_view = view;
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom.max(1.0)
+    }
+
     pub fn request_shake_camera(&mut self) {
         self.shake_timer = 0.24;
     }
 
+    /// Instantly pushes the camera back by given distance, the camera then smoothly returns to
+    /// its place together with the shake offset.
+    pub fn kick(&mut self, distance: f32) {
+        self.shake_offset.z += distance;
+    }
+
     pub fn update(
         &mut self,
         position: Vector3<f32>,
@@ -91,6 +177,7 @@ impl CameraController {
         owner_collider: Handle<Node>,
         scene: &mut Scene,
         time: GameTime,
+        config: &CameraConfig,
     ) {
         if is_walking {
             let (kx, ky) = if is_running { (8.0, 13.0) } else { (5.0, 10.0) };
@@ -124,6 +211,8 @@ impl CameraController {
                 -self.camera_offset.z,
             ));
 
+        self.update_zoom(scene, config);
+
         // Rotate camera hinge - this will make camera move up and down while look at character
         // (well not exactly on character - on characters head)
         scene.graph[self.camera_hinge]
@@ -164,6 +253,35 @@ impl CameraController {
         }
     }
 
+    /// Applies current zoom and sprint widening to the field of view of the camera.
+    fn update_zoom(&mut self, scene: &mut Scene, config: &CameraConfig) {
+        let zoom = self.zoom();
+        self.zoom = zoom + (self.target_zoom.max(1.0) - zoom) * self.zoom_speed;
+
+        let field_of_view = config.field_of_view;
+        let sprint_field_of_view = if config.sprint_effects {
+            config.sprint_field_of_view * self.sprint_factor
+        } else {
+            0.0
+        };
+
+        if let Some(camera) = scene.graph[self.camera].cast_mut::<Camera>() {
+            set_magnification(camera, field_of_view + sprint_field_of_view, self.zoom);
+        }
+
+        if let Some(camera) = scene
+            .graph
+            .try_get_mut(self.scope_camera)
+            .and_then(|n| n.cast_mut::<Camera>())
+        {
+            camera.set_enabled(self.scope_view.is_some());
+            if let Some((magnification, viewport)) = self.scope_view {
+                camera.set_viewport(viewport);
+                set_magnification(camera, field_of_view, magnification);
+            }
+        }
+    }
+
     fn update_shake(&mut self, dt: f32) {
         let xy_range = -0.027..0.027;
         let z_range = 0.01..0.05;
@@ -0,0 +1,49 @@
+//! Battery of the flash light of the player. The flash light drains the battery while it is on
+//! and turns off when the battery is depleted, the battery slowly recharges while the flash light
+//! is off.
+
+use fyrox::{core::visitor::prelude::*, lazy_static::lazy_static};
+use serde::Deserialize;
+use std::fs::File;
+
+#[derive(Deserialize)]
+pub struct FlashLightBatteryDefinition {
+    /// If `false`, the flash light never runs out of charge.
+    pub battery_enabled: bool,
+    pub capacity: f32,
+    /// Charge per second that is spent while the flash light is on.
+    pub drain_rate: f32,
+    /// Charge per second that is restored while the flash light is off.
+    pub recharge_rate: f32,
+    /// Amount of charge given by a single battery pickup.
+    pub pickup_amount: f32,
+}
+
+impl FlashLightBatteryDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/flash_light.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    pub static ref FLASH_LIGHT_BATTERY: FlashLightBatteryDefinition =
+        FlashLightBatteryDefinition::load();
+}
+
+/// Charge of the battery. It is full by default, so the battery is full when a save made before
+/// the battery was added is loaded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BatteryCharge(pub f32);
+
+impl Default for BatteryCharge {
+    fn default() -> Self {
+        Self(FLASH_LIGHT_BATTERY.capacity)
+    }
+}
+
+impl Visit for BatteryCharge {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        self.0.visit(name, visitor)
+    }
+}
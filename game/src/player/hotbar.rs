@@ -0,0 +1,58 @@
+//! Hotbar holds consumables and grenades that are used with a single button press without opening
+//! the inventory. Items are assigned to slots in the inventory, a slot keeps its item even if the
+//! player runs out of it, so picking up more of the item makes the slot usable again.
+
+use crate::{
+    inventory::Inventory,
+    item::{Item, ItemKind},
+};
+use fyrox::core::visitor::prelude::*;
+
+pub const HOTBAR_SIZE: usize = 3;
+
+#[derive(Default, Clone, Debug, Visit)]
+pub struct Hotbar {
+    slots: Vec<Option<ItemKind>>,
+}
+
+impl Hotbar {
+    /// Checks whether given item can be put on the hotbar, only consumables and grenades can.
+    pub fn can_hold(item: ItemKind) -> bool {
+        Item::get_definition(item).consumable || item.associated_grenade().is_some()
+    }
+
+    pub fn slot(&self, index: usize) -> Option<ItemKind> {
+        self.slots.get(index).cloned().flatten()
+    }
+
+    /// Puts an item into given slot, the item is removed from other slots. Assigning an item to
+    /// the slot that already holds it clears the slot.
+    pub fn assign(&mut self, index: usize, item: ItemKind) {
+        if index >= HOTBAR_SIZE || !Self::can_hold(item) {
+            return;
+        }
+
+        self.slots.resize(HOTBAR_SIZE, None);
+
+        let clear = self.slots[index] == Some(item);
+        for slot in self.slots.iter_mut() {
+            if *slot == Some(item) {
+                *slot = None;
+            }
+        }
+        if !clear {
+            self.slots[index] = Some(item);
+        }
+    }
+
+    /// Returns contents of every slot together with the amount of the item in given inventory.
+    pub fn contents<'a>(
+        &'a self,
+        inventory: &'a Inventory,
+    ) -> impl Iterator<Item = Option<(ItemKind, u32)>> + 'a {
+        (0..HOTBAR_SIZE).map(move |index| {
+            self.slot(index)
+                .map(|item| (item, inventory.item_count(item)))
+        })
+    }
+}
@@ -0,0 +1,53 @@
+//! Things that the player can interact with using the action button.
+
+use crate::{
+    control_scheme::ControlScheme,
+    item::{Item, ItemKind},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interaction {
+    PickUp(ItemKind),
+    /// An item that can't be picked up, because the reserve of such items is full.
+    ReserveFull(ItemKind),
+    OpenDoor,
+    RideElevator,
+    CallElevator,
+    ClimbLadder,
+    UseTerminal,
+    ThrowBackGrenade,
+    LeaveLevel,
+    Takedown,
+}
+
+impl Interaction {
+    pub fn description(self) -> String {
+        match self {
+            Interaction::PickUp(kind) => format!("pick up {}", Item::get_definition(kind).name),
+            Interaction::ReserveFull(kind) => {
+                format!("{} is full", Item::get_definition(kind).name)
+            }
+            Interaction::OpenDoor => "open".to_owned(),
+            Interaction::RideElevator => "ride the elevator".to_owned(),
+            Interaction::CallElevator => "call the elevator".to_owned(),
+            Interaction::ClimbLadder => "climb the ladder".to_owned(),
+            Interaction::UseTerminal => "use the terminal".to_owned(),
+            Interaction::ThrowBackGrenade => "throw the grenade back".to_owned(),
+            Interaction::LeaveLevel => "go to the next area".to_owned(),
+            Interaction::Takedown => "take down the enemy silently".to_owned(),
+        }
+    }
+
+    /// Returns text of on-screen prompt using the button that is currently bound to the action.
+    pub fn prompt(self, control_scheme: &ControlScheme) -> String {
+        if let Interaction::ReserveFull(_) = self {
+            return self.description();
+        }
+
+        format!(
+            "Press [{}] to {}",
+            control_scheme.action.button.name(),
+            self.description()
+        )
+    }
+}
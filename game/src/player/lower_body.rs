@@ -70,6 +70,15 @@ pub struct LowerBodyMachine {
     pub dying_animation: Handle<Animation>,
     pub hit_reaction_pistol_animation: Handle<Animation>,
     pub hit_reaction_rifle_animation: Handle<Animation>,
+    /// Saves made before sliding was added have no slide state.
+    #[visit(optional)]
+    pub slide_animation: Handle<Animation>,
+    /// Saves made before mantling was added have no mantle state.
+    #[visit(optional)]
+    pub mantle_animation: Handle<Animation>,
+    /// Saves made before ladders were added have no climb state.
+    #[visit(optional)]
+    pub climb_animation: Handle<Animation>,
     pub walk_state: Handle<State>,
     pub jump_state: Handle<State>,
     pub fall_state: Handle<State>,
@@ -82,6 +91,9 @@ pub struct LowerBodyMachine {
 pub struct LowerBodyMachineInput {
     pub is_walking: bool,
     pub is_jumping: bool,
+    pub is_sliding: bool,
+    pub is_mantling: bool,
+    pub is_climbing: bool,
     pub run_factor: f32,
     pub has_ground_contact: bool,
     pub is_dead: bool,
@@ -112,6 +124,22 @@ impl LowerBodyMachine {
     const HIT_REACTION_TO_WALK: &'static str = "HitReactionToWalk";
     const HIT_REACTION_TO_DYING: &'static str = "HitReactionToDying";
 
+    const WALK_TO_SLIDE: &'static str = "WalkToSlide";
+    const SLIDE_TO_WALK: &'static str = "SlideToWalk";
+    const SLIDE_TO_IDLE: &'static str = "SlideToIdle";
+    const SLIDE_TO_FALL: &'static str = "SlideToFall";
+    const SLIDE_TO_DYING: &'static str = "SlideToDying";
+
+    const IDLE_TO_MANTLE: &'static str = "IdleToMantle";
+    const WALK_TO_MANTLE: &'static str = "WalkToMantle";
+    const JUMP_TO_MANTLE: &'static str = "JumpToMantle";
+    const MANTLE_TO_IDLE: &'static str = "MantleToIdle";
+
+    const IDLE_TO_CLIMB: &'static str = "IdleToClimb";
+    const WALK_TO_CLIMB: &'static str = "WalkToClimb";
+    const CLIMB_TO_IDLE: &'static str = "ClimbToIdle";
+    const CLIMB_TO_MANTLE: &'static str = "ClimbToMantle";
+
     pub const JUMP_SIGNAL: u64 = 1;
     pub const LANDING_SIGNAL: u64 = 2;
     pub const FOOTSTEP_SIGNAL: u64 = 3;
@@ -138,6 +166,8 @@ impl LowerBodyMachine {
             dying_animation_resource,
             hit_reaction_rifle_animation_resource,
             hit_reaction_pistol_animation_resource,
+            slide_animation_resource,
+            climb_animation_resource,
         ) = fyrox::core::futures::join!(
             resource_manager.request_model("data/animations/agent_walking_lower_body.fbx",),
             resource_manager.request_model("data/animations/agent_idle.fbx"),
@@ -148,6 +178,8 @@ impl LowerBodyMachine {
             resource_manager.request_model("data/animations/agent_dying.fbx"),
             resource_manager.request_model("data/animations/agent_hit_reaction_rifle.fbx",),
             resource_manager.request_model("data/animations/agent_hit_reaction_pistol.fbx",),
+            resource_manager.request_model("data/animations/agent_landing_hard.fbx"),
+            resource_manager.request_model("data/animations/agent_walking_lower_body1.fbx"),
         );
 
         let HitReactionStateDefinition {
@@ -171,8 +203,9 @@ impl LowerBodyMachine {
             model,
         );
 
+        let jump_animation_resource = jump_animation_resource.unwrap();
         let (jump_animation, jump_state) = create_play_animation_state(
-            jump_animation_resource.unwrap(),
+            jump_animation_resource.clone(),
             "Jump",
             &mut machine,
             scene,
@@ -203,6 +236,34 @@ impl LowerBodyMachine {
             model,
         );
 
+        // Hard landing drops the model to the knees and raises it back, it is stretched over
+        // the duration of a slide.
+        let (slide_animation, slide_state) = create_play_animation_state(
+            slide_animation_resource.unwrap(),
+            "Slide",
+            &mut machine,
+            scene,
+            model,
+        );
+
+        // There is no mantle animation, legs push off like in the jump while the body is lifted.
+        let (mantle_animation, mantle_state) = create_play_animation_state(
+            jump_animation_resource,
+            "Mantle",
+            &mut machine,
+            scene,
+            model,
+        );
+
+        // There is no climb animation, legs step in place with the spare walk clip.
+        let (climb_animation, climb_state) = create_play_animation_state(
+            climb_animation_resource.unwrap(),
+            "Climb",
+            &mut machine,
+            scene,
+            model,
+        );
+
         let WalkStateDefinition {
             walk_animation,
             state: walk_state,
@@ -258,6 +319,18 @@ impl LowerBodyMachine {
             .set_enabled(false)
             .set_loop(false);
 
+        scene
+            .animations
+            .get_mut(slide_animation)
+            .set_enabled(false)
+            .set_loop(false);
+
+        scene
+            .animations
+            .get_mut(mantle_animation)
+            .set_enabled(false)
+            .set_loop(false);
+
         // Add transitions between states. This is the "heart" of animation blending state machine
         // it defines how it will respond to input parameters.
         machine.add_transition(Transition::new(
@@ -399,6 +472,103 @@ impl LowerBodyMachine {
             Self::HIT_REACTION_TO_DYING,
         ));
 
+        // Slide transitions.
+        machine.add_transition(Transition::new(
+            "Walk->Slide",
+            walk_state,
+            slide_state,
+            0.15,
+            Self::WALK_TO_SLIDE,
+        ));
+        machine.add_transition(Transition::new(
+            "Slide->Walk",
+            slide_state,
+            walk_state,
+            0.30,
+            Self::SLIDE_TO_WALK,
+        ));
+        machine.add_transition(Transition::new(
+            "Slide->Idle",
+            slide_state,
+            idle_state,
+            0.30,
+            Self::SLIDE_TO_IDLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Slide->Falling",
+            slide_state,
+            fall_state,
+            0.20,
+            Self::SLIDE_TO_FALL,
+        ));
+        machine.add_transition(Transition::new(
+            "Slide->Dying",
+            slide_state,
+            dying_state,
+            0.20,
+            Self::SLIDE_TO_DYING,
+        ));
+
+        // Mantle transitions, mantling may start right at the beginning of a jump.
+        machine.add_transition(Transition::new(
+            "Idle->Mantle",
+            idle_state,
+            mantle_state,
+            0.10,
+            Self::IDLE_TO_MANTLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Walk->Mantle",
+            walk_state,
+            mantle_state,
+            0.10,
+            Self::WALK_TO_MANTLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Jump->Mantle",
+            jump_state,
+            mantle_state,
+            0.10,
+            Self::JUMP_TO_MANTLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Mantle->Idle",
+            mantle_state,
+            idle_state,
+            0.20,
+            Self::MANTLE_TO_IDLE,
+        ));
+
+        // Climb transitions, the top of a ladder is left by mantling onto the floor.
+        machine.add_transition(Transition::new(
+            "Idle->Climb",
+            idle_state,
+            climb_state,
+            0.20,
+            Self::IDLE_TO_CLIMB,
+        ));
+        machine.add_transition(Transition::new(
+            "Walk->Climb",
+            walk_state,
+            climb_state,
+            0.20,
+            Self::WALK_TO_CLIMB,
+        ));
+        machine.add_transition(Transition::new(
+            "Climb->Idle",
+            climb_state,
+            idle_state,
+            0.20,
+            Self::CLIMB_TO_IDLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Climb->Mantle",
+            climb_state,
+            mantle_state,
+            0.10,
+            Self::CLIMB_TO_MANTLE,
+        ));
+
         machine.set_entry_state(idle_state);
 
         Self {
@@ -410,6 +580,9 @@ impl LowerBodyMachine {
             dying_animation,
             hit_reaction_pistol_animation,
             hit_reaction_rifle_animation,
+            slide_animation,
+            mantle_animation,
+            climb_animation,
             walk_state,
             jump_state,
             fall_state,
@@ -428,7 +601,7 @@ impl LowerBodyMachine {
         sender: &MessageSender,
         has_ground_contact: bool,
         self_collider: Handle<Node>,
-    ) {
+    ) -> bool {
         let (current_hit_reaction_animation, index) = match input.weapon_kind {
             CombatWeaponKind::Rifle => (self.hit_reaction_rifle_animation, 0),
             CombatWeaponKind::Pistol => (self.hit_reaction_pistol_animation, 1),
@@ -479,6 +652,31 @@ impl LowerBodyMachine {
             .set_parameter(Self::HIT_REACTION_TO_IDLE, Parameter::Rule(recovered))
             .set_parameter(Self::HIT_REACTION_TO_WALK, Parameter::Rule(recovered))
             .set_parameter(Self::HIT_REACTION_TO_DYING, Parameter::Rule(input.is_dead))
+            .set_parameter(Self::WALK_TO_SLIDE, Parameter::Rule(input.is_sliding))
+            .set_parameter(
+                Self::SLIDE_TO_WALK,
+                Parameter::Rule(!input.is_sliding && input.is_walking),
+            )
+            .set_parameter(
+                Self::SLIDE_TO_IDLE,
+                Parameter::Rule(!input.is_sliding && !input.is_walking),
+            )
+            .set_parameter(
+                Self::SLIDE_TO_FALL,
+                Parameter::Rule(!input.has_ground_contact),
+            )
+            .set_parameter(Self::SLIDE_TO_DYING, Parameter::Rule(input.is_dead))
+            .set_parameter(Self::IDLE_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(Self::WALK_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(Self::JUMP_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(Self::MANTLE_TO_IDLE, Parameter::Rule(!input.is_mantling))
+            .set_parameter(Self::IDLE_TO_CLIMB, Parameter::Rule(input.is_climbing))
+            .set_parameter(Self::WALK_TO_CLIMB, Parameter::Rule(input.is_climbing))
+            .set_parameter(
+                Self::CLIMB_TO_IDLE,
+                Parameter::Rule(!input.is_climbing && !input.is_mantling),
+            )
+            .set_parameter(Self::CLIMB_TO_MANTLE, Parameter::Rule(input.is_mantling))
             .set_parameter(Self::WALK_FACTOR, Parameter::Weight(1.0 - input.run_factor))
             .set_parameter(Self::RUN_FACTOR, Parameter::Weight(input.run_factor))
             .evaluate_pose(&scene.animations, dt)
@@ -486,6 +684,7 @@ impl LowerBodyMachine {
 
         let begin = scene.graph[self.model].global_position() + Vector3::new(0.0, 0.5, 0.0);
 
+        let mut made_footstep = false;
         while let Some((walking, evt)) = scene
             .animations
             .get_mut(self.walk_animation)
@@ -507,14 +706,51 @@ impl LowerBodyMachine {
                 || input.run_factor >= 0.5 && !walking
             {
                 footstep_ray_check(begin, scene, self_collider, sender.clone());
+                made_footstep = true;
             }
         }
 
         while let Some(evt) = scene.animations.get_mut(self.land_animation).pop_event() {
             if evt.signal_id == Self::FOOTSTEP_SIGNAL {
                 footstep_ray_check(begin, scene, self_collider, sender.clone());
+                made_footstep = true;
             }
         }
+
+        made_footstep
+    }
+
+    /// Starts the slide animation over, it is stretched to last given time.
+    pub fn start_slide(&self, scene: &mut Scene, duration: f32) {
+        if self.slide_animation.is_none() {
+            return;
+        }
+
+        let animation = scene.animations.get_mut(self.slide_animation);
+        let speed = animation.length() / duration;
+        animation.set_enabled(true).set_speed(speed).rewind();
+    }
+
+    /// Starts the mantle animation over, it is stretched to last given time.
+    pub fn start_mantle(&self, scene: &mut Scene, duration: f32) {
+        if self.mantle_animation.is_none() {
+            return;
+        }
+
+        let animation = scene.animations.get_mut(self.mantle_animation);
+        let speed = animation.length() / duration;
+        animation.set_enabled(true).set_speed(speed).rewind();
+    }
+
+    /// Sets speed of the climb animation, negative speed plays it backwards when the player climbs
+    /// down and zero speed freezes it.
+    pub fn set_climb_speed(&self, scene: &mut Scene, speed: f32) {
+        if self.climb_animation.is_some() {
+            scene
+                .animations
+                .get_mut(self.climb_animation)
+                .set_speed(speed);
+        }
     }
 
     pub fn is_stunned(&self, scene: &Scene) -> bool {
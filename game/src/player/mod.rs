@@ -1,27 +1,40 @@
 use crate::{
-    actor::Actor,
-    character::{find_hit_boxes, Character},
+    actor::{Actor, TargetDescriptor, TargetKind},
+    character::{find_hit_boxes, Character, ARMOR_DEFINITION},
+    config::{DoubleJumpConfig, DownedStateConfig, HealthRegenerationConfig},
     control_scheme::{ControlButton, ControlScheme},
     create_display_material,
-    door::DoorContainer,
+    door::{door_ref, DoorContainer},
     elevator::{
         call_button::{CallButtonContainer, CallButtonKind},
-        ElevatorContainer,
+        interaction_radius, ElevatorContainer,
     },
-    gui::journal::Journal,
+    gamepad::GamepadInput,
+    gui::{journal::Journal, low_health::LOW_HEALTH, weapon_display::AmmoStatus},
     inventory::Inventory,
-    item::{ItemContainer, ItemKind},
-    level::UpdateContext,
+    item::{Item, ItemContainer, ItemKind},
+    level::{
+        ladder::LadderContainer,
+        terminal::TerminalContainer,
+        transition::{level_transition_ref, LevelTransitionContainer},
+        UpdateContext,
+    },
     message::Message,
     player::{
         camera::CameraController,
+        flash_light::{BatteryCharge, FLASH_LIGHT_BATTERY},
+        hotbar::{Hotbar, HOTBAR_SIZE},
+        interaction::Interaction,
         lower_body::{LowerBodyMachine, LowerBodyMachineInput},
         upper_body::{CombatWeaponKind, UpperBodyMachine, UpperBodyMachineInput},
     },
+    sound::NoiseKind,
+    stats::SessionStats,
+    utils::deviate,
     weapon::{
-        definition::WeaponKind,
-        projectile::{ProjectileKind, Shooter},
-        WeaponContainer,
+        definition::{FireMode, ScopeDefinition, WeaponDefinition, WeaponKind},
+        projectile::{ProjectileContainer, ProjectileKind, Shooter},
+        Weapon, WeaponContainer, WeaponPersistentData, JAM_SOUND,
     },
     CollisionGroups, GameTime, MessageSender,
 };
@@ -31,10 +44,10 @@ use fyrox::{
         Animation,
     },
     core::{
-        algebra::{Matrix4, UnitQuaternion, Vector3},
+        algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3},
         color::Color,
         color_gradient::{ColorGradient, ColorGradientBuilder, GradientPoint},
-        math::{self, SmoothAngle, Vector3Ext},
+        math::{self, ray::Ray, SmoothAngle, Vector3Ext},
         parking_lot::Mutex,
         pool::Handle,
         sstorage::ImmutableString,
@@ -42,12 +55,17 @@ use fyrox::{
     },
     engine::resource_manager::ResourceManager,
     event::{DeviceEvent, ElementState, Event, MouseScrollDelta, WindowEvent},
-    material::{shader::SamplerFallback, PropertyValue},
+    lazy_static::lazy_static,
+    material::{shader::SamplerFallback, Material, PropertyValue},
+    rand::{thread_rng, Rng},
     resource::{model::Model, texture::Texture},
     scene::{
         base::BaseBuilder,
         collider::{BitMask, ColliderBuilder, ColliderShape, InteractionGroups},
-        graph::physics::CoefficientCombineRule,
+        graph::{
+            physics::{CoefficientCombineRule, Intersection, RayCastOptions},
+            Graph,
+        },
         light::{spot::SpotLightBuilder, BaseLight, BaseLightBuilder},
         mesh::{
             surface::{SurfaceBuilder, SurfaceData},
@@ -62,15 +80,161 @@ use fyrox::{
     },
     utils::log::Log,
 };
+use serde::Deserialize;
 use std::{
+    fs::File,
     ops::{Deref, DerefMut},
+    path::PathBuf,
     sync::Arc,
 };
 
 mod camera;
+mod flash_light;
+pub mod hotbar;
+pub mod interaction;
 mod lower_body;
 mod upper_body;
 
+#[derive(Deserialize)]
+pub struct PlayerDefinition {
+    pub pain_sounds: Vec<String>,
+    #[serde(default)]
+    pub death_sounds: Vec<String>,
+    /// Whether the player should turn into a ragdoll on death instead of playing dying animation.
+    #[serde(default)]
+    pub ragdoll: bool,
+}
+
+impl PlayerDefinition {
+    fn load() -> Self {
+        let file = File::open("data/configs/player.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    pub static ref PLAYER_DEFINITION: PlayerDefinition = PlayerDefinition::load();
+}
+
+/// Radius in which bots can hear footsteps of the player while walking.
+const WALK_NOISE_RADIUS: f32 = 3.0;
+/// Radius in which bots can hear footsteps of the player while running.
+const RUN_NOISE_RADIUS: f32 = 10.0;
+
+const BODY_RADIUS: f32 = 0.2;
+/// Half of the height of the cylindrical part of the capsule.
+const BODY_HEIGHT: f32 = 0.25;
+/// Distance from the center of the capsule to the feet.
+const FEET_OFFSET: f32 = BODY_HEIGHT + BODY_RADIUS;
+
+/// Maximum height (above the feet) of a ledge that the player can climb on.
+const MANTLE_MAX_HEIGHT: f32 = 0.6;
+/// Obstacles lower than this are just stepped over.
+const MANTLE_MIN_HEIGHT: f32 = 0.15;
+/// Maximum distance (from the center of the capsule) to a ledge in front of the player.
+const MANTLE_REACH: f32 = 0.45;
+/// Duration (in seconds) of mantling.
+const MANTLE_DURATION: f32 = 0.5;
+
+/// Distance between the capsule and the ladder while climbing.
+const LADDER_OFFSET: f32 = 0.1;
+
+/// Duration (in seconds) of a slide.
+const SLIDE_DURATION: f32 = 0.9;
+/// Minimal run factor at which the player is able to slide.
+const SLIDE_MIN_RUN_FACTOR: f32 = 0.8;
+/// How fast the player can change direction while sliding, 1.0 means instantly.
+const SLIDE_STEERING: f32 = 0.02;
+/// Height of the capsule while sliding relative to its normal height.
+const SLIDE_CAPSULE_SCALE: f32 = 0.5;
+/// Multiplier for the recoil of shots made while sliding.
+const SLIDE_RECOIL_FACTOR: f32 = 2.5;
+/// Height of the capsule while crouching relative to its normal height.
+const CROUCH_CAPSULE_SCALE: f32 = 0.65;
+/// Multiplier for the movement speed while crouching.
+const CROUCH_SPEED_FACTOR: f32 = 0.5;
+/// Angle (in degrees) at which the model leans forward while crouching.
+const CROUCH_LEAN_ANGLE: f32 = 15.0;
+/// Multiplier for the recoil and the spread of shots made while standing still crouched. Shots
+/// still go along the barrel, so they stay in line with the laser sight.
+const CROUCH_STABILITY_FACTOR: f32 = 0.6;
+/// Maximum distance of the point under the crosshair at which projectiles converge.
+const MAX_CONVERGENCE_DISTANCE: f32 = 200.0;
+/// Angle (in degrees) at which the weapon is lowered while sprinting.
+const SPRINT_WEAPON_LOWER_ANGLE: f32 = 25.0;
+/// Sprint effects (wider view and speed lines) start when run factor exceeds this value.
+const SPRINT_EFFECT_THRESHOLD: f32 = 0.5;
+
+/// Health of the player in the downed state, any further damage is lethal.
+const DOWNED_HEALTH: f32 = 1.0;
+/// Angle (in degrees) at which the model leans forward in the downed state.
+const DOWNED_LEAN_ANGLE: f32 = 75.0;
+/// Items that are used to revive from the downed state, in order of preference.
+const REVIVE_ITEMS: [ItemKind; 2] = [ItemKind::Medpack, ItemKind::Medkit];
+
+/// Height of the armor bar at full armor, in units of the health rig.
+const ARMOR_BAR_HEIGHT: f32 = 10.0;
+const ARMOR_BAR_COLOR: Color = Color::opaque(40, 120, 255);
+
+/// Angle (in degrees) of the aim punch per point of received damage.
+const FLINCH_PER_DAMAGE: f32 = 0.15;
+/// Maximum angle (in degrees) of the aim punch of a single hit.
+const MAX_FLINCH_ANGLE: f32 = 4.0;
+/// Multiplier for the aim punch while the player has armor.
+const FLINCH_ARMOR_FACTOR: f32 = 0.5;
+/// How fast (in rad/s) the aim recovers from a punch.
+const FLINCH_RECOVERY_SPEED: f32 = 0.6;
+
+/// Angle (in degrees) around the crosshair in which the aim assist looks for hit boxes.
+const AIM_ASSIST_ANGLE: f32 = 6.0;
+/// Hit boxes further than this are ignored by the aim assist.
+const AIM_ASSIST_RANGE: f32 = 30.0;
+/// Multiplier for the turn speed of the stick near a target at full aim assist strength.
+const AIM_ASSIST_SLOWDOWN: f32 = 0.5;
+/// Fraction of the angle to a target by which the camera is pulled per second at full aim assist
+/// strength.
+const AIM_ASSIST_PULL: f32 = 3.0;
+
+/// Time (in seconds) during which the direction of received damage is shown.
+const DAMAGE_INDICATOR_LIFETIME: f32 = 1.0;
+
+/// Initial speed of grenades tossed by the player.
+const GRENADE_THROW_SPEED: f32 = 15.0;
+/// Initial speed of grenades tossed with full charge.
+const GRENADE_MAX_THROW_SPEED: f32 = 22.0;
+/// Time (in seconds) of holding the toss button needed to fully charge a throw.
+const GRENADE_CHARGE_TIME: f32 = 1.0;
+/// Upward component added to the throw direction, makes grenades fly in an arc.
+const GRENADE_THROW_ARC: f32 = 0.3;
+/// Live grenades closer than this are shown on the HUD.
+const GRENADE_WARNING_RADIUS: f32 = 6.0;
+/// Maximum distance at which the player can pick up a live grenade to throw it back.
+const GRENADE_THROW_BACK_DISTANCE: f32 = 1.2;
+/// Maximum distance to an enemy that can be hit in close combat.
+const MELEE_DISTANCE: f32 = 1.2;
+/// Maximum angle (in degrees) between look direction of the player and direction to an enemy
+/// that can be hit in close combat.
+const MELEE_ANGLE: f32 = 45.0;
+/// Maximum angle (in degrees) between look direction of an enemy and direction from the player
+/// to the enemy at which the player is considered standing behind the enemy.
+const TAKEDOWN_ANGLE: f32 = 60.0;
+/// Damage of a melee hit on an enemy that is aware of the player.
+const MELEE_DAMAGE: f32 = 15.0;
+/// Duration (in seconds) of the takedown animation.
+const TAKEDOWN_DURATION: f32 = 0.8;
+
+/// Fraction of move and aim speed that is lost per kilogram of weight of the equipped weapon.
+const WEAPON_WEIGHT_PENALTY: f32 = 0.02;
+/// Heavy weapons never slow the player down more than that.
+const MAX_WEAPON_WEIGHT_PENALTY: f32 = 0.2;
+
+/// Items that can be tossed using toss grenade button, in switching order.
+const GRENADE_ITEMS: [ItemKind; 3] = [
+    ItemKind::Grenade,
+    ItemKind::SmokeGrenade,
+    ItemKind::Flashbang,
+];
+
 pub struct HitReactionStateDefinition {
     state: Handle<State>,
     hit_reaction_rifle_animation: Handle<Animation>,
@@ -129,6 +293,36 @@ pub fn make_hit_reaction_state(
     }
 }
 
+/// Tuning of jumps and movement in the air.
+#[derive(Visit, Clone, Debug)]
+pub struct JumpSettings {
+    /// Vertical speed (in m/s) that the player gets at the beginning of a jump.
+    pub strength: f32,
+    /// Multiplier of the gravity that affects the player.
+    pub gravity_scale: f32,
+    /// How quickly the player can change direction in the air, 1.0 means the same as on the
+    /// ground and 0.0 means no control at all.
+    pub air_control: f32,
+    /// Vertical speed (in m/s) that the player gets from the second jump.
+    #[visit(optional)]
+    pub double_jump_strength: f32,
+    /// Time (in seconds) after the second jump before it can be used again.
+    #[visit(optional)]
+    pub double_jump_cooldown: f32,
+}
+
+impl Default for JumpSettings {
+    fn default() -> Self {
+        Self {
+            strength: 3.0,
+            gravity_scale: 1.0,
+            air_control: 1.0,
+            double_jump_strength: 3.0,
+            double_jump_cooldown: 1.0,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct InputController {
     walk_forward: bool,
@@ -140,11 +334,46 @@ pub struct InputController {
     pitch: f32,
     aim: bool,
     toss_grenade: bool,
+    throw_knife: bool,
     shoot: bool,
     run: bool,
+    slide: bool,
+    crouch: bool,
     action: bool,
     cursor_up: bool,
     cursor_down: bool,
+    /// Physical state of the aim button, it is used to ignore key repeats when aiming is toggled.
+    aim_pressed: bool,
+    /// Physical state of the run button, it is used to ignore key repeats when running is toggled.
+    run_pressed: bool,
+    /// Physical state of the crouch button, it is used to ignore key repeats when crouching is
+    /// toggled.
+    crouch_pressed: bool,
+    /// Physical state of the jump button, it is used to ignore key repeats in the air.
+    jump_pressed: bool,
+    double_jump: bool,
+    /// Hotbar slot which item should be used on the next update.
+    use_hotbar_slot: Option<usize>,
+    /// Physical state of the hotbar buttons, it is used to ignore key repeats, so holding a
+    /// button does not waste items.
+    hotbar_pressed: [bool; HOTBAR_SIZE],
+    /// Turn speed (in radians per second) given by the right stick of a gamepad.
+    stick_look: Vector2<f32>,
+    /// Physical state of the aim trigger of a gamepad.
+    gamepad_aim_pressed: bool,
+    /// Strength of the aim assist, zero when it is disabled.
+    aim_assist_strength: f32,
+}
+
+/// Updates state of an action that is either held or toggled by a button.
+fn hold_or_toggle(action: &mut bool, pressed: &mut bool, toggle: bool, state: ElementState) {
+    let is_pressed = state == ElementState::Pressed;
+    if !toggle {
+        *action = is_pressed;
+    } else if is_pressed && !*pressed {
+        *action = !*action;
+    }
+    *pressed = is_pressed;
 }
 
 impl Deref for Player {
@@ -185,8 +414,13 @@ impl Default for RequiredWeapon {
 pub struct PlayerPersistentData {
     pub inventory: Inventory,
     pub health: f32,
+    pub armor: f32,
     pub current_weapon: u32,
-    pub weapons: Vec<WeaponKind>,
+    pub weapons: Vec<WeaponPersistentData>,
+    pub journal: Journal,
+    pub flash_light_battery: f32,
+    pub stats: SessionStats,
+    pub hotbar: Hotbar,
 }
 
 #[derive(Default, Visit)]
@@ -204,6 +438,9 @@ pub struct Player {
     hips: Handle<Node>,
     move_speed: f32,
     weapon_change_direction: RequiredWeapon,
+    /// Kind of the weapon that was held before the last weapon change.
+    #[visit(optional)]
+    last_weapon: Option<WeaponKind>,
     weapon_yaw_correction: SmoothAngle,
     weapon_pitch_correction: SmoothAngle,
     weapon_origin: Handle<Node>,
@@ -217,12 +454,128 @@ pub struct Player {
     journal_display: Handle<Node>,
     item_display: Handle<Node>,
     health_cylinder: Handle<Node>,
+    /// Open state of the displays, the displays are shown either in the world (on the wrist and
+    /// on the weapon) or on the HUD, depending on the settings.
+    #[visit(optional)]
+    inventory_open: bool,
+    #[visit(optional)]
+    journal_open: bool,
+    #[visit(skip)]
+    weapon_display_open: bool,
+    #[visit(optional)]
+    armor_bar: Handle<Node>,
     last_health: f32,
     health_color_gradient: ColorGradient,
     v_recoil: SmoothAngle,
     h_recoil: SmoothAngle,
     rig_light: Handle<Node>,
     pub journal: Journal,
+    #[visit(optional)]
+    stats: SessionStats,
+    #[visit(optional)]
+    grenade_index: u32,
+    /// Charge of the grenade throw in `[0; 1]` range, grows while the toss button is held.
+    #[visit(skip)]
+    grenade_charge: f32,
+    /// Charge of the battery of the flash light.
+    #[visit(optional)]
+    flash_light_battery: BatteryCharge,
+    #[visit(optional)]
+    hotbar: Hotbar,
+    #[visit(optional)]
+    pub jump_settings: JumpSettings,
+    /// Whether the second jump was made during current airborne period.
+    #[visit(skip)]
+    double_jump_used: bool,
+    #[visit(skip)]
+    double_jump_cooldown: f32,
+    #[visit(skip)]
+    flash_timer: f32,
+    #[visit(skip)]
+    flash_duration: f32,
+    /// State of the trigger on the previous frame, used to detect trigger pulls.
+    #[visit(skip)]
+    trigger_held: bool,
+    /// Amount of shots left to do for the last trigger pull in single or burst fire modes.
+    #[visit(skip)]
+    queued_shots: u32,
+    /// Aim settle factor after sprinting, 1.0 right after sprint and 0.0 when the aim is settled.
+    #[visit(skip)]
+    sprint_settle: f32,
+    /// The closest thing that the player can interact with.
+    #[visit(skip)]
+    interaction: Option<Interaction>,
+    #[visit(skip)]
+    slide: Option<Slide>,
+    #[visit(optional)]
+    crouch: bool,
+    /// Normal shape of the capsule while it is lowered by a slide or crouching, `None` if the
+    /// capsule has its normal shape. Lowered capsule is saved with the scene, so the shape must be saved too.
+    #[visit(optional)]
+    standing_capsule: Option<ColliderShape>,
+    #[visit(skip)]
+    mantle: Option<Mantle>,
+    #[visit(skip)]
+    climb: Option<Climb>,
+    /// Time (in seconds) left until the end of the takedown animation.
+    #[visit(skip)]
+    takedown_time: f32,
+    /// Health on the previous frame, it is used to detect damage.
+    #[visit(skip)]
+    prev_health: f32,
+    /// Time (in seconds) since the player has taken damage last time.
+    #[visit(skip)]
+    time_since_damage: f32,
+    #[visit(skip)]
+    damage_indicators: Vec<DamageIndicator>,
+    /// Aim punch of received hits, it is added to the pitch and yaw of the camera.
+    #[visit(skip)]
+    flinch_pitch: SmoothAngle,
+    #[visit(skip)]
+    flinch_yaw: SmoothAngle,
+    /// Time (in seconds) left to revive, `None` if the player is not downed.
+    #[visit(optional)]
+    downed_timer: Option<f32>,
+}
+
+/// Direction of received damage that is shown on the HUD for a while.
+#[derive(Clone, Debug)]
+struct DamageIndicator {
+    /// Position of the attacker at the moment of the hit.
+    source: Vector3<f32>,
+    lifetime: f32,
+}
+
+/// Runtime state of climbing a ladder.
+#[derive(Clone, Debug)]
+struct Climb {
+    /// Position of the body on the ladder, Y is ignored.
+    anchor: Vector3<f32>,
+    /// Height of the body at the bottom of the ladder.
+    min_height: f32,
+    /// Height of the body at the top of the ladder.
+    max_height: f32,
+    /// Horizontal direction from the ladder towards the climber.
+    normal: Vector3<f32>,
+    speed: f32,
+}
+
+/// Runtime state of climbing onto a ledge.
+#[derive(Clone, Debug)]
+struct Mantle {
+    start: Vector3<f32>,
+    target: Vector3<f32>,
+    elapsed: f32,
+}
+
+/// Runtime state of a slide, that is performed by pressing crouch button while running. The slide
+/// lasts until there is enough room to stand up, even if its momentum is spent.
+#[derive(Clone, Debug)]
+struct Slide {
+    direction: Vector3<f32>,
+    /// Speed (in units per second) at the beginning of the slide.
+    speed: f32,
+    elapsed: f32,
 }
 
 fn make_color_gradient() -> ColorGradient {
@@ -244,8 +597,8 @@ impl Player {
         journal_texture: Texture,
         persistent_data: Option<PlayerPersistentData>,
     ) -> Self {
-        let body_radius = 0.2;
-        let body_height = 0.25;
+        let body_radius = BODY_RADIUS;
+        let body_height = BODY_HEIGHT;
 
         let (model_resource, health_rig_resource) = fyrox::core::futures::join!(
             resource_manager.request_model("data/models/agent/agent.rgs"),
@@ -376,6 +729,36 @@ impl Player {
 
         let health_cylinder = scene.graph.find_by_name(health_rig, "HealthCylinder");
 
+        // Armor is shown as a bar next to the health cylinder, its height depends on amount of
+        // armor.
+        let armor_bar = MeshBuilder::new(
+            BaseBuilder::new()
+                .with_cast_shadows(false)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(4.0, 0.0, 0.0))
+                        .build(),
+                ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(Arc::new(Mutex::new(
+            SurfaceData::make_cylinder(8, 0.5, ARMOR_BAR_HEIGHT, true, &Matrix4::identity()),
+        )))
+        .with_material(Arc::new(Mutex::new({
+            let mut material = Material::standard();
+            Log::verify(material.set_property(
+                &ImmutableString::new("diffuseColor"),
+                PropertyValue::Color(ARMOR_BAR_COLOR),
+            ));
+            Log::verify(material.set_property(
+                &ImmutableString::new("emissionStrength"),
+                PropertyValue::Vector3(ARMOR_BAR_COLOR.as_frgb().scale(10.0)),
+            ));
+            material
+        })))
+        .build()])
+        .build(&mut scene.graph);
+        scene.graph.link_nodes(armor_bar, health_rig);
+
         let weapon_display = MeshBuilder::new(BaseBuilder::new().with_cast_shadows(false))
             .with_surfaces(vec![SurfaceBuilder::new(Arc::new(Mutex::new(
                 SurfaceData::make_quad(&Matrix4::new_scaling(0.07)),
@@ -440,21 +823,42 @@ impl Player {
         .build(&mut scene.graph);
         scene.graph.link_nodes(journal_display, pivot);
 
-        let (health, inventory, current_weapon) = if let Some(persistent_data) = persistent_data {
-            (
-                persistent_data.health,
-                persistent_data.inventory,
-                persistent_data.current_weapon,
-            )
-        } else {
-            let mut inventory = Inventory::new();
-
-            inventory.add_item(ItemKind::Medpack, 2);
-            inventory.add_item(ItemKind::Ammo, 100);
-            inventory.add_item(ItemKind::Grenade, 2);
-
-            (100.0, inventory, 0)
-        };
+        let (health, armor, inventory, current_weapon, journal, flash_light_battery, stats, hotbar) =
+            if let Some(persistent_data) = persistent_data {
+                (
+                    persistent_data.health,
+                    persistent_data.armor,
+                    persistent_data.inventory,
+                    persistent_data.current_weapon,
+                    persistent_data.journal,
+                    persistent_data.flash_light_battery,
+                    persistent_data.stats,
+                    persistent_data.hotbar,
+                )
+            } else {
+                let mut inventory = Inventory::new();
+
+                inventory.add_item(ItemKind::Medpack, 2);
+                inventory.add_item(ItemKind::Ammo, 100);
+                inventory.add_item(ItemKind::Grenade, 2);
+                inventory.add_item(ItemKind::ThrowingKnife, 3);
+
+                // Starting items are put on the hotbar, so new players could see how it works.
+                let mut hotbar = Hotbar::default();
+                hotbar.assign(0, ItemKind::Medpack);
+                hotbar.assign(1, ItemKind::Grenade);
+
+                (
+                    100.0,
+                    0.0,
+                    inventory,
+                    0,
+                    Journal::new(),
+                    FLASH_LIGHT_BATTERY.capacity,
+                    SessionStats::default(),
+                    hotbar,
+                )
+            };
 
         Self {
             character: Character {
@@ -464,6 +868,7 @@ impl Player {
                 weapon_pivot,
                 hit_boxes: find_hit_boxes(pivot, scene),
                 health,
+                armor,
                 current_weapon,
                 inventory,
                 ..Default::default()
@@ -480,6 +885,7 @@ impl Player {
             },
             lower_body_machine: locomotion_machine,
             health_cylinder,
+            armor_bar,
             upper_body_machine: combat_machine,
             spine: scene.graph.find_by_name(model_handle, "mixamorig:Spine"),
             hips: scene.graph.find_by_name(model_handle, "mixamorig:Hips"),
@@ -495,6 +901,7 @@ impl Player {
                 speed: 10.0,
             },
             weapon_change_direction: RequiredWeapon::None,
+            last_weapon: None,
             weapon_yaw_correction: SmoothAngle {
                 angle: 0.0,
                 target: 30.0f32.to_radians(),
@@ -525,27 +932,377 @@ impl Player {
                 speed: 1.5, // rad/s
             },
             journal_display,
-            journal: Journal::new(),
+            inventory_open: false,
+            journal_open: false,
+            weapon_display_open: false,
+            journal,
+            stats,
+            grenade_index: 0,
+            grenade_charge: 0.0,
+            flash_light_battery: BatteryCharge(flash_light_battery),
+            hotbar,
+            jump_settings: Default::default(),
+            double_jump_used: false,
+            double_jump_cooldown: 0.0,
+            flash_timer: 0.0,
+            flash_duration: 0.0,
+            trigger_held: false,
+            queued_shots: 0,
+            sprint_settle: 0.0,
+            interaction: None,
+            slide: None,
+            crouch: false,
+            standing_capsule: None,
+            mantle: None,
+            climb: None,
+            takedown_time: 0.0,
+            prev_health: health,
+            time_since_damage: 0.0,
+            damage_indicators: Default::default(),
+            flinch_pitch: Default::default(),
+            flinch_yaw: Default::default(),
+            downed_timer: None,
         }
     }
 
-    pub fn persistent_data(&self, weapons: &WeaponContainer) -> PlayerPersistentData {
+    /// Releases every held button, keeping the direction of view.
+    pub fn reset_input(&mut self) {
+        self.controller = InputController {
+            yaw: self.controller.yaw,
+            pitch: self.controller.pitch,
+            ..Default::default()
+        };
+    }
+
+    pub fn persistent_data(
+        &self,
+        weapons: &WeaponContainer,
+        graph: &Graph,
+    ) -> PlayerPersistentData {
         PlayerPersistentData {
             inventory: self.inventory.clone(),
             health: self.health,
+            armor: self.armor,
             current_weapon: self.current_weapon,
             weapons: self
                 .weapons
                 .iter()
-                .map(|w| weapons[*w].kind())
+                .map(|w| weapons[*w].persistent_data(graph))
                 .collect::<Vec<_>>(),
+            journal: self.journal.for_next_level(),
+            flash_light_battery: self.flash_light_battery.0,
+            stats: self.stats.clone(),
+            hotbar: self.hotbar.clone(),
         }
     }
 
+    /// Returns intensity of sprint effects in `[0; 1]` range, it follows run factor once it has
+    /// crossed the threshold.
+    pub fn sprint_intensity(&self) -> f32 {
+        if self.is_dead() {
+            0.0
+        } else {
+            ((self.run_factor - SPRINT_EFFECT_THRESHOLD) / (1.0 - SPRINT_EFFECT_THRESHOLD))
+                .clamp(0.0, 1.0)
+        }
+    }
+
+    pub fn stats(&self) -> &SessionStats {
+        &self.stats
+    }
+
+    pub fn stats_mut(&mut self) -> &mut SessionStats {
+        &mut self.stats
+    }
+
     pub fn camera_controller(&self) -> &CameraController {
         &self.camera_controller
     }
 
+    pub fn selected_grenade(&self) -> ItemKind {
+        GRENADE_ITEMS[self.grenade_index as usize % GRENADE_ITEMS.len()]
+    }
+
+    pub fn hotbar(&self) -> &Hotbar {
+        &self.hotbar
+    }
+
+    pub fn hotbar_mut(&mut self) -> &mut Hotbar {
+        &mut self.hotbar
+    }
+
+    /// Uses a consumable from the hotbar slot that was pressed, grenades are tossed right when
+    /// their slot is pressed, so they are not handled here.
+    fn use_hotbar_item(&mut self, self_handle: Handle<Actor>, sender: &MessageSender) {
+        let item = match self
+            .controller
+            .use_hotbar_slot
+            .take()
+            .and_then(|index| self.hotbar.slot(index))
+        {
+            Some(item) => item,
+            None => return,
+        };
+
+        if Item::get_definition(item).consumable
+            && self.inventory.try_extract_exact_items(item, 1) == 1
+        {
+            sender.send(Message::UseItem {
+                actor: self_handle,
+                kind: item,
+            });
+            sender.send(Message::SyncInventory);
+        }
+    }
+
+    fn start_grenade_toss(&mut self, scene: &mut Scene) {
+        self.controller.toss_grenade = true;
+        self.grenade_charge = 0.0;
+        scene
+            .animations
+            .get_mut(self.upper_body_machine.toss_grenade_animation)
+            .set_enabled(true)
+            .rewind();
+    }
+
+    /// Selects next grenade type that is present in the inventory.
+    fn switch_grenade(&mut self) {
+        for offset in 1..=GRENADE_ITEMS.len() {
+            let index = (self.grenade_index as usize + offset) % GRENADE_ITEMS.len();
+            if self.inventory.item_count(GRENADE_ITEMS[index]) > 0 {
+                self.grenade_index = index as u32;
+                break;
+            }
+        }
+    }
+
+    /// Blinds the player for given amount of seconds.
+    pub fn flash(&mut self, duration: f32) {
+        // Do not shorten current flash if the new one is weaker.
+        if duration > self.flash_timer {
+            self.flash_timer = duration;
+            self.flash_duration = duration;
+        }
+    }
+
+    /// Returns current flash intensity in `[0; 1]` range, it fades out over time.
+    pub fn flash_intensity(&self) -> f32 {
+        if self.flash_duration > 0.0 {
+            (self.flash_timer / self.flash_duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns intensity of low health feedback in `[0; 1]` range, zero means that health is
+    /// above the threshold.
+    pub fn low_health_intensity(&self) -> f32 {
+        if self.is_dead() || self.health >= LOW_HEALTH.threshold {
+            0.0
+        } else {
+            (1.0 - self.health / LOW_HEALTH.threshold.max(f32::EPSILON)).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Pushes the camera back by given distance, it is used as a feedback on hits.
+    pub fn kick_camera(&mut self, distance: f32) {
+        self.camera_controller.kick(distance);
+    }
+
+    /// Disturbs aim of the player by a random punch that is proportional to the damage, the aim
+    /// quickly recovers after that. Armor softens the punch.
+    pub fn flinch(&mut self, damage: f32) {
+        if damage <= 0.0 || self.is_dead() {
+            return;
+        }
+
+        let armor_factor = if self.armor > 0.0 {
+            FLINCH_ARMOR_FACTOR
+        } else {
+            1.0
+        };
+        let angle = (damage * FLINCH_PER_DAMAGE * armor_factor)
+            .min(MAX_FLINCH_ANGLE)
+            .to_radians();
+
+        let mut rng = thread_rng();
+        // Hits always push the aim up, horizontal direction is random.
+        let pitch = (self.flinch_pitch.angle - angle * rng.gen_range(0.5..1.0)).clamp(
+            -MAX_FLINCH_ANGLE.to_radians(),
+            MAX_FLINCH_ANGLE.to_radians(),
+        );
+        let yaw = (self.flinch_yaw.angle + angle * rng.gen_range(-1.0..1.0)).clamp(
+            -MAX_FLINCH_ANGLE.to_radians(),
+            MAX_FLINCH_ANGLE.to_radians(),
+        );
+
+        self.flinch_pitch = SmoothAngle {
+            angle: pitch,
+            target: 0.0,
+            speed: FLINCH_RECOVERY_SPEED,
+        };
+        self.flinch_yaw = SmoothAngle {
+            angle: yaw,
+            target: 0.0,
+            speed: FLINCH_RECOVERY_SPEED,
+        };
+    }
+
+    /// Remembers position of an attacker to show the direction the damage came from.
+    pub fn add_damage_indicator(&mut self, source: Vector3<f32>) {
+        self.damage_indicators.push(DamageIndicator {
+            source,
+            lifetime: DAMAGE_INDICATOR_LIFETIME,
+        });
+    }
+
+    /// Returns angles (in radians, clockwise from the top of the screen) towards recent
+    /// attackers, together with opacity of each indicator.
+    pub fn damage_indicators(&self, graph: &Graph) -> Vec<(f32, f32)> {
+        self.damage_indicators
+            .iter()
+            .map(|indicator| {
+                let angle = self.screen_angle_to(indicator.source, graph);
+                let opacity = (indicator.lifetime / DAMAGE_INDICATOR_LIFETIME).clamp(0.0, 1.0);
+                (angle, opacity)
+            })
+            .collect()
+    }
+
+    /// Returns angle (in radians, clockwise from the top of the screen) towards the closest live
+    /// grenade, together with its remaining fuse time.
+    pub fn grenade_indicator(
+        &self,
+        projectiles: &ProjectileContainer,
+        graph: &Graph,
+    ) -> Option<(f32, f32)> {
+        if self.is_dead() {
+            return None;
+        }
+
+        projectiles
+            .closest_live_grenade(self.position(graph), GRENADE_WARNING_RADIUS, graph)
+            .map(|(_, grenade)| {
+                (
+                    self.screen_angle_to(grenade.get_position(graph), graph),
+                    grenade.fuse(),
+                )
+            })
+    }
+
+    /// Returns angle (in radians, clockwise from the top of the screen) towards given point.
+    fn screen_angle_to(&self, point: Vector3<f32>, graph: &Graph) -> f32 {
+        let inv_yaw = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), -self.controller.yaw);
+        let local = inv_yaw * (point - self.position(graph));
+        // Local X axis points to the left side of the screen.
+        (-local.x).atan2(local.z)
+    }
+
+    /// Returns amount of shots that can be made from current weapon with ammo in the inventory.
+    pub fn shots_left(&self, weapons: &WeaponContainer) -> u32 {
+        weapons.try_get(self.current_weapon()).map_or(0, |weapon| {
+            self.inventory.item_count(ItemKind::Ammo)
+                / weapon.definition.ammo_consumption_per_shot.max(1)
+        })
+    }
+
+    pub fn ammo_status(&self, weapons: &WeaponContainer) -> AmmoStatus {
+        match weapons.try_get(self.current_weapon()) {
+            Some(weapon) => {
+                AmmoStatus::new(self.shots_left(weapons), weapon.definition.low_ammo_shots)
+            }
+            None => AmmoStatus::Normal,
+        }
+    }
+
+    fn is_aiming_down_sights(&self) -> bool {
+        !self.is_dead()
+            && self.controller.aim
+            && self.upper_body_machine.machine.active_state() == self.upper_body_machine.aim_state
+    }
+
+    /// Returns scope of current weapon if the player is looking through it.
+    pub fn active_scope(&self, weapons: &WeaponContainer) -> Option<&'static ScopeDefinition> {
+        if !self.is_aiming_down_sights() {
+            return None;
+        }
+
+        weapons.try_get(self.current_weapon()).and_then(|weapon| {
+            let definition: &'static WeaponDefinition = weapon.definition;
+            definition.scope.as_ref()
+        })
+    }
+
+    /// Returns zoom of an optic attachment of current weapon if the player is aiming.
+    fn optic_magnification(&self, weapons: &WeaponContainer) -> f32 {
+        if !self.is_aiming_down_sights() {
+            return 1.0;
+        }
+
+        weapons
+            .try_get(self.current_weapon())
+            .and_then(|weapon| weapon.optic_magnification())
+            .unwrap_or(1.0)
+    }
+
+    /// Puts the player in the downed state instead of killing, does nothing if the player is
+    /// downed already - lethal damage in the downed state kills.
+    pub fn go_down(&mut self, config: &DownedStateConfig) {
+        if self.is_dead() && self.downed_timer.is_none() {
+            self.health = DOWNED_HEALTH;
+            self.last_health = DOWNED_HEALTH;
+            self.downed_timer = Some(config.duration);
+            self.interaction = None;
+            self.controller.aim = false;
+            self.controller.shoot = false;
+            self.controller.run = false;
+        }
+    }
+
+    pub fn revive(&mut self) {
+        self.downed_timer = None;
+    }
+
+    pub fn is_downed(&self) -> bool {
+        self.downed_timer.is_some()
+    }
+
+    /// Returns time (in seconds) left to revive, `None` if the player is not downed.
+    pub fn downed_time_left(&self) -> Option<f32> {
+        self.downed_timer
+    }
+
+    /// Counts down time to revive, the player dies when it is over. The action button uses
+    /// the first available revive item.
+    fn update_downed(&mut self, self_handle: Handle<Actor>, sender: &MessageSender, dt: f32) {
+        let downed_timer = match self.downed_timer.as_mut() {
+            Some(downed_timer) => downed_timer,
+            None => return,
+        };
+
+        *downed_timer -= dt;
+        if *downed_timer <= 0.0 {
+            self.downed_timer = None;
+            self.health = 0.0;
+            return;
+        }
+
+        if self.controller.action {
+            self.controller.action = false;
+
+            if let Some(kind) = REVIVE_ITEMS
+                .into_iter()
+                .find(|&kind| self.inventory.try_extract_exact_items(kind, 1) == 1)
+            {
+                sender.send(Message::UseItem {
+                    actor: self_handle,
+                    kind,
+                });
+                sender.send(Message::SyncInventory);
+            }
+        }
+    }
+
     pub fn can_be_removed(&self, _scene: &Scene) -> bool {
         self.health <= 0.0
     }
@@ -557,34 +1314,283 @@ impl Player {
         items: &ItemContainer,
         sender: &MessageSender,
     ) {
-        for (item_handle, item) in items.pair_iter() {
-            let self_position = scene.graph[self.pivot].global_position();
-            let item_position = scene.graph[item.get_pivot()].global_position();
+        let self_position = scene.graph[self.pivot].global_position();
+
+        if let Some((item_handle, item)) = items.first_interactable(self_position, &scene.graph) {
+            let item_position = item.position(&scene.graph);
 
-            let distance = (item_position - self_position).norm();
-            if distance < 0.75 {
-                sender.send(Message::ShowItemDisplay {
-                    item: item.get_kind(),
-                    count: item.stack_size,
+            sender.send(Message::ShowItemDisplay {
+                item: item.get_kind(),
+                count: item.stack_size,
+            });
+
+            // Picking up an item with full reserve would consume it for nothing.
+            if self.controller.action && self.inventory.free_space(item.get_kind()) > 0 {
+                sender.send(Message::PickUpItem {
+                    actor: self_handle,
+                    item: item_handle,
                 });
+                sender.send(Message::SyncInventory);
 
-                if self.controller.action {
-                    sender.send(Message::PickUpItem {
-                        actor: self_handle,
-                        item: item_handle,
-                    });
-                    sender.send(Message::SyncInventory);
+                self.controller.action = false;
+            }
+
+            let display = &mut scene.graph[self.item_display];
+            display
+                .local_transform_mut()
+                .set_position(item_position + Vector3::new(0.0, 0.2, 0.0));
+            display.set_visibility(true);
+        }
+    }
+
+    /// Hides the item display that floats above items in the world.
+    pub fn hide_world_hud(&self, graph: &mut Graph) {
+        graph[self.item_display].set_visibility(false);
+    }
+
+    pub fn interaction(&self) -> Option<Interaction> {
+        self.interaction
+    }
+
+    /// Finds the closest thing that the player can interact with using the action button.
+    fn find_interaction(
+        &self,
+        scene: &Scene,
+        items: &ItemContainer,
+        door_container: &DoorContainer,
+        elevator_container: &ElevatorContainer,
+        call_button_container: &CallButtonContainer,
+        ladder_container: &LadderContainer,
+        terminal_container: &TerminalContainer,
+        transitions: &LevelTransitionContainer,
+        projectiles: &ProjectileContainer,
+        targets: &[TargetDescriptor],
+    ) -> Option<Interaction> {
+        let graph = &scene.graph;
+        let self_position = graph[self.pivot].global_position();
+
+        let mut closest = None;
+        let mut closest_distance = f32::MAX;
+        let mut consider = |interaction: Interaction, position: Vector3<f32>| {
+            let distance = position.metric_distance(&self_position);
+            if distance < closest_distance {
+                closest_distance = distance;
+                closest = Some(interaction);
+            }
+        };
+
+        if let Some((_, item)) = items.first_interactable(self_position, graph) {
+            let kind = item.get_kind();
+            let interaction = if self.inventory.free_space(kind) > 0 {
+                Interaction::PickUp(kind)
+            } else {
+                Interaction::ReserveFull(kind)
+            };
+            consider(interaction, item.position(graph));
+        }
+
+        for door_handle in door_container.doors_in_reach(self.position(graph), graph) {
+            let door = door_ref(door_handle, graph);
+            if door.can_be_opened() {
+                consider(Interaction::OpenDoor, door.initial_position());
+            }
+        }
+
+        for (_, elevator) in elevator_container.pair_iter() {
+            let elevator_position = graph[elevator.node].global_position();
+            let last_index = elevator.points.len().saturating_sub(1) as u32;
+            if elevator_position.metric_distance(&self_position)
+                < interaction_radius(&graph[elevator.node])
+                && (elevator.current_floor == 0 || elevator.current_floor == last_index)
+            {
+                consider(Interaction::RideElevator, elevator_position);
+            }
 
-                    self.controller.action = false;
+            for &call_button_handle in elevator.call_buttons.iter() {
+                let button = &graph[call_button_container[call_button_handle].node];
+                if button.global_position().metric_distance(&self_position)
+                    < interaction_radius(button)
+                {
+                    consider(Interaction::CallElevator, button.global_position());
                 }
+            }
+        }
 
-                let display = &mut scene.graph[self.item_display];
-                display
-                    .local_transform_mut()
-                    .set_position(item_position + Vector3::new(0.0, 0.2, 0.0));
-                display.set_visibility(true);
+        if self.climb.is_none() {
+            let feet_position = self_position - Vector3::new(0.0, FEET_OFFSET, 0.0);
+            if let Some(ladder) = ladder_container.find_grabbable(feet_position, graph) {
+                let mut ladder_position = ladder.bottom();
+                ladder_position.y = self_position.y;
+                consider(Interaction::ClimbLadder, ladder_position);
+            }
+        }
 
-                break;
+        if let Some(terminal) = terminal_container.find_usable(self_position, graph) {
+            consider(Interaction::UseTerminal, graph[terminal].global_position());
+        }
+
+        if let Some(transition) = transitions.find_usable(self_position, graph) {
+            consider(Interaction::LeaveLevel, graph[transition].global_position());
+        }
+
+        // Close combat has the lowest priority, because the action button is used for it only
+        // when there is nothing else to interact with.
+        if closest.is_none() {
+            if let Some((_, true)) = self.find_melee_target(graph, targets) {
+                closest = Some(Interaction::Takedown);
+            }
+        }
+
+        if let Some((_, grenade)) = projectiles
+            .closest_live_grenade(self_position, GRENADE_THROW_BACK_DISTANCE, graph)
+            .filter(|(_, grenade)| grenade.can_be_thrown_back())
+        {
+            // Live grenade is the most important thing around.
+            closest = Some(Interaction::ThrowBackGrenade);
+            closest_distance = grenade.get_position(graph).metric_distance(&self_position);
+        }
+
+        closest
+    }
+
+    fn check_grenades(
+        &mut self,
+        self_handle: Handle<Actor>,
+        scene: &Scene,
+        projectiles: &ProjectileContainer,
+        sender: &MessageSender,
+    ) {
+        if !self.controller.action {
+            return;
+        }
+
+        let self_position = scene.graph[self.pivot].global_position();
+        if let Some((handle, _)) = projectiles
+            .closest_live_grenade(self_position, GRENADE_THROW_BACK_DISTANCE, &scene.graph)
+            .filter(|(_, grenade)| grenade.can_be_thrown_back())
+        {
+            let direction = scene.graph[self.camera_controller.camera()].look_vector();
+            sender.send(Message::ThrowBackGrenade {
+                projectile: handle,
+                position: scene.graph[self.weapon_pivot].global_position(),
+                direction,
+                initial_velocity: direction.scale(GRENADE_THROW_SPEED),
+                shooter: Shooter::Actor(self_handle),
+            });
+
+            self.controller.action = false;
+        }
+    }
+
+    /// Returns the closest enemy that can be hit in close combat, together with a flag that tells
+    /// whether the enemy can be taken down silently. Takedown is possible only if the enemy is
+    /// unaware and the player stands behind it.
+    fn find_melee_target(
+        &self,
+        graph: &Graph,
+        targets: &[TargetDescriptor],
+    ) -> Option<(Handle<Actor>, bool)> {
+        let self_position = self.position(graph);
+        let forward =
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.controller.yaw) * Vector3::z();
+
+        targets
+            .iter()
+            .filter(|target| matches!(target.kind, TargetKind::Bot(_)))
+            .filter_map(|target| {
+                let to_target = target.position - self_position;
+                let distance = to_target.norm();
+                let direction =
+                    Vector3::new(to_target.x, 0.0, to_target.z).try_normalize(f32::EPSILON)?;
+                if distance > MELEE_DISTANCE
+                    || direction.dot(&forward) < MELEE_ANGLE.to_radians().cos()
+                {
+                    return None;
+                }
+
+                // The enemy looks away from the player, so the player is behind it.
+                let facing = Vector3::new(target.facing.x, 0.0, target.facing.z)
+                    .try_normalize(f32::EPSILON)?;
+                let is_behind = facing.dot(&direction) >= TAKEDOWN_ANGLE.to_radians().cos();
+
+                Some((target.handle, distance, is_behind && !target.is_aware))
+            })
+            .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+            .map(|(handle, _, silent)| (handle, silent))
+    }
+
+    fn check_melee(
+        &mut self,
+        self_handle: Handle<Actor>,
+        scene: &mut Scene,
+        targets: &[TargetDescriptor],
+        sender: &MessageSender,
+    ) {
+        if !self.controller.action {
+            return;
+        }
+
+        if let Some((victim, silent)) = self.find_melee_target(&scene.graph, targets) {
+            if silent {
+                self.takedown_time = TAKEDOWN_DURATION;
+                self.upper_body_machine
+                    .start_takedown(scene, TAKEDOWN_DURATION);
+                sender.send(Message::Takedown {
+                    attacker: self_handle,
+                    victim,
+                });
+            } else {
+                sender.send(Message::DamageActor {
+                    actor: victim,
+                    who: self_handle,
+                    weapon: Default::default(),
+                    hitbox: None,
+                    amount: MELEE_DAMAGE,
+                    critical_shot_probability: 0.0,
+                });
+            }
+            self.camera_controller.request_shake_camera();
+
+            self.controller.action = false;
+        }
+    }
+
+    fn check_terminals(
+        &mut self,
+        self_handle: Handle<Actor>,
+        scene: &Scene,
+        terminal_container: &TerminalContainer,
+        sender: &MessageSender,
+    ) {
+        if self.controller.action {
+            let self_position = scene.graph[self.pivot].global_position();
+            if let Some(terminal) = terminal_container.find_usable(self_position, &scene.graph) {
+                sender.send(Message::UseTerminal {
+                    terminal,
+                    actor: self_handle,
+                });
+
+                self.controller.action = false;
+            }
+        }
+    }
+
+    fn check_transitions(
+        &mut self,
+        scene: &Scene,
+        transitions: &LevelTransitionContainer,
+        sender: &MessageSender,
+    ) {
+        if self.controller.action {
+            let self_position = scene.graph[self.pivot].global_position();
+            if let Some(transition) = transitions.find_usable(self_position, &scene.graph) {
+                let transition = level_transition_ref(transition, &scene.graph);
+                sender.send(Message::LoadLevel {
+                    path: transition.destination().to_owned(),
+                    spawn_point: transition.spawn_point().map(|s| s.to_owned()),
+                });
+
+                self.controller.action = false;
             }
         }
     }
@@ -616,10 +1622,15 @@ impl Player {
         let graph = &scene.graph;
         let self_position = graph[self.pivot].global_position();
 
+        // Elevators and their call buttons are moving, so they can't be put in a spatial grid,
+        // but there are just a few of them on each level anyway.
         for (handle, elevator) in elevator_container.pair_iter() {
             // Handle floors.
             let elevator_position = graph[elevator.node].global_position();
-            if (elevator_position - self_position).norm() < 0.75 && self.controller.action {
+            if (elevator_position - self_position).norm()
+                < interaction_radius(&graph[elevator.node])
+                && self.controller.action
+            {
                 let last_index = elevator.points.len().saturating_sub(1) as u32;
                 if elevator.current_floor == last_index {
                     sender.send(Message::CallElevator {
@@ -641,7 +1652,7 @@ impl Player {
                 let button_position = graph[call_button.node].global_position();
 
                 let distance = (button_position - self_position).norm();
-                if distance < 0.75 {
+                if distance < interaction_radius(&graph[call_button.node]) {
                     if let CallButtonKind::FloorSelector = call_button.kind {
                         let new_floor = if self.controller.cursor_down {
                             Some(call_button.floor.saturating_sub(1))
@@ -690,12 +1701,31 @@ impl Player {
                     || self.lower_body_machine.machine.active_state()
                         == self.lower_body_machine.jump_state)
             {
-                new_y_vel = Some(3.0 * dt);
+                new_y_vel = Some(self.jump_settings.strength * dt);
             }
         }
         new_y_vel
     }
 
+    /// Returns new vertical velocity (scaled by `dt`) if the player has requested a second jump
+    /// in the air and it is available.
+    fn try_double_jump(&mut self, config: &DoubleJumpConfig, dt: f32) -> Option<f32> {
+        self.double_jump_cooldown = (self.double_jump_cooldown - dt).max(0.0);
+
+        if !std::mem::take(&mut self.controller.double_jump)
+            || !config.enabled
+            || self.double_jump_used
+            || self.double_jump_cooldown > 0.0
+            || self.in_air_time <= 0.0
+        {
+            return None;
+        }
+
+        self.double_jump_used = true;
+        self.double_jump_cooldown = self.jump_settings.double_jump_cooldown;
+        Some(self.jump_settings.double_jump_strength * dt)
+    }
+
     fn handle_weapon_grab_signal(
         &mut self,
         self_handle: Handle<Actor>,
@@ -725,6 +1755,22 @@ impl Player {
         }
     }
 
+    /// Scales the weapon switch animations by the switch speed of the current weapon. The current
+    /// weapon changes in the middle of the grab animation, so the weapon being put back and the
+    /// weapon being grabbed affect their own parts of the switch.
+    fn update_weapon_switch_speed(&self, scene: &mut Scene, weapons: &WeaponContainer) {
+        let speed = UpperBodyMachine::WEAPON_SWITCH_SPEED
+            * weapons
+                .try_get(self.current_weapon())
+                .map_or(1.0, |weapon| weapon.definition.switch_speed.max(0.1));
+        for animation in [
+            self.upper_body_machine.put_back_animation,
+            self.upper_body_machine.grab_animation,
+        ] {
+            scene.animations.get_mut(animation).set_speed(speed);
+        }
+    }
+
     fn handle_put_back_weapon_end_signal(&self, scene: &mut Scene) {
         while let Some(event) = scene
             .animations
@@ -752,23 +1798,107 @@ impl Player {
             .pop_event()
         {
             if event.signal_id == UpperBodyMachine::TOSS_GRENADE_SIGNAL {
-                let position = scene.graph[self.weapon_pivot].global_position();
-                let direction = scene.graph[self.camera_controller.camera()].look_vector();
+                let (position, initial_velocity) = self.grenade_launch(&scene.graph);
+                self.grenade_charge = 0.0;
 
-                if self.inventory.try_extract_exact_items(ItemKind::Grenade, 1) == 1 {
+                let item = self.selected_grenade();
+                if self.inventory.try_extract_exact_items(item, 1) == 1 {
                     sender.send(Message::CreateProjectile {
-                        kind: ProjectileKind::Grenade,
+                        kind: item.associated_grenade().unwrap_or(ProjectileKind::Grenade),
                         position,
-                        direction,
-                        initial_velocity: direction.scale(15.0),
+                        direction: initial_velocity.normalize(),
+                        initial_velocity,
                         shooter: Shooter::Actor(self_handle),
                     });
                 }
+
+                // Fall back to another grenade type when the last one of selected type is gone.
+                if self.inventory.item_count(item) == 0 {
+                    self.switch_grenade();
+                }
             }
         }
     }
 
-    fn update_velocity(&mut self, scene: &Scene, can_move: bool, dt: f32) {
+    /// Holds the grenade right before the toss while the toss button is pressed and charges the
+    /// throw, the grenade is tossed once the button is released.
+    fn update_grenade_charge(&mut self, scene: &mut Scene, dt: f32) {
+        let animation = scene
+            .animations
+            .get_mut(self.upper_body_machine.toss_grenade_animation);
+
+        if animation.has_ended()
+            || animation.get_time_position() < UpperBodyMachine::TOSS_GRENADE_HOLD_TIME
+        {
+            return;
+        }
+
+        if self.controller.toss_grenade {
+            animation.set_enabled(false);
+            self.grenade_charge = (self.grenade_charge + dt / GRENADE_CHARGE_TIME).min(1.0);
+        } else if !animation.is_enabled() {
+            animation.set_enabled(true);
+        }
+    }
+
+    /// Returns position and initial velocity of a grenade that is tossed right now.
+    fn grenade_launch(&self, graph: &Graph) -> (Vector3<f32>, Vector3<f32>) {
+        let position = graph[self.weapon_pivot].global_position();
+        let look = graph[self.camera_controller.camera()].look_vector();
+        let direction = (look + Vector3::y().scale(GRENADE_THROW_ARC))
+            .try_normalize(f32::EPSILON)
+            .unwrap_or(look);
+        let speed = GRENADE_THROW_SPEED
+            + (GRENADE_MAX_THROW_SPEED - GRENADE_THROW_SPEED) * self.grenade_charge;
+        (position, direction.scale(speed))
+    }
+
+    /// Returns position and initial velocity of a grenade that would be tossed if the player
+    /// released the toss button now, `None` if the player is not going to toss a grenade.
+    pub fn grenade_throw_preview(&self, graph: &Graph) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        if self.controller.toss_grenade
+            && !self.is_dead()
+            && self.inventory.item_count(self.selected_grenade()) > 0
+        {
+            Some(self.grenade_launch(graph))
+        } else {
+            None
+        }
+    }
+
+    fn throw_knife(&mut self, self_handle: Handle<Actor>, scene: &Scene, sender: &MessageSender) {
+        if !std::mem::take(&mut self.controller.throw_knife) {
+            return;
+        }
+
+        if self
+            .inventory
+            .try_extract_exact_items(ItemKind::ThrowingKnife, 1)
+            == 1
+        {
+            let position = scene.graph[self.weapon_pivot].global_position();
+            let direction = scene.graph[self.camera_controller.camera()].look_vector();
+
+            // Knives are silent, so no noise is made here.
+            sender.send(Message::CreateProjectile {
+                kind: ProjectileKind::Knife,
+                position,
+                direction,
+                initial_velocity: direction.scale(25.0),
+                shooter: Shooter::Actor(self_handle),
+            });
+        }
+    }
+
+    /// Returns a multiplier for move and aim speed, heavier weapons make the player slower.
+    fn weapon_mobility(&self, weapons: &WeaponContainer) -> f32 {
+        let weight = weapons
+            .try_get(self.current_weapon())
+            .map_or(0.0, |weapon| weapon.definition.weight);
+        1.0 - (weight * WEAPON_WEIGHT_PENALTY).clamp(0.0, MAX_WEAPON_WEIGHT_PENALTY)
+    }
+
+    fn update_velocity(&mut self, scene: &Scene, can_move: bool, mobility: f32, dt: f32) {
         let pivot = &scene.graph[self.pivot];
 
         let look_vector = pivot
@@ -797,7 +1927,15 @@ impl Player {
         }
 
         let speed = if can_move {
-            math::lerpf(self.move_speed, self.move_speed * 4.0, self.run_factor) * dt
+            let crouch_factor = if self.crouch || self.slide.is_some() {
+                CROUCH_SPEED_FACTOR
+            } else {
+                1.0
+            };
+            math::lerpf(self.move_speed, self.move_speed * 4.0, self.run_factor)
+                * mobility
+                * crouch_factor
+                * dt
         } else {
             0.0
         };
@@ -808,7 +1946,453 @@ impl Player {
             .map(|v| v.scale(speed))
             .unwrap_or_default();
 
-        self.velocity.follow(&self.target_velocity, 0.15);
+        if let Some(slide) = self
+            .slide
+            .as_mut()
+            .filter(|slide| slide.elapsed < SLIDE_DURATION)
+        {
+            // Momentum is preserved, so the player can barely change direction while sliding.
+            if let Some(desired) = self.target_velocity.try_normalize(f32::EPSILON) {
+                slide.direction = slide
+                    .direction
+                    .lerp(&desired, SLIDE_STEERING)
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or(slide.direction);
+            }
+
+            let k = 1.0 - slide.elapsed / SLIDE_DURATION;
+            self.velocity = slide.direction.scale(slide.speed * k * dt);
+        } else if self.in_air_time > 0.0 {
+            self.velocity.follow(
+                &self.target_velocity,
+                0.15 * self.jump_settings.air_control.clamp(0.0, 1.0),
+            );
+        } else {
+            self.velocity.follow(&self.target_velocity, 0.15);
+        }
+    }
+
+    fn cast_ray(
+        &self,
+        graph: &mut Graph,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        query_buffer: &mut Vec<Intersection>,
+    ) -> Option<Intersection> {
+        let ray = Ray::new(origin, direction);
+        graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(ray.origin),
+                ray_direction: ray.dir,
+                max_len: ray.dir.norm(),
+                groups: InteractionGroups::new(
+                    BitMask(0xFFFF),
+                    BitMask(!(CollisionGroups::ActorCapsule as u32)),
+                ),
+                sort_results: true,
+            },
+            query_buffer,
+        );
+        query_buffer
+            .iter()
+            .find(|i| {
+                i.collider != self.capsule_collider
+                    && !self.hit_boxes.iter().any(|h| h.collider == i.collider)
+            })
+            .cloned()
+    }
+
+    /// Returns direction from the shot point of given weapon to the point under the crosshair.
+    /// Falls back to the direction of the weapon if there is nothing under the crosshair.
+    /// Returns direction from given origin to the point under the crosshair, shots and the laser
+    /// sight of converging weapons use it. Falls back to the direction of the barrel if there is
+    /// nothing under the crosshair.
+    pub fn convergence_direction(
+        &self,
+        graph: &mut Graph,
+        weapon: &Weapon,
+        origin: Vector3<f32>,
+    ) -> Vector3<f32> {
+        let straight = weapon.shot_direction(graph);
+
+        let camera = &graph[self.camera_controller.camera()];
+        let camera_position = camera.global_position();
+        let look = camera.look_vector().try_normalize(f32::EPSILON);
+
+        let mut query_buffer = Vec::new();
+        look.and_then(|look| {
+            self.cast_ray(
+                graph,
+                camera_position,
+                look.scale(MAX_CONVERGENCE_DISTANCE),
+                &mut query_buffer,
+            )
+        })
+        .map(|hit| hit.position.coords - origin)
+        // Ignore points behind the weapon, it happens when the camera looks past an obstacle.
+        .filter(|to_target| to_target.dot(&straight) > 0.0)
+        .and_then(|to_target| to_target.try_normalize(f32::EPSILON))
+        .unwrap_or(straight)
+    }
+
+    /// Looks for a ledge in front of the player that is low enough to climb on it. Returns
+    /// position of the body on top of the ledge.
+    fn find_ledge(&self, graph: &mut Graph) -> Option<Vector3<f32>> {
+        let pivot = &graph[self.pivot];
+        let position = pivot.global_position();
+        let forward = Vector3::new(pivot.look_vector().x, 0.0, pivot.look_vector().z)
+            .try_normalize(f32::EPSILON)?;
+        let feet = position.y - FEET_OFFSET;
+
+        let mut query_buffer = Vec::new();
+
+        // There must be an obstacle in front.
+        let obstacle = self.cast_ray(
+            graph,
+            Vector3::new(position.x, feet + MANTLE_MIN_HEIGHT, position.z),
+            forward.scale(MANTLE_REACH),
+            &mut query_buffer,
+        )?;
+        if obstacle.normal.y.abs() > 0.3 {
+            // Slopes and stairs are walkable.
+            return None;
+        }
+
+        // And it must not be taller than the limit.
+        let above_limit = Vector3::new(position.x, feet + MANTLE_MAX_HEIGHT, position.z);
+        if self
+            .cast_ray(
+                graph,
+                above_limit,
+                forward.scale(MANTLE_REACH + BODY_RADIUS),
+                &mut query_buffer,
+            )
+            .is_some()
+        {
+            return None;
+        }
+
+        // Find the top of the obstacle.
+        let mut top_origin = obstacle.position.coords + forward.scale(BODY_RADIUS);
+        top_origin.y = feet + MANTLE_MAX_HEIGHT;
+        let top = self.cast_ray(
+            graph,
+            top_origin,
+            Vector3::new(0.0, -(MANTLE_MAX_HEIGHT - MANTLE_MIN_HEIGHT), 0.0),
+            &mut query_buffer,
+        )?;
+
+        if top.normal.y < 0.7 {
+            // Not a walkable surface.
+            return None;
+        }
+
+        Some(Vector3::new(
+            top_origin.x,
+            top.position.y + FEET_OFFSET + 0.01,
+            top_origin.z,
+        ))
+    }
+
+    pub fn is_mantling(&self) -> bool {
+        self.mantle.is_some()
+    }
+
+    fn update_mantle(&mut self, scene: &mut Scene, has_ground_contact: bool, dt: f32) {
+        if self.mantle.is_none()
+            && self.slide.is_none()
+            && self.climb.is_none()
+            && has_ground_contact
+            && (self.controller.jump || self.controller.walk_forward)
+        {
+            if let Some(target) = self.find_ledge(&mut scene.graph) {
+                self.mantle = Some(Mantle {
+                    start: scene.graph[self.body].global_position(),
+                    target,
+                    elapsed: 0.0,
+                });
+                self.lower_body_machine.start_mantle(scene, MANTLE_DURATION);
+                self.upper_body_machine.start_mantle(scene, MANTLE_DURATION);
+            }
+        }
+
+        if let Some(mantle) = self.mantle.as_mut() {
+            mantle.elapsed += dt;
+
+            // Lift the body first and only then move it onto the ledge, so it won't get stuck
+            // on the edge.
+            let t = (mantle.elapsed / MANTLE_DURATION).min(1.0);
+            let lift = (t / 0.6).min(1.0);
+            let advance = ((t - 0.6) / 0.4).max(0.0);
+            let position = Vector3::new(
+                math::lerpf(mantle.start.x, mantle.target.x, advance),
+                math::lerpf(mantle.start.y, mantle.target.y, lift),
+                math::lerpf(mantle.start.z, mantle.target.z, advance),
+            );
+
+            // Physics is overridden while mantling.
+            let body = scene.graph[self.body].as_rigid_body_mut();
+            body.local_transform_mut().set_position(position);
+            body.set_lin_vel(Default::default());
+            self.velocity = Default::default();
+
+            if t >= 1.0 {
+                self.mantle = None;
+            }
+        }
+    }
+
+    pub fn is_climbing(&self) -> bool {
+        self.climb.is_some()
+    }
+
+    fn update_climb(&mut self, scene: &mut Scene, ladders: &LadderContainer, dt: f32) {
+        if self.climb.is_none()
+            && self.mantle.is_none()
+            && self.slide.is_none()
+            && self.controller.action
+        {
+            let feet_position =
+                scene.graph[self.body].global_position() - Vector3::new(0.0, FEET_OFFSET, 0.0);
+            if let Some(ladder) = ladders.find_grabbable(feet_position, &scene.graph) {
+                self.climb = Some(Climb {
+                    anchor: ladder.bottom() + ladder.normal().scale(BODY_RADIUS + LADDER_OFFSET),
+                    min_height: ladder.bottom().y + FEET_OFFSET,
+                    max_height: ladder.top().y + FEET_OFFSET,
+                    normal: ladder.normal(),
+                    speed: ladder.climb_speed(),
+                });
+
+                // Prevent other things from reacting to the same press of the action button.
+                self.controller.action = false;
+            }
+        }
+
+        if let Some(climb) = self.climb.clone() {
+            let body = scene.graph[self.body].as_rigid_body_mut();
+
+            let mut height = body
+                .global_position()
+                .y
+                .clamp(climb.min_height, climb.max_height);
+            if self.controller.walk_forward {
+                height += climb.speed * dt;
+            }
+            if self.controller.walk_backward {
+                height -= climb.speed * dt;
+            }
+
+            // Physics is overridden while climbing.
+            let position = Vector3::new(
+                climb.anchor.x,
+                height.clamp(climb.min_height, climb.max_height),
+                climb.anchor.z,
+            );
+            body.local_transform_mut().set_position(position);
+            body.set_lin_vel(Default::default());
+            self.velocity = Default::default();
+
+            if self.controller.jump || height < climb.min_height {
+                // Jump off the ladder or step off it at the bottom.
+                self.climb = None;
+            } else if height > climb.max_height {
+                // Climb onto the floor at the top of the ladder.
+                let mut target =
+                    climb.anchor - climb.normal.scale(2.0 * (BODY_RADIUS + LADDER_OFFSET));
+                target.y = climb.max_height + 0.01;
+                self.mantle = Some(Mantle {
+                    start: position,
+                    target,
+                    elapsed: 0.0,
+                });
+                self.lower_body_machine.start_mantle(scene, MANTLE_DURATION);
+                self.upper_body_machine.start_mantle(scene, MANTLE_DURATION);
+                self.climb = None;
+            }
+        }
+    }
+
+    pub fn is_sliding(&self) -> bool {
+        self.slide.is_some()
+    }
+
+    /// Lowers the top of the capsule to given fraction of its normal height. The normal shape is
+    /// kept, so it can be restored when the player stands up.
+    fn lower_capsule(&mut self, scene: &mut Scene, scale: f32) {
+        let collider = scene.graph[self.capsule_collider].as_collider_mut();
+        let mut lowered_shape = self
+            .standing_capsule
+            .get_or_insert_with(|| collider.shape().clone())
+            .clone();
+        if let ColliderShape::Capsule(capsule) = &mut lowered_shape {
+            capsule.end = capsule.begin + (capsule.end - capsule.begin).scale(scale);
+        }
+        collider.set_shape(lowered_shape);
+    }
+
+    /// Checks whether there is enough room above the lowered capsule to raise its top to given
+    /// fraction of the normal height. Physics only allows to cast rays, so the space is probed by
+    /// vertical rays from the center and the sides of the capsule.
+    fn has_headroom(&self, graph: &mut Graph, scale: f32) -> bool {
+        let collider = &graph[self.capsule_collider];
+        let (standing, lowered) = match (
+            self.standing_capsule.as_ref(),
+            collider.as_collider().shape(),
+        ) {
+            (Some(ColliderShape::Capsule(standing)), ColliderShape::Capsule(lowered)) => {
+                (standing.clone(), lowered.clone())
+            }
+            _ => return true,
+        };
+        let top = collider.global_position() + lowered.end;
+        let target_end = standing.begin + (standing.end - standing.begin).scale(scale);
+        if target_end.y <= lowered.end.y {
+            return true;
+        }
+        let height = target_end.y - lowered.end.y + standing.radius;
+
+        let mut query_buffer = Vec::new();
+        [
+            Vector3::default(),
+            Vector3::x(),
+            -Vector3::x(),
+            Vector3::z(),
+            -Vector3::z(),
+        ]
+        .iter()
+        .all(|side| {
+            self.cast_ray(
+                graph,
+                top + side.scale(lowered.radius * 0.9),
+                Vector3::new(0.0, height, 0.0),
+                &mut query_buffer,
+            )
+            .is_none()
+        })
+    }
+
+    /// Raises the top of the lowered capsule to given fraction of its normal height, 1.0 gives
+    /// the capsule its normal shape. Returns false if something is in the way, the capsule stays
+    /// as is in this case.
+    fn try_raise_capsule(&mut self, scene: &mut Scene, scale: f32) -> bool {
+        if !self.has_headroom(&mut scene.graph, scale) {
+            return false;
+        }
+        if scale < 1.0 {
+            self.lower_capsule(scene, scale);
+        } else if let Some(standing_capsule) = self.standing_capsule.take() {
+            scene.graph[self.capsule_collider]
+                .as_collider_mut()
+                .set_shape(standing_capsule);
+        }
+        true
+    }
+
+    fn start_slide(&mut self, scene: &mut Scene, dt: f32) {
+        let horizontal_velocity = Vector3::new(self.velocity.x, 0.0, self.velocity.z);
+        let direction = match horizontal_velocity.try_normalize(f32::EPSILON) {
+            Some(direction) => direction,
+            None => return,
+        };
+
+        // Lower the top of the capsule, so the player can slide under obstacles.
+        self.lower_capsule(scene, SLIDE_CAPSULE_SCALE);
+        self.lower_body_machine.start_slide(scene, SLIDE_DURATION);
+
+        self.slide = Some(Slide {
+            direction,
+            speed: horizontal_velocity.norm() / dt,
+            elapsed: 0.0,
+        });
+    }
+
+    /// Ends the slide in crouch or stand, depending on the crouch button, if there is enough room
+    /// for it. Otherwise the player stays low and keeps moving slowly until there is enough room.
+    fn end_slide(&mut self, scene: &mut Scene) {
+        let scale = if self.controller.crouch {
+            CROUCH_CAPSULE_SCALE
+        } else {
+            1.0
+        };
+        if self.slide.is_some() && self.try_raise_capsule(scene, scale) {
+            self.slide = None;
+        }
+    }
+
+    pub fn is_crouching(&self) -> bool {
+        self.crouch
+    }
+
+    /// Returns true if the player stands still while crouching, such stance makes shooting more
+    /// accurate.
+    fn is_stable(&self) -> bool {
+        self.crouch && !self.is_walking()
+    }
+
+    fn start_crouch(&mut self, scene: &mut Scene) {
+        self.lower_capsule(scene, CROUCH_CAPSULE_SCALE);
+        self.crouch = true;
+    }
+
+    /// Stands the player up if there is enough room, otherwise the player stays crouched.
+    fn end_crouch(&mut self, scene: &mut Scene) {
+        if self.crouch && self.try_raise_capsule(scene, 1.0) {
+            self.crouch = false;
+        }
+    }
+
+    fn update_crouch(&mut self, scene: &mut Scene, has_ground_contact: bool) {
+        let can_crouch = self.controller.crouch
+            && has_ground_contact
+            && !self.controller.jump
+            && self.slide.is_none()
+            && self.mantle.is_none()
+            && self.climb.is_none()
+            && !self.is_downed();
+
+        if can_crouch && !self.crouch {
+            self.start_crouch(scene);
+        } else if !can_crouch {
+            self.end_crouch(scene);
+        }
+    }
+
+    fn update_slide(&mut self, scene: &mut Scene, has_ground_contact: bool, dt: f32) {
+        // Pressing crouch while running starts a slide instead.
+        if std::mem::take(&mut self.controller.slide)
+            && self.slide.is_none()
+            && has_ground_contact
+            && self.run_factor >= SLIDE_MIN_RUN_FACTOR
+            && self.is_walking()
+        {
+            self.end_crouch(scene);
+            self.start_slide(scene, dt);
+            return;
+        }
+
+        if let Some(slide) = self.slide.as_mut() {
+            if slide.elapsed < SLIDE_DURATION {
+                slide.elapsed += dt;
+
+                // Velocity of the body is the result of the last physics step, if it is much
+                // lower than requested one, then the player has bumped into a wall.
+                let expected_speed =
+                    slide.speed * (1.0 - (slide.elapsed / SLIDE_DURATION).min(1.0));
+                let lin_vel = scene.graph[self.body].as_rigid_body().lin_vel();
+                let actual_speed = Vector3::new(lin_vel.x, 0.0, lin_vel.z).norm();
+                let hit_wall = slide.elapsed > 0.1 && actual_speed < expected_speed * 0.3;
+
+                if hit_wall || !has_ground_contact {
+                    // Momentum is lost, but the player is able to stand up only when there is
+                    // enough room.
+                    slide.elapsed = SLIDE_DURATION;
+                }
+            }
+
+            if slide.elapsed >= SLIDE_DURATION {
+                self.end_slide(scene);
+            }
+        }
     }
 
     fn current_weapon_kind(&self, weapons: &WeaponContainer) -> CombatWeaponKind {
@@ -869,6 +2453,95 @@ impl Player {
             .set_color(color);
     }
 
+    /// Tints the display on the weapon when ammo is low, empty display flashes.
+    fn update_weapon_display_tint(
+        &self,
+        scene: &mut Scene,
+        weapons: &WeaponContainer,
+        time: GameTime,
+    ) {
+        let tint = match self.ammo_status(weapons) {
+            AmmoStatus::Normal => Color::WHITE,
+            status => status.color(time.elapsed as f32),
+        };
+        let mesh = scene.graph[self.weapon_display].as_mesh_mut();
+        let surface = mesh.surfaces_mut().first_mut().unwrap();
+        Log::verify(surface.material().lock().set_property(
+            &ImmutableString::new("diffuseColor"),
+            PropertyValue::Color(tint),
+        ));
+    }
+
+    /// Returns charge of the flash light battery in `[0; 1]` range, `None` if the flash light does
+    /// not need a battery.
+    pub fn flash_light_charge(&self) -> Option<f32> {
+        if FLASH_LIGHT_BATTERY.battery_enabled {
+            Some((self.flash_light_battery.0 / FLASH_LIGHT_BATTERY.capacity).clamp(0.0, 1.0))
+        } else {
+            None
+        }
+    }
+
+    pub fn pick_up_battery(&mut self) {
+        self.recharge_flash_light(FLASH_LIGHT_BATTERY.pickup_amount);
+    }
+
+    fn recharge_flash_light(&mut self, amount: f32) {
+        self.flash_light_battery.0 =
+            (self.flash_light_battery.0 + amount.abs()).min(FLASH_LIGHT_BATTERY.capacity);
+    }
+
+    fn update_flash_light_battery(
+        &mut self,
+        scene: &mut Scene,
+        weapons: &WeaponContainer,
+        dt: f32,
+    ) {
+        if !FLASH_LIGHT_BATTERY.battery_enabled {
+            return;
+        }
+
+        let weapon = weapons.try_get(self.current_weapon());
+        if weapon.map_or(false, |weapon| weapon.is_flash_light_enabled(&scene.graph)) {
+            self.flash_light_battery.0 -= FLASH_LIGHT_BATTERY.drain_rate * dt;
+            if self.flash_light_battery.0 <= 0.0 {
+                self.flash_light_battery.0 = 0.0;
+                if let Some(weapon) = weapon {
+                    weapon.set_flash_light_enabled(&mut scene.graph, false);
+                }
+            }
+        } else {
+            self.recharge_flash_light(FLASH_LIGHT_BATTERY.recharge_rate * dt);
+        }
+    }
+
+    fn update_health_regeneration(&mut self, config: &HealthRegenerationConfig, dt: f32) {
+        if self.health < self.prev_health {
+            // Any damage postpones regeneration.
+            self.time_since_damage = 0.0;
+        } else {
+            self.time_since_damage += dt;
+        }
+
+        if config.enabled && self.time_since_damage >= config.delay && self.health < config.cap {
+            self.health = (self.health + config.rate * dt).min(config.cap);
+        }
+
+        self.prev_health = self.health;
+    }
+
+    fn update_armor_bar(&self, scene: &mut Scene) {
+        if let Some(armor_bar) = scene.graph.try_get_mut(self.armor_bar) {
+            let fraction = (self.armor / ARMOR_DEFINITION.max_armor).clamp(0.0, 1.0);
+            armor_bar.set_visibility(fraction > 0.0);
+            armor_bar.local_transform_mut().set_scale(Vector3::new(
+                1.0,
+                fraction.max(f32::EPSILON),
+                1.0,
+            ));
+        }
+    }
+
     fn update_animation_machines(
         &mut self,
         dt: f32,
@@ -878,7 +2551,7 @@ impl Player {
         has_ground_contact: bool,
         weapons: &WeaponContainer,
         sender: &MessageSender,
-    ) {
+    ) -> bool {
         let weapon_kind = self.current_weapon_kind(weapons);
 
         let should_be_stunned = self.should_be_stunned();
@@ -886,12 +2559,15 @@ impl Player {
             self.stun(scene);
         }
 
-        self.lower_body_machine.apply(
+        let made_footstep = self.lower_body_machine.apply(
             scene,
             dt,
             LowerBodyMachineInput {
                 is_walking,
                 is_jumping,
+                is_sliding: self.slide.is_some(),
+                is_mantling: self.mantle.is_some(),
+                is_climbing: self.climb.is_some(),
                 has_ground_contact: self.in_air_time <= 0.3,
                 run_factor: self.run_factor,
                 is_dead: self.is_dead(),
@@ -918,8 +2594,13 @@ impl Player {
                 run_factor: self.run_factor,
                 is_dead: self.is_dead(),
                 should_be_stunned,
+                is_mantling: self.mantle.is_some(),
+                is_climbing: self.climb.is_some(),
+                is_taking_down: self.takedown_time > 0.0,
             },
         );
+
+        made_footstep
     }
 
     fn calculate_model_angle(&self) -> f32 {
@@ -972,47 +2653,130 @@ impl Player {
         self.v_recoil.update(time.delta);
         self.h_recoil.update(time.delta);
 
+        let trigger_pulled = self.controller.shoot && !self.trigger_held;
+        self.trigger_held = self.controller.shoot;
+
+        // Shots are less accurate right after sprinting, until the aim settles.
+        let settle_time = weapons
+            .try_get(self.current_weapon())
+            .map_or(0.0, |weapon| weapon.definition.sprint_settle_time);
+        if self.is_running(scene) {
+            self.sprint_settle = 1.0;
+        } else if settle_time > 0.0 {
+            self.sprint_settle = (self.sprint_settle - time.delta / settle_time).max(0.0);
+        } else {
+            self.sprint_settle = 0.0;
+        }
+
         if let Some(&current_weapon_handle) = self
             .character
             .weapons
             .get(self.character.current_weapon as usize)
         {
+            // Aim returns back once the player stops shooting.
+            if weapons[current_weapon_handle].is_recoil_reset(time) {
+                self.v_recoil.set_target(0.0);
+                self.h_recoil.set_target(0.0);
+            }
+
             if self.upper_body_machine.machine.active_state() == self.upper_body_machine.aim_state {
                 let weapon = &weapons[current_weapon_handle];
                 weapon.laser_sight().set_visible(true, &mut scene.graph);
-                let weapon_display = &mut scene.graph[self.weapon_display];
-                weapon_display.set_visibility(true);
-                weapon_display
+                self.weapon_display_open = true;
+                scene.graph[self.weapon_display]
                     .local_transform_mut()
                     .set_position(weapon.definition.ammo_indicator_offset());
+                self.update_weapon_display_tint(scene, weapons, time);
+
+                let fire_mode = weapon.fire_mode();
+                if trigger_pulled {
+                    self.queued_shots = match fire_mode {
+                        FireMode::Single => 1,
+                        FireMode::Burst(count) => count,
+                        FireMode::Auto => 0,
+                    };
+                }
 
-                if self.controller.shoot && weapon.can_shoot(time) {
+                let wants_shoot = match fire_mode {
+                    FireMode::Auto => self.controller.shoot,
+                    FireMode::Single | FireMode::Burst(_) => self.queued_shots > 0,
+                };
+
+                if weapon.is_jammed() {
+                    // Jammed weapon only clicks until the jam is cleared.
+                    if trigger_pulled {
+                        sender.send(Message::PlaySound {
+                            path: PathBuf::from(JAM_SOUND),
+                            position: weapon.shot_position(&scene.graph),
+                            gain: 0.5,
+                            rolloff_factor: 1.0,
+                            radius: 1.0,
+                        });
+                    }
+                    self.queued_shots = 0;
+                } else if wants_shoot && weapon.can_shoot(time) {
                     let ammo_per_shot = weapons[current_weapon_handle]
                         .definition
                         .ammo_consumption_per_shot;
 
+                    self.queued_shots = self.queued_shots.saturating_sub(1);
+
                     if self
                         .inventory
                         .try_extract_exact_items(ItemKind::Ammo, ammo_per_shot)
                         == ammo_per_shot
                     {
+                        // Projectiles are launched from the weapon, not from the camera, so aim
+                        // them at the point under the crosshair.
+                        let converge = weapon.definition.converges_shots();
+                        let aim_direction = if converge {
+                            let origin = weapon.shot_position(&scene.graph);
+                            self.convergence_direction(&mut scene.graph, weapon, origin)
+                        } else {
+                            weapon.shot_direction(&scene.graph)
+                        };
+
+                        let stability_factor = if self.is_stable() {
+                            CROUCH_STABILITY_FACTOR
+                        } else {
+                            1.0
+                        };
+                        let sprint_spread =
+                            weapon.definition.sprint_spread * self.sprint_settle * stability_factor;
                         sender.send(Message::ShootWeapon {
                             weapon: current_weapon_handle,
-                            direction: None,
+                            direction: if sprint_spread > 0.0 {
+                                Some(deviate(aim_direction, sprint_spread.to_radians()))
+                            } else if converge {
+                                Some(aim_direction)
+                            } else {
+                                None
+                            },
                         });
 
                         self.camera_controller.request_shake_camera();
-                        self.v_recoil
-                            .set_target(weapon.definition.gen_v_recoil_angle());
-                        self.h_recoil
-                            .set_target(weapon.definition.gen_h_recoil_angle());
+                        // Shooting while sliding is much less accurate, while standing still
+                        // crouched is more accurate.
+                        let recoil_factor = if self.slide.is_some() {
+                            SLIDE_RECOIL_FACTOR
+                        } else {
+                            stability_factor
+                        };
+                        let (v_recoil, h_recoil) = weapon.gen_recoil_angles(time);
+                        self.v_recoil.set_target(v_recoil * recoil_factor);
+                        self.h_recoil.set_target(h_recoil * recoil_factor);
                     }
                 }
             } else {
-                weapons[current_weapon_handle]
+                // Interrupt unfinished burst.
+                self.queued_shots = 0;
+
+                // Laser sight could be kept on even if the player is not aiming.
+                let weapon = &weapons[current_weapon_handle];
+                weapon
                     .laser_sight()
-                    .set_visible(false, &mut scene.graph);
-                scene.graph[self.weapon_display].set_visibility(false);
+                    .set_visible(weapon.is_laser_sight_enabled(), &mut scene.graph);
+                self.weapon_display_open = false;
             }
         }
     }
@@ -1040,13 +2804,16 @@ impl Player {
                     (-12.0f32, -4.0f32)
                 };
 
+            // Weapon is raised from the lowered sprint position while the aim settles.
             self.weapon_yaw_correction
                 .set_target(yaw_correction.to_radians());
-            self.weapon_pitch_correction
-                .set_target(pitch_correction.to_radians());
+            self.weapon_pitch_correction.set_target(
+                (pitch_correction + SPRINT_WEAPON_LOWER_ANGLE * self.sprint_settle).to_radians(),
+            );
         } else {
             self.weapon_yaw_correction.set_target(30.0f32.to_radians());
-            self.weapon_pitch_correction.set_target(8.0f32.to_radians());
+            self.weapon_pitch_correction
+                .set_target((8.0 + SPRINT_WEAPON_LOWER_ANGLE * self.run_factor).to_radians());
         }
 
         if can_move {
@@ -1066,12 +2833,19 @@ impl Player {
 
     fn is_running(&self, scene: &Scene) -> bool {
         !self.is_dead()
+            && !self.is_downed()
             && self.controller.run
             && !self.controller.aim
+            && !self.controller.crouch
             && !self.lower_body_machine.is_stunned(scene)
     }
 
-    pub fn update(&mut self, self_handle: Handle<Actor>, context: &mut UpdateContext) {
+    pub fn update(
+        &mut self,
+        self_handle: Handle<Actor>,
+        context: &mut UpdateContext,
+        targets: &[TargetDescriptor],
+    ) {
         let UpdateContext {
             time,
             scene,
@@ -1079,33 +2853,84 @@ impl Player {
             items,
             sender,
             doors,
+            ladders,
+            terminals,
+            transitions,
+            projectiles,
             elevators,
             call_buttons,
+            health_regeneration,
+            camera,
+            downed_state,
+            double_jump,
             ..
         } = context;
 
         self.update_health_cylinder(scene);
+        self.update_armor_bar(scene);
+        self.update_stick_look(self_handle, &scene.graph, targets, time.delta);
+
+        self.flash_timer = (self.flash_timer - time.delta).max(0.0);
+        self.damage_indicators.retain_mut(|indicator| {
+            indicator.lifetime -= time.delta;
+            indicator.lifetime > 0.0
+        });
 
         let has_ground_contact = self.has_ground_contact(&scene.graph);
         let is_walking = self.is_walking();
         let is_jumping = has_ground_contact && self.controller.jump;
         let position = scene.graph[self.pivot].global_position();
 
-        self.update_animation_machines(
-            time.delta,
-            scene,
-            is_walking,
-            is_jumping,
-            has_ground_contact,
-            weapons,
-            sender,
-        );
+        if !self.is_dead() {
+            self.stats.track_position(position);
+        }
+
+        if self.ragdoll.is_active() {
+            self.character.ragdoll.update(&mut scene.graph, time.delta);
+        } else {
+            let made_footstep = self.update_animation_machines(
+                time.delta,
+                scene,
+                is_walking,
+                is_jumping,
+                has_ground_contact,
+                weapons,
+                sender,
+            );
+
+            if made_footstep {
+                sender.send(Message::MakeNoise {
+                    position,
+                    radius: if self.run_factor >= 0.5 {
+                        RUN_NOISE_RADIUS
+                    } else {
+                        WALK_NOISE_RADIUS
+                    },
+                    kind: NoiseKind::FootStep,
+                    source: self_handle,
+                });
+            }
+        }
 
         let quat_yaw = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.controller.yaw);
 
+        self.flinch_pitch.update(time.delta);
+        self.flinch_yaw.update(time.delta);
+
         let is_running = self.is_running(scene);
 
         if !self.is_dead() {
+            self.update_downed(self_handle, sender, time.delta);
+            self.use_hotbar_item(self_handle, sender);
+        }
+
+        if !self.is_dead() {
+            let is_downed = self.is_downed();
+            if !is_downed {
+                self.update_health_regeneration(health_regeneration, time.delta);
+            }
+            self.update_flash_light_battery(scene, weapons, time.delta);
+
             if is_running {
                 self.target_run_factor = 1.0;
             } else {
@@ -1114,14 +2939,33 @@ impl Player {
             self.run_factor += (self.target_run_factor - self.run_factor) * 0.1;
 
             let can_move = self.can_move();
-            self.update_velocity(scene, can_move, time.delta);
-            let new_y_vel = self.handle_jump_signal(scene, time.delta);
+            self.takedown_time = (self.takedown_time - time.delta).max(0.0);
+            self.update_slide(scene, has_ground_contact, time.delta);
+            self.update_crouch(scene, has_ground_contact);
+            let mobility = if is_downed {
+                downed_state.crawl_speed
+            } else {
+                self.weapon_mobility(weapons)
+            };
+            self.update_velocity(scene, can_move, mobility, time.delta);
+            self.update_mantle(scene, has_ground_contact, time.delta);
+            self.update_climb(scene, ladders, time.delta);
+            let new_y_vel = self
+                .handle_jump_signal(scene, time.delta)
+                .or_else(|| self.try_double_jump(double_jump, time.delta))
+                .filter(|_| self.mantle.is_none() && self.climb.is_none() && !is_downed);
             self.handle_weapon_grab_signal(self_handle, scene, sender);
             self.handle_put_back_weapon_end_signal(scene);
+            self.update_weapon_switch_speed(scene, weapons);
+            self.update_grenade_charge(scene, time.delta);
             self.handle_toss_grenade_signal(self_handle, scene, sender);
+            self.throw_knife(self_handle, scene, sender);
 
             let body = scene.graph[self.body].as_rigid_body_mut();
             body.set_ang_vel(Default::default());
+            if body.gravity_scale() != self.jump_settings.gravity_scale {
+                body.set_gravity_scale(self.jump_settings.gravity_scale);
+            }
             if let Some(new_y_vel) = new_y_vel {
                 body.set_lin_vel(Vector3::new(
                     self.velocity.x / time.delta,
@@ -1212,10 +3056,57 @@ impl Player {
                 }
             }
 
+            if self.crouch {
+                // TODO: There is no crouch animation yet, so just lean the model forward.
+                let yaw = if self.controller.aim {
+                    0.0
+                } else {
+                    self.model_yaw.angle
+                };
+                scene.graph[self.model].local_transform_mut().set_rotation(
+                    UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw)
+                        * UnitQuaternion::from_axis_angle(
+                            &Vector3::x_axis(),
+                            CROUCH_LEAN_ANGLE.to_radians(),
+                        ),
+                );
+            }
+
+            if is_downed {
+                // TODO: There is no crawl animation yet, so just lay the model down.
+                scene.graph[self.model].local_transform_mut().set_rotation(
+                    UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.model_yaw.angle)
+                        * UnitQuaternion::from_axis_angle(
+                            &Vector3::x_axis(),
+                            DOWNED_LEAN_ANGLE.to_radians(),
+                        ),
+                );
+            }
+
+            if let Some(climb) = self.climb.as_ref() {
+                // Hands and legs move only while the player moves along the ladder.
+                let climb_speed =
+                    match (self.controller.walk_forward, self.controller.walk_backward) {
+                        (true, false) => 1.0,
+                        (false, true) => -1.0,
+                        _ => 0.0,
+                    };
+                self.lower_body_machine.set_climb_speed(scene, climb_speed);
+                self.upper_body_machine.set_climb_speed(scene, climb_speed);
+
+                // Face the ladder regardless of the camera.
+                let facing = UnitQuaternion::face_towards(&-climb.normal, &Vector3::y());
+                let body_rotation = **scene.graph[self.body].local_transform().rotation();
+                scene.graph[self.model]
+                    .local_transform_mut()
+                    .set_rotation(body_rotation.inverse() * facing);
+            }
+
             self.apply_weapon_angular_correction(scene, can_move, time.delta, weapons);
 
-            if has_ground_contact {
+            if has_ground_contact || self.climb.is_some() {
                 self.in_air_time = 0.0;
+                self.double_jump_used = false;
             } else {
                 self.in_air_time += time.delta;
             }
@@ -1231,10 +3122,38 @@ impl Player {
 
             scene.graph[self.item_display].set_visibility(false);
 
-            self.check_items(self_handle, scene, items, sender);
-            self.check_doors(self_handle, scene, doors, sender);
-            self.check_elevators(scene, elevators, call_buttons, sender);
-            self.update_shooting(scene, weapons, *time, sender);
+            if is_downed {
+                // Downed player can only crawl and revive.
+                self.interaction = None;
+                self.controller.aim = false;
+            } else {
+                // Live grenade takes priority over everything else.
+                self.check_grenades(self_handle, scene, projectiles, sender);
+                self.check_items(self_handle, scene, items, sender);
+                self.check_terminals(self_handle, scene, terminals, sender);
+                self.check_transitions(scene, transitions, sender);
+                self.check_doors(self_handle, scene, doors, sender);
+                self.check_elevators(scene, elevators, call_buttons, sender);
+                self.interaction = self.find_interaction(
+                    scene,
+                    items,
+                    doors,
+                    elevators,
+                    call_buttons,
+                    ladders,
+                    terminals,
+                    transitions,
+                    projectiles,
+                    targets,
+                );
+                if self
+                    .interaction
+                    .map_or(true, |interaction| interaction == Interaction::Takedown)
+                {
+                    self.check_melee(self_handle, scene, targets, sender);
+                }
+                self.update_shooting(scene, weapons, *time, sender);
+            }
 
             let spine_transform = scene.graph[self.spine].local_transform_mut();
             let rotation = **spine_transform.rotation();
@@ -1244,11 +3163,18 @@ impl Player {
                     * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.h_recoil.angle()),
             );
         } else {
-            for &dying_animation in &[
-                self.lower_body_machine.dying_animation,
-                self.upper_body_machine.dying_animation,
-            ] {
-                scene.animations.get_mut(dying_animation).set_enabled(true);
+            self.interaction = None;
+            self.climb = None;
+
+            if PLAYER_DEFINITION.ragdoll {
+                self.character.activate_ragdoll(&mut scene.graph);
+            } else {
+                for &dying_animation in &[
+                    self.lower_body_machine.dying_animation,
+                    self.upper_body_machine.dying_animation,
+                ] {
+                    scene.animations.get_mut(dying_animation).set_enabled(true);
+                }
             }
 
             // Lock player on the place he died.
@@ -1257,19 +3183,224 @@ impl Player {
             body.set_lin_vel(Vector3::new(0.0, body.lin_vel().y, 0.0));
         }
 
+        // Picture-in-picture scopes magnify their own view only.
+        let scope = self.active_scope(weapons);
+        self.camera_controller.set_target_zoom(
+            scope
+                .filter(|scope| scope.picture_in_picture.is_none())
+                .map_or_else(
+                    || self.optic_magnification(weapons),
+                    |scope| scope.magnification,
+                ),
+            self.weapon_mobility(weapons),
+        );
+        self.camera_controller
+            .set_scope_view(scope.and_then(|scope| {
+                scope
+                    .picture_in_picture
+                    .map(|pip| (scope.magnification, pip.viewport()))
+            }));
+        self.camera_controller
+            .set_sprint_factor(self.sprint_intensity());
+        self.update_display_visibility(&mut scene.graph, camera.diegetic_displays);
+
         self.camera_controller.update(
             position + self.velocity,
-            self.controller.pitch,
-            quat_yaw,
+            self.controller.pitch + self.flinch_pitch.angle(),
+            quat_yaw * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.flinch_yaw.angle()),
             is_walking,
             is_running,
             self.controller.aim,
             self.capsule_collider,
             scene,
             *time,
+            camera,
         );
     }
 
+    fn set_journal_visible(&mut self, visible: bool, sender: &MessageSender) {
+        if self.journal_open != visible {
+            self.journal_open = visible;
+            sender.send(Message::SetJournalVisible { visible });
+        }
+    }
+
+    pub fn is_inventory_open(&self) -> bool {
+        self.inventory_open
+    }
+
+    pub fn is_journal_open(&self) -> bool {
+        self.journal_open
+    }
+
+    pub fn is_weapon_display_open(&self) -> bool {
+        self.weapon_display_open
+    }
+
+    /// Shows open displays in the world, if diegetic displays are disabled, then the displays are
+    /// shown on the HUD instead.
+    fn update_display_visibility(&self, graph: &mut Graph, diegetic: bool) {
+        for (display, open) in [
+            (self.inventory_display, self.inventory_open),
+            (self.journal_display, self.journal_open),
+            (self.weapon_display, self.weapon_display_open),
+        ] {
+            graph[display].set_visibility(diegetic && open);
+        }
+    }
+
+    /// Takes state of a gamepad, `None` means that there is no gamepad and it should not affect
+    /// the player.
+    pub fn process_gamepad_input(
+        &mut self,
+        input: Option<GamepadInput>,
+        control_scheme: &ControlScheme,
+    ) {
+        let input = input.unwrap_or_default();
+
+        self.controller.stick_look = input.look.scale(control_scheme.stick_sens);
+        self.controller.aim_assist_strength = if control_scheme.aim_assist {
+            control_scheme.aim_assist_strength
+        } else {
+            0.0
+        };
+
+        // Only changes of the trigger matter, so it does not fight with the aim button.
+        if input.aim != self.controller.gamepad_aim_pressed {
+            hold_or_toggle(
+                &mut self.controller.aim,
+                &mut self.controller.gamepad_aim_pressed,
+                control_scheme.aim_toggle,
+                if input.aim {
+                    ElementState::Pressed
+                } else {
+                    ElementState::Released
+                },
+            );
+        }
+    }
+
+    /// Finds a hit box of a visible hostile target that is the closest to the crosshair, returns
+    /// yaw and pitch that are needed to put it under the crosshair.
+    fn find_aim_assist_target(
+        &self,
+        self_handle: Handle<Actor>,
+        graph: &Graph,
+        targets: &[TargetDescriptor],
+    ) -> Option<Vector2<f32>> {
+        let camera = &graph[self.camera_controller.camera()];
+        let origin = camera.global_position();
+        let look = camera.look_vector().try_normalize(f32::EPSILON)?;
+
+        let mut query_buffer = Vec::new();
+        let mut closest = None;
+        let mut closest_angle = AIM_ASSIST_ANGLE.to_radians();
+        for target in targets {
+            if target.handle == self_handle
+                || target.health <= 0.0
+                || self.faction.is_friend_of(target.faction)
+            {
+                continue;
+            }
+
+            for &hit_box in target.hit_boxes.iter() {
+                let to_hit_box = match graph.try_get(hit_box) {
+                    Some(hit_box) => hit_box.global_position() - origin,
+                    None => continue,
+                };
+                let distance = to_hit_box.norm();
+                if distance > AIM_ASSIST_RANGE || distance <= f32::EPSILON {
+                    continue;
+                }
+
+                let angle = look
+                    .dot(&to_hit_box.scale(1.0 / distance))
+                    .clamp(-1.0, 1.0)
+                    .acos();
+                if angle >= closest_angle {
+                    continue;
+                }
+
+                graph.physics.cast_ray(
+                    RayCastOptions {
+                        ray_origin: Point3::from(origin),
+                        ray_direction: to_hit_box,
+                        max_len: distance,
+                        groups: Default::default(),
+                        sort_results: true,
+                    },
+                    &mut query_buffer,
+                );
+
+                // The hit box is visible if the first obstacle on the way belongs to the target.
+                let visible = query_buffer
+                    .iter()
+                    .find(|hit| {
+                        hit.collider != self.capsule_collider
+                            && self.hit_boxes.iter().all(|h| h.collider != hit.collider)
+                    })
+                    .map_or(false, |hit| {
+                        hit.collider == target.capsule_collider
+                            || target.hit_boxes.contains(&hit.collider)
+                    });
+                if visible {
+                    closest_angle = angle;
+                    closest = Some(to_hit_box);
+                }
+            }
+        }
+
+        let direction_angles = |direction: Vector3<f32>| {
+            Vector2::new(
+                direction.x.atan2(direction.z),
+                (-direction.y).atan2(direction.xz().norm()),
+            )
+        };
+
+        closest.map(|direction| {
+            let offset = direction_angles(direction) - direction_angles(look);
+            Vector2::new(
+                (offset.x + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+                    - std::f32::consts::PI,
+                offset.y,
+            )
+        })
+    }
+
+    /// Turns the camera by the right stick of a gamepad. Aim assist slows the stick down near
+    /// a target and gently pulls the crosshair to it while aiming.
+    fn update_stick_look(
+        &mut self,
+        self_handle: Handle<Actor>,
+        graph: &Graph,
+        targets: &[TargetDescriptor],
+        dt: f32,
+    ) {
+        let stick_look = self.controller.stick_look;
+        if stick_look == Vector2::default() {
+            return;
+        }
+
+        let strength = self.controller.aim_assist_strength;
+        let mut speed_factor = 1.0;
+        let mut pull = Vector2::default();
+        if strength > 0.0 {
+            if let Some(offset) = self.find_aim_assist_target(self_handle, graph, targets) {
+                speed_factor -= strength * AIM_ASSIST_SLOWDOWN;
+                if self.controller.aim {
+                    pull = offset.scale((strength * AIM_ASSIST_PULL * dt).min(1.0));
+                }
+            }
+        }
+
+        // Lower sensitivity while zoomed to allow fine aiming, as with the mouse.
+        let turn = stick_look.scale(speed_factor * dt / self.camera_controller.zoom());
+        self.controller.yaw += pull.x - turn.x;
+        self.controller.pitch = (self.controller.pitch + pull.y - turn.y)
+            .max(-90.0f32.to_radians())
+            .min(90.0f32.to_radians());
+    }
+
     pub fn process_input_event(
         &mut self,
         event: &Event<()>,
@@ -1310,7 +3441,8 @@ impl Player {
                     Some((ControlButton::Mouse(button as u16), state))
                 }
                 DeviceEvent::MouseMotion { delta } => {
-                    let mouse_sens = control_scheme.mouse_sens * dt;
+                    // Lower sensitivity while zoomed to allow fine aiming.
+                    let mouse_sens = control_scheme.mouse_sens * dt / self.camera_controller.zoom();
                     self.controller.yaw -= (delta.0 as f32) * mouse_sens;
                     let pitch_direction = if control_scheme.mouse_y_inverse {
                         -1.0
@@ -1342,10 +3474,15 @@ impl Player {
 
         if let Some((button, state)) = button_state {
             if button == control_scheme.aim.button {
-                self.controller.aim = state == ElementState::Pressed;
+                hold_or_toggle(
+                    &mut self.controller.aim,
+                    &mut self.controller.aim_pressed,
+                    control_scheme.aim_toggle,
+                    state,
+                );
                 if state == ElementState::Pressed {
-                    scene.graph[self.inventory_display].set_visibility(false);
-                    scene.graph[self.journal_display].set_visibility(false);
+                    self.inventory_open = false;
+                    self.set_journal_visible(false, sender);
                 }
             } else if button == control_scheme.move_forward.button {
                 self.controller.walk_forward = state == ElementState::Pressed;
@@ -1374,12 +3511,71 @@ impl Player {
                 }
 
                 self.controller.jump = state == ElementState::Pressed && can_jump;
+
+                let is_pressed = state == ElementState::Pressed;
+                if is_pressed && !self.controller.jump_pressed {
+                    self.controller.double_jump = true;
+                }
+                self.controller.jump_pressed = is_pressed;
             } else if button == control_scheme.run.button {
-                self.controller.run = state == ElementState::Pressed;
+                hold_or_toggle(
+                    &mut self.controller.run,
+                    &mut self.controller.run_pressed,
+                    control_scheme.run_toggle,
+                    state,
+                );
+            } else if button == control_scheme.crouch.button {
+                if state == ElementState::Pressed && !self.controller.crouch_pressed {
+                    self.controller.slide = true;
+                }
+                hold_or_toggle(
+                    &mut self.controller.crouch,
+                    &mut self.controller.crouch_pressed,
+                    control_scheme.crouch_toggle,
+                    state,
+                );
             } else if button == control_scheme.flash_light.button {
                 if state == ElementState::Pressed {
                     let current_weapon = self.current_weapon();
-                    sender.send(Message::SwitchFlashLight {
+                    let is_lit = weapons
+                        .try_get(current_weapon)
+                        .map_or(false, |weapon| weapon.is_flash_light_enabled(&scene.graph));
+                    // Dead battery is unable to light the flash light.
+                    if is_lit
+                        || self
+                            .flash_light_charge()
+                            .map_or(true, |charge| charge > 0.0)
+                    {
+                        sender.send(Message::SwitchFlashLight {
+                            weapon: current_weapon,
+                        });
+                    }
+                }
+            } else if button == control_scheme.toggle_silencer.button {
+                if state == ElementState::Pressed {
+                    let current_weapon = self.current_weapon();
+                    sender.send(Message::ToggleSilencer {
+                        weapon: current_weapon,
+                    });
+                }
+            } else if button == control_scheme.fire_mode.button {
+                if state == ElementState::Pressed {
+                    let current_weapon = self.current_weapon();
+                    sender.send(Message::CycleFireMode {
+                        weapon: current_weapon,
+                    });
+                }
+            } else if button == control_scheme.clear_jam.button {
+                if state == ElementState::Pressed {
+                    let current_weapon = self.current_weapon();
+                    sender.send(Message::ClearJam {
+                        weapon: current_weapon,
+                    });
+                }
+            } else if button == control_scheme.toggle_laser_sight.button {
+                if state == ElementState::Pressed {
+                    let current_weapon = self.current_weapon();
+                    sender.send(Message::ToggleLaserSight {
                         weapon: current_weapon,
                     });
                 }
@@ -1400,6 +3596,33 @@ impl Player {
                 if current_weapon_kind.map_or(false, |k| k != WeaponKind::Glock) {
                     weapon_change_direction = Some(RequiredWeapon::Specific(WeaponKind::Glock));
                 }
+            } else if let Some(slot) = control_scheme
+                .weapon_slots()
+                .iter()
+                .position(|slot| slot.button == button)
+            {
+                if state == ElementState::Pressed && can_change_weapon {
+                    if let Some(&weapon) = self.weapons.get(slot) {
+                        if weapon != self.current_weapon() {
+                            weapon_change_direction =
+                                Some(RequiredWeapon::Specific(weapons[weapon].kind()));
+                        }
+                    }
+                }
+            } else if button == control_scheme.last_weapon.button {
+                if state == ElementState::Pressed && can_change_weapon {
+                    let has_last_weapon = self.last_weapon.map_or(false, |last| {
+                        Some(last) != current_weapon_kind
+                            && self.weapons.iter().any(|&w| weapons[w].kind() == last)
+                    });
+                    weapon_change_direction = if has_last_weapon {
+                        self.last_weapon.map(RequiredWeapon::Specific)
+                    } else {
+                        // Last weapon was dropped, use the next one instead.
+                        let next = (self.current_weapon as usize + 1) % self.weapons.len();
+                        Some(RequiredWeapon::Specific(weapons[self.weapons[next]].kind()))
+                    };
+                }
             } else if button == control_scheme.next_weapon.button {
                 if state == ElementState::Pressed
                     && self.current_weapon < self.weapons.len().saturating_sub(1) as u32
@@ -1412,16 +3635,54 @@ impl Player {
                     weapon_change_direction = Some(RequiredWeapon::Previous);
                 }
             } else if button == control_scheme.toss_grenade.button {
-                if self.inventory.item_count(ItemKind::Grenade) > 0 {
-                    self.controller.toss_grenade = state == ElementState::Pressed;
+                if state == ElementState::Pressed
+                    && self.inventory.item_count(self.selected_grenade()) == 0
+                {
+                    self.switch_grenade();
+                }
+                if self.inventory.item_count(self.selected_grenade()) > 0 {
                     if state == ElementState::Pressed {
-                        scene
-                            .animations
-                            .get_mut(self.upper_body_machine.toss_grenade_animation)
-                            .set_enabled(true)
-                            .rewind();
+                        self.start_grenade_toss(scene);
+                    } else {
+                        self.controller.toss_grenade = false;
+                    }
+                }
+            } else if let Some(slot) = control_scheme
+                .hotbar_slots()
+                .iter()
+                .position(|slot| slot.button == button)
+            {
+                // Slots are assigned while the inventory is open.
+                if !self.inventory_open {
+                    let item = self.hotbar.slot(slot);
+                    match item.and_then(|item| GRENADE_ITEMS.iter().position(|&g| g == item)) {
+                        Some(grenade_index) => {
+                            if state == ElementState::Pressed {
+                                if self.inventory.item_count(GRENADE_ITEMS[grenade_index]) > 0 {
+                                    self.grenade_index = grenade_index as u32;
+                                    self.start_grenade_toss(scene);
+                                }
+                            } else {
+                                self.controller.toss_grenade = false;
+                            }
+                        }
+                        None => {
+                            let pressed = state == ElementState::Pressed;
+                            if pressed && !self.controller.hotbar_pressed[slot] {
+                                self.controller.use_hotbar_slot = Some(slot);
+                            }
+                            self.controller.hotbar_pressed[slot] = pressed;
+                        }
                     }
                 }
+            } else if button == control_scheme.throw_knife.button {
+                if state == ElementState::Pressed {
+                    self.controller.throw_knife = true;
+                }
+            } else if button == control_scheme.switch_grenade.button {
+                if state == ElementState::Pressed {
+                    self.switch_grenade();
+                }
             } else if button == control_scheme.shoot.button {
                 self.controller.shoot = state == ElementState::Pressed;
             } else if button == control_scheme.cursor_up.button {
@@ -1434,31 +3695,28 @@ impl Player {
                 && state == ElementState::Pressed
                 && !self.controller.aim
             {
-                scene.graph[self.journal_display].set_visibility(false);
+                self.set_journal_visible(false, sender);
 
-                let inventory = &mut scene.graph[self.inventory_display];
-                let new_visibility = !inventory.visibility();
-                inventory.set_visibility(new_visibility);
-                if new_visibility {
+                self.inventory_open = !self.inventory_open;
+                if self.inventory_open {
                     sender.send(Message::SyncInventory);
                 }
             } else if button == control_scheme.journal.button
                 && state == ElementState::Pressed
                 && !self.controller.aim
             {
-                scene.graph[self.inventory_display].set_visibility(false);
+                self.inventory_open = false;
 
-                let journal = &mut scene.graph[self.journal_display];
-                let new_visibility = !journal.visibility();
-                journal.set_visibility(new_visibility);
-                if new_visibility {
-                    sender.send(Message::SyncJournal);
-                }
+                let new_visibility = !self.journal_open;
+                self.set_journal_visible(new_visibility, sender);
             }
         }
 
         if let Some(weapon_change_direction) = weapon_change_direction {
             self.weapon_change_direction = weapon_change_direction;
+            if current_weapon_kind.is_some() {
+                self.last_weapon = current_weapon_kind;
+            }
 
             scene
                 .animations
@@ -1474,6 +3732,10 @@ impl Player {
     }
 
     pub fn is_completely_dead(&self, scene: &Scene) -> bool {
+        if self.ragdoll.is_active() {
+            return self.ragdoll.is_settled();
+        }
+
         self.is_dead()
             && (scene.animations[self.upper_body_machine.dying_animation].has_ended()
                 || scene.animations[self.lower_body_machine.dying_animation].has_ended())
@@ -1487,6 +3749,8 @@ impl Player {
         item_texture: Texture,
         journal_texture: Texture,
     ) {
+        self.journal.resolve();
+
         Log::verify(
             scene.graph[self.weapon_display]
                 .as_mesh_mut()
@@ -1543,5 +3807,11 @@ impl Player {
             .set_texture(Some(item_texture));
 
         self.health_color_gradient = make_color_gradient();
+
+        // Slides are not saved, but a save made mid-slide keeps the capsule lowered. Continue in
+        // crouch, the player stands up as soon as there is enough room.
+        if self.standing_capsule.is_some() {
+            self.crouch = true;
+        }
     }
 }
@@ -163,6 +163,15 @@ pub struct UpperBodyMachine {
     pub dying_animation: Handle<Animation>,
     pub hit_reaction_pistol_animation: Handle<Animation>,
     pub hit_reaction_rifle_animation: Handle<Animation>,
+    /// Saves made before mantling was added have no mantle state.
+    #[visit(optional)]
+    pub mantle_animation: Handle<Animation>,
+    /// Saves made before ladders were added have no climb state.
+    #[visit(optional)]
+    pub climb_animation: Handle<Animation>,
+    /// Saves made before takedowns were added have no takedown state.
+    #[visit(optional)]
+    pub takedown_animation: Handle<Animation>,
 }
 
 fn disable_leg_tracks(
@@ -196,6 +205,9 @@ pub struct UpperBodyMachineInput {
     pub change_weapon: bool,
     pub is_dead: bool,
     pub should_be_stunned: bool,
+    pub is_mantling: bool,
+    pub is_climbing: bool,
+    pub is_taking_down: bool,
 }
 
 impl UpperBodyMachine {
@@ -249,6 +261,23 @@ impl UpperBodyMachine {
     const HIT_REACTION_TO_DYING: &'static str = "HitReactionToDying";
     const HIT_REACTION_TO_AIM: &'static str = "HitReactionToAim";
 
+    const IDLE_TO_MANTLE: &'static str = "IdleToMantle";
+    const WALK_TO_MANTLE: &'static str = "WalkToMantle";
+    const AIM_TO_MANTLE: &'static str = "AimToMantle";
+    const JUMP_TO_MANTLE: &'static str = "JumpToMantle";
+    const MANTLE_TO_IDLE: &'static str = "MantleToIdle";
+
+    const IDLE_TO_CLIMB: &'static str = "IdleToClimb";
+    const WALK_TO_CLIMB: &'static str = "WalkToClimb";
+    const AIM_TO_CLIMB: &'static str = "AimToClimb";
+    const CLIMB_TO_IDLE: &'static str = "ClimbToIdle";
+    const CLIMB_TO_MANTLE: &'static str = "ClimbToMantle";
+
+    const IDLE_TO_TAKEDOWN: &'static str = "IdleToTakedown";
+    const WALK_TO_TAKEDOWN: &'static str = "WalkToTakedown";
+    const AIM_TO_TAKEDOWN: &'static str = "AimToTakedown";
+    const TAKEDOWN_TO_IDLE: &'static str = "TakedownToIdle";
+
     const HIT_REACTION_WEAPON_KIND: &'static str = "HitReactionWeaponKind";
     const IDLE_STATE_WEAPON_KIND: &'static str = "IdleStateWeaponKind";
     const WALK_STATE_WEAPON_KIND: &'static str = "IdleStateWeaponKind";
@@ -257,6 +286,15 @@ impl UpperBodyMachine {
     pub const GRAB_WEAPON_SIGNAL: u64 = 1;
     pub const PUT_BACK_WEAPON_END_SIGNAL: u64 = 1;
     pub const TOSS_GRENADE_SIGNAL: u64 = 1;
+    /// Time position of the toss animation at which the grenade leaves the hand.
+    pub const TOSS_GRENADE_TIME: f32 = 1.7;
+    /// Time position of the toss animation at which the grenade is held while the throw is
+    /// being charged.
+    pub const TOSS_GRENADE_HOLD_TIME: f32 = 1.5;
+
+    /// Base speed of the animations of weapon switching, it is scaled by the switch speed of
+    /// weapons.
+    pub const WEAPON_SWITCH_SPEED: f32 = 3.0;
 
     pub async fn new(
         scene: &mut Scene,
@@ -344,14 +382,25 @@ impl UpperBodyMachine {
         ]));
         let aim_state = machine.add_state(State::new("Aim", aim_node));
 
+        let toss_grenade_animation_resource = toss_grenade_animation_resource.unwrap();
         let (toss_grenade_animation, toss_grenade_state) = create_play_animation_state(
-            toss_grenade_animation_resource.unwrap(),
+            toss_grenade_animation_resource.clone(),
             "TossGrenade",
             &mut machine,
             scene,
             model,
         );
 
+        // There is no takedown animation, the overhand swing of the grenade toss is used as a
+        // strike.
+        let (takedown_animation, takedown_state) = create_play_animation_state(
+            toss_grenade_animation_resource,
+            "Takedown",
+            &mut machine,
+            scene,
+            model,
+        );
+
         let IdleStateDefinition {
             state: idle_state,
             idle_animation,
@@ -397,14 +446,33 @@ impl UpperBodyMachine {
             model,
         );
 
+        let grab_animation_resource = grab_animation_resource.unwrap();
         let (grab_animation, grab_state) = create_play_animation_state(
-            grab_animation_resource.unwrap(),
+            grab_animation_resource.clone(),
             "Grab",
             &mut machine,
             scene,
             model,
         );
 
+        // There is no mantle animation, hands reach for the ledge like they reach for a weapon.
+        let (mantle_animation, mantle_state) = create_play_animation_state(
+            grab_animation_resource.clone(),
+            "Mantle",
+            &mut machine,
+            scene,
+            model,
+        );
+
+        // There is no climb animation, hands keep reaching for the next rung with the grab clip.
+        let (climb_animation, climb_state) = create_play_animation_state(
+            grab_animation_resource,
+            "Climb",
+            &mut machine,
+            scene,
+            model,
+        );
+
         let (dying_animation, dying_state) = create_play_animation_state(
             dying_animation_resource.unwrap(),
             "Dying",
@@ -441,14 +509,14 @@ impl UpperBodyMachine {
             .animations
             .get_mut(grab_animation)
             .set_loop(false)
-            .set_speed(3.0)
+            .set_speed(Self::WEAPON_SWITCH_SPEED)
             .set_enabled(false)
             .add_signal(AnimationSignal::new(Self::GRAB_WEAPON_SIGNAL, 0.3));
         let put_back_duration = scene.animations.get(put_back_animation).length();
         scene
             .animations
             .get_mut(put_back_animation)
-            .set_speed(3.0)
+            .set_speed(Self::WEAPON_SWITCH_SPEED)
             .add_signal(AnimationSignal::new(
                 Self::PUT_BACK_WEAPON_END_SIGNAL,
                 put_back_duration,
@@ -458,7 +526,10 @@ impl UpperBodyMachine {
             .animations
             .get_mut(toss_grenade_animation)
             .set_speed(1.5)
-            .add_signal(AnimationSignal::new(Self::TOSS_GRENADE_SIGNAL, 1.7))
+            .add_signal(AnimationSignal::new(
+                Self::TOSS_GRENADE_SIGNAL,
+                Self::TOSS_GRENADE_TIME,
+            ))
             .set_enabled(false)
             .set_loop(false);
 
@@ -467,6 +538,16 @@ impl UpperBodyMachine {
             .get_mut(dying_animation)
             .set_enabled(false)
             .set_loop(false);
+        scene
+            .animations
+            .get_mut(mantle_animation)
+            .set_enabled(false)
+            .set_loop(false);
+        scene
+            .animations
+            .get_mut(takedown_animation)
+            .set_enabled(false)
+            .set_loop(false);
 
         machine.add_transition(Transition::new(
             "Walk->Idle",
@@ -757,6 +838,110 @@ impl UpperBodyMachine {
             Self::HIT_REACTION_TO_AIM,
         ));
 
+        // Mantle transitions, mantling may start right at the beginning of a jump.
+        machine.add_transition(Transition::new(
+            "Idle->Mantle",
+            idle_state,
+            mantle_state,
+            0.10,
+            Self::IDLE_TO_MANTLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Walk->Mantle",
+            walk_state,
+            mantle_state,
+            0.10,
+            Self::WALK_TO_MANTLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Aim->Mantle",
+            aim_state,
+            mantle_state,
+            0.10,
+            Self::AIM_TO_MANTLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Jump->Mantle",
+            jump_state,
+            mantle_state,
+            0.10,
+            Self::JUMP_TO_MANTLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Mantle->Idle",
+            mantle_state,
+            idle_state,
+            0.20,
+            Self::MANTLE_TO_IDLE,
+        ));
+
+        // Climb transitions, the top of a ladder is left by mantling onto the floor.
+        machine.add_transition(Transition::new(
+            "Idle->Climb",
+            idle_state,
+            climb_state,
+            0.20,
+            Self::IDLE_TO_CLIMB,
+        ));
+        machine.add_transition(Transition::new(
+            "Walk->Climb",
+            walk_state,
+            climb_state,
+            0.20,
+            Self::WALK_TO_CLIMB,
+        ));
+        machine.add_transition(Transition::new(
+            "Aim->Climb",
+            aim_state,
+            climb_state,
+            0.20,
+            Self::AIM_TO_CLIMB,
+        ));
+        machine.add_transition(Transition::new(
+            "Climb->Idle",
+            climb_state,
+            idle_state,
+            0.20,
+            Self::CLIMB_TO_IDLE,
+        ));
+        machine.add_transition(Transition::new(
+            "Climb->Mantle",
+            climb_state,
+            mantle_state,
+            0.10,
+            Self::CLIMB_TO_MANTLE,
+        ));
+
+        // Takedown transitions.
+        machine.add_transition(Transition::new(
+            "Idle->Takedown",
+            idle_state,
+            takedown_state,
+            0.10,
+            Self::IDLE_TO_TAKEDOWN,
+        ));
+        machine.add_transition(Transition::new(
+            "Walk->Takedown",
+            walk_state,
+            takedown_state,
+            0.10,
+            Self::WALK_TO_TAKEDOWN,
+        ));
+        machine.add_transition(Transition::new(
+            "Aim->Takedown",
+            aim_state,
+            takedown_state,
+            0.10,
+            Self::AIM_TO_TAKEDOWN,
+        ));
+        machine.add_transition(Transition::new(
+            "Takedown->Idle",
+            takedown_state,
+            idle_state,
+            0.20,
+            Self::TAKEDOWN_TO_IDLE,
+        ));
+
         for leg in &["mixamorig:LeftUpLeg", "mixamorig:RightUpLeg"] {
             for &animation in &[
                 aim_pistol_animation,
@@ -776,6 +961,9 @@ impl UpperBodyMachine {
                 dying_animation,
                 hit_reaction_rifle_animation,
                 hit_reaction_pistol_animation,
+                mantle_animation,
+                climb_animation,
+                takedown_animation,
             ] {
                 disable_leg_tracks(animation, model, leg, scene);
             }
@@ -798,6 +986,9 @@ impl UpperBodyMachine {
             dying_animation,
             hit_reaction_pistol_animation,
             hit_reaction_rifle_animation,
+            mantle_animation,
+            climb_animation,
+            takedown_animation,
         }
     }
 
@@ -972,6 +1163,32 @@ impl UpperBodyMachine {
                 Parameter::Rule(input.toss_grenade && input.is_aiming),
             )
             .set_parameter(Self::IDLE_STATE_WEAPON_KIND, Parameter::Index(index))
+            .set_parameter(Self::IDLE_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(Self::WALK_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(Self::AIM_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(Self::JUMP_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(Self::MANTLE_TO_IDLE, Parameter::Rule(!input.is_mantling))
+            .set_parameter(Self::IDLE_TO_CLIMB, Parameter::Rule(input.is_climbing))
+            .set_parameter(Self::WALK_TO_CLIMB, Parameter::Rule(input.is_climbing))
+            .set_parameter(Self::AIM_TO_CLIMB, Parameter::Rule(input.is_climbing))
+            .set_parameter(
+                Self::CLIMB_TO_IDLE,
+                Parameter::Rule(!input.is_climbing && !input.is_mantling),
+            )
+            .set_parameter(Self::CLIMB_TO_MANTLE, Parameter::Rule(input.is_mantling))
+            .set_parameter(
+                Self::IDLE_TO_TAKEDOWN,
+                Parameter::Rule(input.is_taking_down),
+            )
+            .set_parameter(
+                Self::WALK_TO_TAKEDOWN,
+                Parameter::Rule(input.is_taking_down),
+            )
+            .set_parameter(Self::AIM_TO_TAKEDOWN, Parameter::Rule(input.is_taking_down))
+            .set_parameter(
+                Self::TAKEDOWN_TO_IDLE,
+                Parameter::Rule(!input.is_taking_down),
+            )
             .evaluate_pose(&scene.animations, dt)
             .apply_with(&mut scene.graph, |node, handle, pose| {
                 if handle == hips_handle {
@@ -987,6 +1204,39 @@ impl UpperBodyMachine {
             });
     }
 
+    /// Starts the mantle animation over, it is stretched to last given time.
+    pub fn start_mantle(&self, scene: &mut Scene, duration: f32) {
+        if self.mantle_animation.is_none() {
+            return;
+        }
+
+        let animation = scene.animations.get_mut(self.mantle_animation);
+        let speed = animation.length() / duration;
+        animation.set_enabled(true).set_speed(speed).rewind();
+    }
+
+    /// Starts the takedown animation over, it is stretched to last given time.
+    pub fn start_takedown(&self, scene: &mut Scene, duration: f32) {
+        if self.takedown_animation.is_none() {
+            return;
+        }
+
+        let animation = scene.animations.get_mut(self.takedown_animation);
+        let speed = animation.length() / duration;
+        animation.set_enabled(true).set_speed(speed).rewind();
+    }
+
+    /// Sets speed of the climb animation, negative speed plays it backwards when the player climbs
+    /// down and zero speed freezes it.
+    pub fn set_climb_speed(&self, scene: &mut Scene, speed: f32) {
+        if self.climb_animation.is_some() {
+            scene
+                .animations
+                .get_mut(self.climb_animation)
+                .set_speed(speed);
+        }
+    }
+
     pub fn hit_reaction_animations(&self) -> [Handle<Animation>; 2] {
         [
             self.hit_reaction_rifle_animation,
@@ -0,0 +1,209 @@
+use crate::{character::HitBox, CollisionGroups};
+use fyrox::{
+    core::{
+        algebra::Vector3,
+        math::Matrix4Ext,
+        pool::Handle,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{
+        base::BaseBuilder,
+        collider::{BitMask, Collider, InteractionGroups},
+        graph::Graph,
+        joint::{BallJoint, JointBuilder, JointParams},
+        node::Node,
+        rigidbody::{RigidBody, RigidBodyType},
+        transform::TransformBuilder,
+    },
+};
+
+/// Velocity below which a limb is considered to be in rest.
+const REST_VELOCITY: f32 = 0.1;
+/// How long all limbs must be in rest to freeze the ragdoll.
+const SETTLE_TIME: f32 = 1.0;
+/// Ragdoll will be frozen after this time even if it is still moving (stuck somewhere, etc.)
+const MAX_SIMULATION_TIME: f32 = 10.0;
+
+/// Physics-driven ragdoll that is built from the hit boxes of a character. Every hit box collider
+/// is attached to a rigid body, which is also a bone of the skeleton, so once the bodies are
+/// simulated the skinned mesh simply follows them.
+#[derive(Default, Visit)]
+pub struct Ragdoll {
+    limbs: Vec<Handle<Node>>,
+    joints: Vec<Handle<Node>>,
+    active: bool,
+    settled: bool,
+    rest_time: f32,
+    simulation_time: f32,
+}
+
+impl Ragdoll {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.settled
+    }
+
+    /// Turns hit boxes into a ragdoll. Does nothing if the ragdoll is already active.
+    pub fn activate(&mut self, hit_boxes: &[HitBox], graph: &mut Graph) {
+        if self.active {
+            return;
+        }
+
+        self.active = true;
+
+        for hit_box in hit_boxes {
+            let body = graph[hit_box.collider].parent();
+            if graph
+                .try_get(body)
+                .map_or(false, |n| n.cast::<RigidBody>().is_some())
+                && !self.limbs.contains(&body)
+            {
+                self.limbs.push(body);
+            }
+        }
+
+        // Connect every limb with its closest parent limb. Hit boxes are found by depth-first
+        // traversal, so parent limbs always come first.
+        for &limb in self.limbs.iter() {
+            let mut parent = graph[limb].parent();
+            while parent.is_some() && !self.limbs.contains(&parent) {
+                parent = graph[parent].parent();
+            }
+
+            if parent.is_some() {
+                let joint = JointBuilder::new(
+                    BaseBuilder::new().with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(graph[limb].global_position())
+                            .build(),
+                    ),
+                )
+                .with_params(JointParams::BallJoint(BallJoint::default()))
+                .with_body1(parent)
+                .with_body2(limb)
+                .build(graph);
+
+                self.joints.push(joint);
+            }
+        }
+
+        // Remember global transforms first, because unlinking a limb invalidates global
+        // transforms of its descendants until next graph update.
+        let transforms = self
+            .limbs
+            .iter()
+            .map(|&limb| {
+                let basis = graph[limb].global_transform().basis();
+                (
+                    graph[limb].global_position(),
+                    graph.global_rotation(limb),
+                    Vector3::new(
+                        basis.column(0).norm(),
+                        basis.column(1).norm(),
+                        basis.column(2).norm(),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Ragdoll must not push living actors around, and it must not collide with itself,
+        // otherwise overlapping hit boxes will make it explode.
+        let groups = InteractionGroups::new(
+            BitMask(CollisionGroups::Ragdoll as u32),
+            BitMask(
+                CollisionGroups::All as u32
+                    & !(CollisionGroups::ActorCapsule as u32 | CollisionGroups::Ragdoll as u32),
+            ),
+        );
+
+        for (&limb, (position, rotation, scale)) in self.limbs.iter().zip(transforms) {
+            graph.unlink_nodes(limb);
+
+            graph[limb]
+                .local_transform_mut()
+                .set_position(position)
+                .set_rotation(rotation)
+                .set_scale(scale);
+
+            graph[limb]
+                .as_rigid_body_mut()
+                .set_body_type(RigidBodyType::Dynamic);
+
+            for child in graph[limb].children().to_vec() {
+                if let Some(collider) = graph[child].cast_mut::<Collider>() {
+                    collider.set_collision_groups(groups);
+                }
+            }
+        }
+    }
+
+    pub fn update(&mut self, graph: &mut Graph, dt: f32) {
+        if !self.active || self.settled {
+            return;
+        }
+
+        self.simulation_time += dt;
+
+        let in_rest = self.limbs.iter().all(|&limb| {
+            graph
+                .try_get(limb)
+                .and_then(|n| n.cast::<RigidBody>())
+                .map_or(true, |body| body.lin_vel().norm() < REST_VELOCITY)
+        });
+
+        if in_rest {
+            self.rest_time += dt;
+        } else {
+            self.rest_time = 0.0;
+        }
+
+        if self.rest_time >= SETTLE_TIME || self.simulation_time >= MAX_SIMULATION_TIME {
+            self.settled = true;
+
+            // Freeze settled ragdoll, there is no need to simulate it anymore.
+            for &limb in self.limbs.iter() {
+                if let Some(body) = graph
+                    .try_get_mut(limb)
+                    .and_then(|n| n.cast_mut::<RigidBody>())
+                {
+                    body.set_body_type(RigidBodyType::KinematicPositionBased);
+                }
+            }
+        }
+    }
+
+    /// Adds given velocity to every limb, it wakes up a settled ragdoll.
+    pub fn add_velocity(&mut self, velocity: Vector3<f32>, graph: &mut Graph) {
+        if !self.active || velocity == Vector3::default() {
+            return;
+        }
+
+        for &limb in self.limbs.iter() {
+            if let Some(body) = graph
+                .try_get_mut(limb)
+                .and_then(|n| n.cast_mut::<RigidBody>())
+            {
+                body.set_body_type(RigidBodyType::Dynamic);
+                body.set_lin_vel(body.lin_vel() + velocity);
+            }
+        }
+
+        self.settled = false;
+        self.rest_time = 0.0;
+        self.simulation_time = 0.0;
+    }
+
+    pub fn clean_up(&mut self, graph: &mut Graph) {
+        for &node in self.joints.iter().chain(self.limbs.iter()) {
+            if graph.is_valid_handle(node) {
+                graph.remove_node(node);
+            }
+        }
+
+        self.joints.clear();
+        self.limbs.clear();
+    }
+}
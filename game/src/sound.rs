@@ -1,14 +1,24 @@
-use crate::message::Message;
+use crate::{door::Door, message::Message, CollisionGroups};
 use fyrox::scene::mesh::Mesh;
 use fyrox::{
-    core::{algebra::Vector3, pool::Handle, sstorage::ImmutableString, visitor::prelude::*},
+    core::{
+        algebra::{Point3, Vector3},
+        pool::Handle,
+        sstorage::ImmutableString,
+        visitor::prelude::*,
+    },
     engine::resource_manager::ResourceManager,
     material::PropertyValue,
     rand::{self, seq::SliceRandom},
     scene::{
         base::BaseBuilder,
-        graph::{physics::FeatureId, Graph},
+        collider::{BitMask, Collider, InteractionGroups},
+        graph::{
+            physics::{FeatureId, RayCastOptions},
+            Graph,
+        },
         node::Node,
+        rigidbody::{RigidBody, RigidBodyType},
         sound::{
             effect::{BaseEffectBuilder, Effect, EffectInput, ReverbEffectBuilder},
             SoundBuilder, Status,
@@ -16,6 +26,10 @@ use fyrox::{
         transform::TransformBuilder,
         Scene,
     },
+    sound::{
+        buffer::{DataSource, SoundBufferResource},
+        dsp::filters::{Biquad, BiquadKind},
+    },
     utils::log::{Log, MessageKind},
 };
 use serde::Deserialize;
@@ -43,6 +57,13 @@ pub enum SoundKind {
     FootStep,
 }
 
+/// Kinds of noises that can be heard by bots.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NoiseKind {
+    Gunshot,
+    FootStep,
+}
+
 #[derive(Deserialize, Debug, Default)]
 pub struct SoundBase {
     material_to_sound: HashMap<MaterialType, HashMap<SoundKind, Vec<PathBuf>>>,
@@ -176,17 +197,169 @@ impl SoundMap {
     }
 }
 
-#[derive(Default, Visit)]
+/// Gain multiplier for each obstacle between listener and a sound source.
+const OCCLUSION_GAIN_FACTOR: f32 = 0.5;
+/// Max amount of obstacles that is taken into account, sounds behind more obstacles are muffled
+/// the same.
+const MAX_OCCLUDERS: usize = 3;
+/// Normalized cutoff frequency of low-pass filter for a sound behind a single obstacle.
+const OCCLUSION_CUTOFF: f32 = 0.05;
+/// Amount of horizontal rays that probe surroundings of a point, one more ray probes ceiling.
+const ENCLOSURE_RAY_COUNT: usize = 8;
+/// Walls farther than this do not make a space enclosed.
+const ENCLOSURE_RAY_LENGTH: f32 = 6.0;
+
+/// Collision groups of everything except characters and ragdolls.
+fn level_geometry_groups() -> InteractionGroups {
+    InteractionGroups::new(
+        BitMask(0xFFFF),
+        BitMask(!(CollisionGroups::ActorCapsule as u32 | CollisionGroups::Ragdoll as u32)),
+    )
+}
+
+/// Checks whether given collider is a part of level geometry. Walls, floors, other static
+/// obstacles and closed doors block sounds, open doors, props and trigger volumes do not.
+fn is_level_geometry(graph: &Graph, collider: Handle<Node>) -> bool {
+    graph.try_get(collider).map_or(false, |node| {
+        node.cast::<Collider>()
+            .map_or(false, |collider| !collider.is_sensor())
+            && graph.try_get(node.parent()).map_or(false, |parent| {
+                if let Some(door) = parent.script().and_then(|s| s.cast::<Door>()) {
+                    door.is_closed()
+                } else {
+                    parent
+                        .cast::<RigidBody>()
+                        .map_or(false, |body| body.body_type() == RigidBodyType::Static)
+                }
+            })
+    })
+}
+
+/// Counts obstacles between listener and a sound source.
+fn count_occluders(graph: &Graph, listener: Vector3<f32>, source: Vector3<f32>) -> usize {
+    let dir = source - listener;
+    let mut query_buffer = Vec::new();
+    graph.physics.cast_ray(
+        RayCastOptions {
+            ray_origin: Point3::from(listener),
+            ray_direction: dir,
+            groups: level_geometry_groups(),
+            max_len: dir.norm(),
+            sort_results: false,
+        },
+        &mut query_buffer,
+    );
+
+    query_buffer
+        .iter()
+        .filter(|hit| is_level_geometry(graph, hit.collider))
+        .count()
+        .min(MAX_OCCLUDERS)
+}
+
+/// Makes a copy of given buffer passed through a low-pass filter, so the sound is muffled as if
+/// it was heard through given amount of obstacles.
+fn make_muffled_buffer(
+    buffer: &SoundBufferResource,
+    occluders: usize,
+) -> Option<SoundBufferResource> {
+    let data = buffer.data_ref();
+    let channel_count = data.channel_count();
+    if channel_count == 0 {
+        return None;
+    }
+
+    let mut filters = (0..channel_count)
+        .map(|_| {
+            Biquad::new(
+                BiquadKind::LowPass,
+                OCCLUSION_CUTOFF / occluders as f32,
+                1.0,
+                0.707,
+            )
+        })
+        .collect::<Vec<_>>();
+    // Samples of channels are interleaved, each channel is filtered separately.
+    let samples = data
+        .samples()
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| filters[i % channel_count].feed(sample))
+        .collect();
+
+    SoundBufferResource::new_generic(DataSource::Raw {
+        sample_rate: data.sample_rate(),
+        channel_count,
+        samples,
+    })
+    .ok()
+}
+
+/// Returns how enclosed given point is in `[0; 1]` range: zero means open area, one means that
+/// there are walls and ceiling close to the point in every direction.
+pub fn enclosure_factor(graph: &Graph, position: Vector3<f32>) -> f32 {
+    let directions = (0..ENCLOSURE_RAY_COUNT)
+        .map(|i| {
+            let angle = i as f32 / ENCLOSURE_RAY_COUNT as f32 * std::f32::consts::TAU;
+            Vector3::new(angle.cos(), 0.0, angle.sin())
+        })
+        .chain(std::iter::once(Vector3::y()));
+
+    let mut query_buffer = Vec::new();
+    let mut hits = 0;
+    for direction in directions {
+        query_buffer.clear();
+        graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(position),
+                ray_direction: direction,
+                groups: level_geometry_groups(),
+                max_len: ENCLOSURE_RAY_LENGTH,
+                sort_results: false,
+            },
+            &mut query_buffer,
+        );
+
+        if query_buffer
+            .iter()
+            .any(|hit| is_level_geometry(graph, hit.collider))
+        {
+            hits += 1;
+        }
+    }
+
+    hits as f32 / (ENCLOSURE_RAY_COUNT + 1) as f32
+}
+
+#[derive(Visit)]
 pub struct SoundManager {
     reverb: Handle<Effect>,
     #[visit(skip)]
     sound_base: SoundBase,
     #[visit(skip)]
     sound_map: SoundMap,
+    /// Multiplier for gains of every sound played by the manager.
+    #[visit(skip)]
+    sfx_volume: f32,
+    /// Muffled copies of sound buffers by path and amount of obstacles.
+    #[visit(skip)]
+    muffled_buffers: HashMap<(PathBuf, usize), SoundBufferResource>,
+}
+
+impl Default for SoundManager {
+    fn default() -> Self {
+        Self {
+            reverb: Default::default(),
+            sound_base: Default::default(),
+            sound_map: Default::default(),
+            sfx_volume: 1.0,
+            muffled_buffers: Default::default(),
+        }
+    }
 }
 
 impl SoundManager {
-    pub fn new(scene: &mut Scene) -> Self {
+    pub fn new(scene: &mut Scene, sfx_volume: f32) -> Self {
         let reverb = ReverbEffectBuilder::new(BaseEffectBuilder::new().with_gain(0.7))
             .with_wet(0.5)
             .with_dry(0.5)
@@ -199,12 +372,34 @@ impl SoundManager {
             reverb,
             sound_map: SoundMap::new(scene, &sound_base),
             sound_base,
+            sfx_volume,
+            muffled_buffers: Default::default(),
+        }
+    }
+
+    pub fn set_sfx_volume(&mut self, volume: f32) {
+        self.sfx_volume = volume;
+    }
+
+    /// Returns muffled copy of given buffer, copies are made once and reused after.
+    fn muffled_buffer(
+        &mut self,
+        path: &Path,
+        buffer: &SoundBufferResource,
+        occluders: usize,
+    ) -> Option<SoundBufferResource> {
+        let key = (path.to_path_buf(), occluders);
+        if !self.muffled_buffers.contains_key(&key) {
+            let muffled = make_muffled_buffer(buffer, occluders)?;
+            self.muffled_buffers.insert(key.clone(), muffled);
         }
+        self.muffled_buffers.get(&key).cloned()
     }
 
     async fn play_sound(
-        &self,
+        &mut self,
         graph: &mut Graph,
+        listener: Handle<Node>,
         path: &Path,
         position: Vector3<f32>,
         gain: f32,
@@ -213,6 +408,19 @@ impl SoundManager {
         resource_manager: ResourceManager,
     ) {
         if let Ok(buffer) = resource_manager.request_sound_buffer(path).await {
+            // Sounds behind walls are quieter and muffled. Both the direct sound and the reverb
+            // are muffled, so the filter is applied to the buffer itself.
+            let occluders = graph.try_get(listener).map_or(0, |listener| {
+                count_occluders(graph, listener.global_position(), position)
+            });
+            let gain = gain * self.sfx_volume * OCCLUSION_GAIN_FACTOR.powi(occluders as i32);
+            let buffer = if occluders > 0 {
+                self.muffled_buffer(path, &buffer, occluders)
+                    .unwrap_or(buffer)
+            } else {
+                buffer
+            };
+
             let sound = SoundBuilder::new(
                 BaseBuilder::new().with_local_transform(
                     TransformBuilder::new()
@@ -244,9 +452,38 @@ impl SoundManager {
         }
     }
 
+    /// Returns material type of a surface at a given feature of a collider.
+    pub fn material_of(&self, collider: Handle<Node>, feature: FeatureId) -> Option<MaterialType> {
+        self.sound_map
+            .ranges_of(collider)
+            .map(|ranges| {
+                match feature {
+                    FeatureId::Face(idx) => {
+                        let mut material = None;
+                        for range in ranges {
+                            if range.range.contains(&idx) {
+                                material = Some(range.material);
+                                break;
+                            }
+                        }
+                        material
+                    }
+                    _ => {
+                        // Some object have convex shape colliders, they're not provide any
+                        // useful info about the point of impact, so we have to use first
+                        // available material.
+                        ranges.first().map(|first_range| first_range.material)
+                    }
+                }
+            })
+            .flatten()
+    }
+
+    /// Handles sound messages, `listener` is the camera through which the player hears sounds.
     pub async fn handle_message(
         &mut self,
         graph: &mut Graph,
+        listener: Handle<Node>,
         resource_manager: ResourceManager,
         message: &Message,
     ) {
@@ -260,6 +497,7 @@ impl SoundManager {
             } => {
                 self.play_sound(
                     graph,
+                    listener,
                     path,
                     *position,
                     *gain,
@@ -278,38 +516,15 @@ impl SoundManager {
                 rolloff_factor,
                 radius,
             } => {
-                let material = self
-                    .sound_map
-                    .ranges_of(collider)
-                    .map(|ranges| {
-                        match feature {
-                            FeatureId::Face(idx) => {
-                                let mut material = None;
-                                for range in ranges {
-                                    if range.range.contains(&idx) {
-                                        material = Some(range.material);
-                                        break;
-                                    }
-                                }
-                                material
-                            }
-                            _ => {
-                                // Some object have convex shape colliders, they're not provide any
-                                // useful info about the point of impact, so we have to use first
-                                // available material.
-                                ranges.first().map(|first_range| first_range.material)
-                            }
-                        }
-                    })
-                    .flatten();
-
-                if let Some(material) = material {
+                if let Some(material) = self.material_of(collider, feature) {
                     if let Some(map) = self.sound_base.material_to_sound.get(&material) {
                         if let Some(sound_list) = map.get(&sound_kind) {
-                            if let Some(sound) = sound_list.choose(&mut rand::thread_rng()) {
+                            if let Some(sound) = sound_list.choose(&mut rand::thread_rng()).cloned()
+                            {
                                 self.play_sound(
                                     graph,
-                                    sound.as_ref(),
+                                    listener,
+                                    &sound,
                                     position,
                                     gain,
                                     rolloff_factor,
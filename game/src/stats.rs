@@ -0,0 +1,119 @@
+//! Statistics of a play session: shots, hits, kills, damage and distance. Statistics belong to
+//! the player and travel with the player between levels, the best results of finished sessions
+//! are kept as records in `data/configs/records.ron`.
+
+use crate::config::ConfigError;
+use fyrox::core::{algebra::Vector3, visitor::prelude::*};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+
+/// Movement farther than this distance (in meters) per frame is treated as a teleport (level
+/// transition, elevator, etc.) and is not counted as traveled distance.
+const MAX_STEP: f32 = 2.0;
+
+#[derive(Default, Clone, Debug, Visit)]
+pub struct SessionStats {
+    pub shots_fired: u32,
+    /// Amount of shots that hit an actor.
+    pub hits: u32,
+    pub kills: u32,
+    pub damage_dealt: f32,
+    pub damage_taken: f32,
+    /// Distance (in meters) traveled over the ground.
+    pub distance_traveled: f32,
+    #[visit(skip)]
+    last_position: Option<Vector3<f32>>,
+}
+
+impl SessionStats {
+    /// Returns percentage of shots that hit an actor.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            // A piercing shot could hit more than one actor.
+            (100.0 * self.hits as f32 / self.shots_fired as f32).min(100.0)
+        }
+    }
+
+    /// Accumulates distance between the previous and given positions, vertical movement is
+    /// ignored.
+    pub fn track_position(&mut self, position: Vector3<f32>) {
+        if let Some(last_position) = self.last_position {
+            let step = Vector3::new(
+                position.x - last_position.x,
+                0.0,
+                position.z - last_position.z,
+            )
+            .norm();
+            if step < MAX_STEP {
+                self.distance_traveled += step;
+            }
+        }
+        self.last_position = Some(position);
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Shots Fired: {}\nAccuracy: {:.1}%\nKills: {}\nDamage Dealt: {:.0}\n\
+            Damage Taken: {:.0}\nDistance Traveled: {:.0} m",
+            self.shots_fired,
+            self.accuracy(),
+            self.kills,
+            self.damage_dealt,
+            self.damage_taken,
+            self.distance_traveled
+        )
+    }
+}
+
+/// The best results among all finished sessions.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct SessionRecords {
+    pub kills: u32,
+    pub accuracy: f32,
+    pub damage_dealt: f32,
+    pub distance_traveled: f32,
+}
+
+impl SessionRecords {
+    const PATH: &'static str = "data/configs/records.ron";
+
+    pub fn load() -> Result<Self, ConfigError> {
+        let file = File::open(Self::PATH)?;
+        Ok(ron::de::from_reader(file)?)
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let file = File::create(Self::PATH)?;
+        ron::ser::to_writer_pretty(file, self, PrettyConfig::default())?;
+        Ok(())
+    }
+
+    /// Updates records with results of a session, returns `true` if any record was beaten.
+    pub fn submit(&mut self, stats: &SessionStats) -> bool {
+        let mut beaten = false;
+        let mut update = |record: &mut f32, value: f32| {
+            if value > *record {
+                *record = value;
+                beaten = true;
+            }
+        };
+        update(&mut self.accuracy, stats.accuracy());
+        update(&mut self.damage_dealt, stats.damage_dealt);
+        update(&mut self.distance_traveled, stats.distance_traveled);
+        if stats.kills > self.kills {
+            self.kills = stats.kills;
+            beaten = true;
+        }
+        beaten
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Records: {} kills, {:.1}% accuracy, {:.0} damage, {:.0} m",
+            self.kills, self.accuracy, self.damage_dealt, self.distance_traveled
+        )
+    }
+}
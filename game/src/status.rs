@@ -0,0 +1,117 @@
+//! Status effects deal damage over time to characters. Effects are applied by certain weapons,
+//! projectiles and attacks of bots, damage is dealt in ticks through the regular damage path.
+
+use crate::{actor::Actor, message::Message, MessageSender};
+use fyrox::core::{
+    pool::Handle,
+    visitor::{Visit, VisitResult, Visitor},
+};
+use serde::Deserialize;
+
+/// Time (in seconds) between two ticks of damage of a status effect.
+const TICK_INTERVAL: f32 = 0.5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Deserialize, Visit)]
+pub enum StatusEffectKind {
+    Burning,
+    Bleeding,
+    Poisoned,
+}
+
+impl Default for StatusEffectKind {
+    fn default() -> Self {
+        Self::Burning
+    }
+}
+
+impl StatusEffectKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusEffectKind::Burning => "Burning",
+            StatusEffectKind::Bleeding => "Bleeding",
+            StatusEffectKind::Poisoned => "Poisoned",
+        }
+    }
+}
+
+/// Describes a status effect that is applied by a weapon, projectile or attack.
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct StatusEffectDefinition {
+    pub kind: StatusEffectKind,
+    /// Duration in seconds.
+    pub duration: f32,
+    pub damage_per_second: f32,
+}
+
+#[derive(Default, Clone, Debug, Visit)]
+pub struct StatusEffect {
+    kind: StatusEffectKind,
+    time_left: f32,
+    damage_per_second: f32,
+    /// Actor who applied the effect, damage is attributed to it.
+    who: Handle<Actor>,
+    tick_timer: f32,
+}
+
+#[derive(Default, Clone, Debug, Visit)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Applies an effect, effects of the same kind do not stack - the existing effect is
+    /// refreshed instead and the strongest damage of the two is kept.
+    pub fn apply(&mut self, definition: &StatusEffectDefinition, who: Handle<Actor>) {
+        if let Some(effect) = self.effects.iter_mut().find(|e| e.kind == definition.kind) {
+            effect.time_left = effect.time_left.max(definition.duration);
+            effect.damage_per_second = effect.damage_per_second.max(definition.damage_per_second);
+            effect.who = who;
+        } else {
+            self.effects.push(StatusEffect {
+                kind: definition.kind,
+                time_left: definition.duration,
+                damage_per_second: definition.damage_per_second,
+                who,
+                tick_timer: TICK_INTERVAL,
+            });
+        }
+    }
+
+    pub fn cure(&mut self, kind: StatusEffectKind) {
+        self.effects.retain(|e| e.kind != kind);
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    /// Returns kinds of active effects together with remaining time of each effect.
+    pub fn active(&self) -> impl Iterator<Item = (StatusEffectKind, f32)> + '_ {
+        self.effects.iter().map(|e| (e.kind, e.time_left))
+    }
+
+    /// Advances every effect, damage of each tick is sent to the actor.
+    pub fn update(&mut self, actor: Handle<Actor>, sender: &MessageSender, dt: f32) {
+        for effect in self.effects.iter_mut() {
+            let dt = dt.min(effect.time_left);
+            effect.time_left -= dt;
+            effect.tick_timer -= dt;
+
+            if effect.tick_timer <= 0.0 || effect.time_left <= 0.0 {
+                let elapsed = TICK_INTERVAL - effect.tick_timer;
+                effect.tick_timer = TICK_INTERVAL;
+
+                sender.send(Message::DamageActor {
+                    actor,
+                    who: effect.who,
+                    weapon: Default::default(),
+                    hitbox: None,
+                    amount: effect.damage_per_second * elapsed,
+                    critical_shot_probability: 0.0,
+                });
+            }
+        }
+
+        self.effects.retain(|e| e.time_left > 0.0);
+    }
+}
@@ -26,6 +26,7 @@ use fyrox::{
 use std::collections::HashMap;
 
 pub mod model_map;
+pub mod spatial_grid;
 
 struct ImpactEntry {
     k: f32,
@@ -169,3 +170,29 @@ pub fn create_play_animation_state(
 pub fn is_probability_event_occurred(probability: f32) -> bool {
     return rand::thread_rng().gen_range(0.0..1.0) < probability.clamp(0.0, 1.0);
 }
+
+/// Returns random direction that deviates from given one by at most `max_angle` radians.
+pub fn deviate(direction: Vector3<f32>, max_angle: f32) -> Vector3<f32> {
+    let mut rng = rand::thread_rng();
+
+    let (direction, side) = match direction.try_normalize(f32::EPSILON) {
+        Some(direction) => (
+            direction,
+            direction
+                .cross(&Vector3::y())
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::x),
+        ),
+        None => return direction,
+    };
+
+    let axis = UnitQuaternion::from_axis_angle(
+        &Unit::new_unchecked(direction),
+        rng.gen_range(0.0..std::f32::consts::TAU),
+    ) * side;
+
+    UnitQuaternion::from_axis_angle(
+        &Unit::new_normalize(axis),
+        rng.gen_range(0.0..=max_angle.max(0.0)),
+    ) * direction
+}
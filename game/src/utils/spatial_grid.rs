@@ -0,0 +1,93 @@
+//! Uniform grid that allows to quickly find static objects near some point, instead of checking
+//! every object.
+
+use fyrox::core::algebra::Vector3;
+use std::collections::HashMap;
+
+type Cell = (i32, i32, i32);
+
+pub struct SpatialGrid<T> {
+    cell_size: f32,
+    cells: HashMap<Cell, Vec<(Vector3<f32>, T)>>,
+    len: usize,
+}
+
+impl<T> Default for SpatialGrid<T> {
+    fn default() -> Self {
+        Self::new(2.0)
+    }
+}
+
+impl<T> SpatialGrid<T> {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: Default::default(),
+            len: 0,
+        }
+    }
+
+    fn cell_of(&self, position: Vector3<f32>) -> Cell {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.len = 0;
+    }
+}
+
+impl<T: Copy + PartialEq> SpatialGrid<T> {
+    pub fn insert(&mut self, position: Vector3<f32>, value: T) {
+        let cell = self.cell_of(position);
+        let entries = self.cells.entry(cell).or_default();
+        if !entries.iter().any(|(_, v)| *v == value) {
+            entries.push((position, value));
+            self.len += 1;
+        }
+    }
+
+    pub fn remove(&mut self, value: T) {
+        let mut removed = 0;
+        self.cells.retain(|_, entries| {
+            let count = entries.len();
+            entries.retain(|(_, v)| *v != value);
+            removed += count - entries.len();
+            !entries.is_empty()
+        });
+        self.len -= removed;
+    }
+
+    /// Returns every value (with the position it was inserted at) within given radius around
+    /// the point.
+    pub fn query(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+    ) -> impl Iterator<Item = (Vector3<f32>, T)> + '_ {
+        let extent = Vector3::repeat(radius);
+        let min = self.cell_of(position - extent);
+        let max = self.cell_of(position + extent);
+
+        (min.0..=max.0)
+            .flat_map(move |x| {
+                (min.1..=max.1).flat_map(move |y| (min.2..=max.2).map(move |z| (x, y, z)))
+            })
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |(p, _)| p.metric_distance(&position) <= radius)
+            .map(|(p, v)| (*p, *v))
+    }
+}
@@ -0,0 +1,74 @@
+//! Weapon attachments are items that are mounted into slots of a weapon (optic, muzzle, grip)
+//! and modify its characteristics. Slots that a weapon supports are listed in its definition,
+//! modifiers of each attachment are stored in `data/configs/attachments.ron`.
+
+use crate::item::ItemKind;
+use fyrox::lazy_static::lazy_static;
+use serde::Deserialize;
+use std::{collections::HashMap, fs::File};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Deserialize, Hash)]
+pub enum AttachmentSlot {
+    Optic,
+    Muzzle,
+    Grip,
+}
+
+impl AttachmentSlot {
+    pub fn name(self) -> &'static str {
+        match self {
+            AttachmentSlot::Optic => "Optic",
+            AttachmentSlot::Muzzle => "Muzzle",
+            AttachmentSlot::Grip => "Grip",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AttachmentDefinition {
+    pub slot: AttachmentSlot,
+    /// Zoom of the view while aiming, `None` means no zoom.
+    #[serde(default)]
+    pub magnification: Option<f32>,
+    /// Multiplier for the gain of shot sounds.
+    #[serde(default = "default_factor")]
+    pub sound_gain_factor: f32,
+    /// Multiplier for the radius in which shot sounds are heard.
+    #[serde(default = "default_factor")]
+    pub sound_radius_factor: f32,
+    /// Multiplier for the radius in which bots can hear shots.
+    #[serde(default = "default_factor")]
+    pub noise_factor: f32,
+    /// Multiplier for the damage of hitscan weapons.
+    #[serde(default = "default_factor")]
+    pub damage_factor: f32,
+    /// Multiplier for the recoil of the weapon, both the pattern and the jitter.
+    #[serde(default = "default_factor")]
+    pub recoil_factor: f32,
+}
+
+fn default_factor() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Default)]
+pub struct AttachmentDefinitionContainer {
+    map: HashMap<ItemKind, AttachmentDefinition>,
+}
+
+impl AttachmentDefinitionContainer {
+    pub fn new() -> Self {
+        let file = File::open("data/configs/attachments.ron").unwrap();
+        ron::de::from_reader(file).unwrap()
+    }
+}
+
+lazy_static! {
+    static ref DEFINITIONS: AttachmentDefinitionContainer = AttachmentDefinitionContainer::new();
+}
+
+/// Returns definition of an attachment that is represented by given item, `None` if the item is
+/// not an attachment.
+pub fn attachment_definition(item: ItemKind) -> Option<&'static AttachmentDefinition> {
+    DEFINITIONS.map.get(&item)
+}
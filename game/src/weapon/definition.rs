@@ -1,10 +1,14 @@
 use crate::{
     item::ItemKind,
-    weapon::projectile::{Damage, ProjectileKind},
+    weapon::{
+        attachment::AttachmentSlot,
+        projectile::{Damage, Projectile, ProjectileKind},
+    },
 };
 use fyrox::{
-    core::{algebra::Vector3, rand::Rng, visitor::prelude::*},
+    core::{algebra::Vector3, color::Color, math::Rect, rand::Rng, visitor::prelude::*},
     lazy_static::lazy_static,
+    utils::log::{Log, MessageKind},
 };
 use serde::Deserialize;
 use std::{collections::HashMap, fs::File};
@@ -45,6 +49,26 @@ impl WeaponKind {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Deserialize)]
+pub enum FireMode {
+    /// One shot per trigger pull.
+    Single,
+    /// Fixed amount of shots per trigger pull.
+    Burst(u32),
+    /// Shoots while trigger is held.
+    Auto,
+}
+
+impl FireMode {
+    pub fn name(self) -> String {
+        match self {
+            FireMode::Single => "SEMI".to_owned(),
+            FireMode::Burst(count) => format!("BURST {}", count),
+            FireMode::Auto => "AUTO".to_owned(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Deserialize)]
 pub enum WeaponProjectile {
     Projectile(ProjectileKind),
@@ -58,19 +82,179 @@ pub enum WeaponProjectile {
 pub struct WeaponDefinition {
     pub model: String,
     pub shot_sounds: Vec<String>,
+    /// Reverb tails that are played after a shot, volume of a tail depends on how enclosed the
+    /// space around the weapon is. Empty list means no tail.
+    #[serde(default)]
+    pub tail_sounds: Vec<String>,
     pub projectile: WeaponProjectile,
+    /// Interval between shots, it is used as a delay between shots of a burst as well.
     pub shoot_interval: f64,
+    /// List of fire modes that can be cycled, first one is the default.
+    pub fire_modes: Vec<FireMode>,
     pub yaw_correction: f32,
     pub pitch_correction: f32,
     pub ammo_indicator_offset: (f32, f32, f32),
     pub ammo_consumption_per_shot: u32,
+    /// Amount of shots left at which the ammo indicator warns about low ammo.
+    pub low_ammo_shots: u32,
+    /// Range (in degrees) of random vertical jitter that is added to the recoil pattern.
     pub v_recoil: (f32, f32),
+    /// Range (in degrees) of random horizontal jitter that is added to the recoil pattern.
     pub h_recoil: (f32, f32),
+    /// Vertical and horizontal offsets (in degrees) of aim for each shot of continuous fire,
+    /// the last one is used for every shot after the end of the pattern.
+    pub recoil_pattern: Vec<(f32, f32)>,
+    /// Pause in shooting (in seconds) after which the recoil pattern starts over.
+    pub recoil_reset_time: f32,
+    /// Maximum extra spread (in degrees) of shots that are fired right after sprinting.
+    pub sprint_spread: f32,
+    /// Time (in seconds) that it takes for the aim to settle after sprinting.
+    pub sprint_settle_time: f32,
     pub shot_effect: ShotEffect,
+    /// Only every Nth shot leaves a visible trail, 1 means that every shot is a tracer.
+    pub tracer_frequency: u32,
     pub base_critical_shot_probability: f32,
+    /// Radius in which bots can hear shots of the weapon.
+    pub noise_radius: f32,
+    /// Weapons with silencer are much quieter, which allows to kill enemies silently. `None`
+    /// means that a silencer cannot be attached to the weapon.
+    pub silencer: Option<SilencerDefinition>,
+    /// Scoped weapons zoom the view while aiming.
+    pub scope: Option<ScopeDefinition>,
+    /// Weight (in kilograms), heavy weapons slow down movement and aiming of the player.
+    #[serde(default)]
+    pub weight: f32,
+    /// Speed multiplier of the animations of putting the weapon back and grabbing it, heavy
+    /// weapons should be switched slower.
+    #[serde(default = "default_switch_speed")]
+    pub switch_speed: f32,
+    #[serde(default)]
+    pub muzzle_flash: MuzzleFlashDefinition,
+    /// Impulse that a shot gives to a hit body, actors are knocked back by it too.
+    #[serde(default = "default_impact_force")]
+    pub impact_force: f32,
+    #[serde(default)]
+    pub sight_calibration: SightCalibration,
+    /// Slots for attachments that can be mounted on the weapon.
+    #[serde(default)]
+    pub attachment_slots: Vec<AttachmentSlot>,
+}
+
+fn default_impact_force() -> f32 {
+    30.0
+}
+
+fn default_switch_speed() -> f32 {
+    1.0
+}
+
+/// Summary of weapon characteristics that is meant to be shown in UI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeaponStats {
+    /// Damage of a single shot.
+    pub damage: f32,
+    /// `None` for weapons that damage only what was hit directly.
+    pub splash_radius: Option<f32>,
+    /// Shots per minute.
+    pub rate_of_fire: f32,
+    /// Average recoil (in degrees) of a single shot, it is the only source of inaccuracy.
+    pub recoil: f32,
+    pub critical_shot_probability: f32,
+    pub ammo: ItemKind,
+    pub ammo_per_shot: u32,
+    pub fire_modes: Vec<FireMode>,
+}
+
+#[derive(Deserialize)]
+pub struct ScopeDefinition {
+    pub magnification: f32,
+    /// Path to a texture that will be drawn over the screen while scoped.
+    pub overlay: String,
+    /// If `true`, parts of the screen that are not covered by the overlay will be black.
+    pub darken_screen: bool,
+    /// If set, the magnified view is rendered by a separate camera into a panel on the screen,
+    /// the main view is not zoomed and the overlay is not shown.
+    #[serde(default)]
+    pub picture_in_picture: Option<PictureInPictureDefinition>,
+}
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct PictureInPictureDefinition {
+    /// Position of the top-left corner of the panel in fractions of the screen size.
+    pub position: (f32, f32),
+    /// Size of the panel in fractions of the screen size.
+    pub size: (f32, f32),
+}
+
+impl PictureInPictureDefinition {
+    /// Returns the panel rectangle in normalized screen coordinates.
+    pub fn viewport(&self) -> Rect<f32> {
+        Rect::new(self.position.0, self.position.1, self.size.0, self.size.1)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MuzzleFlashDefinition {
+    /// Time (in seconds) that the muzzle flash and its light stay visible after a shot.
+    pub duration: f32,
+    pub light_radius: f32,
+    pub light_color: (u8, u8, u8),
+    /// Multiplier for the brightness of the light color.
+    pub light_intensity: f32,
+}
+
+impl Default for MuzzleFlashDefinition {
+    fn default() -> Self {
+        Self {
+            duration: 0.075,
+            light_radius: 2.0,
+            light_color: (255, 255, 255),
+            light_intensity: 1.0,
+        }
+    }
+}
+
+/// Calibration of the laser sight of a weapon, it makes the laser dot match the point of impact.
+#[derive(Default, Deserialize)]
+pub struct SightCalibration {
+    /// Offset of the laser origin from the shot point in local coordinates of the weapon model.
+    pub laser_offset: (f32, f32, f32),
+    /// Whether shots of the player converge at the point under the crosshair instead of flying
+    /// along the barrel, the laser is aimed at the same point then. `None` means that only
+    /// projectiles converge.
+    pub converge_shots: Option<bool>,
+}
+
+impl MuzzleFlashDefinition {
+    pub fn light_color(&self) -> Color {
+        let (r, g, b) = self.light_color;
+        let scale = |c: u8| (c as f32 * self.light_intensity).clamp(0.0, 255.0) as u8;
+        Color::opaque(scale(r), scale(g), scale(b))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SilencerDefinition {
+    /// Sounds that will be used instead of regular shot sounds. If the list is empty, regular
+    /// sounds are played with the gain and the radius of the silencer.
+    #[serde(default)]
+    pub shot_sounds: Vec<String>,
+    pub sound_gain: f32,
+    pub sound_radius: f32,
+    /// Multiplier for the noise radius of the weapon.
+    pub noise_factor: f32,
+    /// Multiplier for the damage of the weapon, silenced shots are slightly weaker.
+    pub damage_factor: f32,
 }
 
 impl WeaponDefinition {
+    pub fn fire_mode(&self, index: u32) -> FireMode {
+        self.fire_modes
+            .get(index as usize)
+            .cloned()
+            .unwrap_or(FireMode::Auto)
+    }
+
     pub fn ammo_indicator_offset(&self) -> Vector3<f32> {
         Vector3::new(
             self.ammo_indicator_offset.0,
@@ -79,6 +263,69 @@ impl WeaponDefinition {
         )
     }
 
+    pub fn laser_offset(&self) -> Vector3<f32> {
+        let (x, y, z) = self.sight_calibration.laser_offset;
+        Vector3::new(x, y, z)
+    }
+
+    pub fn converges_shots(&self) -> bool {
+        self.sight_calibration
+            .converge_shots
+            .unwrap_or(matches!(self.projectile, WeaponProjectile::Projectile(_)))
+    }
+
+    pub fn damage(&self) -> Damage {
+        match self.projectile {
+            WeaponProjectile::Projectile(kind) => Projectile::get_definition(kind).damage(),
+            WeaponProjectile::Ray { damage } => damage,
+        }
+    }
+
+    pub fn stats(&self) -> WeaponStats {
+        let damage = self.damage();
+
+        let average = |range: (f32, f32)| (range.0.abs() + range.1.abs()) * 0.5;
+
+        let pattern_recoil = if self.recoil_pattern.is_empty() {
+            0.0
+        } else {
+            self.recoil_pattern
+                .iter()
+                .map(|(v, h)| v.hypot(*h))
+                .sum::<f32>()
+                / self.recoil_pattern.len() as f32
+        };
+
+        WeaponStats {
+            damage: damage.amount(),
+            splash_radius: match damage {
+                Damage::Splash { radius, .. } => Some(radius),
+                Damage::Point(_) => None,
+            },
+            rate_of_fire: if self.shoot_interval > 0.0 {
+                (60.0 / self.shoot_interval) as f32
+            } else {
+                0.0
+            },
+            recoil: pattern_recoil + average(self.v_recoil).hypot(average(self.h_recoil)),
+            critical_shot_probability: self.base_critical_shot_probability,
+            // Every weapon uses the same ammo.
+            ammo: ItemKind::Ammo,
+            ammo_per_shot: self.ammo_consumption_per_shot,
+            fire_modes: self.fire_modes.clone(),
+        }
+    }
+
+    /// Returns vertical and horizontal offsets (in degrees) of aim for a shot with given index
+    /// in continuous fire.
+    pub fn recoil_pattern_step(&self, index: usize) -> (f32, f32) {
+        self.recoil_pattern
+            .get(index)
+            .or_else(|| self.recoil_pattern.last())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     pub fn gen_v_recoil_angle(&self) -> f32 {
         fyrox::rand::thread_rng()
             .gen_range(self.v_recoil.0.to_radians()..self.v_recoil.1.to_radians())
@@ -95,10 +342,33 @@ pub struct WeaponDefinitionContainer {
     pub map: HashMap<WeaponKind, WeaponDefinition>,
 }
 
+/// Weapon definitions are loaded from this file at startup, so balance can be tweaked without
+/// recompiling the game.
+const DEFINITIONS_PATH: &str = "data/configs/weapons.ron";
+/// Definitions that are built into the executable, they are used if the file is missing or
+/// broken and fill in weapons that the file does not define.
+const BUILT_IN_DEFINITIONS: &str = include_str!("../../../data/configs/weapons.ron");
+
 impl WeaponDefinitionContainer {
     pub fn new() -> Self {
-        let file = File::open("data/configs/weapons.ron").unwrap();
-        ron::de::from_reader(file).unwrap()
+        let mut container: Self = ron::de::from_str(BUILT_IN_DEFINITIONS).unwrap();
+
+        match File::open(DEFINITIONS_PATH)
+            .map_err(|e| e.to_string())
+            .and_then(|file| ron::de::from_reader::<_, Self>(file).map_err(|e| e.to_string()))
+        {
+            Ok(loaded) => container.map.extend(loaded.map),
+            Err(e) => Log::writeln(
+                MessageKind::Error,
+                format!(
+                    "Unable to load weapon definitions from {}, built-in definitions will be \
+                    used. Reason: {}",
+                    DEFINITIONS_PATH, e
+                ),
+            ),
+        }
+
+        container
     }
 }
 
@@ -3,9 +3,16 @@
 use crate::{
     actor::{Actor, ActorContainer},
     character::HitBox,
+    config::{LaserSightConfig, WeaponJamConfig},
+    item::ItemKind,
     message::Message,
+    sound::{enclosure_factor, NoiseKind},
     weapon::{
-        definition::{WeaponDefinition, WeaponKind, WeaponProjectile},
+        attachment::{attachment_definition, AttachmentDefinition, AttachmentSlot},
+        definition::{
+            FireMode, SilencerDefinition, WeaponDefinition, WeaponKind, WeaponProjectile,
+            WeaponStats,
+        },
         projectile::Shooter,
         sight::LaserSight,
     },
@@ -23,7 +30,7 @@ use fyrox::{
     },
     engine::resource_manager::ResourceManager,
     material::{shader::SamplerFallback, PropertyValue},
-    rand::seq::SliceRandom,
+    rand::{seq::SliceRandom, thread_rng, Rng},
     scene::{
         base::BaseBuilder,
         collider::InteractionGroups,
@@ -47,10 +54,18 @@ use std::{
     path::PathBuf,
 };
 
+pub mod attachment;
 pub mod definition;
 pub mod projectile;
 pub mod sight;
 
+const SILENCED_MUZZLE_FLASH_SCALE: f32 = 0.4;
+/// Gain of the tail of a shot in an open area relative to the gain in an enclosed space.
+const OPEN_AREA_TAIL_GAIN: f32 = 0.15;
+
+/// Sound of a jammed weapon and of clearing the jam.
+pub const JAM_SOUND: &str = "data/sounds/click.ogg";
+
 #[derive(Visit)]
 pub struct Weapon {
     kind: WeaponKind,
@@ -66,6 +81,56 @@ pub struct Weapon {
     pub definition: &'static WeaponDefinition,
     flash_light: Handle<Node>,
     laser_sight: LaserSight,
+    #[visit(optional)]
+    silencer: Handle<Node>,
+    #[visit(optional)]
+    silenced: bool,
+    #[visit(optional)]
+    fire_mode_index: u32,
+    /// Keeps the laser sight on even if the owner is not aiming.
+    #[visit(optional)]
+    laser_sight_enabled: bool,
+    /// Amount of shots since the last tracer.
+    #[visit(optional)]
+    shots_since_tracer: u32,
+    /// Index of the step of the recoil pattern for the next shot of continuous fire.
+    #[visit(optional)]
+    recoil_step: u32,
+    /// Condition in `[0; 1]` range, worn weapons could jam if jamming is enabled.
+    #[visit(optional)]
+    condition: f32,
+    #[visit(optional)]
+    jammed: bool,
+    /// Time left to clear the jam, `None` if nobody is clearing it.
+    #[visit(skip)]
+    jam_clear_timer: Option<f32>,
+    /// Items that are mounted into attachment slots, one per slot.
+    #[visit(optional)]
+    attachments: Vec<ItemKind>,
+}
+
+/// Runtime state of a weapon that is carried over to the next level.
+#[derive(Clone, Debug)]
+pub struct WeaponPersistentData {
+    pub kind: WeaponKind,
+    pub flash_light_enabled: bool,
+    pub fire_mode_index: u32,
+    pub laser_sight_enabled: bool,
+    pub condition: f32,
+    pub attachments: Vec<ItemKind>,
+}
+
+impl WeaponPersistentData {
+    pub fn new(kind: WeaponKind) -> Self {
+        Self {
+            kind,
+            flash_light_enabled: false,
+            fire_mode_index: 0,
+            laser_sight_enabled: false,
+            condition: 1.0,
+            attachments: Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -145,8 +210,9 @@ pub fn ray_hit(
                         Shooter::Weapon(weapon) => weapons[weapon].owner(),
                     };
 
-                    // Ignore intersections with owners.
-                    if who == actor_handle {
+                    // Ignore intersections with owners and with friends of owners, unless friendly
+                    // fire is enabled.
+                    if who == actor_handle || !actors.can_damage(who, actor_handle) {
                         continue 'actor_loop;
                     }
 
@@ -198,6 +264,16 @@ impl Default for Weapon {
             shot_light: Default::default(),
             flash_light: Default::default(),
             laser_sight: Default::default(),
+            silencer: Default::default(),
+            silenced: false,
+            fire_mode_index: 0,
+            laser_sight_enabled: false,
+            shots_since_tracer: 0,
+            recoil_step: 0,
+            condition: 1.0,
+            jammed: false,
+            jam_clear_timer: None,
+            attachments: Default::default(),
         }
     }
 }
@@ -241,9 +317,9 @@ impl Weapon {
             let light = PointLightBuilder::new(
                 BaseLightBuilder::new(BaseBuilder::new().with_visibility(false))
                     .with_scatter_enabled(false)
-                    .with_color(Color::opaque(255, 255, 255)),
+                    .with_color(definition.muzzle_flash.light_color()),
             )
-            .with_radius(2.0)
+            .with_radius(definition.muzzle_flash.light_radius)
             .build(&mut scene.graph);
 
             scene.graph.link_nodes(light, muzzle_flash);
@@ -276,6 +352,12 @@ impl Weapon {
             Handle::NONE
         };
 
+        // Optional silencer model, it is hidden until the silencer is attached.
+        let silencer = scene.graph.find_by_name(model, "Weapon:Silencer");
+        if silencer.is_some() {
+            scene.graph[silencer].set_visibility(false);
+        }
+
         Weapon {
             kind,
             model,
@@ -284,6 +366,7 @@ impl Weapon {
             muzzle_flash,
             shot_light,
             flash_light,
+            silencer,
             laser_sight: LaserSight::new(scene, resource_manager),
             ..Default::default()
         }
@@ -300,10 +383,32 @@ impl Weapon {
         self.model
     }
 
-    pub fn update(&mut self, scene: &mut Scene, actors: &ActorContainer, dt: f32) {
+    pub fn is_laser_sight_enabled(&self) -> bool {
+        self.laser_sight_enabled
+    }
+
+    pub fn set_laser_sight_enabled(&mut self, enabled: bool) {
+        self.laser_sight_enabled = enabled;
+    }
+
+    pub fn update(
+        &mut self,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        laser_sight_config: &LaserSightConfig,
+        dt: f32,
+    ) {
         let node = &mut scene.graph[self.model];
         self.shot_position = node.global_position();
 
+        if let Some(timer) = self.jam_clear_timer.as_mut() {
+            *timer -= dt;
+            if *timer <= 0.0 {
+                self.jammed = false;
+                self.jam_clear_timer = None;
+            }
+        }
+
         self.muzzle_flash_timer -= dt;
         if self.muzzle_flash_timer <= 0.0 && self.muzzle_flash.is_some() {
             scene.graph[self.muzzle_flash].set_visibility(false);
@@ -315,8 +420,17 @@ impl Weapon {
             ignored_collider = actors.get(self.owner).capsule_collider;
         }
 
-        let dir = self.shot_direction(&scene.graph);
-        let pos = self.shot_position(&scene.graph);
+        // The laser must point exactly where the shots will go, so it follows the crosshair of
+        // the player if shots of the weapon converge there.
+        let pos = self.laser_origin(&scene.graph);
+        let dir = match actors.try_get(self.owner) {
+            Some(Actor::Player(player)) if self.definition.converges_shots() => {
+                player.convergence_direction(&mut scene.graph, self, pos)
+            }
+            _ => self.shot_direction(&scene.graph),
+        };
+        self.laser_sight
+            .apply_config(&mut scene.graph, laser_sight_config);
         self.laser_sight
             .update(scene, pos, dir, ignored_collider, dt)
     }
@@ -330,6 +444,10 @@ impl Weapon {
         }
     }
 
+    pub fn laser_origin(&self, graph: &Graph) -> Vector3<f32> {
+        self.shot_position(graph) + self.world_basis(graph) * self.definition.laser_offset()
+    }
+
     pub fn shot_direction(&self, graph: &Graph) -> Vector3<f32> {
         graph[self.model].look_vector().normalize()
     }
@@ -358,6 +476,208 @@ impl Weapon {
         }
     }
 
+    pub fn is_flash_light_enabled(&self, graph: &Graph) -> bool {
+        graph
+            .try_get(self.flash_light)
+            .map_or(false, |flash_light| flash_light.visibility())
+    }
+
+    pub fn set_flash_light_enabled(&self, graph: &mut Graph, enabled: bool) {
+        if let Some(flash_light) = graph.try_get_mut(self.flash_light) {
+            flash_light.set_visibility(enabled);
+        }
+    }
+
+    pub fn persistent_data(&self, graph: &Graph) -> WeaponPersistentData {
+        WeaponPersistentData {
+            kind: self.kind,
+            flash_light_enabled: self.is_flash_light_enabled(graph),
+            fire_mode_index: self.fire_mode_index,
+            laser_sight_enabled: self.laser_sight_enabled,
+            condition: self.condition,
+            attachments: self.attachments.clone(),
+        }
+    }
+
+    /// Applies state from previous level. Definition of the weapon might have been changed
+    /// since then, so the state is clamped to what the weapon supports.
+    pub fn restore(&mut self, data: &WeaponPersistentData, graph: &mut Graph) {
+        self.set_flash_light_enabled(graph, data.flash_light_enabled);
+        self.laser_sight_enabled = data.laser_sight_enabled;
+        self.condition = data.condition.clamp(0.0, 1.0);
+        self.fire_mode_index = data
+            .fire_mode_index
+            .min((self.definition.fire_modes.len() as u32).saturating_sub(1));
+        self.attachments.clear();
+        for &item in data.attachments.iter() {
+            if self.can_attach(item) {
+                self.attach(item);
+            }
+        }
+    }
+
+    pub fn condition(&self) -> f32 {
+        self.condition
+    }
+
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
+    }
+
+    pub fn is_clearing_jam(&self) -> bool {
+        self.jam_clear_timer.is_some()
+    }
+
+    pub fn repair(&mut self, amount: f32) {
+        self.condition = (self.condition + amount).min(1.0);
+    }
+
+    /// Wears the weapon down by a shot, returns `true` if the weapon has jammed. The chance to
+    /// jam grows as the condition drops below the threshold.
+    pub fn wear(&mut self, config: &WeaponJamConfig) -> bool {
+        self.condition = (self.condition - config.wear_per_shot).max(0.0);
+        if !self.jammed && config.jam_threshold > 0.0 && self.condition < config.jam_threshold {
+            let chance = config.max_jam_chance * (1.0 - self.condition / config.jam_threshold);
+            self.jammed = thread_rng().gen_bool(chance.clamp(0.0, 1.0) as f64);
+        }
+        self.jammed
+    }
+
+    /// Starts clearing of the jam, the weapon can shoot again after given time.
+    pub fn clear_jam(&mut self, time: f32) {
+        if self.jammed && self.jam_clear_timer.is_none() {
+            self.jam_clear_timer = Some(time);
+        }
+    }
+
+    pub fn fire_mode(&self) -> FireMode {
+        self.definition.fire_mode(self.fire_mode_index)
+    }
+
+    /// Switches the weapon to the next fire mode from the list in its definition.
+    pub fn cycle_fire_mode(&mut self) {
+        let count = self.definition.fire_modes.len() as u32;
+        if count > 0 {
+            self.fire_mode_index = (self.fire_mode_index + 1) % count;
+        }
+    }
+
+    pub fn can_be_silenced(&self) -> bool {
+        self.definition.silencer.is_some()
+    }
+
+    pub fn is_silenced(&self) -> bool {
+        self.silenced
+    }
+
+    /// Attaches or detaches silencer, does nothing if the weapon does not support silencers.
+    pub fn set_silenced(&mut self, silenced: bool, graph: &mut Graph) {
+        if !self.can_be_silenced() || self.silenced == silenced {
+            return;
+        }
+
+        self.silenced = silenced;
+
+        if self.silencer.is_some() {
+            graph[self.silencer].set_visibility(silenced);
+        }
+
+        // Silencer hides most of the muzzle flash.
+        if self.muzzle_flash.is_some() {
+            let transform = graph[self.muzzle_flash].local_transform_mut();
+            let scale = **transform.scale();
+            transform.set_scale(if silenced {
+                scale.scale(SILENCED_MUZZLE_FLASH_SCALE)
+            } else {
+                scale.scale(1.0 / SILENCED_MUZZLE_FLASH_SCALE)
+            });
+        }
+    }
+
+    pub fn attachments(&self) -> &[ItemKind] {
+        &self.attachments
+    }
+
+    fn attachment_definitions(&self) -> impl Iterator<Item = &'static AttachmentDefinition> + '_ {
+        self.attachments
+            .iter()
+            .filter_map(|&item| attachment_definition(item))
+    }
+
+    /// Checks whether given item is an attachment and the weapon has a slot for it.
+    pub fn can_attach(&self, item: ItemKind) -> bool {
+        attachment_definition(item).map_or(false, |attachment| {
+            self.definition.attachment_slots.contains(&attachment.slot)
+        })
+    }
+
+    /// Mounts given attachment and returns the item that is freed by it - the attachment that
+    /// occupied the same slot or given item itself if the weapon has no slot for it.
+    pub fn attach(&mut self, item: ItemKind) -> Option<ItemKind> {
+        match attachment_definition(item) {
+            Some(attachment) if self.can_attach(item) => {
+                let replaced = self.detach(attachment.slot);
+                self.attachments.push(item);
+                replaced
+            }
+            _ => Some(item),
+        }
+    }
+
+    /// Removes attachment from given slot, returns the removed item.
+    pub fn detach(&mut self, slot: AttachmentSlot) -> Option<ItemKind> {
+        let index = self.attachments.iter().position(|&item| {
+            attachment_definition(item).map_or(false, |attachment| attachment.slot == slot)
+        })?;
+        Some(self.attachments.remove(index))
+    }
+
+    /// Removes every attachment from the weapon, returns the removed items.
+    pub fn detach_all(&mut self) -> Vec<ItemKind> {
+        std::mem::take(&mut self.attachments)
+    }
+
+    /// Returns combined multiplier of every attachment for a characteristic of the weapon.
+    fn attachment_factor(&self, factor: impl Fn(&AttachmentDefinition) -> f32) -> f32 {
+        self.attachment_definitions().map(factor).product()
+    }
+
+    /// Returns zoom of the view while aiming that is given by an optic attachment.
+    pub fn optic_magnification(&self) -> Option<f32> {
+        self.attachment_definitions()
+            .find_map(|attachment| attachment.magnification)
+    }
+
+    /// Returns stats of the weapon with attachments taken into account.
+    pub fn stats(&self) -> WeaponStats {
+        let mut stats = self.definition.stats();
+        if let WeaponProjectile::Ray { .. } = self.definition.projectile {
+            stats.damage *= self.damage_factor();
+        }
+        stats.recoil *= self.attachment_factor(|a| a.recoil_factor);
+        stats
+    }
+
+    /// Returns damage multiplier of hitscan shots, silencer and attachments make shots weaker.
+    fn damage_factor(&self) -> f32 {
+        self.active_silencer()
+            .map_or(1.0, |silencer| silencer.damage_factor)
+            * self.attachment_factor(|a| a.damage_factor)
+    }
+
+    fn active_silencer(&self) -> Option<&SilencerDefinition> {
+        self.definition.silencer.as_ref().filter(|_| self.silenced)
+    }
+
+    /// Returns radius in which bots can hear shots of the weapon.
+    pub fn shot_noise_radius(&self) -> f32 {
+        self.definition.noise_radius
+            * self
+                .active_silencer()
+                .map_or(1.0, |silencer| silencer.noise_factor)
+            * self.attachment_factor(|a| a.noise_factor)
+    }
+
     pub fn laser_sight(&self) -> &LaserSight {
         &self.laser_sight
     }
@@ -370,6 +690,33 @@ impl Weapon {
         time.elapsed - self.last_shot_time >= self.definition.shoot_interval
     }
 
+    /// Checks whether there was a pause in shooting long enough to start the recoil pattern
+    /// over.
+    pub fn is_recoil_reset(&self, time: GameTime) -> bool {
+        time.elapsed - self.last_shot_time >= self.definition.recoil_reset_time as f64
+    }
+
+    fn current_recoil_step(&self, time: GameTime) -> u32 {
+        if self.is_recoil_reset(time) {
+            0
+        } else {
+            self.recoil_step
+        }
+    }
+
+    /// Returns vertical and horizontal recoil angles (in radians) for a shot made at given time.
+    /// Recoil follows the pattern of the weapon with a bit of random jitter on top of it.
+    pub fn gen_recoil_angles(&self, time: GameTime) -> (f32, f32) {
+        let (v_offset, h_offset) = self
+            .definition
+            .recoil_pattern_step(self.current_recoil_step(time) as usize);
+        let factor = self.attachment_factor(|a| a.recoil_factor);
+        (
+            (v_offset.to_radians() + self.definition.gen_v_recoil_angle()) * factor,
+            (h_offset.to_radians() + self.definition.gen_h_recoil_angle()) * factor,
+        )
+    }
+
     pub fn shoot(
         &mut self,
         self_handle: Handle<Weapon>,
@@ -379,24 +726,59 @@ impl Weapon {
         direction: Option<Vector3<f32>>,
         sender: &MessageSender,
     ) {
+        self.recoil_step = self.current_recoil_step(time) + 1;
         self.last_shot_time = time.elapsed;
 
         let position = self.shot_position(&scene.graph);
 
-        if let Some(random_shot_sound) = self
+        let (shot_sounds, gain, radius) = match self.active_silencer() {
+            Some(silencer) => (
+                if silencer.shot_sounds.is_empty() {
+                    &self.definition.shot_sounds
+                } else {
+                    &silencer.shot_sounds
+                },
+                silencer.sound_gain,
+                silencer.sound_radius,
+            ),
+            None => (&self.definition.shot_sounds, 1.0, 3.0),
+        };
+        let gain = gain * self.attachment_factor(|a| a.sound_gain_factor);
+        let radius = radius * self.attachment_factor(|a| a.sound_radius_factor);
+
+        if let Some(random_shot_sound) = shot_sounds.choose(&mut fyrox::rand::thread_rng()) {
+            sender.send(Message::PlaySound {
+                path: PathBuf::from(random_shot_sound.clone()),
+                position,
+                gain,
+                rolloff_factor: 5.0,
+                radius,
+            });
+        }
+
+        // Echo of the shot, it is pronounced in corridors and rooms and is barely heard outdoors.
+        if let Some(tail_sound) = self
             .definition
-            .shot_sounds
+            .tail_sounds
             .choose(&mut fyrox::rand::thread_rng())
         {
+            let enclosure = enclosure_factor(&scene.graph, position);
             sender.send(Message::PlaySound {
-                path: PathBuf::from(random_shot_sound.clone()),
+                path: PathBuf::from(tail_sound.clone()),
                 position,
-                gain: 1.0,
-                rolloff_factor: 5.0,
-                radius: 3.0,
+                gain: gain * (OPEN_AREA_TAIL_GAIN + (1.0 - OPEN_AREA_TAIL_GAIN) * enclosure),
+                rolloff_factor: 2.0,
+                radius: radius * 2.0,
             });
         }
 
+        sender.send(Message::MakeNoise {
+            position,
+            radius: self.shot_noise_radius(),
+            kind: NoiseKind::Gunshot,
+            source: self.owner,
+        });
+
         if self.muzzle_flash.is_some() {
             let muzzle_flash = &mut scene.graph[self.muzzle_flash];
             muzzle_flash.set_visibility(true);
@@ -418,8 +800,8 @@ impl Weapon {
                     },
                 ));
             }
-            scene.graph[self.shot_light].set_visibility(true);
-            self.muzzle_flash_timer = 0.075;
+            scene.graph[self.shot_light].set_visibility(!self.silenced);
+            self.muzzle_flash_timer = self.definition.muzzle_flash.duration;
         }
 
         let position = self.shot_position(&scene.graph);
@@ -437,17 +819,31 @@ impl Weapon {
                 initial_velocity: Default::default(),
             }),
             WeaponProjectile::Ray { damage } => {
+                let damage = damage.scale(self.damage_factor());
+
                 sender.send(Message::ShootRay {
                     shooter: Shooter::Weapon(self_handle),
                     begin: position,
                     end: position + direction.scale(1000.0),
                     damage,
-                    shot_effect: self.definition.shot_effect,
+                    shot_effect: if self.next_shot_is_tracer() {
+                        Some(self.definition.shot_effect)
+                    } else {
+                        None
+                    },
                 });
             }
         }
     }
 
+    /// Only every Nth shot leaves a visible trail, first shot is always a tracer.
+    fn next_shot_is_tracer(&mut self) -> bool {
+        let is_tracer = self.shots_since_tracer == 0;
+        self.shots_since_tracer =
+            (self.shots_since_tracer + 1) % self.definition.tracer_frequency.max(1);
+        is_tracer
+    }
+
     pub fn clean_up(&mut self, scene: &mut Scene) {
         scene.graph.remove_node(self.model);
         self.laser_sight.clean_up(scene);
@@ -476,6 +872,10 @@ impl WeaponContainer {
         self.pool.try_borrow(weapon)
     }
 
+    pub fn try_get_mut(&mut self, weapon: Handle<Weapon>) -> Option<&mut Weapon> {
+        self.pool.try_borrow_mut(weapon)
+    }
+
     pub fn contains(&self, weapon: Handle<Weapon>) -> bool {
         self.pool.is_valid_handle(weapon)
     }
@@ -488,9 +888,15 @@ impl WeaponContainer {
         self.pool.iter_mut()
     }
 
-    pub fn update(&mut self, scene: &mut Scene, actors: &ActorContainer, dt: f32) {
+    pub fn update(
+        &mut self,
+        scene: &mut Scene,
+        actors: &ActorContainer,
+        laser_sight_config: &LaserSightConfig,
+        dt: f32,
+    ) {
         for weapon in self.pool.iter_mut() {
-            weapon.update(scene, actors, dt)
+            weapon.update(scene, actors, laser_sight_config, dt)
         }
     }
 
@@ -1,16 +1,18 @@
 use crate::{
     actor::{Actor, ActorContainer},
     effects::EffectKind,
+    item::ItemKind,
     level::turret::Turret,
     message::Message,
+    status::StatusEffectDefinition,
     weapon::{ray_hit, sight::SightReaction, Hit, Weapon, WeaponContainer},
-    GameTime, MessageSender,
+    GameTime, MessageSender, FIXED_FPS,
 };
-use fyrox::scene::rigidbody::RigidBody;
+use fyrox::scene::rigidbody::{RigidBody, RigidBodyType};
 use fyrox::scene::sprite::Sprite;
 use fyrox::{
     core::{
-        algebra::Vector3,
+        algebra::{UnitQuaternion, Vector3},
         math::{vector_to_quat, Vector3Ext},
         pool::{Handle, Pool},
         visitor::{Visit, VisitResult, Visitor},
@@ -26,6 +28,9 @@ use std::{collections::HashMap, collections::HashSet, fs::File, path::PathBuf};
 pub enum ProjectileKind {
     Plasma,
     Grenade,
+    Smoke,
+    Flashbang,
+    Knife,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Visit)]
@@ -95,6 +100,9 @@ pub struct Projectile {
     definition: &'static ProjectileDefinition,
     #[visit(skip)]
     hits: HashSet<Hit>,
+    /// Type of the rigid body before the projectile was put in the pool of free projectiles.
+    #[visit(optional)]
+    parked_body_type: Option<RigidBodyType>,
 }
 
 impl Default for Projectile {
@@ -111,6 +119,7 @@ impl Default for Projectile {
             last_position: Default::default(),
             definition: Self::get_definition(ProjectileKind::Plasma),
             hits: Default::default(),
+            parked_body_type: None,
         }
     }
 }
@@ -125,6 +134,42 @@ pub struct ProjectileDefinition {
     is_kinematic: bool,
     impact_sound: String,
     model: String,
+    homing: Option<HomingDefinition>,
+    /// If `false`, projectile will bounce off surfaces and detonate only when its lifetime is
+    /// over.
+    detonate_on_contact: bool,
+    /// Additional effect of detonation.
+    detonation: Option<Detonation>,
+    /// Status effect that is applied to actors hit by the projectile.
+    #[serde(default)]
+    status_effect: Option<StatusEffectDefinition>,
+}
+
+#[derive(Deserialize, Copy, Clone, Debug)]
+pub enum Detonation {
+    Smoke {
+        radius: f32,
+        lifetime: f32,
+    },
+    Flashbang {
+        range: f32,
+        duration: f32,
+    },
+    /// Projectile turns into an item that stays where the projectile has stopped.
+    Embed {
+        item: ItemKind,
+    },
+}
+
+/// Homing projectiles steer towards the closest actor in front of them.
+#[derive(Deserialize)]
+pub struct HomingDefinition {
+    /// Max distance at which a target can be picked.
+    range: f32,
+    /// Half-angle of the cone (in degrees) in which a target can be picked.
+    cone_angle: f32,
+    /// Max turn rate in degrees per second.
+    turn_rate: f32,
 }
 
 #[derive(Deserialize, Default)]
@@ -139,10 +184,35 @@ impl ProjectileDefinitionContainer {
     }
 }
 
+/// Grenades that are about to detonate can't be thrown back.
+const MIN_THROW_BACK_FUSE: f32 = 0.5;
+
+/// Max amount of dead projectiles that are kept for reuse.
+const MAX_FREE_PROJECTILES: usize = 32;
+
+/// Position where dead projectiles are waiting to be reused.
+const PARKING_POSITION: Vector3<f32> = Vector3::new(0.0, -10000.0, 0.0);
+
 lazy_static! {
     static ref DEFINITIONS: ProjectileDefinitionContainer = ProjectileDefinitionContainer::new();
 }
 
+impl ProjectileDefinition {
+    pub fn damage(&self) -> Damage {
+        self.damage
+    }
+
+    /// Returns speed (in meters per second) of projectiles that fly in a straight line with
+    /// constant speed, `None` for physical projectiles.
+    pub fn flight_speed(&self) -> Option<f32> {
+        if self.is_kinematic && self.speed > 0.0 {
+            Some(self.speed * FIXED_FPS)
+        } else {
+            None
+        }
+    }
+}
+
 impl Projectile {
     pub fn get_definition(kind: ProjectileKind) -> &'static ProjectileDefinition {
         DEFINITIONS.map.get(&kind).unwrap()
@@ -188,10 +258,89 @@ impl Projectile {
         }
     }
 
+    fn shooter_actor(&self, weapons: &WeaponContainer) -> Handle<Actor> {
+        match self.owner {
+            Shooter::Actor(actor) => actor,
+            Shooter::Weapon(weapon) if weapons.contains(weapon) => weapons[weapon].owner(),
+            _ => Handle::NONE,
+        }
+    }
+
+    fn steer(
+        &mut self,
+        position: Vector3<f32>,
+        graph: &Graph,
+        actors: &ActorContainer,
+        weapons: &WeaponContainer,
+        homing: &HomingDefinition,
+        dt: f32,
+    ) {
+        // Shooter must never be picked as a target.
+        let shooter = self.shooter_actor(weapons);
+        let cone_cos = homing.cone_angle.to_radians().cos();
+
+        let mut closest_distance = homing.range;
+        let mut desired_dir = None;
+        for (actor_handle, actor) in actors.pair_iter() {
+            if actor_handle == shooter || actor.is_dead() {
+                continue;
+            }
+
+            let to_target = actor.position(graph) - position;
+            let distance = to_target.norm();
+            if distance < closest_distance {
+                if let Some(dir) = to_target.try_normalize(std::f32::EPSILON) {
+                    if dir.dot(&self.dir) >= cone_cos {
+                        closest_distance = distance;
+                        desired_dir = Some(dir);
+                    }
+                }
+            }
+        }
+
+        if let Some(desired_dir) = desired_dir {
+            if let Some(rotation) = UnitQuaternion::rotation_between(&self.dir, &desired_dir) {
+                let max_angle = homing.turn_rate.to_radians() * dt;
+                let angle = rotation.angle();
+                let rotation = if angle > max_angle {
+                    rotation.powf(max_angle / angle)
+                } else {
+                    rotation
+                };
+                self.dir = rotation
+                    .transform_vector(&self.dir)
+                    .try_normalize(std::f32::EPSILON)
+                    .unwrap_or(self.dir);
+            }
+        }
+    }
+
     pub fn is_dead(&self) -> bool {
         self.lifetime <= 0.0
     }
 
+    pub fn kind(&self) -> ProjectileKind {
+        self.kind
+    }
+
+    /// Returns `true` if the projectile is a grenade which will detonate when its fuse is over.
+    pub fn is_live_grenade(&self) -> bool {
+        !self.is_dead() && !self.definition.is_kinematic && !self.definition.detonate_on_contact
+    }
+
+    /// Remaining time (in seconds) before the detonation of a grenade.
+    pub fn fuse(&self) -> f32 {
+        self.lifetime
+    }
+
+    pub fn set_fuse(&mut self, fuse: f32) {
+        self.lifetime = fuse;
+    }
+
+    pub fn can_be_thrown_back(&self) -> bool {
+        self.is_live_grenade() && self.lifetime > MIN_THROW_BACK_FUSE
+    }
+
     pub fn kill(&mut self) {
         self.lifetime = 0.0;
     }
@@ -230,30 +379,35 @@ impl Projectile {
             collider,
         );
 
-        let (effect_position, effect_normal, effect_kind) = if let Some(hit) = ray_hit {
-            let position = hit.position;
-            let normal = hit.normal;
-            let blood_effect = hit.actor.is_some();
-
-            self.hits.insert(hit);
-            self.kill();
+        let (effect_position, effect_normal, effect_kind) =
+            if let Some(hit) = ray_hit.filter(|_| self.definition.detonate_on_contact) {
+                let position = hit.position;
+                let normal = hit.normal;
+                let blood_effect = hit.actor.is_some();
+
+                self.hits.insert(hit);
+                self.kill();
+
+                (
+                    position,
+                    normal,
+                    if blood_effect {
+                        EffectKind::BloodSpray
+                    } else {
+                        EffectKind::BulletImpact
+                    },
+                )
+            } else {
+                (
+                    self.get_position(&scene.graph),
+                    Vector3::y(),
+                    EffectKind::BulletImpact,
+                )
+            };
 
-            (
-                position,
-                normal,
-                if blood_effect {
-                    EffectKind::BloodSpray
-                } else {
-                    EffectKind::BulletImpact
-                },
-            )
-        } else {
-            (
-                self.get_position(&scene.graph),
-                Vector3::y(),
-                EffectKind::BulletImpact,
-            )
-        };
+        if let Some(homing) = self.definition.homing.as_ref() {
+            self.steer(position, &scene.graph, actors, weapons, homing, time.delta);
+        }
 
         // Movement of kinematic projectiles are controlled explicitly.
         if self.definition.is_kinematic {
@@ -297,6 +451,48 @@ impl Projectile {
                 rolloff_factor: 4.0,
                 radius: 3.0,
             });
+
+            match self.definition.detonation {
+                Some(Detonation::Smoke { radius, lifetime }) => {
+                    sender.send(Message::CreateSmokeCloud {
+                        position: effect_position,
+                        radius,
+                        lifetime,
+                    })
+                }
+                Some(Detonation::Flashbang { range, duration }) => {
+                    sender.send(Message::DetonateFlashbang {
+                        position: effect_position,
+                        range,
+                        duration,
+                    })
+                }
+                Some(Detonation::Embed { item }) => sender.send(Message::EmbedItem {
+                    item,
+                    position: effect_position,
+                    direction: self.dir,
+                    // Stick into a body of an actor, so the item will move with it.
+                    parent: self
+                        .hits
+                        .iter()
+                        .find(|hit| hit.actor.is_some())
+                        .map_or(Handle::NONE, |hit| hit.collider),
+                }),
+                None => (),
+            }
+
+            // Grenades with a fuse deal splash damage only when the fuse is over.
+            if !self.definition.detonate_on_contact {
+                if let Damage::Splash { radius, amount } = self.definition.damage {
+                    sender.send(Message::ApplySplashDamage {
+                        amount,
+                        radius,
+                        center: effect_position,
+                        who: self.shooter_actor(weapons),
+                        critical_shot_probability: 0.0,
+                    });
+                }
+            }
         }
 
         for hit in self.hits.drain() {
@@ -328,13 +524,41 @@ impl Projectile {
                     who: hit.who,
                     critical_shot_probability,
                 }),
-                Damage::Point(amount) => sender.send(Message::DamageActor {
-                    actor: hit.actor,
-                    who: hit.who,
-                    hitbox: hit.hit_box,
-                    amount,
-                    critical_shot_probability,
-                }),
+                Damage::Point(amount) => {
+                    sender.send(Message::DamageActor {
+                        actor: hit.actor,
+                        who: hit.who,
+                        weapon: match self.owner {
+                            Shooter::Weapon(weapon) => weapon,
+                            _ => Handle::NONE,
+                        },
+                        hitbox: hit.hit_box,
+                        amount,
+                        critical_shot_probability,
+                    });
+
+                    if let Some(effect) = self.definition.status_effect {
+                        if hit.actor.is_some() {
+                            sender.send(Message::ApplyStatusEffect {
+                                actor: hit.actor,
+                                who: hit.who,
+                                effect,
+                            });
+                        }
+                    }
+
+                    if hit.actor.is_none() {
+                        sender.send(Message::DamageDoor {
+                            collider: hit.collider,
+                            amount,
+                        });
+                        sender.send(Message::DamageBarrel {
+                            collider: hit.collider,
+                            amount,
+                            who: hit.who,
+                        });
+                    }
+                }
             }
         }
 
@@ -345,6 +569,55 @@ impl Projectile {
         graph[self.model].global_position()
     }
 
+    /// Hides projectile and moves it far away from the level, so it can be reused later without
+    /// instantiating its model again.
+    fn park(&mut self, scene: &mut Scene) {
+        scene.graph[self.model].set_visibility(false);
+
+        if let Some(body) = scene.graph[self.body].cast_mut::<RigidBody>() {
+            self.parked_body_type = Some(body.body_type());
+            body.set_body_type(RigidBodyType::KinematicPositionBased);
+            body.set_lin_vel(Default::default());
+            body.set_ang_vel(Default::default());
+        }
+
+        scene.graph[self.body]
+            .local_transform_mut()
+            .set_position(PARKING_POSITION);
+    }
+
+    /// Brings parked projectile back to life, it must behave exactly as a projectile that was
+    /// just created by [`Self::new`].
+    fn reuse(
+        &mut self,
+        scene: &mut Scene,
+        dir: Vector3<f32>,
+        position: Vector3<f32>,
+        owner: Shooter,
+        initial_velocity: Vector3<f32>,
+    ) {
+        scene.graph[self.model].set_visibility(true);
+
+        let body_ref = &mut scene.graph[self.body];
+        body_ref.local_transform_mut().set_position(position);
+        if let Some(body) = body_ref.cast_mut::<RigidBody>() {
+            if let Some(body_type) = self.parked_body_type.take() {
+                body.set_body_type(body_type);
+            }
+            body.set_lin_vel(initial_velocity);
+        }
+
+        self.lifetime = self.definition.lifetime;
+        self.initial_velocity = initial_velocity;
+        self.dir = dir
+            .try_normalize(std::f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+        self.last_position = position;
+        self.owner = owner;
+        self.rotation_angle = 0.0;
+        self.hits.clear();
+    }
+
     fn clean_up(&mut self, scene: &mut Scene) {
         if scene.graph.is_valid_handle(self.body) {
             scene.graph.remove_node(self.body);
@@ -361,21 +634,92 @@ impl Projectile {
 #[derive(Default, Visit)]
 pub struct ProjectileContainer {
     pool: Pool<Projectile>,
+    /// Dead projectiles that are kept parked in the scene to be reused by [`Self::try_reuse`].
+    #[visit(optional)]
+    free: Vec<Projectile>,
 }
 
 impl ProjectileContainer {
     pub fn new() -> Self {
-        Self { pool: Pool::new() }
+        Self {
+            pool: Pool::new(),
+            free: Default::default(),
+        }
     }
 
     pub fn add(&mut self, projectile: Projectile) -> Handle<Projectile> {
         self.pool.spawn(projectile)
     }
 
+    /// Tries to revive a dead projectile of given kind instead of creating new one. Returns
+    /// [`Handle::NONE`] if there is no such projectile in the pool of free projectiles.
+    pub fn try_reuse(
+        &mut self,
+        kind: ProjectileKind,
+        scene: &mut Scene,
+        dir: Vector3<f32>,
+        position: Vector3<f32>,
+        owner: Shooter,
+        initial_velocity: Vector3<f32>,
+    ) -> Handle<Projectile> {
+        match self.free.iter().position(|p| p.kind == kind) {
+            Some(index) => {
+                let mut projectile = self.free.swap_remove(index);
+                projectile.reuse(scene, dir, position, owner, initial_velocity);
+                self.add(projectile)
+            }
+            None => Handle::NONE,
+        }
+    }
+
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Projectile> {
         self.pool.iter_mut()
     }
 
+    pub fn try_get(&self, handle: Handle<Projectile>) -> Option<&Projectile> {
+        self.pool.try_borrow(handle)
+    }
+
+    pub fn try_get_mut(&mut self, handle: Handle<Projectile>) -> Option<&mut Projectile> {
+        self.pool.try_borrow_mut(handle)
+    }
+
+    /// Returns the closest live grenade within given radius.
+    pub fn closest_live_grenade(
+        &self,
+        position: Vector3<f32>,
+        radius: f32,
+        graph: &Graph,
+    ) -> Option<(Handle<Projectile>, &Projectile)> {
+        self.pool
+            .pair_iter()
+            .filter(|(_, projectile)| projectile.is_live_grenade())
+            .map(|(handle, projectile)| {
+                let distance = projectile.get_position(graph).metric_distance(&position);
+                (handle, projectile, distance)
+            })
+            .filter(|&(_, _, distance)| distance <= radius)
+            .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+            .map(|(handle, projectile, _)| (handle, projectile))
+    }
+
+    /// Removes projectile without detonation.
+    pub fn remove(&mut self, handle: Handle<Projectile>, scene: &mut Scene) {
+        if self.pool.is_valid_handle(handle) {
+            self.dispose(handle, scene);
+        }
+    }
+
+    fn dispose(&mut self, handle: Handle<Projectile>, scene: &mut Scene) {
+        let mut projectile = self.pool.free(handle);
+        if self.free.len() < MAX_FREE_PROJECTILES {
+            projectile.park(scene);
+            self.free.push(projectile);
+        } else {
+            projectile.clean_up(scene);
+        }
+    }
+
     pub fn update(
         &mut self,
         scene: &mut Scene,
@@ -384,18 +728,21 @@ impl ProjectileContainer {
         time: GameTime,
         sender: &MessageSender,
     ) {
-        for projectile in self.pool.iter_mut() {
+        let mut dead = Vec::new();
+        for (handle, projectile) in self.pool.pair_iter_mut() {
             projectile.update(scene, actors, weapons, time, sender);
             if projectile.is_dead() {
-                projectile.clean_up(scene);
+                dead.push(handle);
             }
         }
 
-        self.pool.retain(|proj| !proj.is_dead());
+        for handle in dead {
+            self.dispose(handle, scene);
+        }
     }
 
     pub fn resolve(&mut self) {
-        for projectile in self.pool.iter_mut() {
+        for projectile in self.pool.iter_mut().chain(self.free.iter_mut()) {
             projectile.resolve();
         }
     }
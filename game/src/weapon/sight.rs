@@ -1,4 +1,4 @@
-use crate::CollisionGroups;
+use crate::{config::LaserSightConfig, CollisionGroups};
 use fyrox::core::parking_lot::Mutex;
 use fyrox::core::sstorage::ImmutableString;
 use fyrox::scene::collider::{BitMask, InteractionGroups};
@@ -31,12 +31,29 @@ use fyrox::{
 };
 use std::sync::Arc;
 
-#[derive(Default, Visit)]
+#[derive(Visit)]
 pub struct LaserSight {
     ray: Handle<Node>,
     tip: Handle<Node>,
     light: Handle<Node>,
     reaction_state: Option<ReactionState>,
+    #[visit(optional)]
+    color: Color,
+    #[visit(optional)]
+    show_dot: bool,
+}
+
+impl Default for LaserSight {
+    fn default() -> Self {
+        Self {
+            ray: Default::default(),
+            tip: Default::default(),
+            light: Default::default(),
+            reaction_state: None,
+            color: NORMAL_COLOR,
+            show_dot: true,
+        }
+    }
 }
 
 #[derive(Visit)]
@@ -125,10 +142,29 @@ impl LaserSight {
             ray,
             tip,
             light,
-            reaction_state: None,
+            ..Default::default()
         }
     }
 
+    /// Applies color and dot visibility from the settings. Does nothing if the appearance is
+    /// already up to date, so it is fine to call it every frame.
+    pub fn apply_config(&mut self, graph: &mut Graph, config: &LaserSightConfig) {
+        let color = config.color.color();
+        if self.color == color && self.show_dot == config.show_dot {
+            return;
+        }
+
+        self.color = color;
+        self.show_dot = config.show_dot;
+
+        if self.reaction_state.is_none() {
+            self.set_color(graph, color);
+        }
+
+        let visible = self.is_visible(graph);
+        graph[self.tip].set_visibility(visible && self.show_dot);
+    }
+
     pub fn update(
         &mut self,
         scene: &mut Scene,
@@ -215,13 +251,13 @@ impl LaserSight {
             SightReaction::HitDetected => ReactionState::HitDetected {
                 time_remaining: HIT_DETECTED_TIME,
                 begin_color: Color::from_rgba(200, 0, 0, 200),
-                end_color: NORMAL_COLOR,
+                end_color: self.color,
             },
             SightReaction::EnemyKilled => ReactionState::EnemyKilled {
                 time_remaining: ENEMY_KILLED_TIME,
                 dilation_factor: 1.1,
                 begin_color: Color::from_rgba(255, 0, 0, 200),
-                end_color: NORMAL_COLOR,
+                end_color: self.color,
             },
         });
     }
@@ -258,10 +294,16 @@ impl LaserSight {
     }
 
     pub fn set_visible(&self, visibility: bool, graph: &mut Graph) {
-        graph[self.tip].set_visibility(visibility);
+        graph[self.tip].set_visibility(visibility && self.show_dot);
         graph[self.ray].set_visibility(visibility);
     }
 
+    pub fn is_visible(&self, graph: &Graph) -> bool {
+        graph
+            .try_get(self.ray)
+            .map_or(false, |ray| ray.visibility())
+    }
+
     pub fn clean_up(&mut self, scene: &mut Scene) {
         scene.graph.remove_node(self.ray);
         scene.graph.remove_node(self.tip);